@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -7,11 +8,13 @@ use serde_json::{Value, json};
 use uuid::Uuid;
 
 use mosaic_core::audit::{AuditStore, CommandAudit};
-use mosaic_core::config::ProfileConfig;
+use mosaic_core::config::{ContextBudget, ModelPricing, ProfileConfig};
 use mosaic_core::error::{MosaicError, Result};
-use mosaic_core::provider::{ChatMessage, ChatRequest, ChatResponse, ChatRole, Provider};
+use mosaic_core::provider::{
+    ChatChunkSink, ChatMessage, ChatRequest, ChatResponse, ChatRole, Provider, TokenUsage, ToolSpec,
+};
 use mosaic_core::session::{EventKind, SessionRuntimeMetadata, SessionStore};
-use mosaic_tools::{RunCommandOutput, ToolContext, ToolExecutor};
+use mosaic_tools::{RunCommandOutput, ToolContext, ToolExecutor, builtin_tool_specs};
 
 pub type AgentEventCallback = Arc<dyn Fn(AgentEvent) + Send + Sync>;
 
@@ -50,6 +53,7 @@ pub struct AgentRunOptions {
     pub yes: bool,
     pub interactive: bool,
     pub event_callback: Option<AgentEventCallback>,
+    pub label: Option<String>,
 }
 
 impl std::fmt::Debug for AgentRunOptions {
@@ -61,6 +65,7 @@ impl std::fmt::Debug for AgentRunOptions {
             .field("yes", &self.yes)
             .field("interactive", &self.interactive)
             .field("event_callback", &self.event_callback.is_some())
+            .field("label", &self.label)
             .finish()
     }
 }
@@ -70,6 +75,36 @@ pub struct AgentRunResult {
     pub session_id: String,
     pub response: String,
     pub turns: u32,
+    /// Token usage summed across turns; `None` when the provider reported none.
+    pub usage: Option<TokenUsage>,
+    /// Cost in USD, priced per turn by the model that answered it; `None` when
+    /// there is no usage or a turn's model has no configured pricing.
+    pub estimated_cost_usd: Option<f64>,
+}
+
+/// Running cost of the turns that reported usage; unknown once any of them
+/// used a model without pricing.
+#[derive(Debug, Default)]
+struct TurnCost {
+    usd: f64,
+    priced_turns: u32,
+    unpriced: bool,
+}
+
+impl TurnCost {
+    fn add(&mut self, pricing: Option<&ModelPricing>, usage: &TokenUsage) {
+        match pricing {
+            Some(pricing) => {
+                self.usd += pricing.estimate_cost_usd(usage);
+                self.priced_turns += 1;
+            }
+            None => self.unpriced = true,
+        }
+    }
+
+    fn total(&self) -> Option<f64> {
+        (!self.unpriced && self.priced_turns > 0).then_some(self.usd)
+    }
 }
 
 #[derive(Clone)]
@@ -80,6 +115,7 @@ pub struct AgentRunner {
     audit_store: AuditStore,
     tools: ToolExecutor,
     system_prompt: String,
+    pricing: BTreeMap<String, ModelPricing>,
 }
 
 impl AgentRunner {
@@ -97,6 +133,7 @@ impl AgentRunner {
             audit_store,
             tools,
             system_prompt: SYSTEM_PROMPT.to_string(),
+            pricing: BTreeMap::new(),
         }
     }
 
@@ -120,9 +157,16 @@ impl AgentRunner {
             audit_store,
             tools,
             system_prompt: prompt,
+            pricing: BTreeMap::new(),
         }
     }
 
+    /// Prices used to fill [`AgentRunResult::estimated_cost_usd`].
+    pub fn with_pricing(mut self, pricing: BTreeMap<String, ModelPricing>) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
     pub fn session_store(&self) -> &SessionStore {
         &self.session_store
     }
@@ -132,6 +176,27 @@ impl AgentRunner {
     }
 
     pub async fn ask(&self, prompt: &str, options: AgentRunOptions) -> Result<AgentRunResult> {
+        self.run(prompt, options, None).await
+    }
+
+    /// Like [`Self::ask`], but streams reply text through `on_chunk` as the
+    /// provider produces it. Turns that turn out to be tool calls are not
+    /// forwarded, so `on_chunk` only sees text meant for the user.
+    pub async fn ask_streaming(
+        &self,
+        prompt: &str,
+        options: AgentRunOptions,
+        on_chunk: &mut ChatChunkSink<'_>,
+    ) -> Result<AgentRunResult> {
+        self.run(prompt, options, Some(on_chunk)).await
+    }
+
+    async fn run(
+        &self,
+        prompt: &str,
+        options: AgentRunOptions,
+        mut on_chunk: Option<&mut ChatChunkSink<'_>>,
+    ) -> Result<AgentRunResult> {
         if prompt.trim().is_empty() {
             return Err(MosaicError::Validation(
                 "prompt cannot be empty".to_string(),
@@ -184,6 +249,8 @@ impl AgentRunner {
         );
 
         let mut turns = 0u32;
+        let mut usage: Option<TokenUsage> = None;
+        let mut cost = TurnCost::default();
         loop {
             turns += 1;
             if turns > self.profile.agent.max_turns {
@@ -204,15 +271,44 @@ impl AgentRunner {
                 model: self.profile.provider.model.clone(),
                 temperature: self.profile.agent.temperature,
                 messages,
+                max_tokens: self.profile.agent.max_tokens,
+                top_p: self.profile.agent.top_p,
+                stop: self.profile.agent.stop.clone(),
+                tools: self.native_tool_specs(),
             };
-            let response = self.provider.chat(request).await.inspect_err(|err| {
+            let response = match on_chunk.as_deref_mut() {
+                Some(sink) => {
+                    let mut reply = ReplyStream::new(sink);
+                    let response = self
+                        .provider
+                        .chat_stream(request, &mut |chunk| reply.push(chunk))
+                        .await;
+                    if let Ok(response) = &response {
+                        reply.finish(!parsed_tool_calls(response).is_empty());
+                    }
+                    response
+                }
+                None => self.provider.chat(request).await,
+            }
+            .inspect_err(|err| {
                 emit_error(err.to_string());
             })?;
-            if let Some(tool_call) = parse_tool_call(&response) {
-                self.handle_tool_call(&session_id, tool_call, &options)
-                    .inspect_err(|err| {
-                        emit_error(err.to_string());
-                    })?;
+            if let Some(turn_usage) = &response.usage {
+                usage.get_or_insert_default().accumulate(turn_usage);
+                let model = response
+                    .model
+                    .as_deref()
+                    .unwrap_or(&self.profile.provider.model);
+                cost.add(self.pricing.get(model), turn_usage);
+            }
+            let tool_calls = parsed_tool_calls(&response);
+            if !tool_calls.is_empty() {
+                for tool_call in tool_calls {
+                    self.handle_tool_call(&session_id, tool_call, &options)
+                        .inspect_err(|err| {
+                            emit_error(err.to_string());
+                        })?;
+                }
                 continue;
             }
 
@@ -237,15 +333,25 @@ impl AgentRunner {
                 session_id,
                 response: response.content,
                 turns,
+                usage,
+                estimated_cost_usd: cost.total(),
             });
         }
     }
 
+    /// Schemas for native tool calling, limited to the tools this profile may use.
+    fn native_tool_specs(&self) -> Vec<ToolSpec> {
+        if !self.profile.tools.enabled {
+            return Vec::new();
+        }
+        builtin_tool_specs()
+            .into_iter()
+            .filter(|spec| self.profile.tools.is_tool_allowed(&spec.name))
+            .collect()
+    }
+
     fn build_messages_for_session(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
-        let mut messages = vec![ChatMessage {
-            role: ChatRole::System,
-            content: self.system_prompt.clone(),
-        }];
+        let mut messages = Vec::new();
         let events = self.session_store.read_events(session_id)?;
         for event in events {
             match event.kind {
@@ -266,10 +372,16 @@ impl AgentRunner {
                     }
                 }
                 EventKind::ToolResult => {
-                    let text = event
-                        .payload
-                        .get("result")
-                        .map_or_else(|| "{}".to_string(), |value| value.to_string());
+                    // Truncated results are stored as the already-serialized text.
+                    let text = match event.payload.get("result") {
+                        Some(Value::String(text))
+                            if event.payload.get("truncated_bytes").is_some() =>
+                        {
+                            text.clone()
+                        }
+                        Some(value) => value.to_string(),
+                        None => "{}".to_string(),
+                    };
                     let name = event
                         .payload
                         .get("name")
@@ -280,10 +392,41 @@ impl AgentRunner {
                         content: format!("TOOL_RESULT {name}\n{text}"),
                     });
                 }
-                EventKind::ToolCall | EventKind::System | EventKind::Error => {}
+                EventKind::Error => {
+                    // Only tool failures carry a tool name; they are replayed so
+                    // the model can see why its previous call did not succeed.
+                    let Some(name) = event.payload.get("name").and_then(|value| value.as_str())
+                    else {
+                        continue;
+                    };
+                    let message = event
+                        .payload
+                        .get("message")
+                        .and_then(|value| value.as_str())
+                        .unwrap_or("unknown error");
+                    messages.push(ChatMessage {
+                        role: ChatRole::User,
+                        content: format!("TOOL_ERROR {name}\n{message}"),
+                    });
+                }
+                EventKind::ToolCall | EventKind::System => {}
+            }
+        }
+        let mut history = vec![ChatMessage {
+            role: ChatRole::System,
+            content: self.system_prompt.clone(),
+        }];
+        if let Some(budget) = self.profile.agent.context_budget {
+            let omitted = trim_to_context_budget(&mut messages, budget);
+            if omitted > 0 {
+                history.push(ChatMessage {
+                    role: ChatRole::System,
+                    content: format!("[earlier context omitted: {omitted} messages]"),
+                });
             }
         }
-        Ok(messages)
+        history.extend(messages);
+        Ok(history)
     }
 
     fn ensure_runtime_metadata(
@@ -299,6 +442,9 @@ impl AgentRunner {
         self.session_store.append_event(&event)
     }
 
+    /// Runs one tool call and records its result. A failing tool becomes the
+    /// call's result so the next turn lets the model react to it; only calls
+    /// that need the user's approval and session write failures end the run.
     fn handle_tool_call(
         &self,
         session_id: &str,
@@ -306,9 +452,21 @@ impl AgentRunner {
         options: &AgentRunOptions,
     ) -> Result<()> {
         if !self.profile.tools.enabled {
-            return Err(MosaicError::Tool(
-                "tools are disabled in the current profile".to_string(),
+            let err = MosaicError::Tool("tools are disabled in the current profile".to_string());
+            return self.record_tool_error(session_id, &tool_call.name, &err, options);
+        }
+        if !self.profile.tools.is_tool_allowed(&tool_call.name) {
+            let err = MosaicError::Tool(format!(
+                "tool '{}' is not allowed for this agent (allowed: {})",
+                tool_call.name,
+                self.profile
+                    .tools
+                    .allowed
+                    .as_deref()
+                    .unwrap_or_default()
+                    .join(", ")
             ));
+            return self.record_tool_error(session_id, &tool_call.name, &err, options);
         }
         let tool_name = tool_call.name;
         let tool_args = tool_call.args;
@@ -332,7 +490,14 @@ impl AgentRunner {
             yes: options.yes,
             interactive: options.interactive,
         };
-        let result = self.tools.execute(&tool_name, tool_args, &tool_context)?;
+        let result = match self.tools.execute(&tool_name, tool_args, &tool_context) {
+            Ok(result) => result,
+            Err(err @ MosaicError::ApprovalRequired(_)) => {
+                self.record_tool_error(session_id, &tool_name, &err, options)?;
+                return Err(err);
+            }
+            Err(err) => return self.record_tool_error(session_id, &tool_name, &err, options),
+        };
 
         if tool_name == "run_cmd" {
             let parsed: RunCommandOutput = serde_json::from_value(result.clone())?;
@@ -345,15 +510,24 @@ impl AgentRunner {
                 approved_by: parsed.approved_by,
                 exit_code: parsed.exit_code,
                 duration_ms: parsed.duration_ms,
+                label: options.label.clone(),
+                env_keys: parsed.env_keys,
             })?;
         }
 
-        let result_payload = result.clone();
-        let tool_result_event = SessionStore::build_event(
-            session_id,
-            EventKind::ToolResult,
-            json!({ "name": tool_name, "result": result }),
-        );
+        let mut payload = json!({ "name": tool_name, "result": result });
+        if let Some(max_chars) = self.profile.tools.max_tool_result_chars
+            && let Some((text, truncated_bytes)) = truncate_tool_result(&result, max_chars)
+        {
+            payload["result"] = Value::String(text);
+            payload["truncated_bytes"] = json!(truncated_bytes);
+            if self.profile.tools.keep_full_tool_results {
+                payload["full_result"] = result;
+            }
+        }
+        let result_payload = payload["result"].clone();
+        let tool_result_event =
+            SessionStore::build_event(session_id, EventKind::ToolResult, payload);
         self.session_store.append_event(&tool_result_event)?;
         self.emit_event(
             options,
@@ -366,6 +540,33 @@ impl AgentRunner {
         Ok(())
     }
 
+    /// Records a failed tool call in the session log, where it is replayed to
+    /// the model as the call's result, and reports it as the tool result.
+    fn record_tool_error(
+        &self,
+        session_id: &str,
+        tool_name: &str,
+        err: &MosaicError,
+        options: &AgentRunOptions,
+    ) -> Result<()> {
+        let message = err.to_string();
+        let event = SessionStore::build_event(
+            session_id,
+            EventKind::Error,
+            json!({ "name": tool_name, "message": message }),
+        );
+        self.session_store.append_event(&event)?;
+        self.emit_event(
+            options,
+            AgentEvent::ToolResult {
+                session_id: session_id.to_string(),
+                name: tool_name.to_string(),
+                result: json!({ "error": message }),
+            },
+        );
+        Ok(())
+    }
+
     fn emit_event(&self, options: &AgentRunOptions, event: AgentEvent) {
         if let Some(callback) = &options.event_callback {
             callback(event);
@@ -378,14 +579,61 @@ You are Mosaic CLI agent.
 When you need a local tool, respond with EXACT JSON only:
 {"tool_call":{"name":"read_file","args":{"path":"README.md"}}}
 Tool argument schemas:
-- read_file: {"path":"relative/or/absolute/path"}
+- read_file: {"path":"relative/or/absolute/path","start_line":1,"end_line":200}
 - write_file: {"path":"relative/or/absolute/path","content":"full file contents"}
-- search_text: {"query":"text or regex","path":"optional/path","max_results":50}
-- run_cmd: {"command":"shell command to execute"}
-Prefer read_file and search_text for repository inspection. Use run_cmd only when file tools are insufficient.
+- search_text: {"query":"text or regex","path":"optional/path","max_results":50,"literal":false,"ignore_case":false}
+- apply_patch: {"patch":"unified diff with ---/+++ headers and @@ hunks"}
+- glob: {"pattern":"src/**/*.rs","max_results":200}
+- list_files: {"path":"optional/dir","recursive":false,"max_entries":200}
+- delete_file: {"path":"relative/or/absolute/path","recursive":false}
+- run_cmd: {"command":"shell command to execute","timeout_ms":60000,"max_output_bytes":1000000,"env":{"KEY":"value"}}
+Prefer read_file, search_text and list_files for repository inspection. Use run_cmd only when file tools are insufficient.
 If no tool is needed, answer directly with plain text.
 "#;
 
+/// Forwards one turn of streamed reply text. A reply that opens like a text
+/// protocol tool call (`{` or a code fence) is held back until the turn ends
+/// and dropped if it was one.
+struct ReplyStream<'s, 'a> {
+    sink: &'s mut ChatChunkSink<'a>,
+    held: String,
+    holding: Option<bool>,
+}
+
+impl<'s, 'a> ReplyStream<'s, 'a> {
+    fn new(sink: &'s mut ChatChunkSink<'a>) -> Self {
+        Self {
+            sink,
+            held: String::new(),
+            holding: None,
+        }
+    }
+
+    fn push(&mut self, chunk: &str) {
+        match self.holding {
+            Some(false) => (self.sink)(chunk),
+            Some(true) => self.held.push_str(chunk),
+            None => {
+                self.held.push_str(chunk);
+                let Some(first) = self.held.trim_start().chars().next() else {
+                    return;
+                };
+                let holding = first == '{' || first == '`';
+                self.holding = Some(holding);
+                if !holding {
+                    (self.sink)(&std::mem::take(&mut self.held));
+                }
+            }
+        }
+    }
+
+    fn finish(self, tool_call: bool) {
+        if !tool_call && !self.held.is_empty() {
+            (self.sink)(&self.held);
+        }
+    }
+}
+
 pub fn default_system_prompt() -> &'static str {
     SYSTEM_PROMPT
 }
@@ -407,6 +655,22 @@ struct ToolCallBody {
     args: Value,
 }
 
+/// Prefers structured `tool_calls` from the provider and falls back to the
+/// JSON envelope in the message text for providers without native calling.
+fn parsed_tool_calls(response: &ChatResponse) -> Vec<ParsedToolCall> {
+    if !response.tool_calls.is_empty() {
+        return response
+            .tool_calls
+            .iter()
+            .map(|call| ParsedToolCall {
+                name: call.name.clone(),
+                args: call.arguments.clone(),
+            })
+            .collect();
+    }
+    parse_tool_call(response).into_iter().collect()
+}
+
 fn parse_tool_call(response: &ChatResponse) -> Option<ParsedToolCall> {
     let content = strip_markdown_json_fence(response.content.trim());
     let envelope = serde_json::from_str::<ToolCallEnvelope>(content).ok()?;
@@ -434,6 +698,38 @@ fn strip_markdown_json_fence(input: &str) -> &str {
     input
 }
 
+/// Drops the oldest messages until `messages` fits `budget`, always keeping
+/// the newest one. Returns how many were dropped.
+fn trim_to_context_budget(messages: &mut Vec<ChatMessage>, budget: ContextBudget) -> usize {
+    let mut kept = 0usize;
+    let mut chars = 0usize;
+    for message in messages.iter().rev() {
+        let len = message.content.chars().count();
+        let over_messages = budget.max_messages.is_some_and(|max| kept >= max);
+        let over_chars = budget.max_chars.is_some_and(|max| chars + len > max);
+        if kept > 0 && (over_messages || over_chars) {
+            break;
+        }
+        kept += 1;
+        chars += len;
+    }
+    let omitted = messages.len() - kept;
+    messages.drain(..omitted);
+    omitted
+}
+
+/// Serializes `result` and keeps its first `max_chars` characters, appending a
+/// marker with the number of bytes dropped. Returns `None` when it already fits.
+fn truncate_tool_result(result: &Value, max_chars: usize) -> Option<(String, usize)> {
+    let text = result.to_string();
+    let (cut, _) = text.char_indices().nth(max_chars)?;
+    let truncated_bytes = text.len() - cut;
+    Some((
+        format!("{}\n[truncated {truncated_bytes} bytes]", &text[..cut]),
+        truncated_bytes,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::VecDeque;
@@ -443,13 +739,16 @@ mod tests {
 
     use super::*;
     use mosaic_core::config::{ProfileConfig, RunGuardMode};
-    use mosaic_core::provider::{ModelInfo, ProviderHealth};
+    use mosaic_core::provider::{ModelInfo, NativeToolCall, ProviderHealth};
 
     #[test]
     fn parse_tool_call_from_plain_json() {
         let response = ChatResponse {
             content: r#"{"tool_call":{"name":"read_file","args":{"path":"README.md"}}}"#
                 .to_string(),
+            usage: None,
+            tool_calls: Vec::new(),
+            model: None,
         };
         let parsed = parse_tool_call(&response).expect("should parse tool call");
         assert_eq!(parsed.name, "read_file");
@@ -461,19 +760,42 @@ mod tests {
         let response = ChatResponse {
             content: "```json\n{\"tool_call\":{\"name\":\"run_cmd\",\"args\":{\"command\":\"pwd\"}}}\n```"
                 .to_string(),
+            usage: None,
+            tool_calls: Vec::new(),
+            model: None,
         };
         let parsed = parse_tool_call(&response).expect("should parse tool call");
         assert_eq!(parsed.name, "run_cmd");
     }
 
     struct MockProvider {
-        responses: Mutex<VecDeque<String>>,
+        responses: Mutex<VecDeque<ChatResponse>>,
+        requests: Mutex<Vec<ChatRequest>>,
     }
 
     impl MockProvider {
         fn new(responses: Vec<String>) -> Self {
+            Self::with_responses(
+                responses
+                    .into_iter()
+                    .map(|content| ChatResponse {
+                        content,
+                        usage: Some(TokenUsage {
+                            prompt_tokens: 10,
+                            completion_tokens: 2,
+                            total_tokens: 12,
+                        }),
+                        tool_calls: Vec::new(),
+                        model: None,
+                    })
+                    .collect(),
+            )
+        }
+
+        fn with_responses(responses: Vec<ChatResponse>) -> Self {
             Self {
                 responses: Mutex::new(VecDeque::from(responses)),
+                requests: Mutex::new(Vec::new()),
             }
         }
     }
@@ -484,17 +806,26 @@ mod tests {
             Ok(vec![ModelInfo {
                 id: "mock-model".to_string(),
                 owned_by: Some("mock".to_string()),
+                context_window: None,
+                capabilities: Vec::new(),
+                warnings: Vec::new(),
             }])
         }
 
-        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+            self.requests.lock().expect("lock").push(request);
             let next = self
                 .responses
                 .lock()
                 .expect("lock")
                 .pop_front()
-                .unwrap_or_else(|| "done".to_string());
-            Ok(ChatResponse { content: next })
+                .unwrap_or_else(|| ChatResponse {
+                    content: "done".to_string(),
+                    usage: None,
+                    tool_calls: Vec::new(),
+                    model: None,
+                });
+            Ok(next)
         }
 
         async fn health(&self) -> Result<ProviderHealth> {
@@ -523,6 +854,369 @@ mod tests {
         AgentRunner::new(provider, profile, store, audit, tools)
     }
 
+    fn run_options(temp: &tempfile::TempDir) -> AgentRunOptions {
+        AgentRunOptions {
+            session_id: None,
+            session_metadata: SessionRuntimeMetadata {
+                agent_id: Some("reader".to_string()),
+                profile_name: "default".to_string(),
+            },
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+            event_callback: None,
+            label: None,
+        }
+    }
+
+    fn build_reader_runner(provider: Arc<dyn Provider>, temp: &tempfile::TempDir) -> AgentRunner {
+        let mut profile = ProfileConfig::default();
+        profile.provider.model = "mock-model".to_string();
+        profile.tools.allowed = Some(vec!["read_file".to_string(), "search_text".to_string()]);
+        let store = SessionStore::new(temp.path().join("sessions"));
+        let audit = AuditStore::new(
+            temp.path().join("audit"),
+            temp.path().join("audit/commands.jsonl"),
+        );
+        let tools = ToolExecutor::new(profile.tools.run.guard_mode.clone(), None);
+        AgentRunner::new(provider, profile, store, audit, tools)
+    }
+
+    #[tokio::test]
+    async fn run_cost_is_estimated_from_usage_and_model_pricing() {
+        let temp = tempdir().expect("tempdir");
+        let usage = TokenUsage {
+            prompt_tokens: 1200,
+            completion_tokens: 300,
+            total_tokens: 1500,
+        };
+        let responses = || {
+            Arc::new(MockProvider::with_responses(vec![ChatResponse {
+                content: "priced".to_string(),
+                usage: Some(usage),
+                tool_calls: Vec::new(),
+                model: None,
+            }]))
+        };
+        let pricing = ModelPricing {
+            prompt_per_1k: 0.5,
+            completion_per_1k: 1.5,
+        };
+
+        let runner = build_runner(responses(), &temp, RunGuardMode::Unrestricted)
+            .with_pricing(BTreeMap::from([("mock-model".to_string(), pricing)]));
+        let result = runner.ask("hi", run_options(&temp)).await.expect("ask");
+        let cost = result.estimated_cost_usd.expect("known model has a cost");
+        assert!((cost - 1.05).abs() < 1e-9, "unexpected cost {cost}");
+
+        let runner = build_runner(responses(), &temp, RunGuardMode::Unrestricted)
+            .with_pricing(BTreeMap::from([("other-model".to_string(), pricing)]));
+        let result = runner.ask("hi", run_options(&temp)).await.expect("ask");
+        assert_eq!(result.estimated_cost_usd, None);
+
+        let fallback = Arc::new(MockProvider::with_responses(vec![ChatResponse {
+            content: "priced by fallback".to_string(),
+            usage: Some(usage),
+            tool_calls: Vec::new(),
+            model: Some("fallback-model".to_string()),
+        }]));
+        let runner = build_runner(fallback, &temp, RunGuardMode::Unrestricted).with_pricing(
+            BTreeMap::from([
+                ("mock-model".to_string(), pricing),
+                (
+                    "fallback-model".to_string(),
+                    ModelPricing {
+                        prompt_per_1k: 0.1,
+                        completion_per_1k: 0.2,
+                    },
+                ),
+            ]),
+        );
+        let result = runner.ask("hi", run_options(&temp)).await.expect("ask");
+        let cost = result
+            .estimated_cost_usd
+            .expect("fallback model has a cost");
+        assert!((cost - 0.18).abs() < 1e-9, "unexpected cost {cost}");
+    }
+
+    #[tokio::test]
+    async fn native_tool_calls_are_executed_and_tools_are_offered() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("notes.txt"), "native notes").expect("write notes");
+        let provider = Arc::new(MockProvider::with_responses(vec![
+            ChatResponse {
+                content: String::new(),
+                usage: None,
+                tool_calls: vec![NativeToolCall {
+                    id: Some("call_1".to_string()),
+                    name: "read_file".to_string(),
+                    arguments: json!({ "path": "notes.txt" }),
+                }],
+                model: None,
+            },
+            ChatResponse {
+                content: "saw native notes".to_string(),
+                usage: None,
+                tool_calls: Vec::new(),
+                model: None,
+            },
+        ]));
+        let runner = build_reader_runner(provider.clone(), &temp);
+        let result = runner
+            .ask("read the notes", run_options(&temp))
+            .await
+            .expect("native tool call should run");
+        assert_eq!(result.response, "saw native notes");
+        assert_eq!(result.turns, 2);
+
+        let requests = provider.requests.lock().expect("lock");
+        let offered = requests[0]
+            .tools
+            .iter()
+            .map(|spec| spec.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(offered, vec!["read_file", "search_text"]);
+        let follow_up = &requests[1].messages;
+        assert!(
+            follow_up
+                .iter()
+                .any(|message| message.content.contains("native notes"))
+        );
+    }
+
+    #[test]
+    fn context_budget_drops_oldest_messages_and_marks_omission() {
+        let temp = tempdir().expect("tempdir");
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(Vec::new()));
+        let mut runner = build_runner(provider, &temp, RunGuardMode::Unrestricted);
+        runner.profile.agent.context_budget = Some(ContextBudget {
+            max_messages: Some(4),
+            max_chars: None,
+        });
+        let store = runner.session_store().clone();
+        store.ensure_dirs().expect("dirs");
+        let session_id = store.create_session_id();
+        for index in 0..10 {
+            for (kind, text) in [
+                (EventKind::User, format!("question {index}")),
+                (EventKind::Assistant, format!("answer {index}")),
+            ] {
+                let event = SessionStore::build_event(&session_id, kind, json!({ "text": text }));
+                store.append_event(&event).expect("append");
+            }
+        }
+
+        let messages = runner
+            .build_messages_for_session(&session_id)
+            .expect("messages");
+        let contents = messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(contents[0], runner.system_prompt);
+        assert_eq!(
+            &contents[1..],
+            [
+                "[earlier context omitted: 16 messages]",
+                "question 8",
+                "answer 8",
+                "question 9",
+                "answer 9",
+            ]
+        );
+        assert!(matches!(messages[1].role, ChatRole::System));
+
+        runner.profile.agent.context_budget = Some(ContextBudget {
+            max_messages: None,
+            max_chars: Some(20),
+        });
+        let messages = runner
+            .build_messages_for_session(&session_id)
+            .expect("messages");
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[2].content, "question 9");
+        assert_eq!(messages[3].content, "answer 9");
+    }
+
+    #[tokio::test]
+    async fn large_tool_results_are_truncated_in_replayed_messages() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("big.txt"), "x".repeat(5_000)).expect("write big");
+        let provider = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"read_file","args":{"path":"big.txt"}}}"#.to_string(),
+            "summarized".to_string(),
+        ]));
+        let mut runner = build_reader_runner(provider.clone(), &temp);
+        runner.profile.tools.max_tool_result_chars = Some(200);
+        runner.profile.tools.keep_full_tool_results = true;
+        let result = runner
+            .ask("read the big file", run_options(&temp))
+            .await
+            .expect("ask");
+        assert_eq!(result.response, "summarized");
+
+        let requests = provider.requests.lock().expect("lock");
+        let tool_message = requests[1]
+            .messages
+            .iter()
+            .find(|message| message.content.starts_with("TOOL_RESULT read_file"))
+            .expect("tool result message");
+        assert!(tool_message.content.contains("[truncated "));
+        assert!(tool_message.content.len() < 400);
+        assert!(!tool_message.content.contains(&"x".repeat(500)));
+
+        let events = runner
+            .session_store()
+            .read_events(&result.session_id)
+            .expect("events");
+        let stored = events
+            .iter()
+            .find(|event| matches!(event.kind, EventKind::ToolResult))
+            .expect("tool result event");
+        assert!(stored.payload["truncated_bytes"].as_u64().expect("bytes") > 4_800);
+        assert!(
+            stored.payload["full_result"]
+                .to_string()
+                .contains(&"x".repeat(5_000))
+        );
+    }
+
+    #[tokio::test]
+    async fn ask_streaming_forwards_only_the_final_reply() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("notes.txt"), "hello").expect("write notes");
+        let provider = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"read_file","args":{"path":"notes.txt"}}}"#.to_string(),
+            "the notes say hello".to_string(),
+        ]));
+        let runner = build_reader_runner(provider, &temp);
+        let mut chunks = Vec::new();
+        let result = runner
+            .ask_streaming("read the notes", run_options(&temp), &mut |chunk| {
+                chunks.push(chunk.to_string())
+            })
+            .await
+            .expect("streamed run");
+
+        assert_eq!(result.turns, 2);
+        assert_eq!(chunks.concat(), "the notes say hello");
+        assert_eq!(result.response, "the notes say hello");
+    }
+
+    #[test]
+    fn reply_stream_holds_back_fenced_replies_until_the_turn_ends() {
+        let mut seen = Vec::new();
+        let mut sink = |chunk: &str| seen.push(chunk.to_string());
+        let mut reply = ReplyStream::new(&mut sink);
+        reply.push("  ");
+        reply.push("```json\n{}");
+        reply.push("\n```");
+        reply.finish(false);
+        let mut reply = ReplyStream::new(&mut sink);
+        reply.push("{\"tool_call\"");
+        reply.finish(true);
+        let mut reply = ReplyStream::new(&mut sink);
+        reply.push("Hel");
+        reply.push("lo");
+        reply.finish(false);
+
+        assert_eq!(seen, vec!["  ```json\n{}\n```", "Hel", "lo"]);
+    }
+
+    #[tokio::test]
+    async fn failed_tool_calls_are_recorded_and_replayed() {
+        let temp = tempdir().expect("tempdir");
+        let provider = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"read_file","args":{"path":"missing.txt"}}}"#.to_string(),
+            "recovered".to_string(),
+        ]));
+        let runner = build_reader_runner(provider.clone(), &temp);
+        let result = runner
+            .ask("read the missing file", run_options(&temp))
+            .await
+            .expect("tool errors are fed back to the model");
+        assert_eq!(result.response, "recovered");
+        assert_eq!(result.turns, 2);
+
+        let session_id = runner
+            .session_store()
+            .latest_session_id()
+            .expect("latest session")
+            .expect("session should exist");
+        let events = runner
+            .session_store()
+            .read_events(&session_id)
+            .expect("events");
+        let error = events
+            .iter()
+            .find(|event| matches!(event.kind, EventKind::Error))
+            .expect("error event");
+        assert_eq!(error.payload["name"], "read_file");
+        assert!(
+            error.payload["message"]
+                .as_str()
+                .expect("message")
+                .contains("missing.txt")
+        );
+
+        let requests = provider.requests.lock().expect("lock");
+        assert!(
+            requests[1]
+                .messages
+                .iter()
+                .any(|message| message.content.starts_with("TOOL_ERROR read_file\n"))
+        );
+    }
+
+    #[tokio::test]
+    async fn allowed_tools_block_run_cmd_before_execution() {
+        let temp = tempdir().expect("tempdir");
+        let provider = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"run_cmd","args":{"command":"touch blocked.txt"}}}"#
+                .to_string(),
+        ]));
+        let runner = build_reader_runner(provider.clone(), &temp);
+        let result = runner
+            .ask("create a file", run_options(&temp))
+            .await
+            .expect("blocked tool is reported to the model");
+        assert_eq!(result.turns, 2);
+        let requests = provider.requests.lock().expect("lock");
+        let tool_error = requests[1]
+            .messages
+            .iter()
+            .find(|message| message.content.starts_with("TOOL_ERROR run_cmd\n"))
+            .expect("tool error message");
+        assert!(tool_error.content.contains("tool 'run_cmd' is not allowed"));
+        assert!(!temp.path().join("blocked.txt").exists());
+        assert!(!temp.path().join("audit/commands.jsonl").exists());
+    }
+
+    #[tokio::test]
+    async fn allowed_tools_permit_listed_read_file() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(temp.path().join("notes.txt"), "reader notes").expect("write notes");
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"read_file","args":{"path":"notes.txt"}}}"#.to_string(),
+            "read it".to_string(),
+        ]));
+        let runner = build_reader_runner(provider, &temp);
+        let result = runner
+            .ask("read the notes", run_options(&temp))
+            .await
+            .expect("read_file should be allowed");
+        assert_eq!(result.response, "read it");
+        assert_eq!(result.turns, 2);
+        assert_eq!(
+            result.usage,
+            Some(TokenUsage {
+                prompt_tokens: 20,
+                completion_tokens: 4,
+                total_tokens: 24,
+            })
+        );
+    }
+
     #[tokio::test]
     async fn run_cmd_requires_confirmation_without_yes() {
         let temp = tempdir().expect("tempdir");
@@ -544,6 +1238,7 @@ mod tests {
                     yes: false,
                     interactive: false,
                     event_callback: None,
+                    label: None,
                 },
             )
             .await
@@ -576,6 +1271,7 @@ mod tests {
                     yes: true,
                     interactive: false,
                     event_callback: None,
+                    label: None,
                 },
             )
             .await
@@ -608,6 +1304,7 @@ mod tests {
                     yes: true,
                     interactive: false,
                     event_callback: None,
+                    label: None,
                 },
             )
             .await
@@ -615,4 +1312,38 @@ mod tests {
         assert_eq!(result.response, "done");
         assert!(temp.path().join("alias.txt").exists());
     }
+
+    #[tokio::test]
+    async fn run_cmd_audit_records_run_label() {
+        let temp = tempdir().expect("tempdir");
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::new(vec![
+            r#"{"tool_call":{"name":"run_cmd","args":{"command":"echo labeled"}}}"#.to_string(),
+            "done".to_string(),
+        ]));
+        let runner = build_runner(provider, &temp, RunGuardMode::ConfirmDangerous);
+        runner
+            .ask(
+                "run a labeled command",
+                AgentRunOptions {
+                    session_id: None,
+                    session_metadata: SessionRuntimeMetadata {
+                        agent_id: None,
+                        profile_name: "default".to_string(),
+                    },
+                    cwd: temp.path().to_path_buf(),
+                    yes: true,
+                    interactive: false,
+                    event_callback: None,
+                    label: Some("TICKET-123".to_string()),
+                },
+            )
+            .await
+            .expect("ask should pass");
+        let audit = std::fs::read_to_string(temp.path().join("audit/commands.jsonl"))
+            .expect("audit file should exist");
+        let record: CommandAudit =
+            serde_json::from_str(audit.lines().next().expect("audit line")).expect("audit json");
+        assert_eq!(record.command, "echo labeled");
+        assert_eq!(record.label.as_deref(), Some("TICKET-123"));
+    }
 }
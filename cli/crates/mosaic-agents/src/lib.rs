@@ -1,15 +1,20 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 use mosaic_core::config::{ConfigFile, ProfileConfig, RunGuardMode};
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::write_pretty_state_json_file;
+use mosaic_core::state::lock_state_file;
 
-const CURRENT_AGENTS_VERSION: u32 = 1;
+const CURRENT_AGENTS_VERSION: u32 = 2;
+/// v1 files predate `last_used_at`; they load as-is and are rewritten as v2 on save.
+const LEGACY_AGENTS_VERSION: u32 = 1;
+pub const AGENTS_BUNDLE_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentDefinition {
@@ -23,8 +28,14 @@ pub struct AgentDefinition {
     pub max_turns: Option<u32>,
     pub tools_enabled: Option<bool>,
     pub guard_mode: Option<RunGuardMode>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +49,8 @@ pub struct AddAgentInput {
     pub max_turns: Option<u32>,
     pub tools_enabled: Option<bool>,
     pub guard_mode: Option<RunGuardMode>,
+    pub system_prompt: Option<String>,
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -56,6 +69,10 @@ pub struct UpdateAgentInput {
     pub clear_tools_enabled: bool,
     pub guard_mode: Option<RunGuardMode>,
     pub clear_guard_mode: bool,
+    pub system_prompt: Option<String>,
+    pub clear_system_prompt: bool,
+    pub allowed_tools: Option<Vec<String>>,
+    pub clear_allowed_tools: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -64,6 +81,41 @@ pub struct AgentRoutes {
     pub routes: BTreeMap<String, String>,
 }
 
+impl AgentRoutes {
+    /// Returns the matching route key and agent id: an exact key first, then the
+    /// most specific `*` pattern.
+    pub fn match_route(&self, route_key: &str) -> Option<(&str, &String)> {
+        if let Some((key, agent_id)) = self.routes.get_key_value(route_key) {
+            return Some((key.as_str(), agent_id));
+        }
+        self.routes
+            .iter()
+            .filter(|(pattern, _)| pattern.contains('*') && wildcard_match(pattern, route_key))
+            // `max_by_key` keeps the last maximum; reversing lets the first key win ties.
+            .rev()
+            .max_by_key(|(pattern, _)| route_specificity(pattern))
+            .map(|(pattern, agent_id)| (pattern.as_str(), agent_id))
+    }
+}
+
+/// Portable snapshot of agents and routes for `agents export/import`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentsBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub agents: Vec<AgentDefinition>,
+    pub routes: AgentRoutes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+    pub routes_imported: usize,
+    pub replace: bool,
+    pub integrity: AgentIntegrityReport,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct AgentsFile {
     version: u32,
@@ -128,6 +180,7 @@ impl AgentStore {
             input.max_turns,
         )?;
 
+        let _lock = lock_state_file(&self.agents_path)?;
         let mut file = self.load_agents()?;
         let id = match input.id {
             Some(value) => normalize_agent_id(&value)?,
@@ -150,8 +203,60 @@ impl AgentStore {
             max_turns: input.max_turns,
             tools_enabled: input.tools_enabled,
             guard_mode: input.guard_mode,
+            system_prompt: normalize_system_prompt(input.system_prompt)?,
+            allowed_tools: normalize_allowed_tools(input.allowed_tools)?,
             created_at: now,
             updated_at: now,
+            last_used_at: None,
+        };
+        file.agents.push(agent.clone());
+        self.save_agents(&file)?;
+        Ok(agent)
+    }
+
+    /// Copies every override from `source_id` into a new agent. Routes stay with the
+    /// source since a route key can only point at one agent.
+    pub fn clone_agent(
+        &self,
+        source_id: &str,
+        new_id: &str,
+        new_name: Option<&str>,
+    ) -> Result<AgentDefinition> {
+        let source_id = normalize_agent_id(source_id)?;
+        let new_id = normalize_agent_id(new_id)?;
+        let _lock = lock_state_file(&self.agents_path)?;
+        let mut file = self.load_agents()?;
+        let source = file
+            .agents
+            .iter()
+            .find(|agent| agent.id == source_id)
+            .cloned()
+            .ok_or_else(|| MosaicError::Validation(format!("agent '{source_id}' not found")))?;
+        if file.agents.iter().any(|agent| agent.id == new_id) {
+            return Err(MosaicError::Validation(format!(
+                "agent '{new_id}' already exists"
+            )));
+        }
+        let name = new_name
+            .map(|value| value.trim().to_string())
+            .unwrap_or_else(|| source.name.clone());
+        validate_agent_fields(
+            Some(&new_id),
+            &name,
+            &source.profile,
+            &source.skills,
+            source.temperature,
+            source.max_turns,
+        )?;
+
+        let now = Utc::now();
+        let agent = AgentDefinition {
+            id: new_id,
+            name,
+            created_at: now,
+            updated_at: now,
+            last_used_at: None,
+            ..source
         };
         file.agents.push(agent.clone());
         self.save_agents(&file)?;
@@ -160,6 +265,7 @@ impl AgentStore {
 
     pub fn remove(&self, agent_id: &str) -> Result<bool> {
         let agent_id = normalize_agent_id(agent_id)?;
+        let _agents_lock = lock_state_file(&self.agents_path)?;
         let mut file = self.load_agents()?;
         let before = file.agents.len();
         file.agents.retain(|agent| agent.id != agent_id);
@@ -168,6 +274,7 @@ impl AgentStore {
         }
         self.save_agents(&file)?;
 
+        let _routes_lock = lock_state_file(&self.routes_path)?;
         let mut routes = self.load_routes()?;
         if routes.default_agent_id.as_deref() == Some(agent_id.as_str()) {
             routes.default_agent_id = None;
@@ -181,6 +288,7 @@ impl AgentStore {
         let agent_id = normalize_agent_id(agent_id)?;
         validate_agent_update_input(&input)?;
 
+        let _lock = lock_state_file(&self.agents_path)?;
         let mut file = self.load_agents()?;
         let agent = file
             .agents
@@ -230,12 +338,51 @@ impl AgentStore {
             agent.guard_mode = Some(guard_mode);
         }
 
+        if input.clear_system_prompt {
+            agent.system_prompt = None;
+        } else if input.system_prompt.is_some() {
+            agent.system_prompt = normalize_system_prompt(input.system_prompt)?;
+        }
+
+        if input.clear_allowed_tools {
+            agent.allowed_tools = None;
+        } else if input.allowed_tools.is_some() {
+            agent.allowed_tools = normalize_allowed_tools(input.allowed_tools)?;
+        }
+
+        validate_agent_fields(
+            Some(&agent.id),
+            &agent.name,
+            &agent.profile,
+            &agent.skills,
+            agent.temperature,
+            agent.max_turns,
+        )?;
+        apply_overrides(ProfileConfig::default(), agent)?;
+
         agent.updated_at = Utc::now();
         let updated = agent.clone();
         self.save_agents(&file)?;
         Ok(updated)
     }
 
+    /// Records that `agent_id` was resolved for a run. Leaves `updated_at` alone
+    /// since the definition itself did not change.
+    pub fn touch(&self, agent_id: &str) -> Result<AgentDefinition> {
+        let agent_id = normalize_agent_id(agent_id)?;
+        let _lock = lock_state_file(&self.agents_path)?;
+        let mut file = self.load_agents()?;
+        let agent = file
+            .agents
+            .iter_mut()
+            .find(|agent| agent.id == agent_id)
+            .ok_or_else(|| MosaicError::Validation(format!("agent '{agent_id}' not found")))?;
+        agent.last_used_at = Some(Utc::now());
+        let touched = agent.clone();
+        self.save_agents(&file)?;
+        Ok(touched)
+    }
+
     pub fn load_routes(&self) -> Result<AgentRoutes> {
         if !self.routes_path.exists() {
             return Ok(AgentRoutes::default());
@@ -256,6 +403,7 @@ impl AgentStore {
                 "agent '{agent_id}' not found"
             )));
         }
+        let _lock = lock_state_file(&self.routes_path)?;
         let mut routes = self.load_routes()?;
         routes.default_agent_id = Some(agent_id);
         self.save_routes(&routes)?;
@@ -270,6 +418,7 @@ impl AgentStore {
                 "agent '{agent_id}' not found"
             )));
         }
+        let _lock = lock_state_file(&self.routes_path)?;
         let mut routes = self.load_routes()?;
         routes.routes.insert(route_key, agent_id);
         self.save_routes(&routes)?;
@@ -278,12 +427,45 @@ impl AgentStore {
 
     pub fn remove_route(&self, route_key: &str) -> Result<(AgentRoutes, bool)> {
         let route_key = normalize_route_key(route_key)?;
+        let _lock = lock_state_file(&self.routes_path)?;
         let mut routes = self.load_routes()?;
         let removed = routes.routes.remove(&route_key).is_some();
         self.save_routes(&routes)?;
         Ok((routes, removed))
     }
 
+    /// Drops routes and the default pointing at agents that no longer exist.
+    /// Returns the routes before and after pruning, or `None` when nothing changed.
+    pub fn prune_dangling_routes(&self) -> Result<Option<(AgentRoutes, AgentRoutes)>> {
+        let agent_ids = self
+            .list()?
+            .into_iter()
+            .map(|agent| agent.id)
+            .collect::<BTreeSet<_>>();
+        let _lock = lock_state_file(&self.routes_path)?;
+        let before = self.load_routes()?;
+        let mut after = before.clone();
+        after
+            .routes
+            .retain(|_, agent_id| agent_ids.contains(agent_id));
+        if after
+            .default_agent_id
+            .as_ref()
+            .is_some_and(|agent_id| !agent_ids.contains(agent_id))
+        {
+            after.default_agent_id = None;
+        }
+        if after.routes.len() == before.routes.len()
+            && after.default_agent_id == before.default_agent_id
+        {
+            return Ok(None);
+        }
+        self.save_routes(&after)?;
+        Ok(Some((before, after)))
+    }
+
+    /// Picks the agent for a run. Precedence: explicit agent > exact route >
+    /// wildcard route (most literal characters wins) > default agent.
     pub fn resolve_for_runtime(
         &self,
         explicit_agent_id: Option<&str>,
@@ -302,10 +484,10 @@ impl AgentStore {
         let routes = self.load_routes()?;
         if let Some(route_key) = route_hint {
             let route_key = normalize_route_key(route_key)?;
-            if let Some(agent_id) = routes.routes.get(&route_key) {
+            if let Some((matched_key, agent_id)) = routes.match_route(&route_key) {
                 if self.get(agent_id)?.is_none() {
                     return Err(MosaicError::Validation(format!(
-                        "route '{route_key}' points to missing agent '{agent_id}'"
+                        "route '{matched_key}' points to missing agent '{agent_id}'"
                     )));
                 }
                 return Ok(Some(agent_id.clone()));
@@ -339,6 +521,7 @@ impl AgentStore {
                 profile_name: resolved.profile_name,
                 profile: resolved.profile,
                 agent_skills: Vec::new(),
+                system_prompt: None,
             });
         };
 
@@ -355,48 +538,144 @@ impl AgentStore {
             profile_name: base.profile_name,
             profile: merged,
             agent_skills: agent.skills,
+            system_prompt: agent.system_prompt,
         })
     }
 
-    pub fn check_integrity(&self) -> Result<AgentIntegrityReport> {
-        let agents = self.list()?;
-        let routes = self.load_routes()?;
-        let mut issues = Vec::new();
+    pub fn export(&self) -> Result<AgentsBundle> {
+        Ok(AgentsBundle {
+            version: AGENTS_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            agents: self.list()?,
+            routes: self.load_routes()?,
+        })
+    }
 
-        for agent in &agents {
-            if agent.name.trim().is_empty() {
-                issues.push(format!("agent '{}' has empty name", agent.id));
-            }
-            if agent.profile.trim().is_empty() {
-                issues.push(format!("agent '{}' has empty profile", agent.id));
+    /// Merges a bundle into the store, keeping local agents and routes on conflict,
+    /// or swaps the store for the bundle when `replace` is set. Nothing is written
+    /// unless the resulting state passes the integrity check.
+    pub fn import(&self, value: Value, replace: bool) -> Result<AgentImportSummary> {
+        let version = value.get("version").and_then(Value::as_u64);
+        if version != Some(u64::from(AGENTS_BUNDLE_VERSION)) {
+            return Err(MosaicError::Validation(format!(
+                "unsupported agents bundle version {} expected {}",
+                version.map_or_else(|| "<missing>".to_string(), |value| value.to_string()),
+                AGENTS_BUNDLE_VERSION
+            )));
+        }
+        let bundle = serde_json::from_value::<AgentsBundle>(value)
+            .map_err(|err| MosaicError::Validation(format!("invalid agents bundle: {err}")))?;
+
+        let mut incoming = Vec::with_capacity(bundle.agents.len());
+        for mut agent in bundle.agents {
+            agent.id = normalize_agent_id(&agent.id)?;
+            if incoming
+                .iter()
+                .any(|existing: &AgentDefinition| existing.id == agent.id)
+            {
+                return Err(MosaicError::Validation(format!(
+                    "agents bundle contains duplicate agent '{}'",
+                    agent.id
+                )));
             }
+            validate_agent_fields(
+                Some(&agent.id),
+                &agent.name,
+                &agent.profile,
+                &agent.skills,
+                agent.temperature,
+                agent.max_turns,
+            )?;
+            agent.skills = normalize_skill_ids(agent.skills)?;
+            agent.system_prompt = normalize_system_prompt(agent.system_prompt)?;
+            agent.allowed_tools = normalize_allowed_tools(agent.allowed_tools)?;
+            apply_overrides(ProfileConfig::default(), &agent)?;
+            incoming.push(agent);
         }
 
-        if let Some(default_agent_id) = &routes.default_agent_id
-            && !agents.iter().any(|agent| &agent.id == default_agent_id)
-        {
-            issues.push(format!(
-                "default_agent_id '{}' does not exist",
-                default_agent_id
-            ));
+        let _agents_lock = lock_state_file(&self.agents_path)?;
+        let _routes_lock = lock_state_file(&self.routes_path)?;
+        let (mut file, mut routes) = if replace {
+            (
+                AgentsFile {
+                    version: CURRENT_AGENTS_VERSION,
+                    agents: Vec::new(),
+                },
+                AgentRoutes::default(),
+            )
+        } else {
+            (self.load_agents()?, self.load_routes()?)
+        };
+        let mut imported = 0usize;
+        let mut skipped = 0usize;
+        for agent in incoming {
+            if file.agents.iter().any(|existing| existing.id == agent.id) {
+                skipped += 1;
+                continue;
+            }
+            file.agents.push(agent);
+            imported += 1;
         }
-        for (route, agent_id) in &routes.routes {
-            if !agents.iter().any(|agent| &agent.id == agent_id) {
-                issues.push(format!(
-                    "route '{route}' points to missing agent '{agent_id}'"
-                ));
+        let mut routes_imported = 0usize;
+        for (route_key, agent_id) in bundle.routes.routes {
+            let route_key = normalize_route_key(&route_key)?;
+            if routes.routes.contains_key(&route_key) {
+                continue;
             }
+            routes
+                .routes
+                .insert(route_key, normalize_agent_id(&agent_id)?);
+            routes_imported += 1;
+        }
+        if routes.default_agent_id.is_none() {
+            routes.default_agent_id = bundle
+                .routes
+                .default_agent_id
+                .as_deref()
+                .map(normalize_agent_id)
+                .transpose()?;
         }
 
-        Ok(AgentIntegrityReport {
-            agents_count: agents.len(),
-            routes_count: routes.routes.len(),
-            default_agent_id: routes.default_agent_id,
-            ok: issues.is_empty(),
-            issues,
+        let integrity = integrity_report(&file.agents, &routes);
+        if !integrity.ok {
+            return Err(MosaicError::Validation(format!(
+                "agents bundle failed integrity check: {}",
+                integrity.issues.join("; ")
+            )));
+        }
+        self.ensure_dirs()?;
+        self.save_agents(&file)?;
+        self.save_routes(&routes)?;
+        Ok(AgentImportSummary {
+            imported,
+            skipped,
+            routes_imported,
+            replace,
+            integrity,
         })
     }
 
+    pub fn check_integrity(&self) -> Result<AgentIntegrityReport> {
+        Ok(integrity_report(&self.list()?, &self.load_routes()?))
+    }
+
+    /// Runs [`Self::check_integrity`] and also flags agents whose profile is not
+    /// defined in the loaded config.
+    pub fn check_integrity_with_config(&self, config: &ConfigFile) -> Result<AgentIntegrityReport> {
+        let mut report = self.check_integrity()?;
+        for agent in self.list()? {
+            let profile = agent.profile.trim();
+            if !profile.is_empty() && !config.profiles.contains_key(profile) {
+                report.issues.push(format!(
+                    "agent '{}' references missing profile '{}'",
+                    agent.id, profile
+                ));
+            }
+        }
+        report.ok = report.issues.is_empty();
+        Ok(report)
+    }
+
     fn load_agents(&self) -> Result<AgentsFile> {
         if !self.agents_path.exists() {
             return Ok(AgentsFile {
@@ -405,17 +684,21 @@ impl AgentStore {
             });
         }
         let raw = std::fs::read_to_string(&self.agents_path)?;
-        let file = serde_json::from_str::<AgentsFile>(&raw).map_err(|err| {
+        let mut file = serde_json::from_str::<AgentsFile>(&raw).map_err(|err| {
             MosaicError::Validation(format!(
                 "invalid agents JSON {}: {err}",
                 self.agents_path.display()
             ))
         })?;
-        if file.version != CURRENT_AGENTS_VERSION {
-            return Err(MosaicError::Validation(format!(
-                "unsupported agents file version {} expected {}",
-                file.version, CURRENT_AGENTS_VERSION
-            )));
+        match file.version {
+            CURRENT_AGENTS_VERSION => {}
+            LEGACY_AGENTS_VERSION => file.version = CURRENT_AGENTS_VERSION,
+            version => {
+                return Err(MosaicError::Validation(format!(
+                    "unsupported agents file version {} expected {}",
+                    version, CURRENT_AGENTS_VERSION
+                )));
+            }
         }
         Ok(file)
     }
@@ -444,6 +727,8 @@ pub struct ResolvedAgentProfile {
     pub profile_name: String,
     pub profile: ProfileConfig,
     pub agent_skills: Vec<String>,
+    /// Replaces the runner's default system prompt when the agent sets one.
+    pub system_prompt: Option<String>,
 }
 
 pub fn agents_file_path(data_dir: &Path) -> PathBuf {
@@ -454,6 +739,74 @@ pub fn agent_routes_path(data_dir: &Path) -> PathBuf {
     data_dir.join("agent-routes.json")
 }
 
+fn integrity_report(agents: &[AgentDefinition], routes: &AgentRoutes) -> AgentIntegrityReport {
+    let mut issues = Vec::new();
+    let mut seen_ids = BTreeMap::<&str, usize>::new();
+    for agent in agents {
+        if agent.name.trim().is_empty() {
+            issues.push(format!("agent '{}' has empty name", agent.id));
+        }
+        if agent.profile.trim().is_empty() {
+            issues.push(format!("agent '{}' has empty profile", agent.id));
+        }
+        *seen_ids.entry(agent.id.as_str()).or_default() += 1;
+    }
+    for (agent_id, count) in seen_ids {
+        if count > 1 {
+            issues.push(format!("agent id '{agent_id}' is defined {count} times"));
+        }
+    }
+
+    if let Some(default_agent_id) = &routes.default_agent_id {
+        if agents.is_empty() {
+            issues.push(format!(
+                "default_agent_id '{default_agent_id}' is set but no agents are configured"
+            ));
+        } else if !agents.iter().any(|agent| &agent.id == default_agent_id) {
+            issues.push(format!(
+                "default_agent_id '{}' does not exist",
+                default_agent_id
+            ));
+        }
+    }
+    let mut normalized_keys = BTreeMap::<&str, Vec<&str>>::new();
+    for route in routes.routes.keys() {
+        normalized_keys
+            .entry(route.trim())
+            .or_default()
+            .push(route.as_str());
+    }
+    for (normalized, raw_keys) in normalized_keys {
+        if normalized.is_empty() {
+            issues.push("route key is empty after normalization".to_string());
+        } else if raw_keys.len() > 1 {
+            issues.push(format!(
+                "route keys {} collide after normalization as '{normalized}'",
+                raw_keys
+                    .iter()
+                    .map(|key| format!("'{key}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    for (route, agent_id) in &routes.routes {
+        if !agents.iter().any(|agent| &agent.id == agent_id) {
+            issues.push(format!(
+                "route '{route}' points to missing agent '{agent_id}'"
+            ));
+        }
+    }
+
+    AgentIntegrityReport {
+        agents_count: agents.len(),
+        routes_count: routes.routes.len(),
+        default_agent_id: routes.default_agent_id.clone(),
+        ok: issues.is_empty(),
+        issues,
+    }
+}
+
 fn apply_overrides(mut base: ProfileConfig, agent: &AgentDefinition) -> Result<ProfileConfig> {
     if let Some(model) = &agent.model {
         if model.trim().is_empty() {
@@ -488,6 +841,9 @@ fn apply_overrides(mut base: ProfileConfig, agent: &AgentDefinition) -> Result<P
     if let Some(guard_mode) = &agent.guard_mode {
         base.tools.run.guard_mode = guard_mode.clone();
     }
+    if let Some(allowed_tools) = &agent.allowed_tools {
+        base.tools.allowed = Some(allowed_tools.clone());
+    }
     base.validate()?;
     Ok(base)
 }
@@ -520,6 +876,31 @@ fn normalize_route_key(raw: &str) -> Result<String> {
     Ok(value.to_string())
 }
 
+fn route_specificity(pattern: &str) -> (usize, std::cmp::Reverse<usize>) {
+    let wildcards = pattern.matches('*').count();
+    (pattern.len() - wildcards, std::cmp::Reverse(wildcards))
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if value.len() < first.len() + last.len() || !value.starts_with(first) || !value.ends_with(last)
+    {
+        return false;
+    }
+    let mut middle = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        let Some(found) = middle.find(part) else {
+            return false;
+        };
+        middle = &middle[found + part.len()..];
+    }
+    true
+}
+
 fn generate_agent_id(name: &str) -> String {
     let mut slug = String::new();
     for ch in name.trim().chars() {
@@ -536,6 +917,38 @@ fn generate_agent_id(name: &str) -> String {
     format!("{slug}-{suffix}")
 }
 
+fn normalize_system_prompt(raw: Option<String>) -> Result<Option<String>> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let value = raw.trim();
+    if value.is_empty() {
+        return Err(MosaicError::Validation(
+            "agent system prompt cannot be empty".to_string(),
+        ));
+    }
+    Ok(Some(value.to_string()))
+}
+
+fn normalize_allowed_tools(raw: Option<Vec<String>>) -> Result<Option<Vec<String>>> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let mut tools = Vec::with_capacity(raw.len());
+    for tool in raw {
+        let tool = tool.trim();
+        if tool.is_empty() {
+            return Err(MosaicError::Validation(
+                "allowed tool name cannot be empty".to_string(),
+            ));
+        }
+        tools.push(tool.to_string());
+    }
+    tools.sort();
+    tools.dedup();
+    Ok((!tools.is_empty()).then_some(tools))
+}
+
 fn validate_agent_fields(
     id: Option<&str>,
     name: &str,
@@ -590,6 +1003,10 @@ fn validate_agent_update_input(input: &UpdateAgentInput) -> Result<()> {
         && !input.clear_tools_enabled
         && input.guard_mode.is_none()
         && !input.clear_guard_mode
+        && input.system_prompt.is_none()
+        && !input.clear_system_prompt
+        && input.allowed_tools.is_none()
+        && !input.clear_allowed_tools
     {
         return Err(MosaicError::Validation(
             "no update fields provided".to_string(),
@@ -625,6 +1042,16 @@ fn validate_agent_update_input(input: &UpdateAgentInput) -> Result<()> {
             "cannot use --guard-mode and --clear-guard-mode together".to_string(),
         ));
     }
+    if input.clear_system_prompt && input.system_prompt.is_some() {
+        return Err(MosaicError::Validation(
+            "cannot use --system-prompt and --clear-system-prompt together".to_string(),
+        ));
+    }
+    if input.clear_allowed_tools && input.allowed_tools.is_some() {
+        return Err(MosaicError::Validation(
+            "cannot use --allow-tool and --clear-allowed-tools together".to_string(),
+        ));
+    }
 
     if let Some(name) = &input.name
         && name.trim().is_empty()
@@ -713,6 +1140,8 @@ mod tests {
                 max_turns: Some(4),
                 tools_enabled: Some(true),
                 guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect("add agent");
         assert_eq!(agent.id, "writer");
@@ -725,6 +1154,38 @@ mod tests {
         assert_eq!(resolved.as_deref(), Some("writer"));
     }
 
+    #[test]
+    fn concurrent_adds_do_not_lose_updates() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        let workers = ["left", "right"].map(|prefix| {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                for idx in 0..5 {
+                    store
+                        .add(AddAgentInput {
+                            id: Some(format!("{prefix}-{idx}")),
+                            name: format!("{prefix} {idx}"),
+                            profile: "default".to_string(),
+                            skills: vec![],
+                            model: None,
+                            temperature: None,
+                            max_turns: None,
+                            tools_enabled: None,
+                            guard_mode: None,
+                            system_prompt: None,
+                            allowed_tools: None,
+                        })
+                        .expect("add agent");
+                }
+            })
+        });
+        for worker in workers {
+            worker.join().expect("worker");
+        }
+        assert_eq!(store.list().expect("list").len(), 10);
+    }
+
     #[test]
     fn resolve_uses_route_before_default() {
         let temp = tempdir().expect("tempdir");
@@ -740,6 +1201,8 @@ mod tests {
                 max_turns: None,
                 tools_enabled: None,
                 guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect("add planner");
         store
@@ -753,6 +1216,8 @@ mod tests {
                 max_turns: None,
                 tools_enabled: None,
                 guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect("add writer");
         store.set_default("writer").expect("set default");
@@ -763,6 +1228,167 @@ mod tests {
         assert_eq!(resolved.as_deref(), Some("planner"));
     }
 
+    #[test]
+    fn resolve_prefers_exact_route_over_wildcard() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        for id in ["deployer", "prod-deployer", "ops"] {
+            store
+                .add(AddAgentInput {
+                    id: Some(id.to_string()),
+                    name: id.to_string(),
+                    profile: "default".to_string(),
+                    skills: vec![],
+                    model: None,
+                    temperature: None,
+                    max_turns: None,
+                    tools_enabled: None,
+                    guard_mode: None,
+                    system_prompt: None,
+                    allowed_tools: None,
+                })
+                .expect("add agent");
+        }
+        store.set_default("ops").expect("set default");
+        store.set_route("deploy:*", "deployer").expect("wildcard");
+        store
+            .set_route("deploy:prod*", "prod-deployer")
+            .expect("specific wildcard");
+        store
+            .set_route("deploy:prod-eu", "deployer")
+            .expect("exact route");
+
+        let resolve = |hint: &str| {
+            store
+                .resolve_for_runtime(None, Some(hint))
+                .expect("resolve")
+        };
+        assert_eq!(resolve("deploy:prod-eu").as_deref(), Some("deployer"));
+        assert_eq!(resolve("deploy:prod-us").as_deref(), Some("prod-deployer"));
+        assert_eq!(resolve("deploy:staging").as_deref(), Some("deployer"));
+        assert_eq!(resolve("deployment").as_deref(), Some("ops"));
+        assert_eq!(
+            store
+                .resolve_for_runtime(Some("ops"), Some("deploy:staging"))
+                .expect("explicit")
+                .as_deref(),
+            Some("ops")
+        );
+    }
+
+    #[test]
+    fn resolve_matches_wildcard_only_route() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        store
+            .add(AddAgentInput {
+                id: Some("reviewer".to_string()),
+                name: "Reviewer".to_string(),
+                profile: "default".to_string(),
+                skills: vec![],
+                model: None,
+                temperature: None,
+                max_turns: None,
+                tools_enabled: None,
+                guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
+            })
+            .expect("add reviewer");
+        store.set_route("*:review", "reviewer").expect("wildcard");
+
+        let resolved = store
+            .resolve_for_runtime(None, Some("pr:review"))
+            .expect("resolve");
+        assert_eq!(resolved.as_deref(), Some("reviewer"));
+        let unmatched = store
+            .resolve_for_runtime(None, Some("pr:merge"))
+            .expect("resolve");
+        assert_eq!(unmatched, None);
+        assert!(wildcard_match("a*a", "aa"));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+
+    #[test]
+    fn export_import_round_trip_restores_agents_and_default() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        for (id, model) in [("writer", Some("gpt-4o-mini")), ("reviewer", None)] {
+            store
+                .add(AddAgentInput {
+                    id: Some(id.to_string()),
+                    name: id.to_string(),
+                    profile: "default".to_string(),
+                    skills: vec![],
+                    model: model.map(ToOwned::to_owned),
+                    temperature: Some(0.5),
+                    max_turns: None,
+                    tools_enabled: None,
+                    guard_mode: None,
+                    system_prompt: Some("Stay focused.".to_string()),
+                    allowed_tools: Some(vec!["read_file".to_string()]),
+                })
+                .expect("add agent");
+        }
+        store.set_default("writer").expect("default");
+        store.set_route("deploy:*", "reviewer").expect("route");
+
+        let exported = serde_json::to_value(store.export().expect("export")).expect("bundle");
+        assert_eq!(exported["version"], AGENTS_BUNDLE_VERSION);
+        store.remove("writer").expect("remove writer");
+        store.remove("reviewer").expect("remove reviewer");
+        assert!(store.list().expect("list").is_empty());
+        assert_eq!(store.load_routes().expect("routes").default_agent_id, None);
+
+        let summary = store.import(exported.clone(), false).expect("import");
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped, 0);
+        assert_eq!(summary.routes_imported, 1);
+        assert!(summary.integrity.ok);
+        let reexported = serde_json::to_value(store.export().expect("re-export")).expect("bundle");
+        assert_eq!(reexported["agents"], exported["agents"]);
+        assert_eq!(reexported["routes"], exported["routes"]);
+        assert_eq!(reexported["routes"]["default_agent_id"], "writer");
+
+        let merged = store.import(exported, false).expect("merge again");
+        assert_eq!(merged.imported, 0);
+        assert_eq!(merged.skipped, 2);
+    }
+
+    #[test]
+    fn import_rejects_unknown_versions_and_dangling_routes() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+
+        let err = store
+            .import(
+                serde_json::json!({"version": 99, "agents": [], "routes": {}}),
+                true,
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unsupported agents bundle version 99 expected 1")
+        );
+
+        let err = store
+            .import(
+                serde_json::json!({
+                    "version": 1,
+                    "exported_at": Utc::now(),
+                    "agents": [],
+                    "routes": {"default_agent_id": "ghost", "routes": {}},
+                }),
+                true,
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("default_agent_id 'ghost' is set but no agents are configured")
+        );
+        assert!(!store.routes_path().exists());
+    }
+
     #[test]
     fn resolve_effective_profile_applies_overrides() {
         let temp = tempdir().expect("tempdir");
@@ -778,6 +1404,12 @@ mod tests {
                 max_turns: Some(12),
                 tools_enabled: Some(false),
                 guard_mode: Some(RunGuardMode::AllConfirm),
+                system_prompt: Some("  Write tersely.  ".to_string()),
+                allowed_tools: Some(vec![
+                    "search_text".to_string(),
+                    " read_file".to_string(),
+                    "search_text".to_string(),
+                ]),
             })
             .expect("add writer");
 
@@ -802,6 +1434,165 @@ mod tests {
             resolved.profile.tools.run.guard_mode,
             RunGuardMode::AllConfirm
         ));
+        assert_eq!(resolved.system_prompt.as_deref(), Some("Write tersely."));
+        assert_eq!(
+            resolved.profile.tools.allowed,
+            Some(vec!["read_file".to_string(), "search_text".to_string()])
+        );
+        assert!(!resolved.profile.tools.is_tool_allowed("run_cmd"));
+    }
+
+    fn write_raw_state(store: &AgentStore, agents: serde_json::Value, routes: serde_json::Value) {
+        store.ensure_dirs().expect("dirs");
+        std::fs::write(store.agents_path(), agents.to_string()).expect("write agents");
+        std::fs::write(store.routes_path(), routes.to_string()).expect("write routes");
+    }
+
+    fn raw_agent(id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": id,
+            "profile": "default",
+            "model": null,
+            "temperature": null,
+            "max_turns": null,
+            "tools_enabled": null,
+            "guard_mode": null,
+            "created_at": Utc::now(),
+            "updated_at": Utc::now(),
+        })
+    }
+
+    #[test]
+    fn legacy_agents_file_migrates_and_touch_records_last_use() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        write_raw_state(
+            &store,
+            serde_json::json!({"version": 1, "agents": [raw_agent("writer"), raw_agent("reviewer")]}),
+            serde_json::json!({"default_agent_id": null, "routes": {}}),
+        );
+
+        let agents = store.list().expect("list legacy file");
+        assert_eq!(
+            agents
+                .iter()
+                .map(|agent| agent.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["reviewer", "writer"]
+        );
+        assert!(agents.iter().all(|agent| agent.last_used_at.is_none()));
+
+        let before = store.get("writer").expect("get").expect("writer");
+        let touched = store.touch("writer").expect("touch");
+        assert!(touched.last_used_at.is_some());
+        assert_eq!(touched.updated_at, before.updated_at);
+        assert!(store.touch("ghost").is_err());
+
+        let persisted: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(store.agents_path()).expect("read agents"))
+                .expect("agents json");
+        assert_eq!(persisted["version"], CURRENT_AGENTS_VERSION);
+        let reloaded = store.get("writer").expect("get").expect("writer");
+        assert_eq!(reloaded.last_used_at, touched.last_used_at);
+        assert!(
+            store
+                .get("reviewer")
+                .expect("get")
+                .expect("reviewer")
+                .last_used_at
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn integrity_flags_duplicate_agent_ids() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        write_raw_state(
+            &store,
+            serde_json::json!({"version": 1, "agents": [raw_agent("writer"), raw_agent("writer")]}),
+            serde_json::json!({"default_agent_id": "writer", "routes": {}}),
+        );
+        let report = store.check_integrity().expect("integrity");
+        assert!(!report.ok);
+        assert_eq!(report.issues, vec!["agent id 'writer' is defined 2 times"]);
+    }
+
+    #[test]
+    fn integrity_flags_route_keys_colliding_after_normalization() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        write_raw_state(
+            &store,
+            serde_json::json!({"version": 1, "agents": [raw_agent("writer")]}),
+            serde_json::json!({
+                "default_agent_id": null,
+                "routes": {"ask": "writer", " ask ": "writer", "  ": "writer"}
+            }),
+        );
+        let report = store.check_integrity().expect("integrity");
+        assert!(!report.ok);
+        assert!(
+            report.issues.contains(
+                &"route keys ' ask ', 'ask' collide after normalization as 'ask'".to_string()
+            ),
+            "issues: {:?}",
+            report.issues
+        );
+        assert!(
+            report
+                .issues
+                .contains(&"route key is empty after normalization".to_string())
+        );
+    }
+
+    #[test]
+    fn integrity_flags_default_without_any_agents() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        write_raw_state(
+            &store,
+            serde_json::json!({"version": 1, "agents": []}),
+            serde_json::json!({"default_agent_id": "writer", "routes": {}}),
+        );
+        let report = store.check_integrity().expect("integrity");
+        assert_eq!(
+            report.issues,
+            vec!["default_agent_id 'writer' is set but no agents are configured"]
+        );
+    }
+
+    #[test]
+    fn integrity_with_config_flags_missing_profiles() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        store
+            .add(AddAgentInput {
+                id: Some("writer".to_string()),
+                name: "Writer".to_string(),
+                profile: "staging".to_string(),
+                skills: vec![],
+                model: None,
+                temperature: None,
+                max_turns: None,
+                tools_enabled: None,
+                guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
+            })
+            .expect("add writer");
+
+        let config = ConfigFile::default_for_mode(StateMode::Project);
+        assert!(store.check_integrity().expect("integrity").ok);
+        let report = store
+            .check_integrity_with_config(&config)
+            .expect("config integrity");
+        assert!(!report.ok);
+        assert_eq!(
+            report.issues,
+            vec!["agent 'writer' references missing profile 'staging'".to_string()]
+        );
     }
 
     #[test]
@@ -819,6 +1610,8 @@ mod tests {
                 max_turns: Some(6),
                 tools_enabled: Some(true),
                 guard_mode: Some(RunGuardMode::ConfirmDangerous),
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect("add");
 
@@ -845,6 +1638,187 @@ mod tests {
         assert!(matches!(updated.guard_mode, Some(RunGuardMode::AllConfirm)));
     }
 
+    #[test]
+    fn update_agent_clears_overrides_and_bumps_updated_at() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        let added = store
+            .add(AddAgentInput {
+                id: Some("writer".to_string()),
+                name: "Writer".to_string(),
+                profile: "default".to_string(),
+                skills: vec![],
+                model: Some("gpt-4o-mini".to_string()),
+                temperature: Some(0.5),
+                max_turns: Some(6),
+                tools_enabled: Some(false),
+                guard_mode: Some(RunGuardMode::AllConfirm),
+                system_prompt: None,
+                allowed_tools: None,
+            })
+            .expect("add");
+
+        let updated = store
+            .update(
+                "writer",
+                UpdateAgentInput {
+                    clear_model: true,
+                    clear_max_turns: true,
+                    clear_tools_enabled: true,
+                    clear_guard_mode: true,
+                    ..UpdateAgentInput::default()
+                },
+            )
+            .expect("update");
+        assert_eq!(updated.model, None);
+        assert_eq!(updated.temperature, Some(0.5));
+        assert_eq!(updated.max_turns, None);
+        assert_eq!(updated.tools_enabled, None);
+        assert!(updated.guard_mode.is_none());
+        assert!(updated.updated_at >= added.updated_at);
+        assert_eq!(updated.created_at, added.created_at);
+
+        let stored = store.get("writer").expect("get").expect("agent exists");
+        assert_eq!(stored.model, None);
+        assert_eq!(stored.max_turns, None);
+    }
+
+    #[test]
+    fn update_agent_rejects_missing_agent_and_invalid_values() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        let err = store
+            .update(
+                "ghost",
+                UpdateAgentInput {
+                    name: Some("Ghost".to_string()),
+                    ..UpdateAgentInput::default()
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Validation(_)));
+        assert!(err.to_string().contains("agent 'ghost' not found"));
+
+        store
+            .add(AddAgentInput {
+                id: Some("writer".to_string()),
+                name: "Writer".to_string(),
+                profile: "default".to_string(),
+                skills: vec![],
+                model: None,
+                temperature: None,
+                max_turns: None,
+                tools_enabled: None,
+                guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
+            })
+            .expect("add");
+        let err = store
+            .update(
+                "writer",
+                UpdateAgentInput {
+                    temperature: Some(3.5),
+                    ..UpdateAgentInput::default()
+                },
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("temperature must be in [0.0, 2.0]")
+        );
+        let err = store
+            .update(
+                "writer",
+                UpdateAgentInput {
+                    name: Some("   ".to_string()),
+                    ..UpdateAgentInput::default()
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("agent name cannot be empty"));
+        let stored = store.get("writer").expect("get").expect("agent exists");
+        assert_eq!(stored.name, "Writer");
+        assert_eq!(stored.temperature, None);
+    }
+
+    #[test]
+    fn clone_agent_copies_overrides_under_new_id() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        let source = store
+            .add(AddAgentInput {
+                id: Some("writer".to_string()),
+                name: "Writer".to_string(),
+                profile: "default".to_string(),
+                skills: vec!["writer".to_string()],
+                model: Some("gpt-4o-mini".to_string()),
+                temperature: Some(0.5),
+                max_turns: Some(6),
+                tools_enabled: Some(false),
+                guard_mode: Some(RunGuardMode::AllConfirm),
+                system_prompt: None,
+                allowed_tools: None,
+            })
+            .expect("add");
+        store.set_route("docs", "writer").expect("route");
+
+        let cloned = store
+            .clone_agent("writer", "writer-terse", Some("Terse Writer"))
+            .expect("clone");
+        assert_eq!(cloned.id, "writer-terse");
+        assert_eq!(cloned.name, "Terse Writer");
+        assert_eq!(cloned.profile, source.profile);
+        assert_eq!(cloned.skills, source.skills);
+        assert_eq!(cloned.model, source.model);
+        assert_eq!(cloned.temperature, source.temperature);
+        assert_eq!(cloned.max_turns, source.max_turns);
+        assert_eq!(cloned.tools_enabled, source.tools_enabled);
+        assert!(matches!(cloned.guard_mode, Some(RunGuardMode::AllConfirm)));
+        assert!(cloned.created_at >= source.created_at);
+        assert_eq!(store.list().expect("list").len(), 2);
+        assert_eq!(
+            store.load_routes().expect("routes").routes.get("docs"),
+            Some(&"writer".to_string())
+        );
+
+        let unnamed = store
+            .clone_agent("writer", "writer-2", None)
+            .expect("clone without name");
+        assert_eq!(unnamed.name, "Writer");
+    }
+
+    #[test]
+    fn clone_agent_rejects_existing_or_invalid_ids() {
+        let temp = tempdir().expect("tempdir");
+        let store = build_store(&temp);
+        for id in ["writer", "reviewer"] {
+            store
+                .add(AddAgentInput {
+                    id: Some(id.to_string()),
+                    name: id.to_string(),
+                    profile: "default".to_string(),
+                    skills: vec![],
+                    model: None,
+                    temperature: None,
+                    max_turns: None,
+                    tools_enabled: None,
+                    guard_mode: None,
+                    system_prompt: None,
+                    allowed_tools: None,
+                })
+                .expect("add");
+        }
+
+        let err = store.clone_agent("writer", "reviewer", None).unwrap_err();
+        assert!(err.to_string().contains("agent 'reviewer' already exists"));
+        let err = store.clone_agent("writer", "bad id", None).unwrap_err();
+        assert!(err.to_string().contains("contains invalid characters"));
+        let err = store.clone_agent("ghost", "ghost-2", None).unwrap_err();
+        assert!(err.to_string().contains("agent 'ghost' not found"));
+        assert_eq!(store.list().expect("list").len(), 2);
+    }
+
     #[test]
     fn update_agent_rejects_conflicting_clear_and_set_flags() {
         let temp = tempdir().expect("tempdir");
@@ -860,6 +1834,8 @@ mod tests {
                 max_turns: None,
                 tools_enabled: None,
                 guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect("add");
 
@@ -910,6 +1886,8 @@ mod tests {
                 max_turns: None,
                 tools_enabled: None,
                 guard_mode: None,
+                system_prompt: None,
+                allowed_tools: None,
             })
             .expect_err("secret-like model should fail");
         assert!(err.to_string().contains("blocked agents state"));
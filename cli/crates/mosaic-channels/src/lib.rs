@@ -11,9 +11,11 @@ pub use schema::{
     format_channel_for_output,
 };
 pub use types::{
-    AddChannelInput, ChannelAuthConfig, ChannelCapability, ChannelCapabilityDiagnostics,
+    AddChannelInput, AttemptRecord, ChannelAuthConfig, ChannelCapability,
+    ChannelCapabilityDiagnostics, ChannelDeadLetterEntry, ChannelDeadLetterReplay,
     ChannelDirectoryEntry, ChannelEntry, ChannelImportSummary, ChannelListItem, ChannelLogEntry,
-    ChannelLoginResult, ChannelReplayPayload, ChannelSendOptions, ChannelSendResult, ChannelStatus,
-    ChannelTemplateDefaults, ChannelTokenRotationItem, ChannelTokenRotationSummary, ChannelsFile,
-    DoctorCheck, RotateTokenEnvInput, UpdateChannelInput,
+    ChannelLoginResult, ChannelMetrics, ChannelMetricsCounts, ChannelReplayPayload,
+    ChannelSendOptions, ChannelSendResult, ChannelStatus, ChannelTemplateDefaults,
+    ChannelTokenRotationItem, ChannelTokenRotationSummary, ChannelsFile, DoctorCheck,
+    RotateTokenEnvInput, UpdateChannelInput,
 };
@@ -31,6 +31,14 @@ impl RetryPolicy {
         }
     }
 
+    /// Overrides the per-request HTTP timeout for a single delivery.
+    pub fn with_timeout_ms(mut self, timeout_ms: Option<u64>) -> Self {
+        if let Some(timeout_ms) = timeout_ms {
+            self.timeout = Duration::from_millis(timeout_ms);
+        }
+        self
+    }
+
     pub fn max_attempts(&self) -> usize {
         self.backoff_ms.len() + 1
     }
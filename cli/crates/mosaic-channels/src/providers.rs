@@ -1,25 +1,80 @@
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::{Value, json};
 
 use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::http::with_env_proxy;
 
 use crate::policy::{RetryPolicy, should_retry_http_status};
 use crate::schema::{DEFAULT_TELEGRAM_TOKEN_ENV, mask_endpoint};
-use crate::types::ChannelCapability;
+use crate::types::{AttemptRecord, ChannelCapability};
 
 #[derive(Debug, Clone)]
 pub(crate) struct DeliveryAttemptResult {
     pub ok: bool,
     pub attempts: usize,
+    pub attempts_detail: Vec<AttemptRecord>,
     pub http_status: Option<u16>,
     pub error: Option<String>,
     pub endpoint_masked: Option<String>,
 }
 
+/// Per-attempt timeline for a delivery; offsets are measured from when it was created.
+struct AttemptTimeline {
+    started: Instant,
+    records: Vec<AttemptRecord>,
+}
+
+impl AttemptTimeline {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Waits out the policy backoff before `attempt_idx` and opens its record.
+    async fn begin(&mut self, policy: &RetryPolicy, attempt_idx: usize) {
+        let backoff = policy.backoff_before_attempt(attempt_idx);
+        if let Some(delay) = backoff {
+            tokio::time::sleep(delay).await;
+        }
+        self.records.push(AttemptRecord {
+            attempt: attempt_idx + 1,
+            started_offset_ms: self.started.elapsed().as_millis() as u64,
+            backoff_ms: backoff.map_or(0, |delay| delay.as_millis() as u64),
+            http_status: None,
+            error: None,
+        });
+    }
+
+    fn record_status(&mut self, status: u16) {
+        if let Some(record) = self.records.last_mut() {
+            record.http_status = Some(status);
+        }
+    }
+
+    fn record_error(&mut self, error: Option<&str>) {
+        if let Some(record) = self.records.last_mut() {
+            record.error = error.map(ToOwned::to_owned);
+        }
+    }
+}
+
+fn immediate_success_detail() -> Vec<AttemptRecord> {
+    vec![AttemptRecord {
+        attempt: 1,
+        started_offset_ms: 0,
+        backoff_ms: 0,
+        http_status: Some(200),
+        error: None,
+    }]
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct ChannelDispatchRequest<'a> {
     pub channel_id: &'a str,
@@ -29,6 +84,8 @@ pub(crate) struct ChannelDispatchRequest<'a> {
     pub text: &'a str,
     pub parse_mode: Option<&'a str>,
     pub bearer_token: Option<&'a str>,
+    /// Convert Markdown text to Slack `blocks`; ignored by other kinds.
+    pub slack_blocks: bool,
 }
 
 #[async_trait]
@@ -223,9 +280,15 @@ impl ChannelProvider for SlackWebhookProvider {
         let endpoint = request.endpoint.ok_or_else(|| {
             MosaicError::Validation("slack_webhook channel requires --endpoint".to_string())
         })?;
+        let payload = request
+            .slack_blocks
+            .then(|| markdown_to_slack_blocks(request.text))
+            .flatten()
+            .map(|blocks| json!({ "text": request.text, "blocks": blocks }))
+            .unwrap_or_else(|| json!({ "text": request.text }));
         send_with_retry(
             endpoint,
-            json!({ "text": request.text }),
+            payload,
             request.bearer_token.map(str::to_string),
             policy,
         )
@@ -233,6 +296,138 @@ impl ChannelProvider for SlackWebhookProvider {
     }
 }
 
+/// Slack rejects section text above 3000 characters and messages above 50 blocks.
+const SLACK_SECTION_TEXT_LIMIT: usize = 3000;
+const SLACK_MAX_BLOCKS: usize = 50;
+/// Characters the opening and closing fences add around a code chunk.
+const SLACK_FENCE_OVERHEAD: usize = "```\n\n```".len();
+
+/// Converts Markdown into Slack `section` blocks: fenced code becomes a
+/// preformatted section, other paragraphs are rewritten to Slack `mrkdwn`.
+/// Long sections are split on line boundaries, and each code chunk carries its
+/// own fences. Returns `None` when the text yields no blocks or more than Slack
+/// accepts, so the caller can fall back to plain `text`.
+fn markdown_to_slack_blocks(text: &str) -> Option<Vec<Value>> {
+    let mut sections = Vec::new();
+    let mut paragraph = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    for line in text.lines() {
+        let is_fence = line.trim_start().starts_with("```");
+        match code.as_mut() {
+            Some(code_lines) if is_fence => {
+                push_slack_code(&mut sections, code_lines);
+                code = None;
+            }
+            Some(code_lines) => code_lines.push(line),
+            None if is_fence => {
+                push_slack_paragraph(&mut sections, &mut paragraph);
+                code = Some(Vec::new());
+            }
+            None if line.trim().is_empty() => push_slack_paragraph(&mut sections, &mut paragraph),
+            None => paragraph.push(markdown_line_to_mrkdwn(line)),
+        }
+    }
+    if let Some(code_lines) = code {
+        push_slack_code(&mut sections, &code_lines);
+    }
+    push_slack_paragraph(&mut sections, &mut paragraph);
+
+    let blocks = sections
+        .into_iter()
+        .map(|chunk| json!({ "type": "section", "text": { "type": "mrkdwn", "text": chunk } }))
+        .collect::<Vec<_>>();
+    (!blocks.is_empty() && blocks.len() <= SLACK_MAX_BLOCKS).then_some(blocks)
+}
+
+fn push_slack_paragraph(sections: &mut Vec<String>, paragraph: &mut Vec<String>) {
+    if !paragraph.is_empty() {
+        sections.extend(split_lines_at_char_limit(
+            paragraph.iter().map(String::as_str),
+            SLACK_SECTION_TEXT_LIMIT,
+        ));
+        paragraph.clear();
+    }
+}
+
+fn push_slack_code(sections: &mut Vec<String>, code_lines: &[&str]) {
+    let limit = SLACK_SECTION_TEXT_LIMIT - SLACK_FENCE_OVERHEAD;
+    let chunks = split_lines_at_char_limit(code_lines.iter().copied(), limit);
+    if chunks.is_empty() {
+        sections.push("```\n\n```".to_string());
+    }
+    sections.extend(chunks.into_iter().map(|chunk| format!("```\n{chunk}\n```")));
+}
+
+/// Rewrites `# heading`, `**bold**`, and `[label](url)` to their `mrkdwn` forms.
+fn markdown_line_to_mrkdwn(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let heading = trimmed.trim_start_matches('#');
+    let line = if heading.len() < trimmed.len() && heading.starts_with(' ') {
+        format!("*{}*", heading.trim())
+    } else {
+        line.replace("**", "*")
+    };
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line.as_str();
+    while let Some(open) = rest.find('[') {
+        let link = rest[open..].find("](").and_then(|mid| {
+            let url_start = open + mid + 2;
+            rest[url_start..]
+                .find(')')
+                .map(|end| (open + mid, url_start, url_start + end))
+        });
+        let Some((label_end, url_start, url_end)) = link else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        out.push_str(&format!(
+            "<{}|{}>",
+            &rest[url_start..url_end],
+            &rest[open + 1..label_end]
+        ));
+        rest = &rest[url_end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Joins `lines` into newline-separated chunks of at most `limit` characters,
+/// breaking only between lines; a single line over the limit is split by
+/// character.
+fn split_lines_at_char_limit<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    limit: usize,
+) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut current_len = 0;
+    for line in lines {
+        let chars = line.chars().collect::<Vec<_>>();
+        let pieces = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            chars
+                .chunks(limit)
+                .map(|piece| piece.iter().collect())
+                .collect()
+        };
+        for piece in pieces {
+            let piece_len = piece.chars().count();
+            if !current.is_empty() && current_len + 1 + piece_len > limit {
+                chunks.push(current.join("\n"));
+                current.clear();
+                current_len = 0;
+            }
+            current_len += piece_len + usize::from(!current.is_empty());
+            current.push(piece);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current.join("\n"));
+    }
+    chunks
+}
+
 struct GenericWebhookProvider;
 
 #[async_trait]
@@ -617,20 +812,18 @@ async fn send_with_retry(
         return simulate_mock_http(endpoint, policy).await;
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(policy.timeout)
+    let client = with_env_proxy(reqwest::Client::builder().timeout(policy.timeout))?
         .build()
         .map_err(|err| MosaicError::Network(format!("failed to build HTTP client: {err}")))?;
 
     let mut attempts = 0usize;
+    let mut timeline = AttemptTimeline::new();
     let mut last_error: Option<String> = None;
     let mut last_status: Option<u16> = None;
 
     for attempt_idx in 0..policy.max_attempts() {
         attempts += 1;
-        if let Some(delay) = policy.backoff_before_attempt(attempt_idx) {
-            tokio::time::sleep(delay).await;
-        }
+        timeline.begin(policy, attempt_idx).await;
 
         let mut request = client.post(endpoint).json(&payload);
         if let Some(token) = &bearer_token {
@@ -641,10 +834,12 @@ async fn send_with_retry(
             Ok(response) => {
                 let status = response.status().as_u16();
                 last_status = Some(status);
+                timeline.record_status(status);
                 if response.status().is_success() {
                     return Ok(DeliveryAttemptResult {
                         ok: true,
                         attempts,
+                        attempts_detail: timeline.records,
                         http_status: Some(status),
                         error: None,
                         endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -652,16 +847,20 @@ async fn send_with_retry(
                 }
 
                 if response.status().is_client_error() {
+                    let error = format!("webhook returned client error status {status}");
+                    timeline.record_error(Some(&error));
                     return Ok(DeliveryAttemptResult {
                         ok: false,
                         attempts,
+                        attempts_detail: timeline.records,
                         http_status: Some(status),
-                        error: Some(format!("webhook returned client error status {status}")),
+                        error: Some(error),
                         endpoint_masked: Some(mask_endpoint(endpoint)),
                     });
                 }
 
                 last_error = Some(format!("webhook returned server error status {status}"));
+                timeline.record_error(last_error.as_deref());
                 if !should_retry_http_status(status) || attempt_idx + 1 >= policy.max_attempts() {
                     break;
                 }
@@ -672,6 +871,7 @@ async fn send_with_retry(
                 } else {
                     format!("webhook request failed: {err}")
                 });
+                timeline.record_error(last_error.as_deref());
                 if attempt_idx + 1 >= policy.max_attempts() {
                     break;
                 }
@@ -682,6 +882,7 @@ async fn send_with_retry(
     Ok(DeliveryAttemptResult {
         ok: false,
         attempts,
+        attempts_detail: timeline.records,
         http_status: last_status,
         error: Some(
             last_error.unwrap_or_else(|| "webhook request failed after retries".to_string()),
@@ -720,25 +921,24 @@ async fn send_telegram_with_retry(
         payload["parse_mode"] = Value::String(parse_mode.to_string());
     }
 
-    let client = reqwest::Client::builder()
-        .timeout(policy.timeout)
+    let client = with_env_proxy(reqwest::Client::builder().timeout(policy.timeout))?
         .build()
         .map_err(|err| MosaicError::Network(format!("failed to build HTTP client: {err}")))?;
 
     let mut attempts = 0usize;
+    let mut timeline = AttemptTimeline::new();
     let mut last_error: Option<String> = None;
     let mut last_status: Option<u16> = None;
 
     for attempt_idx in 0..policy.max_attempts() {
         attempts += 1;
-        if let Some(delay) = policy.backoff_before_attempt(attempt_idx) {
-            tokio::time::sleep(delay).await;
-        }
+        timeline.begin(policy, attempt_idx).await;
 
         match client.post(&url).json(&payload).send().await {
             Ok(response) => {
                 let status = response.status().as_u16();
                 last_status = Some(status);
+                timeline.record_status(status);
                 if response.status().is_success() {
                     let body = response.json::<Value>().await.map_err(|err| {
                         MosaicError::Network(format!("failed to parse telegram response: {err}"))
@@ -747,17 +947,22 @@ async fn send_telegram_with_retry(
                         Ok(()) => Ok(DeliveryAttemptResult {
                             ok: true,
                             attempts,
+                            attempts_detail: timeline.records,
                             http_status: Some(status),
                             error: None,
                             endpoint_masked: Some(mask_endpoint(endpoint)),
                         }),
-                        Err(message) => Ok(DeliveryAttemptResult {
-                            ok: false,
-                            attempts,
-                            http_status: Some(status),
-                            error: Some(message),
-                            endpoint_masked: Some(mask_endpoint(endpoint)),
-                        }),
+                        Err(message) => {
+                            timeline.record_error(Some(&message));
+                            Ok(DeliveryAttemptResult {
+                                ok: false,
+                                attempts,
+                                attempts_detail: timeline.records,
+                                http_status: Some(status),
+                                error: Some(message),
+                                endpoint_masked: Some(mask_endpoint(endpoint)),
+                            })
+                        }
                     };
                 }
 
@@ -770,6 +975,7 @@ async fn send_telegram_with_retry(
                             "telegram API rate limited (429), retry_after={}s",
                             retry_after_seconds
                         ));
+                        timeline.record_error(last_error.as_deref());
                         if attempt_idx + 1 >= policy.max_attempts() {
                             break;
                         }
@@ -777,19 +983,19 @@ async fn send_telegram_with_retry(
                             .await;
                         continue;
                     }
-                    let description = telegram_error_description(&body);
+                    let error = match telegram_error_description(&body) {
+                        Some(message) => {
+                            format!("telegram API returned client error status {status}: {message}")
+                        }
+                        None => format!("telegram API returned client error status {status}"),
+                    };
+                    timeline.record_error(Some(&error));
                     return Ok(DeliveryAttemptResult {
                         ok: false,
                         attempts,
+                        attempts_detail: timeline.records,
                         http_status: Some(status),
-                        error: Some(match description {
-                            Some(message) => {
-                                format!(
-                                    "telegram API returned client error status {status}: {message}"
-                                )
-                            }
-                            None => format!("telegram API returned client error status {status}"),
-                        }),
+                        error: Some(error),
                         endpoint_masked: Some(mask_endpoint(endpoint)),
                     });
                 }
@@ -797,6 +1003,7 @@ async fn send_telegram_with_retry(
                 last_error = Some(format!(
                     "telegram API returned server error status {status}"
                 ));
+                timeline.record_error(last_error.as_deref());
                 if !should_retry_http_status(status) || attempt_idx + 1 >= policy.max_attempts() {
                     break;
                 }
@@ -807,6 +1014,7 @@ async fn send_telegram_with_retry(
                 } else {
                     format!("telegram request failed: {err}")
                 });
+                timeline.record_error(last_error.as_deref());
                 if attempt_idx + 1 >= policy.max_attempts() {
                     break;
                 }
@@ -817,6 +1025,7 @@ async fn send_telegram_with_retry(
     Ok(DeliveryAttemptResult {
         ok: false,
         attempts,
+        attempts_detail: timeline.records,
         http_status: last_status,
         error: Some(
             last_error.unwrap_or_else(|| "telegram request failed after retries".to_string()),
@@ -873,6 +1082,7 @@ async fn simulate_mock_http_telegram(
         return Ok(DeliveryAttemptResult {
             ok: true,
             attempts: 1,
+            attempts_detail: immediate_success_detail(),
             http_status: Some(200),
             error: None,
             endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -885,32 +1095,46 @@ async fn simulate_mock_http_telegram(
         .collect::<Vec<_>>();
 
     let mut attempts = 0usize;
+    let mut timeline = AttemptTimeline::new();
     let mut last_status: Option<u16> = None;
     let mut last_error: Option<String> = None;
 
     for (idx, step) in steps.iter().enumerate() {
         attempts += 1;
-        if let Some(delay) = policy.backoff_before_attempt(idx) {
-            tokio::time::sleep(delay).await;
-        }
+        timeline.begin(policy, idx).await;
 
         if step == "timeout" {
             last_error = Some("telegram request timed out".to_string());
+            timeline.record_error(last_error.as_deref());
             continue;
         }
+        if let Some(delay) = mock_delay_step(step) {
+            if delay > policy.timeout {
+                tokio::time::sleep(policy.timeout).await;
+                last_error = Some("telegram request timed out".to_string());
+                timeline.record_error(last_error.as_deref());
+                continue;
+            }
+            tokio::time::sleep(delay).await;
+        }
 
-        let status = step.parse::<u16>().map_err(|_| {
-            MosaicError::Validation(format!(
-                "invalid mock-http response step '{}' in endpoint {}",
-                step, endpoint
-            ))
-        })?;
+        let status = match mock_delay_step(step) {
+            Some(_) => 200,
+            None => step.parse::<u16>().map_err(|_| {
+                MosaicError::Validation(format!(
+                    "invalid mock-http response step '{}' in endpoint {}",
+                    step, endpoint
+                ))
+            })?,
+        };
         last_status = Some(status);
+        timeline.record_status(status);
 
         if (200..300).contains(&status) {
             return Ok(DeliveryAttemptResult {
                 ok: true,
                 attempts,
+                attempts_detail: timeline.records,
                 http_status: Some(status),
                 error: None,
                 endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -919,6 +1143,7 @@ async fn simulate_mock_http_telegram(
 
         if status == 429 {
             last_error = Some("telegram API rate limited (429)".to_string());
+            timeline.record_error(last_error.as_deref());
             if idx + 1 >= policy.max_attempts() {
                 break;
             }
@@ -926,13 +1151,14 @@ async fn simulate_mock_http_telegram(
         }
 
         if (400..500).contains(&status) {
+            let error = format!("telegram API returned client error status {status}");
+            timeline.record_error(Some(&error));
             return Ok(DeliveryAttemptResult {
                 ok: false,
                 attempts,
+                attempts_detail: timeline.records,
                 http_status: Some(status),
-                error: Some(format!(
-                    "telegram API returned client error status {status}"
-                )),
+                error: Some(error),
                 endpoint_masked: Some(mask_endpoint(endpoint)),
             });
         }
@@ -940,23 +1166,34 @@ async fn simulate_mock_http_telegram(
         last_error = Some(format!(
             "telegram API returned server error status {status}"
         ));
+        timeline.record_error(last_error.as_deref());
     }
 
     Ok(DeliveryAttemptResult {
         ok: false,
         attempts,
+        attempts_detail: timeline.records,
         http_status: last_status,
         error: Some(last_error.unwrap_or_else(|| "mock-http failed".to_string())),
         endpoint_masked: Some(mask_endpoint(endpoint)),
     })
 }
 
+/// Parses a `delay-<ms>` mock-http step: a 200 response that arrives after `ms`,
+/// or a timeout when the delay exceeds the policy timeout.
+fn mock_delay_step(step: &str) -> Option<Duration> {
+    step.strip_prefix("delay-")
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_millis)
+}
+
 async fn simulate_mock_http(endpoint: &str, policy: &RetryPolicy) -> Result<DeliveryAttemptResult> {
     let sequence = endpoint.trim_start_matches("mock-http://");
     if sequence.trim().is_empty() {
         return Ok(DeliveryAttemptResult {
             ok: true,
             attempts: 1,
+            attempts_detail: immediate_success_detail(),
             http_status: Some(200),
             error: None,
             endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -969,32 +1206,46 @@ async fn simulate_mock_http(endpoint: &str, policy: &RetryPolicy) -> Result<Deli
         .collect::<Vec<_>>();
 
     let mut attempts = 0usize;
+    let mut timeline = AttemptTimeline::new();
     let mut last_status: Option<u16> = None;
     let mut last_error: Option<String> = None;
 
     for (idx, step) in steps.iter().enumerate() {
         attempts += 1;
-        if let Some(delay) = policy.backoff_before_attempt(idx) {
-            tokio::time::sleep(delay).await;
-        }
+        timeline.begin(policy, idx).await;
 
         if step == "timeout" {
             last_error = Some("webhook request timed out".to_string());
+            timeline.record_error(last_error.as_deref());
             continue;
         }
+        if let Some(delay) = mock_delay_step(step) {
+            if delay > policy.timeout {
+                tokio::time::sleep(policy.timeout).await;
+                last_error = Some("webhook request timed out".to_string());
+                timeline.record_error(last_error.as_deref());
+                continue;
+            }
+            tokio::time::sleep(delay).await;
+        }
 
-        let status = step.parse::<u16>().map_err(|_| {
-            MosaicError::Validation(format!(
-                "invalid mock-http response step '{}' in endpoint {}",
-                step, endpoint
-            ))
-        })?;
+        let status = match mock_delay_step(step) {
+            Some(_) => 200,
+            None => step.parse::<u16>().map_err(|_| {
+                MosaicError::Validation(format!(
+                    "invalid mock-http response step '{}' in endpoint {}",
+                    step, endpoint
+                ))
+            })?,
+        };
 
         last_status = Some(status);
+        timeline.record_status(status);
         if (200..300).contains(&status) {
             return Ok(DeliveryAttemptResult {
                 ok: true,
                 attempts,
+                attempts_detail: timeline.records,
                 http_status: Some(status),
                 error: None,
                 endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -1002,21 +1253,26 @@ async fn simulate_mock_http(endpoint: &str, policy: &RetryPolicy) -> Result<Deli
         }
 
         if (400..500).contains(&status) {
+            let error = format!("webhook returned client error status {status}");
+            timeline.record_error(Some(&error));
             return Ok(DeliveryAttemptResult {
                 ok: false,
                 attempts,
+                attempts_detail: timeline.records,
                 http_status: Some(status),
-                error: Some(format!("webhook returned client error status {status}")),
+                error: Some(error),
                 endpoint_masked: Some(mask_endpoint(endpoint)),
             });
         }
 
         last_error = Some(format!("webhook returned server error status {status}"));
+        timeline.record_error(last_error.as_deref());
     }
 
     Ok(DeliveryAttemptResult {
         ok: false,
         attempts,
+        attempts_detail: timeline.records,
         http_status: last_status,
         error: Some(last_error.unwrap_or_else(|| "mock-http failed".to_string())),
         endpoint_masked: Some(mask_endpoint(endpoint)),
@@ -1027,6 +1283,7 @@ fn local_delivery_success() -> DeliveryAttemptResult {
     DeliveryAttemptResult {
         ok: true,
         attempts: 1,
+        attempts_detail: immediate_success_detail(),
         http_status: Some(200),
         error: None,
         endpoint_masked: None,
@@ -1035,12 +1292,52 @@ fn local_delivery_success() -> DeliveryAttemptResult {
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
-
     use serde_json::json;
 
     use super::*;
 
+    #[test]
+    fn markdown_fenced_code_becomes_slack_section() {
+        let text =
+            "# Deploy\nShipped **v2**, see [notes](https://example.com).\n\n```\ncargo build\n```";
+        let blocks = markdown_to_slack_blocks(text).expect("blocks");
+        assert_eq!(
+            blocks,
+            vec![
+                json!({ "type": "section", "text": {
+                    "type": "mrkdwn",
+                    "text": "*Deploy*\nShipped *v2*, see <https://example.com|notes>.",
+                }}),
+                json!({ "type": "section", "text": {
+                    "type": "mrkdwn",
+                    "text": "```\ncargo build\n```",
+                }}),
+            ]
+        );
+        assert_eq!(markdown_to_slack_blocks("  \n"), None);
+    }
+
+    #[test]
+    fn long_code_block_splits_on_lines_with_fences_per_chunk() {
+        let line = "x".repeat(100);
+        let code = vec![line.as_str(); 40].join("\n");
+        let text = format!("```\n{code}\n```");
+        let blocks = markdown_to_slack_blocks(&text).expect("blocks");
+        assert_eq!(blocks.len(), 2);
+        let mut lines = 0;
+        for block in &blocks {
+            let chunk = block["text"]["text"].as_str().expect("text");
+            assert!(chunk.chars().count() <= SLACK_SECTION_TEXT_LIMIT);
+            let body = chunk
+                .strip_prefix("```\n")
+                .and_then(|rest| rest.strip_suffix("\n```"))
+                .expect("fenced chunk");
+            assert!(body.lines().all(|code_line| code_line == line));
+            lines += body.lines().count();
+        }
+        assert_eq!(lines, 40);
+    }
+
     #[test]
     fn registry_resolves_kind_aliases() {
         assert_eq!(resolve_kind("slack"), Some("slack_webhook".to_string()));
@@ -1075,6 +1372,7 @@ mod tests {
                 text: "hello",
                 parse_mode: None,
                 bearer_token: None,
+                slack_blocks: false,
             },
             &policy,
         )
@@ -1085,6 +1383,62 @@ mod tests {
         assert_eq!(result.http_status, Some(200));
     }
 
+    #[tokio::test]
+    async fn mock_http_records_attempt_timeline() {
+        let policy = RetryPolicy {
+            timeout: Duration::from_millis(10),
+            backoff_ms: vec![5, 7, 9],
+        };
+        let result = dispatch_send(
+            "webhook",
+            ChannelDispatchRequest {
+                channel_id: "ch_1",
+                channel_name: "demo",
+                endpoint: Some("mock-http://500,500,200"),
+                target: None,
+                text: "hello",
+                parse_mode: None,
+                bearer_token: None,
+                slack_blocks: false,
+            },
+            &policy,
+        )
+        .await
+        .expect("send");
+        assert!(result.ok);
+        assert_eq!(result.attempts, 3);
+        let detail = &result.attempts_detail;
+        assert_eq!(detail.len(), 3);
+        assert_eq!(
+            detail
+                .iter()
+                .map(|record| record.attempt)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            detail
+                .iter()
+                .map(|record| record.backoff_ms)
+                .collect::<Vec<_>>(),
+            vec![0, 5, 7]
+        );
+        assert_eq!(
+            detail
+                .iter()
+                .map(|record| record.http_status)
+                .collect::<Vec<_>>(),
+            vec![Some(500), Some(500), Some(200)]
+        );
+        assert_eq!(
+            detail[0].error.as_deref(),
+            Some("webhook returned server error status 500")
+        );
+        assert_eq!(detail[2].error, None);
+        assert!(detail[1].started_offset_ms >= 5);
+        assert!(detail[2].started_offset_ms >= detail[1].started_offset_ms + 7);
+    }
+
     #[tokio::test]
     async fn telegram_mock_http_retries_on_429() {
         let policy = RetryPolicy {
@@ -1101,6 +1455,7 @@ mod tests {
                 text: "hello",
                 parse_mode: Some("MarkdownV2"),
                 bearer_token: Some("test-token"),
+                slack_blocks: false,
             },
             &policy,
         )
@@ -8,26 +8,34 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use mosaic_core::error::{MosaicError, Result};
-use mosaic_core::privacy::{append_sanitized_jsonl, write_pretty_state_json_file};
+use mosaic_core::privacy::{
+    append_sanitized_jsonl, render_sanitized_jsonl, write_pretty_state_json_file,
+};
+use mosaic_core::state::{StateFileLock, lock_state_file, write_atomic};
 
 use crate::policy::RetryPolicy;
 use crate::providers;
 use crate::schema::{
-    CHANNELS_SCHEMA_VERSION, DEFAULT_CHANNEL_TOKEN_ENV, mask_optional_endpoint,
-    mask_optional_target, normalize_channels, normalize_kind, parse_channels_value,
-    validate_channel_for_kind,
+    CHANNELS_SCHEMA_VERSION, DEFAULT_CHANNEL_TOKEN_ENV, format_channel_with_env,
+    mask_optional_endpoint, mask_optional_target, normalize_channels, normalize_kind,
+    parse_channels_value, validate_channel_for_kind,
 };
 use crate::types::{
     AddChannelInput, ChannelAuthConfig, ChannelCapability, ChannelCapabilityDiagnostics,
-    ChannelDirectoryEntry, ChannelEntry, ChannelImportSummary, ChannelListItem, ChannelLogEntry,
-    ChannelLoginResult, ChannelReplayPayload, ChannelSendOptions, ChannelSendResult, ChannelStatus,
-    ChannelTemplateDefaults, ChannelTokenRotationItem, ChannelTokenRotationSummary, ChannelsFile,
-    DoctorCheck, RotateTokenEnvInput, TEXT_PREVIEW_LIMIT, UpdateChannelInput, truncate_text,
+    ChannelDeadLetterEntry, ChannelDeadLetterReplay, ChannelDirectoryEntry, ChannelEntry,
+    ChannelImportSummary, ChannelListItem, ChannelLogEntry, ChannelLoginResult, ChannelMetrics,
+    ChannelMetricsCounts, ChannelReplayPayload, ChannelSendOptions, ChannelSendResult,
+    ChannelStatus, ChannelTemplateDefaults, ChannelTokenRotationItem, ChannelTokenRotationSummary,
+    ChannelsFile, DoctorCheck, RotateTokenEnvInput, TEXT_PREVIEW_LIMIT, UpdateChannelInput,
+    truncate_text,
 };
 
 const CACHE_TTL_SECONDS: i64 = 300;
 const DEFAULT_TELEGRAM_MIN_INTERVAL_MS: u64 = 800;
 const DEFAULT_IDEMPOTENCY_WINDOW_SECONDS: i64 = 86_400;
+/// How long an in-flight idempotency key blocks other sends. Well past the
+/// longest retry schedule, so only reservations left by a crashed process expire.
+const IDEMPOTENCY_RESERVATION_TTL_SECONDS: i64 = 600;
 
 #[derive(Debug, Clone)]
 pub struct ChannelRepository {
@@ -35,6 +43,8 @@ pub struct ChannelRepository {
     events_dir: PathBuf,
     cache_dir: PathBuf,
     rate_dir: PathBuf,
+    idempotency_dir: PathBuf,
+    deadletter_dir: PathBuf,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +58,40 @@ struct ChannelRateState {
     last_sent_at: DateTime<Utc>,
 }
 
+/// Idempotency keys delivered through one channel, kept in
+/// `channel-idempotency/<channel_id>.json` until the dedup window passes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ChannelIdempotencyState {
+    #[serde(default)]
+    keys: BTreeMap<String, ChannelIdempotencyRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChannelIdempotencyRecord {
+    /// When the key was delivered, or reserved while `in_flight`.
+    delivered_at: DateTime<Utc>,
+    http_status: Option<u16>,
+    #[serde(default)]
+    in_flight: bool,
+}
+
+impl ChannelIdempotencyRecord {
+    fn is_live(&self, now: DateTime<Utc>) -> bool {
+        let ttl = if self.in_flight {
+            Duration::seconds(IDEMPOTENCY_RESERVATION_TTL_SECONDS)
+        } else {
+            idempotency_window()
+        };
+        now - self.delivered_at <= ttl
+    }
+}
+
+enum IdempotencyReservation {
+    Reserved,
+    /// Already delivered within the dedup window, with that delivery's status.
+    Delivered(Option<u16>),
+}
+
 impl ChannelRepository {
     pub fn new(channels_path: PathBuf, events_dir: PathBuf) -> Self {
         let cache_parent = events_dir
@@ -56,38 +100,31 @@ impl ChannelRepository {
             .unwrap_or_else(|| events_dir.clone());
         let cache_dir = cache_parent.join("channel-cache");
         let rate_dir = cache_parent.join("channel-rate");
+        let idempotency_dir = cache_parent.join("channel-idempotency");
+        let deadletter_dir = cache_parent.join("channel-deadletter");
         Self {
             channels_path,
             events_dir,
             cache_dir,
             rate_dir,
+            idempotency_dir,
+            deadletter_dir,
         }
     }
 
     pub fn list(&self) -> Result<Vec<ChannelListItem>> {
+        self.list_with_env(|name| std::env::var(name).ok())
+    }
+
+    fn list_with_env(
+        &self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Vec<ChannelListItem>> {
         let file = self.load_channels_file()?;
         let items = file
             .channels
-            .into_iter()
-            .map(|channel| {
-                let target_masked = mask_optional_target(
-                    &channel.kind,
-                    channel.target.as_deref(),
-                    channel.endpoint.as_deref(),
-                );
-                ChannelListItem {
-                    id: channel.id,
-                    name: channel.name,
-                    kind: channel.kind,
-                    endpoint_masked: mask_optional_endpoint(channel.endpoint.as_deref()),
-                    target_masked,
-                    has_template_defaults: channel.template_defaults.is_some(),
-                    created_at: channel.created_at,
-                    last_login_at: channel.last_login_at,
-                    last_send_at: channel.last_send_at,
-                    last_error: channel.last_error,
-                }
-            })
+            .iter()
+            .map(|channel| format_channel_with_env(channel, &lookup))
             .collect::<Vec<_>>();
         Ok(items)
     }
@@ -112,6 +149,7 @@ impl ChannelRepository {
         normalize_channels(&mut imported_channels)?;
         validate_import_uniqueness(&imported_channels)?;
 
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut existing = self.load_channels_file()?;
         let mut imported = 0usize;
         let mut updated = 0usize;
@@ -217,6 +255,7 @@ impl ChannelRepository {
         let normalized_kind = kind.map(|value| normalize_kind(&value)).transpose()?;
         let target_channel_id = channel_id.as_deref();
 
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let mut total = 0usize;
         let mut updated = 0usize;
@@ -342,6 +381,7 @@ impl ChannelRepository {
             token_env,
             template_defaults,
         } = input;
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let name = name.trim();
         if name.is_empty() {
@@ -408,6 +448,7 @@ impl ChannelRepository {
             ));
         }
 
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let idx = file
             .channels
@@ -498,6 +539,7 @@ impl ChannelRepository {
     }
 
     pub fn login(&self, channel_id: &str, token_env: Option<&str>) -> Result<ChannelLoginResult> {
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let channel = file
             .channels
@@ -526,6 +568,7 @@ impl ChannelRepository {
     }
 
     pub fn logout(&self, channel_id: &str) -> Result<ChannelEntry> {
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let channel = file
             .channels
@@ -540,6 +583,7 @@ impl ChannelRepository {
     }
 
     pub fn remove(&self, channel_id: &str) -> Result<ChannelEntry> {
+        let _lock = lock_state_file(&self.channels_path)?;
         let mut file = self.load_channels_file()?;
         let idx = file
             .channels
@@ -553,6 +597,14 @@ impl ChannelRepository {
         if event_path.exists() {
             let _ = std::fs::remove_file(event_path);
         }
+        for path in [
+            self.idempotency_path(channel_id),
+            self.deadletter_path(channel_id),
+        ] {
+            if path.exists() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
 
         Ok(removed)
     }
@@ -588,14 +640,31 @@ impl ChannelRepository {
             ));
         }
 
-        let mut file = self.load_channels_file()?;
-        let idx = file
+        let channel = self
+            .load_channels_file()?
             .channels
-            .iter()
-            .position(|entry| entry.id == channel_id)
+            .into_iter()
+            .find(|entry| entry.id == channel_id)
             .ok_or_else(|| MosaicError::Config(format!("channel '{channel_id}' not found")))?;
-        let channel = file.channels[idx].clone();
+        if options.timeout_ms == Some(0) {
+            return Err(MosaicError::Validation(
+                "send timeout_ms must be greater than 0".to_string(),
+            ));
+        }
 
+        let dead_letter = options.dead_letter.then(|| ChannelDeadLetterEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            ts: Utc::now(),
+            channel_id: channel.id.clone(),
+            text: text.to_string(),
+            options: ChannelSendOptions {
+                dead_letter: false,
+                ..options.clone()
+            },
+            attempts: 0,
+            http_status: None,
+            error: None,
+        });
         let token_env = token_env_override
             .or_else(|| channel.auth.token_env.clone())
             .or_else(|| providers::default_token_env_for_kind(&channel.kind).map(str::to_string));
@@ -611,9 +680,27 @@ impl ChannelRepository {
         } else {
             options.blocks
         };
+        let slack_blocks = !options.plain_text && merged_blocks.is_empty();
         let merged_metadata = options.metadata.or(default_template.metadata);
+        let caller_variables = normalize_template_variables(options.variables)?;
+        // Plain text is only rendered when the caller asked for substitution;
+        // channel defaults alone must not make literal `{{...}}` text fail.
+        let render_text = !caller_variables.is_empty();
+        let mut variables = default_template.variables;
+        variables.extend(caller_variables);
+        let strict = options.strict.unwrap_or(true);
+        let body = match default_template.template.as_deref() {
+            Some(template) => {
+                variables
+                    .entry("text".to_string())
+                    .or_insert_with(|| text.trim().to_string());
+                render_template_variables(template, &variables, strict)?
+            }
+            None if render_text => render_template_variables(text, &variables, strict)?,
+            None => text.to_string(),
+        };
         let rendered_text = render_message_template(
-            text,
+            &body,
             merged_title.as_deref(),
             &merged_blocks,
             merged_metadata.as_ref(),
@@ -624,10 +711,16 @@ impl ChannelRepository {
             parse_mode: parse_mode.clone(),
             idempotency_key: idempotency_key.clone(),
         });
-        if !probe
+        // The key is reserved before sending and settled afterwards, each
+        // under a short lock, so concurrent sends with the same key cannot
+        // both deliver and no lock is held across the network call.
+        let reservation = match idempotency_key.as_deref() {
+            Some(key) if !probe => Some(self.reserve_idempotency_key(&channel.id, key)?),
+            _ => None,
+        };
+        let reserved = matches!(reservation, Some(IdempotencyReservation::Reserved));
+        if let Some(IdempotencyReservation::Delivered(previous_http_status)) = reservation
             && let Some(key) = idempotency_key.as_deref()
-            && let Some(previous_http_status) =
-                self.find_recent_successful_idempotent(&channel.id, key)?
         {
             let event = ChannelLogEntry {
                 ts: Utc::now(),
@@ -635,6 +728,7 @@ impl ChannelRepository {
                 kind: send_kind.to_string(),
                 delivery_status: "deduplicated".to_string(),
                 attempt: 0,
+                attempts_detail: Vec::new(),
                 http_status: previous_http_status,
                 error: None,
                 text_preview,
@@ -645,15 +739,17 @@ impl ChannelRepository {
                 replay_payload: replay_payload.clone(),
             };
             let event_path = self.append_event(&channel.id, &event)?;
-            file.channels[idx].last_send_at = Some(Utc::now());
-            file.channels[idx].last_error = None;
-            self.save_channels_file(&file)?;
+            self.update_channel_entry(&channel.id, |entry| {
+                entry.last_send_at = Some(Utc::now());
+                entry.last_error = None;
+            })?;
 
             return Ok(ChannelSendResult {
                 channel_id: channel.id,
                 delivered_via: channel.kind.clone(),
                 kind: send_kind.to_string(),
                 attempts: 0,
+                attempts_detail: Vec::new(),
                 http_status: previous_http_status,
                 endpoint_masked: mask_optional_endpoint(channel.endpoint.as_deref()),
                 target_masked: mask_optional_target(
@@ -670,22 +766,37 @@ impl ChannelRepository {
             });
         }
 
-        let rate_limited_ms = self.apply_telegram_rate_limit(&channel, probe).await?;
-        let retry_policy = RetryPolicy::from_env();
-        let delivery = providers::dispatch_send(
-            &channel.kind,
-            providers::ChannelDispatchRequest {
-                channel_id: &channel.id,
-                channel_name: &channel.name,
-                endpoint: channel.endpoint.as_deref(),
-                target: channel.target.as_deref(),
-                text: &rendered_text,
-                parse_mode: parse_mode.as_deref(),
-                bearer_token: token.as_deref(),
-            },
-            &retry_policy,
-        )
-        .await?;
+        let outcome = async {
+            let rate_limited_ms = self.apply_telegram_rate_limit(&channel, probe).await?;
+            let retry_policy = RetryPolicy::from_env().with_timeout_ms(options.timeout_ms);
+            let delivery = providers::dispatch_send(
+                &channel.kind,
+                providers::ChannelDispatchRequest {
+                    channel_id: &channel.id,
+                    channel_name: &channel.name,
+                    endpoint: channel.endpoint.as_deref(),
+                    target: channel.target.as_deref(),
+                    text: &rendered_text,
+                    parse_mode: parse_mode.as_deref(),
+                    bearer_token: token.as_deref(),
+                    slack_blocks,
+                },
+                &retry_policy,
+            )
+            .await?;
+            Ok::<_, MosaicError>((rate_limited_ms, delivery))
+        }
+        .await;
+        if reserved && let Some(key) = idempotency_key.as_deref() {
+            let delivered = outcome.as_ref().ok().filter(|(_, delivery)| delivery.ok);
+            self.finish_idempotency_key(
+                &channel.id,
+                key,
+                delivered.is_some(),
+                delivered.and_then(|(_, delivery)| delivery.http_status),
+            )?;
+        }
+        let (rate_limited_ms, delivery) = outcome?;
 
         let event = ChannelLogEntry {
             ts: Utc::now(),
@@ -697,6 +808,7 @@ impl ChannelRepository {
                 "failed".to_string()
             },
             attempt: delivery.attempts,
+            attempts_detail: delivery.attempts_detail.clone(),
             http_status: delivery.http_status,
             error: delivery.error.clone(),
             text_preview,
@@ -708,15 +820,16 @@ impl ChannelRepository {
         };
         let event_path = self.append_event(&channel.id, &event)?;
 
-        if delivery.ok {
-            if !probe {
-                file.channels[idx].last_send_at = Some(Utc::now());
+        self.update_channel_entry(&channel.id, |entry| {
+            if delivery.ok {
+                if !probe {
+                    entry.last_send_at = Some(Utc::now());
+                }
+                entry.last_error = None;
+            } else {
+                entry.last_error = delivery.error.clone();
             }
-            file.channels[idx].last_error = None;
-        } else {
-            file.channels[idx].last_error = delivery.error.clone();
-        }
-        self.save_channels_file(&file)?;
+        })?;
 
         if delivery.ok {
             let target_masked = mask_optional_target(
@@ -729,6 +842,7 @@ impl ChannelRepository {
                 delivered_via: channel.kind,
                 kind: send_kind.to_string(),
                 attempts: delivery.attempts,
+                attempts_detail: delivery.attempts_detail,
                 http_status: delivery.http_status,
                 endpoint_masked: delivery.endpoint_masked,
                 target_masked,
@@ -741,6 +855,14 @@ impl ChannelRepository {
             });
         }
 
+        if !probe && let Some(mut entry) = dead_letter {
+            entry.ts = Utc::now();
+            entry.attempts = delivery.attempts;
+            entry.http_status = delivery.http_status;
+            entry.error = delivery.error.clone();
+            self.append_deadletter(&entry)?;
+        }
+
         Err(MosaicError::Network(
             delivery
                 .error
@@ -748,6 +870,73 @@ impl ChannelRepository {
         ))
     }
 
+    /// Failed sends kept for `channel_id`, oldest first.
+    pub fn deadletter(&self, channel_id: &str) -> Result<Vec<ChannelDeadLetterEntry>> {
+        let path = self.deadletter_path(channel_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = std::fs::read_to_string(&path)?;
+        raw.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<ChannelDeadLetterEntry>(line).map_err(|err| {
+                    MosaicError::Validation(format!(
+                        "invalid channel dead-letter entry {}: {err}",
+                        path.display()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Re-sends every dead-lettered message for `channel_id` with its original
+    /// options and drops the ones that are delivered. Entries that fail again
+    /// stay in the file unchanged.
+    pub async fn replay_deadletter(&self, channel_id: &str) -> Result<ChannelDeadLetterReplay> {
+        let entries = self.deadletter(channel_id)?;
+        let mut delivered = HashSet::new();
+        let mut errors = BTreeMap::new();
+        for entry in &entries {
+            match self
+                .send_with_options(channel_id, &entry.text, None, false, entry.options.clone())
+                .await
+            {
+                Ok(_) => {
+                    delivered.insert(entry.id.clone());
+                }
+                Err(err) => {
+                    errors.insert(entry.id.clone(), err.to_string());
+                }
+            }
+        }
+
+        // Re-read under the lock so sends that failed during the replay are kept.
+        let path = self.deadletter_path(channel_id);
+        let _lock = lock_state_file(&path)?;
+        let remaining = self
+            .deadletter(channel_id)?
+            .into_iter()
+            .filter(|entry| !delivered.contains(&entry.id))
+            .collect::<Vec<_>>();
+        if remaining.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+        } else {
+            let raw = render_sanitized_jsonl(remaining.iter(), "channel dead-letter persistence")?;
+            write_atomic(&path, raw)?;
+        }
+
+        Ok(ChannelDeadLetterReplay {
+            channel_id: channel_id.to_string(),
+            attempted: entries.len(),
+            delivered: delivered.len(),
+            remaining: remaining.len(),
+            errors,
+        })
+    }
+
     pub fn logs(&self, channel_filter: Option<&str>, tail: usize) -> Result<Vec<ChannelLogEntry>> {
         if !self.events_dir.exists() {
             return Ok(Vec::new());
@@ -786,7 +975,7 @@ impl ChannelRepository {
             }
         }
 
-        events.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+        events.sort_by_key(|event| event.ts);
         if events.len() > tail {
             let keep_from = events.len() - tail;
             events = events.split_off(keep_from);
@@ -794,6 +983,37 @@ impl ChannelRepository {
         Ok(events)
     }
 
+    /// Aggregates channel events from the last `window` into per-channel and
+    /// overall delivery counts.
+    pub fn metrics(&self, window: Duration) -> Result<ChannelMetrics> {
+        if window <= Duration::zero() {
+            return Err(MosaicError::Validation(
+                "metrics window must be greater than 0".to_string(),
+            ));
+        }
+        let since = Utc::now() - window;
+        let mut totals = ChannelMetricsCounts::default();
+        let mut channels = BTreeMap::<String, ChannelMetricsCounts>::new();
+        for event in self.logs(None, usize::MAX)? {
+            if event.ts < since {
+                continue;
+            }
+            record_metrics_event(&mut totals, &event);
+            record_metrics_event(
+                channels.entry(event.channel_id.clone()).or_default(),
+                &event,
+            );
+        }
+        finish_metrics_counts(&mut totals);
+        channels.values_mut().for_each(finish_metrics_counts);
+        Ok(ChannelMetrics {
+            since,
+            window_seconds: window.num_seconds(),
+            totals,
+            channels,
+        })
+    }
+
     pub fn capabilities(
         &self,
         kind: Option<&str>,
@@ -1012,6 +1232,34 @@ impl ChannelRepository {
         Ok(checks)
     }
 
+    /// Rewrites the channels file at the current schema version when it is stored
+    /// in a legacy layout. Returns the version found on disk when a rewrite happened.
+    pub fn migrate(&self) -> Result<Option<u32>> {
+        if !self.channels_path.exists() {
+            return Ok(None);
+        }
+        let _lock = lock_state_file(&self.channels_path)?;
+        let raw = std::fs::read_to_string(&self.channels_path)?;
+        let value: Value = serde_json::from_str(&raw).map_err(|err| {
+            MosaicError::Validation(format!(
+                "invalid channels JSON {}: {err}",
+                self.channels_path.display()
+            ))
+        })?;
+        let previous_version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map_or(1, |version| version as u32);
+        let (mut file, migrated) = parse_channels_value(value)?;
+        if !migrated {
+            return Ok(None);
+        }
+        normalize_channels(&mut file.channels)?;
+        file.version = CHANNELS_SCHEMA_VERSION;
+        self.save_channels_file(&file)?;
+        Ok(Some(previous_version))
+    }
+
     fn load_channels_file(&self) -> Result<ChannelsFile> {
         if !self.channels_path.exists() {
             return Ok(ChannelsFile {
@@ -1037,6 +1285,26 @@ impl ChannelRepository {
         Ok(file)
     }
 
+    /// Re-reads the channels file under the state lock before applying `update`,
+    /// so a slow send cannot overwrite edits made while it was in flight.
+    fn update_channel_entry(
+        &self,
+        channel_id: &str,
+        update: impl FnOnce(&mut ChannelEntry),
+    ) -> Result<()> {
+        let _lock = lock_state_file(&self.channels_path)?;
+        let mut file = self.load_channels_file()?;
+        let Some(entry) = file
+            .channels
+            .iter_mut()
+            .find(|entry| entry.id == channel_id)
+        else {
+            return Ok(());
+        };
+        update(entry);
+        self.save_channels_file(&file)
+    }
+
     fn save_channels_file(&self, file: &ChannelsFile) -> Result<()> {
         write_pretty_state_json_file(&self.channels_path, file, "channels state")
     }
@@ -1048,45 +1316,150 @@ impl ChannelRepository {
         Ok(path)
     }
 
-    fn find_recent_successful_idempotent(
+    fn lock_idempotency_state(&self, channel_id: &str) -> Result<StateFileLock> {
+        std::fs::create_dir_all(&self.idempotency_dir)?;
+        lock_state_file(&self.idempotency_path(channel_id))
+    }
+
+    /// Checks `idempotency_key` against earlier sends through `channel_id`
+    /// and, when it is new, marks it in flight so concurrent sends see it.
+    /// A key another send still has in flight is rejected rather than sent twice.
+    fn reserve_idempotency_key(
+        &self,
+        channel_id: &str,
+        idempotency_key: &str,
+    ) -> Result<IdempotencyReservation> {
+        let _lock = self.lock_idempotency_state(channel_id)?;
+        let mut state = self.load_idempotency_state(channel_id)?;
+        let now = Utc::now();
+        if let Some(record) = state
+            .keys
+            .get(idempotency_key)
+            .filter(|record| record.is_live(now))
+        {
+            if record.in_flight {
+                return Err(MosaicError::Validation(format!(
+                    "a send with idempotency key '{idempotency_key}' is already in progress on channel '{channel_id}'; retry once it finishes"
+                )));
+            }
+            return Ok(IdempotencyReservation::Delivered(record.http_status));
+        }
+        state.keys.insert(
+            idempotency_key.to_string(),
+            ChannelIdempotencyRecord {
+                delivered_at: now,
+                http_status: None,
+                in_flight: true,
+            },
+        );
+        self.save_idempotency_state(channel_id, &state)?;
+        Ok(IdempotencyReservation::Reserved)
+    }
+
+    /// Settles a key reserved by [`Self::reserve_idempotency_key`]: a delivered
+    /// key is remembered for the dedup window, a failed one is released so it
+    /// can be retried. Expired keys are dropped on the way.
+    fn finish_idempotency_key(
         &self,
         channel_id: &str,
         idempotency_key: &str,
-    ) -> Result<Option<Option<u16>>> {
+        delivered: bool,
+        http_status: Option<u16>,
+    ) -> Result<()> {
+        let _lock = self.lock_idempotency_state(channel_id)?;
+        let mut state = self.load_idempotency_state(channel_id)?;
+        let now = Utc::now();
+        state.keys.retain(|_, record| record.is_live(now));
+        if delivered {
+            state.keys.insert(
+                idempotency_key.to_string(),
+                ChannelIdempotencyRecord {
+                    delivered_at: now,
+                    http_status,
+                    in_flight: false,
+                },
+            );
+        } else {
+            state.keys.remove(idempotency_key);
+        }
+        self.save_idempotency_state(channel_id, &state)
+    }
+
+    fn save_idempotency_state(
+        &self,
+        channel_id: &str,
+        state: &ChannelIdempotencyState,
+    ) -> Result<()> {
+        write_pretty_state_json_file(
+            &self.idempotency_path(channel_id),
+            state,
+            "channel idempotency state",
+        )
+    }
+
+    /// Loads the sidecar for `channel_id`. Channels from before the sidecar
+    /// existed have none yet, so the first load seeds it from keys the event
+    /// log shows as delivered within the dedup window.
+    fn load_idempotency_state(&self, channel_id: &str) -> Result<ChannelIdempotencyState> {
+        let path = self.idempotency_path(channel_id);
+        if !path.exists() {
+            let state = self.idempotency_state_from_events(channel_id)?;
+            write_pretty_state_json_file(&path, &state, "channel idempotency state")?;
+            return Ok(state);
+        }
+        let raw = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&raw).map_err(|err| {
+            MosaicError::Validation(format!(
+                "invalid channel idempotency state {}: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    fn idempotency_state_from_events(&self, channel_id: &str) -> Result<ChannelIdempotencyState> {
+        let mut state = ChannelIdempotencyState::default();
         let path = self.events_dir.join(format!("{channel_id}.jsonl"));
         if !path.exists() {
-            return Ok(None);
+            return Ok(state);
         }
-        let window_seconds = std::env::var("MOSAIC_CHANNELS_IDEMPOTENCY_WINDOW_SECONDS")
-            .ok()
-            .and_then(|value| value.parse::<i64>().ok())
-            .unwrap_or(DEFAULT_IDEMPOTENCY_WINDOW_SECONDS)
-            .max(1);
         let now = Utc::now();
-        let raw = std::fs::read_to_string(path)?;
-        let mut latest_status: Option<Option<u16>> = None;
-        for line in raw.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
+        let window = idempotency_window();
+        let raw = std::fs::read_to_string(&path)?;
+        for line in raw.lines().filter(|line| !line.trim().is_empty()) {
             let event = serde_json::from_str::<ChannelLogEntry>(line).map_err(|err| {
                 MosaicError::Validation(format!("invalid channel event entry: {err}"))
             })?;
-            if event.kind != "message" {
-                continue;
-            }
-            if event.idempotency_key.as_deref() != Some(idempotency_key) {
-                continue;
-            }
-            if event.delivery_status != "success" && event.delivery_status != "deduplicated" {
+            let delivered = matches!(event.delivery_status.as_str(), "success" | "deduplicated");
+            if event.kind != "message" || !delivered || now - event.ts > window {
                 continue;
             }
-            if now - event.ts > Duration::seconds(window_seconds) {
-                continue;
+            if let Some(key) = event.idempotency_key {
+                state.keys.insert(
+                    key,
+                    ChannelIdempotencyRecord {
+                        delivered_at: event.ts,
+                        http_status: event.http_status,
+                        in_flight: false,
+                    },
+                );
             }
-            latest_status = Some(event.http_status);
         }
-        Ok(latest_status)
+        Ok(state)
+    }
+
+    fn append_deadletter(&self, entry: &ChannelDeadLetterEntry) -> Result<()> {
+        std::fs::create_dir_all(&self.deadletter_dir)?;
+        let path = self.deadletter_path(&entry.channel_id);
+        let _lock = lock_state_file(&path)?;
+        append_sanitized_jsonl(&path, entry, "channel dead-letter persistence")
+    }
+
+    fn deadletter_path(&self, channel_id: &str) -> PathBuf {
+        self.deadletter_dir.join(format!("{channel_id}.jsonl"))
+    }
+
+    fn idempotency_path(&self, channel_id: &str) -> PathBuf {
+        self.idempotency_dir.join(format!("{channel_id}.json"))
     }
 
     async fn apply_telegram_rate_limit(
@@ -1164,6 +1537,38 @@ impl ChannelRepository {
     }
 }
 
+fn record_metrics_event(counts: &mut ChannelMetricsCounts, event: &ChannelLogEntry) {
+    counts.events += 1;
+    counts.attempts += event.attempt;
+    match event.delivery_status.as_str() {
+        "success" => counts.successes += 1,
+        "deduplicated" => {
+            counts.successes += 1;
+            counts.deduplicated += 1;
+        }
+        "failed" => counts.failures += 1,
+        _ => {}
+    }
+    if event.kind == "test_probe" {
+        counts.probes += 1;
+    }
+    counts.last_event_at = counts.last_event_at.max(Some(event.ts));
+}
+
+fn finish_metrics_counts(counts: &mut ChannelMetricsCounts) {
+    let finished = counts.successes + counts.failures;
+    counts.success_rate = (finished > 0).then(|| counts.successes as f64 / finished as f64);
+}
+
+fn idempotency_window() -> Duration {
+    let seconds = std::env::var("MOSAIC_CHANNELS_IDEMPOTENCY_WINDOW_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_IDEMPOTENCY_WINDOW_SECONDS)
+        .max(1);
+    Duration::seconds(seconds)
+}
+
 fn normalize_optional(value: Option<String>) -> Option<String> {
     value.and_then(|raw| {
         let trimmed = raw.trim();
@@ -1223,8 +1628,16 @@ fn normalize_template_defaults_for_kind(
             Some(value)
         }
     });
-
-    if parse_mode.is_none() && title.is_none() && blocks.is_empty() && metadata.is_none() {
+    let template = normalize_optional(defaults.template);
+    let variables = normalize_template_variables(defaults.variables)?;
+
+    if parse_mode.is_none()
+        && title.is_none()
+        && blocks.is_empty()
+        && metadata.is_none()
+        && template.is_none()
+        && variables.is_empty()
+    {
         return Ok(None);
     }
 
@@ -1233,6 +1646,8 @@ fn normalize_template_defaults_for_kind(
         title,
         blocks,
         metadata,
+        template,
+        variables,
     }))
 }
 
@@ -1248,12 +1663,64 @@ fn merge_template_defaults(
         patch.blocks
     };
     let metadata = patch.metadata.or(current.metadata);
+    let template = patch.template.or(current.template);
+    let mut variables = current.variables;
+    variables.extend(patch.variables);
     ChannelTemplateDefaults {
         parse_mode,
         title,
         blocks,
         metadata,
+        template,
+        variables,
+    }
+}
+
+fn normalize_template_variables(
+    variables: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>> {
+    let mut normalized = BTreeMap::new();
+    for (name, value) in variables {
+        let name = name.trim();
+        if name.is_empty() || name.contains(['{', '}']) {
+            return Err(MosaicError::Validation(format!(
+                "invalid template variable name '{name}'"
+            )));
+        }
+        normalized.insert(name.to_string(), value);
+    }
+    Ok(normalized)
+}
+
+/// Replaces `{{name}}` placeholders in one pass, so substituted values are never
+/// expanded again. Unknown names fail when `strict`, otherwise stay as written.
+fn render_template_variables(
+    template: &str,
+    variables: &BTreeMap<String, String>,
+    strict: bool,
+) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let name = after_open[..end].trim();
+        match variables.get(name) {
+            Some(value) => rendered.push_str(value),
+            None if strict => {
+                return Err(MosaicError::Validation(format!(
+                    "template variable '{name}' has no value (pass it as a variable or disable strict mode)"
+                )));
+            }
+            None => rendered.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after_open[end + 2..];
     }
+    rendered.push_str(rest);
+    Ok(rendered)
 }
 
 fn normalize_parse_mode(parse_mode: Option<String>, kind: &str) -> Result<Option<String>> {
@@ -1356,6 +1823,7 @@ pub fn channels_events_dir(data_dir: &Path) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
+    use mosaic_core::state::lock_state_file_with_timeout;
     use serde_json::json;
     use tempfile::tempdir;
 
@@ -1388,6 +1856,68 @@ mod tests {
         assert!(list[0].last_send_at.is_none());
     }
 
+    #[tokio::test]
+    async fn send_timeout_override_fails_fast_on_slow_endpoint() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "slow".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://delay-200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+
+        let err = repo
+            .send_with_options(
+                &channel.id,
+                "hello",
+                None,
+                false,
+                ChannelSendOptions {
+                    timeout_ms: Some(0),
+                    ..ChannelSendOptions::default()
+                },
+            )
+            .await
+            .expect_err("zero timeout should be rejected");
+        assert!(matches!(err, MosaicError::Validation(_)));
+
+        let err = repo
+            .send_with_options(
+                &channel.id,
+                "hello",
+                None,
+                false,
+                ChannelSendOptions {
+                    timeout_ms: Some(10),
+                    ..ChannelSendOptions::default()
+                },
+            )
+            .await
+            .expect_err("slow endpoint should time out");
+        assert!(matches!(err, MosaicError::Network(_)));
+        assert!(err.to_string().contains("timed out"));
+
+        let result = repo
+            .send_with_options(
+                &channel.id,
+                "hello",
+                None,
+                false,
+                ChannelSendOptions::default(),
+            )
+            .await
+            .expect("default timeout send");
+        assert_eq!(result.http_status, Some(200));
+    }
+
     #[test]
     fn capabilities_and_resolve_work() {
         let temp = tempdir().expect("tempdir");
@@ -1417,7 +1947,7 @@ mod tests {
             .expect("capability diagnostics");
         assert_eq!(diagnostics.channel_id, channel.id);
         assert_eq!(diagnostics.channel_name, "alerts");
-        assert_eq!(diagnostics.ready_for_send, true);
+        assert!(diagnostics.ready_for_send);
         assert!(diagnostics.issues.is_empty());
 
         let resolved = repo.resolve("slack_webhook", "alert").expect("resolve");
@@ -1457,7 +1987,7 @@ mod tests {
             Some(crate::schema::DEFAULT_TELEGRAM_TOKEN_ENV)
         );
         assert_eq!(diagnostics.token_present, Some(false));
-        assert_eq!(diagnostics.ready_for_send, false);
+        assert!(!diagnostics.ready_for_send);
         assert!(
             diagnostics
                 .issues
@@ -1467,6 +1997,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn list_reports_token_presence_from_env() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let set_env = "MOSAIC_TEST_CHANNEL_LIST_TOKEN_SET";
+        let unset_env = "MOSAIC_TEST_CHANNEL_LIST_TOKEN_UNSET";
+        for (name, token_env) in [
+            ("ready", Some(set_env)),
+            ("missing", Some(unset_env)),
+            ("webhook", None),
+        ] {
+            repo.add(AddChannelInput {
+                name: name.to_string(),
+                kind: if token_env.is_some() {
+                    "telegram_bot".to_string()
+                } else {
+                    "slack_webhook".to_string()
+                },
+                endpoint: Some("mock-http://200".to_string()),
+                target: token_env.map(|_| "-10000009".to_string()),
+                token_env: token_env.map(str::to_string),
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+        }
+
+        let list = repo
+            .list_with_env(|name| (name == set_env).then(|| "token".to_string()))
+            .expect("list");
+        let presence = |name: &str| {
+            list.iter()
+                .find(|item| item.name == name)
+                .expect("channel listed")
+                .token_present
+        };
+        assert_eq!(presence("ready"), Some(true));
+        assert_eq!(presence("missing"), Some(false));
+        assert_eq!(presence("webhook"), None);
+    }
+
     #[test]
     fn update_channel_merges_template_defaults() {
         let temp = tempdir().expect("tempdir");
@@ -1496,6 +2069,7 @@ mod tests {
                         title: Some("Release".to_string()),
                         blocks: vec!["service=mosaic".to_string()],
                         metadata: Some(json!({ "env": "staging" })),
+                        ..ChannelTemplateDefaults::default()
                     }),
                     ..UpdateChannelInput::default()
                 },
@@ -1530,6 +2104,453 @@ mod tests {
         assert!(defaults.metadata.is_none());
     }
 
+    #[tokio::test]
+    async fn send_renders_template_variables_over_defaults() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults {
+                    template: Some("[{{env}}] {{text}} ({{ owner }})".to_string()),
+                    variables: BTreeMap::from([
+                        ("env".to_string(), "staging".to_string()),
+                        ("owner".to_string(), "ops".to_string()),
+                    ]),
+                    ..ChannelTemplateDefaults::default()
+                },
+            })
+            .expect("add");
+
+        repo.send_with_options(
+            &channel.id,
+            "deploy {{done}}",
+            None,
+            false,
+            ChannelSendOptions {
+                variables: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+                ..ChannelSendOptions::default()
+            },
+        )
+        .await
+        .expect("send");
+        let logs = repo.logs(Some(&channel.id), 1).expect("logs");
+        let payload = logs[0].replay_payload.as_ref().expect("payload");
+        assert_eq!(payload.text, "[prod] deploy {{done}} (ops)");
+
+        let plain = repo
+            .add(AddChannelInput {
+                name: "plain".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add plain");
+        let options = ChannelSendOptions {
+            variables: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+            ..ChannelSendOptions::default()
+        };
+        let err = repo
+            .send_with_options(
+                &plain.id,
+                "{{env}}: {{missing}}",
+                None,
+                false,
+                options.clone(),
+            )
+            .await
+            .map(|_| ())
+            .expect_err("strict unknown variable");
+        assert!(err.to_string().contains("'missing'"));
+        assert!(repo.logs(Some(&plain.id), 1).expect("logs").is_empty());
+
+        repo.send_with_options(
+            &plain.id,
+            "{{env}}: {{missing}}",
+            None,
+            false,
+            ChannelSendOptions {
+                strict: Some(false),
+                ..options
+            },
+        )
+        .await
+        .expect("lenient send");
+        let logs = repo.logs(Some(&plain.id), 1).expect("logs");
+        let payload = logs[0].replay_payload.as_ref().expect("payload");
+        assert_eq!(payload.text, "prod: {{missing}}");
+
+        let defaults_only = repo
+            .add(AddChannelInput {
+                name: "defaults-only".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults {
+                    variables: BTreeMap::from([("env".to_string(), "staging".to_string())]),
+                    ..ChannelTemplateDefaults::default()
+                },
+            })
+            .expect("add defaults-only");
+        repo.send_with_options(
+            &defaults_only.id,
+            "{{env}} uses {{ mustache }}",
+            None,
+            false,
+            ChannelSendOptions::default(),
+        )
+        .await
+        .expect("plain text with default vars only");
+        let logs = repo.logs(Some(&defaults_only.id), 1).expect("logs");
+        let payload = logs[0].replay_payload.as_ref().expect("payload");
+        assert_eq!(payload.text, "{{env}} uses {{ mustache }}");
+    }
+
+    #[tokio::test]
+    async fn idempotency_keys_persist_across_repository_instances() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+        let options = ChannelSendOptions {
+            idempotency_key: Some("release-42".to_string()),
+            ..ChannelSendOptions::default()
+        };
+
+        let first = repo
+            .send_with_options(&channel.id, "deploy", None, false, options.clone())
+            .await
+            .expect("first send");
+        assert!(!first.deduplicated);
+        assert_eq!(first.attempts, 1);
+
+        // A fresh repository stands in for a later process; the event log is
+        // gone, so only the idempotency sidecar can remember the key.
+        std::fs::remove_file(
+            channels_events_dir(temp.path()).join(format!("{}.jsonl", channel.id)),
+        )
+        .expect("remove events");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let second = repo
+            .send_with_options(&channel.id, "deploy", None, false, options)
+            .await
+            .expect("second send");
+        assert!(second.deduplicated);
+        assert_eq!(second.attempts, 0);
+        assert_eq!(second.http_status, Some(200));
+
+        let logs = repo.logs(Some(&channel.id), 10).expect("logs");
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].delivery_status, "deduplicated");
+        assert!(logs.iter().all(|event| event.attempt == 0));
+    }
+
+    #[tokio::test]
+    async fn idempotency_lock_is_not_held_while_sending() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://delay-1000".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+        let options = ChannelSendOptions {
+            idempotency_key: Some("release-42".to_string()),
+            ..ChannelSendOptions::default()
+        };
+        let sidecar = repo.idempotency_path(&channel.id);
+
+        let (first, concurrent) = tokio::join!(
+            repo.send_with_options(&channel.id, "deploy", None, false, options.clone()),
+            async {
+                // Wait until the first send has reserved the key and is sending.
+                for _ in 0..200 {
+                    let in_flight = std::fs::read_to_string(&sidecar)
+                        .is_ok_and(|raw| raw.contains("\"in_flight\": true"));
+                    if in_flight {
+                        break;
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                }
+                let lock =
+                    lock_state_file_with_timeout(&sidecar, std::time::Duration::from_millis(100));
+                assert!(lock.is_ok(), "sidecar lock held during send");
+                drop(lock);
+                repo.send_with_options(&channel.id, "deploy", None, false, options.clone())
+                    .await
+            }
+        );
+
+        let first = first.expect("first send");
+        assert!(!first.deduplicated);
+        let err = concurrent.expect_err("concurrent send with the same key");
+        assert!(err.to_string().contains("already in progress"));
+        let second = repo
+            .send_with_options(&channel.id, "deploy", None, false, options)
+            .await
+            .expect("later send");
+        assert!(second.deduplicated);
+        assert_eq!(second.http_status, Some(200));
+    }
+
+    #[tokio::test]
+    async fn failed_send_releases_its_idempotency_key() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://400".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+        let options = ChannelSendOptions {
+            idempotency_key: Some("release-42".to_string()),
+            ..ChannelSendOptions::default()
+        };
+
+        repo.send_with_options(&channel.id, "deploy", None, false, options.clone())
+            .await
+            .expect_err("client error");
+        repo.update_channel_entry(&channel.id, |entry| {
+            entry.endpoint = Some("mock-http://200".to_string());
+        })
+        .expect("point at a healthy endpoint");
+        let retried = repo
+            .send_with_options(&channel.id, "deploy", None, false, options)
+            .await
+            .expect("retried send");
+        assert!(!retried.deduplicated);
+        assert_eq!(retried.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn idempotency_sidecar_is_seeded_from_event_log() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://200".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+        let options = ChannelSendOptions {
+            idempotency_key: Some("release-42".to_string()),
+            ..ChannelSendOptions::default()
+        };
+        repo.send_with_options(&channel.id, "deploy", None, false, options.clone())
+            .await
+            .expect("first send");
+
+        // Drop the sidecar to mimic state written before it existed; only the
+        // event log still knows the key was delivered.
+        std::fs::remove_file(repo.idempotency_path(&channel.id)).expect("remove sidecar");
+        let second = repo
+            .send_with_options(&channel.id, "deploy", None, false, options)
+            .await
+            .expect("second send");
+        assert!(second.deduplicated);
+        assert_eq!(second.http_status, Some(200));
+        assert!(repo.idempotency_path(&channel.id).exists());
+    }
+
+    fn metrics_event(
+        channel_id: &str,
+        hours_ago: i64,
+        status: &str,
+        attempt: usize,
+    ) -> ChannelLogEntry {
+        ChannelLogEntry {
+            ts: Utc::now() - Duration::hours(hours_ago),
+            channel_id: channel_id.to_string(),
+            kind: "message".to_string(),
+            delivery_status: status.to_string(),
+            attempt,
+            attempts_detail: Vec::new(),
+            http_status: None,
+            error: None,
+            text_preview: "hello".to_string(),
+            parse_mode: None,
+            idempotency_key: None,
+            rate_limited_ms: None,
+            deduplicated: status == "deduplicated",
+            replay_payload: None,
+        }
+    }
+
+    #[test]
+    fn metrics_count_events_inside_window() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        for event in [
+            metrics_event("ch_a", 1, "success", 1),
+            metrics_event("ch_a", 2, "failed", 3),
+            metrics_event("ch_a", 3, "deduplicated", 0),
+            metrics_event("ch_a", 30, "failed", 3),
+            metrics_event("ch_b", 5, "success", 2),
+        ] {
+            repo.append_event(&event.channel_id.clone(), &event)
+                .expect("append event");
+        }
+
+        let metrics = repo.metrics(Duration::hours(24)).expect("metrics");
+        assert_eq!(metrics.window_seconds, 86_400);
+        assert_eq!(metrics.totals.events, 4);
+        assert_eq!(metrics.totals.successes, 3);
+        assert_eq!(metrics.totals.failures, 1);
+        assert_eq!(metrics.totals.attempts, 6);
+        let channel_a = &metrics.channels["ch_a"];
+        assert_eq!(
+            (channel_a.events, channel_a.successes, channel_a.failures),
+            (3, 2, 1)
+        );
+        assert_eq!(channel_a.deduplicated, 1);
+        assert_eq!(channel_a.attempts, 4);
+        assert!((channel_a.success_rate.expect("rate") - 2.0 / 3.0).abs() < 1e-9);
+
+        let recent = repo.metrics(Duration::hours(4)).expect("recent metrics");
+        assert_eq!(recent.totals.events, 3);
+        assert!(!recent.channels.contains_key("ch_b"));
+        assert!(repo.metrics(Duration::zero()).is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_sends_are_dead_lettered_and_replayed() {
+        let temp = tempdir().expect("tempdir");
+        let repo = ChannelRepository::new(
+            channels_file_path(temp.path()),
+            channels_events_dir(temp.path()),
+        );
+        let channel = repo
+            .add(AddChannelInput {
+                name: "alerts".to_string(),
+                kind: "slack_webhook".to_string(),
+                endpoint: Some("mock-http://400".to_string()),
+                target: None,
+                token_env: None,
+                template_defaults: ChannelTemplateDefaults::default(),
+            })
+            .expect("add");
+
+        repo.send_with_options(
+            &channel.id,
+            "lost without retry",
+            None,
+            false,
+            ChannelSendOptions::default(),
+        )
+        .await
+        .expect_err("send fails");
+        assert!(repo.deadletter(&channel.id).expect("deadletter").is_empty());
+
+        repo.send_with_options(
+            &channel.id,
+            "deploy {{env}}",
+            None,
+            false,
+            ChannelSendOptions {
+                title: Some("Release".to_string()),
+                variables: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+                dead_letter: true,
+                ..ChannelSendOptions::default()
+            },
+        )
+        .await
+        .expect_err("send fails");
+        let entries = repo.deadletter(&channel.id).expect("deadletter");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "deploy {{env}}");
+        assert_eq!(entries[0].http_status, Some(400));
+        assert!(!entries[0].options.dead_letter);
+
+        let replay = repo.replay_deadletter(&channel.id).await.expect("replay");
+        assert_eq!((replay.delivered, replay.remaining), (0, 1));
+        assert_eq!(replay.errors.len(), 1);
+
+        repo.update(
+            &channel.id,
+            UpdateChannelInput {
+                endpoint: Some("mock-http://200".to_string()),
+                ..UpdateChannelInput::default()
+            },
+        )
+        .expect("update endpoint");
+        let replay = repo.replay_deadletter(&channel.id).await.expect("replay");
+        assert_eq!(
+            (replay.attempted, replay.delivered, replay.remaining),
+            (1, 1, 0)
+        );
+        assert!(repo.deadletter(&channel.id).expect("deadletter").is_empty());
+        let logs = repo.logs(Some(&channel.id), 1).expect("logs");
+        let payload = logs[0].replay_payload.as_ref().expect("payload");
+        assert_eq!(payload.text, "Release\n\ndeploy prod");
+    }
+
+    #[test]
+    fn render_template_variables_strict_and_lenient() {
+        let variables = BTreeMap::from([("name".to_string(), "{{name}}".to_string())]);
+        assert_eq!(
+            render_template_variables("hi {{name}} {{", &variables, true).expect("render"),
+            "hi {{name}} {{"
+        );
+        let err = render_template_variables("{{name}} {{other}}", &variables, true)
+            .expect_err("strict unknown variable");
+        assert!(err.to_string().contains("template variable 'other'"));
+        assert_eq!(
+            render_template_variables("{{name}} {{other}}", &variables, false)
+                .expect("lenient render"),
+            "{{name}} {{other}}"
+        );
+    }
+
     #[test]
     fn update_channel_requires_changes() {
         let temp = tempdir().expect("tempdir");
@@ -1728,6 +2749,34 @@ mod tests {
         assert!(matches!(err, MosaicError::Validation(_)));
     }
 
+    #[test]
+    fn migrate_rewrites_legacy_channels_file_once() {
+        let temp = tempdir().expect("tempdir");
+        let path = channels_file_path(temp.path());
+        std::fs::write(
+            &path,
+            json!([{
+                "id": "ch_1",
+                "name": "legacy",
+                "kind": "webhook",
+                "endpoint": "https://example.com/webhook",
+                "created_at": "2026-02-10T00:00:00Z",
+                "last_login_at": null,
+                "last_login_token_env": null
+            }])
+            .to_string(),
+        )
+        .expect("write legacy channels");
+        let repo = ChannelRepository::new(path.clone(), channels_events_dir(temp.path()));
+
+        assert_eq!(repo.migrate().expect("migrate"), Some(1));
+        let stored: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).expect("read")).expect("json");
+        assert_eq!(stored["version"], CHANNELS_SCHEMA_VERSION);
+        assert_eq!(repo.migrate().expect("second migrate"), None);
+        assert_eq!(repo.list().expect("list").len(), 1);
+    }
+
     #[test]
     fn rotate_token_env_dry_run_does_not_persist() {
         let temp = tempdir().expect("tempdir");
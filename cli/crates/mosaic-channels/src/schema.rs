@@ -216,6 +216,17 @@ pub(crate) fn mask_endpoint(endpoint: &str) -> String {
 }
 
 pub fn format_channel_for_output(channel: &ChannelEntry) -> ChannelListItem {
+    format_channel_with_env(channel, |name| std::env::var(name).ok())
+}
+
+/// [`format_channel_for_output`] with `lookup` standing in for the process
+/// environment when reporting whether the token variable is set.
+pub(crate) fn format_channel_with_env(
+    channel: &ChannelEntry,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> ChannelListItem {
+    let token_env = normalize_optional_string(channel.auth.token_env.clone());
+    let token_present = token_env.as_deref().map(|env| lookup(env).is_some());
     ChannelListItem {
         id: channel.id.clone(),
         name: channel.name.clone(),
@@ -227,6 +238,8 @@ pub fn format_channel_for_output(channel: &ChannelEntry) -> ChannelListItem {
             channel.endpoint.as_deref(),
         ),
         has_template_defaults: channel.template_defaults.is_some(),
+        token_env,
+        token_present,
         created_at: channel.created_at,
         last_login_at: channel.last_login_at,
         last_send_at: channel.last_send_at,
@@ -255,6 +268,7 @@ fn normalize_template_defaults(
     }) {
         template.metadata = None;
     }
+    template.template = normalize_optional_string(template.template);
     if let Some(parse_mode) = template.parse_mode.clone() {
         if channel_kind != "telegram_bot" {
             return Err(MosaicError::Validation(
@@ -267,6 +281,8 @@ fn normalize_template_defaults(
         && template.title.is_none()
         && template.blocks.is_empty()
         && template.metadata.is_none()
+        && template.template.is_none()
+        && template.variables.is_empty()
     {
         *defaults = None;
     } else {
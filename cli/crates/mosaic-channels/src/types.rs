@@ -17,6 +17,12 @@ pub struct ChannelTemplateDefaults {
     #[serde(default)]
     pub blocks: Vec<String>,
     pub metadata: Option<Value>,
+    /// Message body with `{{name}}` placeholders; `{{text}}` is the send text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Fallback values for template placeholders not passed at send time.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +55,8 @@ pub struct ChannelListItem {
     pub endpoint_masked: Option<String>,
     pub target_masked: Option<String>,
     pub has_template_defaults: bool,
+    pub token_env: Option<String>,
+    pub token_present: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub last_login_at: Option<DateTime<Utc>>,
     pub last_send_at: Option<DateTime<Utc>>,
@@ -76,13 +84,51 @@ pub struct UpdateChannelInput {
     pub clear_template_defaults: bool,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChannelSendOptions {
     pub parse_mode: Option<String>,
     pub title: Option<String>,
     pub blocks: Vec<String>,
     pub idempotency_key: Option<String>,
     pub metadata: Option<Value>,
+    pub timeout_ms: Option<u64>,
+    /// Placeholder values, overriding the channel's default variables.
+    pub variables: BTreeMap<String, String>,
+    /// Unknown placeholders are an error unless this is `Some(false)`, which
+    /// leaves them in the text as written.
+    pub strict: Option<bool>,
+    /// Keep the text and options in the channel's dead-letter file when
+    /// delivery fails after all retries.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dead_letter: bool,
+    /// Send `slack_webhook` text as-is instead of converting Markdown to blocks.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub plain_text: bool,
+}
+
+/// A send that failed after all retries, as stored in
+/// `channel-deadletter/<channel_id>.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelDeadLetterEntry {
+    pub id: String,
+    pub ts: DateTime<Utc>,
+    pub channel_id: String,
+    pub text: String,
+    pub options: ChannelSendOptions,
+    pub attempts: usize,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelDeadLetterReplay {
+    pub channel_id: String,
+    pub attempted: usize,
+    pub delivered: usize,
+    pub remaining: usize,
+    /// Errors from entries that failed again, keyed by dead-letter entry id.
+    pub errors: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,12 +138,23 @@ pub struct ChannelReplayPayload {
     pub idempotency_key: Option<String>,
 }
 
+/// One delivery attempt; `started_offset_ms` is measured from the start of the send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    pub attempt: usize,
+    pub started_offset_ms: u64,
+    pub backoff_ms: u64,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ChannelSendResult {
     pub channel_id: String,
     pub delivered_via: String,
     pub kind: String,
     pub attempts: usize,
+    pub attempts_detail: Vec<AttemptRecord>,
     pub http_status: Option<u16>,
     pub endpoint_masked: Option<String>,
     pub target_masked: Option<String>,
@@ -118,6 +175,29 @@ pub struct ChannelStatus {
     pub last_send_at: Option<DateTime<Utc>>,
 }
 
+/// Delivery counts over a metrics window. Deduplicated sends count as
+/// successes; `success_rate` is `None` until something was delivered or failed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ChannelMetricsCounts {
+    pub events: usize,
+    pub successes: usize,
+    pub failures: usize,
+    pub deduplicated: usize,
+    pub probes: usize,
+    pub attempts: usize,
+    pub success_rate: Option<f64>,
+    pub last_event_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelMetrics {
+    pub since: DateTime<Utc>,
+    pub window_seconds: i64,
+    pub totals: ChannelMetricsCounts,
+    /// Keyed by channel id; channels without events in the window are omitted.
+    pub channels: BTreeMap<String, ChannelMetricsCounts>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelLogEntry {
     pub ts: DateTime<Utc>,
@@ -125,6 +205,8 @@ pub struct ChannelLogEntry {
     pub kind: String,
     pub delivery_status: String,
     pub attempt: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts_detail: Vec<AttemptRecord>,
     pub http_status: Option<u16>,
     pub error: Option<String>,
     pub text_preview: String,
@@ -1,13 +1,17 @@
-use serde_json::json;
+use serde_json::{Value, json};
 
 use mosaic_agents::{
     AddAgentInput, AgentStore, UpdateAgentInput, agent_routes_path, agents_file_path,
 };
 use mosaic_core::config::ConfigManager;
 use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::state::StatePaths;
 use mosaic_plugins::{ExtensionRegistry, RegistryRoots};
 
-use super::{AgentsArgs, AgentsCommand, AgentsRouteCommand, Cli, print_json, resolve_state_paths};
+use super::{
+    AgentsArgs, AgentsCommand, AgentsRouteCommand, Cli, ListFormatArg, print_json, render_table,
+    resolve_state_paths,
+};
 
 pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
@@ -20,7 +24,7 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
     store.ensure_dirs()?;
 
     match args.command {
-        AgentsCommand::List => {
+        AgentsCommand::List { format } => {
             let agents = store.list()?;
             let routes = store.load_routes()?;
             if cli.json {
@@ -48,8 +52,11 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                             "max_turns": &agent.max_turns,
                             "tools_enabled": &agent.tools_enabled,
                             "guard_mode": &agent.guard_mode,
+                            "system_prompt": &agent.system_prompt,
+                            "allowed_tools": &agent.allowed_tools,
                             "created_at": &agent.created_at,
                             "updated_at": &agent.updated_at,
+                            "last_used_at": &agent.last_used_at,
                             "is_default": routes.default_agent_id.as_deref() == Some(agent.id.as_str()),
                             "route_keys": route_keys,
                         })
@@ -62,6 +69,56 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                 }));
             } else if agents.is_empty() {
                 println!("No agents found.");
+            } else if format == ListFormatArg::Table {
+                let rows = agents
+                    .iter()
+                    .map(|agent| {
+                        let route_keys = routes
+                            .routes
+                            .iter()
+                            .filter(|(_, id)| *id == &agent.id)
+                            .map(|(route, _)| route.as_str())
+                            .collect::<Vec<_>>();
+                        vec![
+                            agent.id.clone(),
+                            agent.name.clone(),
+                            agent.profile.clone(),
+                            agent.model.clone().unwrap_or_else(|| "-".to_string()),
+                            if agent.skills.is_empty() {
+                                "-".to_string()
+                            } else {
+                                agent.skills.join(",")
+                            },
+                            (routes.default_agent_id.as_deref() == Some(agent.id.as_str()))
+                                .to_string(),
+                            if route_keys.is_empty() {
+                                "-".to_string()
+                            } else {
+                                route_keys.join(",")
+                            },
+                            agent
+                                .last_used_at
+                                .map(|value| value.to_rfc3339())
+                                .unwrap_or_else(|| "never".to_string()),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                print!(
+                    "{}",
+                    render_table(
+                        &[
+                            "ID",
+                            "NAME",
+                            "PROFILE",
+                            "MODEL",
+                            "SKILLS",
+                            "DEFAULT",
+                            "ROUTES",
+                            "LAST_USED",
+                        ],
+                        &rows,
+                    )
+                );
             } else {
                 println!("agents: {}", agents.len());
                 if let Some(default_agent_id) = &routes.default_agent_id {
@@ -99,7 +156,7 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                         }
                     };
                     println!(
-                        "- {} ({}) profile={} skills={} tags={} model={} temperature={} max_turns={}",
+                        "- {} ({}) profile={} skills={} tags={} model={} temperature={} max_turns={} last_used={}",
                         agent.id,
                         agent.name,
                         agent.profile,
@@ -113,7 +170,11 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                         agent
                             .max_turns
                             .map(|value| value.to_string())
-                            .unwrap_or_else(|| "-".to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        agent
+                            .last_used_at
+                            .map(|value| value.to_rfc3339())
+                            .unwrap_or_else(|| "never".to_string())
                     );
                 }
             }
@@ -145,7 +206,8 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
             };
             let route_agent_id = normalized_route
                 .as_ref()
-                .and_then(|route_key| routes.routes.get(route_key).cloned());
+                .and_then(|route_key| routes.match_route(route_key))
+                .map(|(_, agent_id)| agent_id.clone());
             let resolved = store.resolve_effective_profile(
                 &config,
                 &cli.profile,
@@ -212,6 +274,8 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
             max_turns,
             tools_enabled,
             guard_mode,
+            system_prompt,
+            allowed_tools,
             set_default,
             route_keys,
         } => {
@@ -223,7 +287,7 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
             let config = manager.load()?;
             let profile = profile.unwrap_or_else(|| cli.profile.clone());
             let _ = config.resolve_profile(Some(&profile))?;
-            let validated_skills = resolve_skill_ids(&paths.root_dir, skills)?;
+            let validated_skills = resolve_skill_ids(&paths, skills)?;
 
             let created = store.add(AddAgentInput {
                 id,
@@ -235,6 +299,8 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                 max_turns,
                 tools_enabled,
                 guard_mode: guard_mode.map(Into::into),
+                system_prompt,
+                allowed_tools: (!allowed_tools.is_empty()).then_some(allowed_tools),
             })?;
             if set_default {
                 store.set_default(&created.id)?;
@@ -275,6 +341,10 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
             clear_tools_enabled,
             guard_mode,
             clear_guard_mode,
+            system_prompt,
+            clear_system_prompt,
+            allowed_tools,
+            clear_allowed_tools,
             set_default,
             route_keys,
         } => {
@@ -290,7 +360,7 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
             let resolved_skills = if skills.is_empty() {
                 None
             } else {
-                Some(resolve_skill_ids(&paths.root_dir, skills)?)
+                Some(resolve_skill_ids(&paths, skills)?)
             };
 
             let updated = store.update(
@@ -310,6 +380,10 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                     clear_tools_enabled,
                     guard_mode: guard_mode.map(Into::into),
                     clear_guard_mode,
+                    system_prompt,
+                    clear_system_prompt,
+                    allowed_tools: (!allowed_tools.is_empty()).then_some(allowed_tools),
+                    clear_allowed_tools,
                 },
             )?;
 
@@ -397,6 +471,29 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                         .map(|value| format!("{value:?}"))
                         .unwrap_or_else(|| "-".to_string())
                 );
+                println!(
+                    "system_prompt: {}",
+                    agent
+                        .system_prompt
+                        .as_deref()
+                        .map(|value| format!("custom ({} chars)", value.chars().count()))
+                        .unwrap_or_else(|| "-".to_string())
+                );
+                println!(
+                    "last_used_at: {}",
+                    agent
+                        .last_used_at
+                        .map(|value| value.to_rfc3339())
+                        .unwrap_or_else(|| "never".to_string())
+                );
+                println!(
+                    "allowed_tools: {}",
+                    agent
+                        .allowed_tools
+                        .as_deref()
+                        .map(|tools| tools.join(", "))
+                        .unwrap_or_else(|| "<all>".to_string())
+                );
                 println!(
                     "default: {}",
                     routes.default_agent_id.as_deref() == Some(agent_id.as_str())
@@ -408,6 +505,25 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                 }
             }
         }
+        AgentsCommand::Clone {
+            source_id,
+            new_id,
+            name,
+        } => {
+            let agent = store.clone_agent(&source_id, &new_id, name.as_deref())?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "source_id": source_id,
+                    "agent": agent,
+                }));
+            } else {
+                println!(
+                    "Cloned agent {} -> {} ({})",
+                    source_id, agent.id, agent.name
+                );
+            }
+        }
         AgentsCommand::Remove { agent_id } => {
             let removed = store.remove(&agent_id)?;
             if !removed {
@@ -459,6 +575,127 @@ pub(super) fn handle_agents(cli: &Cli, args: AgentsArgs) -> Result<()> {
                 }
             }
         },
+        AgentsCommand::Export { out } => {
+            let bundle = store.export()?;
+            if let Some(path) = out {
+                if let Some(parent) = path.parent().filter(|value| !value.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let rendered = serde_json::to_string_pretty(&bundle).map_err(|err| {
+                    MosaicError::Validation(format!("failed to encode agents export JSON: {err}"))
+                })?;
+                std::fs::write(&path, rendered)?;
+                if cli.json {
+                    print_json(&json!({
+                        "ok": true,
+                        "path": path.display().to_string(),
+                        "agents": bundle.agents.len(),
+                        "routes": bundle.routes.routes.len(),
+                    }));
+                } else {
+                    println!(
+                        "Exported {} agents and {} routes to {}",
+                        bundle.agents.len(),
+                        bundle.routes.routes.len(),
+                        path.display()
+                    );
+                }
+            } else if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "bundle": bundle,
+                }));
+            } else {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&bundle).map_err(|err| {
+                        MosaicError::Validation(format!(
+                            "failed to render agents export JSON: {err}"
+                        ))
+                    })?
+                );
+            }
+        }
+        AgentsCommand::Import { file, replace } => {
+            let raw = std::fs::read_to_string(&file).map_err(|err| {
+                MosaicError::Config(format!(
+                    "failed to read agents import file {}: {err}",
+                    file.display()
+                ))
+            })?;
+            let value = serde_json::from_str::<Value>(&raw).map_err(|err| {
+                MosaicError::Validation(format!(
+                    "invalid agents import JSON {}: {err}",
+                    file.display()
+                ))
+            })?;
+            // Accept the `--json` export envelope as well as a bare bundle.
+            let bundle = value
+                .as_object()
+                .and_then(|obj| obj.get("bundle"))
+                .cloned()
+                .unwrap_or(value);
+            let summary = store.import(bundle, replace)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "file": file.display().to_string(),
+                    "summary": summary,
+                }));
+            } else {
+                println!(
+                    "Imported agents from {}: imported={} skipped={} routes={} replace={}",
+                    file.display(),
+                    summary.imported,
+                    summary.skipped,
+                    summary.routes_imported,
+                    summary.replace
+                );
+                println!(
+                    "agents integrity: agents={} routes={} default={} ok={}",
+                    summary.integrity.agents_count,
+                    summary.integrity.routes_count,
+                    summary.integrity.default_agent_id.as_deref().unwrap_or("-"),
+                    summary.integrity.ok
+                );
+            }
+        }
+        AgentsCommand::Check => {
+            let report = if manager.exists() {
+                store.check_integrity_with_config(&manager.load()?)?
+            } else {
+                store.check_integrity()?
+            };
+            if cli.json {
+                if !report.ok {
+                    return Err(MosaicError::Validation(format!(
+                        "agent integrity check failed: {}",
+                        report.issues.join("; ")
+                    )));
+                }
+                print_json(&json!({
+                    "ok": true,
+                    "report": report,
+                }));
+            } else {
+                println!(
+                    "agents integrity: agents={} routes={} default={} ok={}",
+                    report.agents_count,
+                    report.routes_count,
+                    report.default_agent_id.as_deref().unwrap_or("-"),
+                    report.ok
+                );
+                for issue in &report.issues {
+                    println!("- {issue}");
+                }
+                if !report.ok {
+                    return Err(MosaicError::Validation(format!(
+                        "agent integrity check failed with {} issue(s)",
+                        report.issues.len()
+                    )));
+                }
+            }
+        }
         AgentsCommand::Route { command } => match command {
             AgentsRouteCommand::List => {
                 let routes = store.load_routes()?;
@@ -540,13 +777,13 @@ fn normalize_route_key(raw: &str) -> Result<String> {
     Ok(value.to_string())
 }
 
-fn resolve_skill_ids(state_root: &std::path::Path, skills: Vec<String>) -> Result<Vec<String>> {
+fn resolve_skill_ids(paths: &StatePaths, skills: Vec<String>) -> Result<Vec<String>> {
     if skills.is_empty() {
         return Ok(Vec::new());
     }
-    let registry = ExtensionRegistry::new(RegistryRoots::from_state_root(state_root.to_path_buf()));
+    let registry = ExtensionRegistry::new(RegistryRoots::from_state_paths(paths));
     let available = registry
-        .list_skills()?
+        .list_skills(None)?
         .into_iter()
         .map(|skill| skill.id)
         .collect::<std::collections::BTreeSet<_>>();
@@ -18,7 +18,7 @@ use super::{
     save_webhooks, webhook_execution_error, webhooks_file_path,
 };
 
-fn resolve_logs_cutoff(
+pub(super) fn resolve_logs_cutoff(
     since_minutes: Option<u64>,
     command_hint: &str,
 ) -> Result<Option<DateTime<Utc>>> {
@@ -156,13 +156,15 @@ pub(super) fn execute_hook_command(
     payload: Value,
 ) -> Result<HookExecutionReport> {
     let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
-    let approval_store = ApprovalStore::new(paths.approvals_policy_path.clone());
+    let approval_store = ApprovalStore::new(paths.approvals_policy_path.clone())
+        .with_audit_path(paths.approvals_audit_path.clone());
     let sandbox_store = SandboxStore::new(paths.sandbox_policy_path.clone());
     let runtime_policy = RuntimePolicy {
         approval: approval_store.load_or_default()?,
         sandbox: sandbox_store.load_or_default()?,
     };
-    let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(runtime_policy));
+    let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(runtime_policy))
+        .with_approval_audit(approval_store);
     let context = ToolContext {
         cwd,
         yes: cli.yes,
@@ -328,7 +330,7 @@ pub(super) fn read_hook_events(
             }
         }
     }
-    events.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+    events.sort_by_key(|lhs| lhs.ts);
     if events.len() > tail {
         let keep_from = events.len() - tail;
         events = events.split_off(keep_from);
@@ -482,7 +484,7 @@ pub(super) fn read_cron_events(
             }
         }
     }
-    events.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+    events.sort_by_key(|lhs| lhs.ts);
     if events.len() > tail {
         let keep_from = events.len() - tail;
         events = events.split_off(keep_from);
@@ -742,7 +744,7 @@ pub(super) fn read_webhook_events(
             }
         }
     }
-    events.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+    events.sort_by_key(|lhs| lhs.ts);
     if events.len() > tail {
         let keep_from = events.len() - tail;
         events = events.split_off(keep_from);
@@ -10,8 +10,8 @@ use mosaic_channels::{
 use mosaic_core::error::{MosaicError, Result};
 
 use super::{
-    ChannelsArgs, ChannelsCommand, Cli, ReplayReasonArg, parse_json_input, print_json,
-    resolve_state_paths, save_json_file,
+    ChannelsArgs, ChannelsCommand, Cli, ListFormatArg, ReplayReasonArg, parse_json_input,
+    print_json, render_table, resolve_state_paths, save_json_file,
 };
 
 pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()> {
@@ -21,7 +21,7 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
     let channel_events_dir = channels_events_dir(&paths.data_dir);
     let repository = ChannelRepository::new(channels_path.clone(), channel_events_dir);
     match args.command {
-        ChannelsCommand::List => {
+        ChannelsCommand::List { format } => {
             let channels = repository.list()?;
             if cli.json {
                 print_json(&json!({
@@ -31,16 +31,59 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                 }));
             } else if channels.is_empty() {
                 println!("No channels configured.");
+            } else if format == ListFormatArg::Table {
+                let rows = channels
+                    .into_iter()
+                    .map(|channel| {
+                        vec![
+                            channel.id,
+                            channel.name,
+                            channel.kind,
+                            channel.target_masked.unwrap_or_else(|| "-".to_string()),
+                            match channel.token_present {
+                                Some(true) => "present",
+                                Some(false) => "missing",
+                                None => "-",
+                            }
+                            .to_string(),
+                            channel
+                                .last_send_at
+                                .map(|v| v.to_rfc3339())
+                                .unwrap_or_else(|| "-".to_string()),
+                            channel.last_error.unwrap_or_else(|| "-".to_string()),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                print!(
+                    "{}",
+                    render_table(
+                        &[
+                            "ID",
+                            "NAME",
+                            "KIND",
+                            "TARGET",
+                            "TOKEN",
+                            "LAST_SEND",
+                            "LAST_ERROR",
+                        ],
+                        &rows,
+                    )
+                );
             } else {
                 for channel in channels {
                     println!(
-                        "{} name={} kind={} endpoint={} target={} defaults={} last_login={} last_send={} last_error={}",
+                        "{} name={} kind={} endpoint={} target={} defaults={} token={} last_login={} last_send={} last_error={}",
                         channel.id,
                         channel.name,
                         channel.kind,
                         channel.endpoint_masked.unwrap_or_else(|| "-".to_string()),
                         channel.target_masked.unwrap_or_else(|| "-".to_string()),
                         channel.has_template_defaults,
+                        match channel.token_present {
+                            Some(true) => "present",
+                            Some(false) => "missing",
+                            None => "-",
+                        },
                         channel
                             .last_login_at
                             .map(|v| v.to_rfc3339())
@@ -76,6 +119,75 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                 }
             }
         }
+        ChannelsCommand::Metrics { hours } => {
+            if hours == 0 {
+                return Err(MosaicError::Validation(
+                    "--hours must be greater than 0".to_string(),
+                ));
+            }
+            let metrics = repository.metrics(Duration::hours(hours as i64))?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "hours": hours,
+                    "metrics": metrics,
+                }));
+            } else {
+                println!(
+                    "window: last {hours}h (since {})",
+                    metrics.since.to_rfc3339()
+                );
+                println!("{}", format_metrics_counts("total", &metrics.totals));
+                for (channel_id, counts) in &metrics.channels {
+                    println!("{}", format_metrics_counts(channel_id, counts));
+                }
+            }
+        }
+        ChannelsCommand::Deadletter { channel_id, replay } => {
+            if replay {
+                let summary = repository.replay_deadletter(&channel_id).await?;
+                if cli.json {
+                    print_json(&json!({
+                        "ok": true,
+                        "replay": summary,
+                    }));
+                } else {
+                    println!(
+                        "dead-letter replay channel={} attempted={} delivered={} remaining={}",
+                        summary.channel_id, summary.attempted, summary.delivered, summary.remaining
+                    );
+                    for (entry_id, error) in &summary.errors {
+                        println!("- {entry_id}: {error}");
+                    }
+                }
+            } else {
+                let entries = repository.deadletter(&channel_id)?;
+                if cli.json {
+                    print_json(&json!({
+                        "ok": true,
+                        "channel_id": channel_id,
+                        "entries": entries,
+                    }));
+                } else if entries.is_empty() {
+                    println!("No dead-lettered sends for {channel_id}.");
+                } else {
+                    for entry in entries {
+                        println!(
+                            "{} {} attempts={} http={} error={} text={}",
+                            entry.id,
+                            entry.ts.to_rfc3339(),
+                            entry.attempts,
+                            entry
+                                .http_status
+                                .map(|status| status.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            entry.error.as_deref().unwrap_or("-"),
+                            entry.text
+                        );
+                    }
+                }
+            }
+        }
         ChannelsCommand::Add {
             name,
             kind,
@@ -86,10 +198,13 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
             default_title,
             default_block,
             default_metadata,
+            default_template,
+            default_vars,
         } => {
             let default_metadata = default_metadata
                 .map(|value| parse_json_input(&value, "channels add default metadata"))
                 .transpose()?;
+            let default_vars = parse_template_vars(default_vars, "--default-var")?;
             let entry = repository.add(AddChannelInput {
                 name,
                 kind,
@@ -101,6 +216,8 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                     title: default_title,
                     blocks: default_block,
                     metadata: default_metadata,
+                    template: default_template,
+                    variables: default_vars,
                 },
             })?;
             let rendered = format_channel_for_output(&entry);
@@ -125,21 +242,28 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
             default_title,
             default_block,
             default_metadata,
+            default_template,
+            default_vars,
             clear_defaults,
         } => {
             let default_metadata = default_metadata
                 .map(|value| parse_json_input(&value, "channels update default metadata"))
                 .transpose()?;
+            let default_vars = parse_template_vars(default_vars, "--default-var")?;
             let template_defaults = if default_parse_mode.is_some()
                 || default_title.is_some()
                 || !default_block.is_empty()
                 || default_metadata.is_some()
+                || default_template.is_some()
+                || !default_vars.is_empty()
             {
                 Some(ChannelTemplateDefaults {
                     parse_mode: default_parse_mode,
                     title: default_title,
                     blocks: default_block,
                     metadata: default_metadata,
+                    template: default_template,
+                    variables: default_vars,
                 })
             } else {
                 None
@@ -199,12 +323,23 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
             title,
             block,
             metadata,
+            vars,
+            no_strict_vars,
+            dead_letter,
+            plain_text,
             idempotency_key,
             token_env,
+            timeout_ms,
         } => {
+            if timeout_ms == Some(0) {
+                return Err(MosaicError::Validation(
+                    "--timeout-ms must be greater than 0".to_string(),
+                ));
+            }
             let metadata = metadata
                 .map(|value| parse_json_input(&value, "channels send metadata"))
                 .transpose()?;
+            let variables = parse_template_vars(vars, "--var")?;
             let result = repository
                 .send_with_options(
                     &channel_id,
@@ -217,6 +352,11 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                         blocks: block,
                         idempotency_key,
                         metadata,
+                        timeout_ms,
+                        variables,
+                        strict: Some(!no_strict_vars),
+                        dead_letter,
+                        plain_text,
                     },
                 )
                 .await?;
@@ -227,6 +367,7 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                     "kind": result.kind,
                     "delivered_via": result.delivered_via,
                     "attempts": result.attempts,
+                    "attempts_detail": result.attempts_detail,
                     "http_status": result.http_status,
                     "endpoint_masked": result.endpoint_masked,
                     "target_masked": result.target_masked,
@@ -238,6 +379,21 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                 }));
             } else {
                 println!("Message sent via {}", result.delivered_via);
+                if result.attempts > 1 {
+                    for record in &result.attempts_detail {
+                        println!(
+                            "attempt {}: +{}ms backoff={}ms status={} error={}",
+                            record.attempt,
+                            record.started_offset_ms,
+                            record.backoff_ms,
+                            record
+                                .http_status
+                                .map(|status| status.to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            record.error.as_deref().unwrap_or("-")
+                        );
+                    }
+                }
                 if let Some(endpoint) = result.endpoint_masked {
                     println!("endpoint: {endpoint}");
                 }
@@ -648,6 +804,10 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                                 blocks: Vec::new(),
                                 idempotency_key: candidate.idempotency_key.clone(),
                                 metadata: None,
+                                timeout_ms: None,
+                                // Replayed text was already rendered once.
+                                strict: Some(false),
+                                ..ChannelSendOptions::default()
                             },
                         )
                         .await;
@@ -1219,6 +1379,7 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
                     "kind": result.kind,
                     "probe": result.probe,
                     "attempts": result.attempts,
+                    "attempts_detail": result.attempts_detail,
                     "http_status": result.http_status,
                     "endpoint_masked": result.endpoint_masked,
                     "target_masked": result.target_masked,
@@ -1246,6 +1407,35 @@ pub(super) async fn handle_channels(cli: &Cli, args: ChannelsArgs) -> Result<()>
     Ok(())
 }
 
+fn format_metrics_counts(label: &str, counts: &mosaic_channels::ChannelMetricsCounts) -> String {
+    format!(
+        "{label} events={} success={} failed={} deduplicated={} probes={} attempts={} success_rate={}",
+        counts.events,
+        counts.successes,
+        counts.failures,
+        counts.deduplicated,
+        counts.probes,
+        counts.attempts,
+        counts
+            .success_rate
+            .map(|rate| format!("{:.1}%", rate * 100.0))
+            .unwrap_or_else(|| "-".to_string()),
+    )
+}
+
+fn parse_template_vars(raw: Vec<String>, flag: &str) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for entry in raw {
+        let Some((name, value)) = entry.split_once('=') else {
+            return Err(MosaicError::Validation(format!(
+                "invalid {flag} value '{entry}', expected KEY=VALUE"
+            )));
+        };
+        vars.insert(name.trim().to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
 fn summarize_channel_events(events: &[mosaic_channels::ChannelLogEntry]) -> Value {
     #[derive(Default)]
     struct PerChannel {
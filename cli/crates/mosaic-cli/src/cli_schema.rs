@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use mosaic_core::config::RunGuardMode;
-use mosaic_ops::{ApprovalMode, SandboxProfile, SystemEvent};
+use mosaic_memory::DEFAULT_EMBEDDING_MODEL;
+use mosaic_ops::{ApprovalMode, SystemEvent};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -20,6 +21,13 @@ struct Cli {
     profile: String,
     #[arg(long)]
     project_state: bool,
+    #[arg(long, env = "MOSAIC_PROJECT_DIR")]
+    state_dir: Option<String>,
+    /// Config file to use instead of the resolved state paths; other state is
+    /// kept beside it. Takes precedence over `--project-state`; `reset` and
+    /// `uninstall` refuse to run with it.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
     #[arg(long)]
     json: bool,
     #[arg(long)]
@@ -70,6 +78,7 @@ enum Commands {
     Agents(AgentsArgs),
     Plugins(PluginsArgs),
     Skills(SkillsArgs),
+    #[command(visible_alias = "completions")]
     Completion(CompletionArgs),
     Directory(DirectoryArgs),
     Dashboard,
@@ -83,7 +92,7 @@ enum Commands {
     Clawbot(ClawbotArgs),
     Status,
     Health,
-    Doctor,
+    Doctor(DoctorArgs),
 }
 
 #[derive(Args, Debug, Clone)]
@@ -124,6 +133,9 @@ struct ConfigureArgs {
     tools_enabled: Option<bool>,
     #[arg(long, value_enum)]
     guard_mode: Option<GuardModeArg>,
+    /// Create the `--profile` profile as a copy of this existing profile.
+    #[arg(long, value_name = "PROFILE")]
+    copy_from: Option<String>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -135,6 +147,8 @@ enum ConfigureCommand {
     Patch(ConfigurePatchArgs),
     Preview(ConfigurePreviewArgs),
     Template(ConfigureTemplateArgs),
+    Diff { left: String, right: String },
+    Validate,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -238,6 +252,8 @@ struct AskArgs {
     session: Option<String>,
     #[arg(long)]
     agent: Option<String>,
+    #[arg(long, value_name = "LABEL")]
+    label: Option<String>,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -271,6 +287,16 @@ enum SessionCommand {
     Resume {
         session_id: String,
     },
+    Fork {
+        session_id: String,
+    },
+    Rename {
+        session_id: String,
+        title: String,
+    },
+    Search {
+        query: String,
+    },
     Clear {
         session_id: Option<String>,
         #[arg(long)]
@@ -416,9 +442,20 @@ enum GatewayCommand {
         method: String,
         #[arg(long)]
         params: Option<String>,
+        /// Gateway URL; `ws://` and `wss://` use the WebSocket transport.
+        #[arg(long)]
+        url: Option<String>,
+    },
+    Probe {
+        /// Gateway URL; `ws://` and `wss://` use the WebSocket transport.
+        #[arg(long)]
+        url: Option<String>,
+    },
+    Discover {
+        /// Gateway URL; `ws://` and `wss://` use the WebSocket transport.
+        #[arg(long)]
+        url: Option<String>,
     },
-    Probe,
-    Discover,
     Diagnose {
         #[arg(long)]
         method: Option<String>,
@@ -916,7 +953,10 @@ struct ChannelsArgs {
 
 #[derive(Subcommand, Debug, Clone)]
 enum ChannelsCommand {
-    List,
+    List {
+        #[arg(long, value_enum, default_value_t = ListFormatArg::Plain)]
+        format: ListFormatArg,
+    },
     Status,
     Add {
         #[arg(long)]
@@ -937,6 +977,10 @@ enum ChannelsCommand {
         default_block: Vec<String>,
         #[arg(long)]
         default_metadata: Option<String>,
+        #[arg(long)]
+        default_template: Option<String>,
+        #[arg(long = "default-var", value_name = "KEY=VALUE", action = ArgAction::Append)]
+        default_vars: Vec<String>,
     },
     Update {
         channel_id: String,
@@ -958,13 +1002,19 @@ enum ChannelsCommand {
         default_block: Vec<String>,
         #[arg(long)]
         default_metadata: Option<String>,
+        #[arg(long)]
+        default_template: Option<String>,
+        #[arg(long = "default-var", value_name = "KEY=VALUE", action = ArgAction::Append)]
+        default_vars: Vec<String>,
         #[arg(
             long,
             conflicts_with_all = [
                 "default_parse_mode",
                 "default_title",
                 "default_block",
-                "default_metadata"
+                "default_metadata",
+                "default_template",
+                "default_vars"
             ]
         )]
         clear_defaults: bool,
@@ -986,16 +1036,36 @@ enum ChannelsCommand {
         block: Vec<String>,
         #[arg(long)]
         metadata: Option<String>,
+        #[arg(long = "var", value_name = "KEY=VALUE", action = ArgAction::Append)]
+        vars: Vec<String>,
+        #[arg(long)]
+        no_strict_vars: bool,
+        #[arg(long)]
+        dead_letter: bool,
+        /// Send slack_webhook text as-is instead of converting Markdown to blocks
+        #[arg(long)]
+        plain_text: bool,
         #[arg(long)]
         idempotency_key: Option<String>,
         #[arg(long)]
         token_env: Option<String>,
+        #[arg(long)]
+        timeout_ms: Option<u64>,
     },
     Test {
         channel_id: String,
         #[arg(long)]
         token_env: Option<String>,
     },
+    Metrics {
+        #[arg(long, default_value_t = 24)]
+        hours: u64,
+    },
+    Deadletter {
+        channel_id: String,
+        #[arg(long)]
+        replay: bool,
+    },
     Logs {
         #[arg(long)]
         channel: Option<String>,
@@ -1157,13 +1227,35 @@ enum SystemCommand {
         #[arg(long)]
         data: Option<String>,
     },
-    Presence,
+    Presence {
+        /// Also show the N most recent presence entries
+        #[arg(long, value_name = "N")]
+        history: Option<usize>,
+    },
     List {
         #[arg(long, default_value_t = 50)]
         tail: usize,
         #[arg(long)]
         name: Option<String>,
     },
+    /// Query recorded system events, newest first
+    Events {
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        since_minutes: Option<u64>,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// List audited command executions, newest first
+    Audit {
+        #[arg(long)]
+        session: Option<String>,
+        #[arg(long)]
+        since_minutes: Option<u64>,
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
 }
 
 #[derive(Args, Debug, Clone)]
@@ -1205,9 +1297,9 @@ struct SandboxArgs {
 #[derive(Subcommand, Debug, Clone)]
 enum SandboxCommand {
     Get,
+    /// Select a built-in profile (restricted, standard, elevated) or a custom one.
     Set {
-        #[arg(value_enum)]
-        profile: SandboxProfileArg,
+        profile: String,
     },
     Check {
         #[arg(long)]
@@ -1215,8 +1307,8 @@ enum SandboxCommand {
     },
     List,
     Explain {
-        #[arg(long, value_enum)]
-        profile: Option<SandboxProfileArg>,
+        #[arg(long)]
+        profile: Option<String>,
     },
 }
 
@@ -1262,12 +1354,19 @@ enum MemoryCommand {
         stale_after_hours: Option<u64>,
         #[arg(long, default_value_t = false)]
         retain_missing: bool,
+        #[arg(long, default_value_t = false)]
+        no_ignore: bool,
         #[arg(long, default_value_t = 500)]
         max_files: usize,
         #[arg(long, default_value_t = 262_144)]
         max_file_size: usize,
         #[arg(long, default_value_t = 16_384)]
         max_content_bytes: usize,
+        /// Also store embeddings from the active profile's provider
+        #[arg(long, default_value_t = false)]
+        embed: bool,
+        #[arg(long, default_value = DEFAULT_EMBEDDING_MODEL)]
+        embedding_model: String,
     },
     Search {
         query: String,
@@ -1275,6 +1374,11 @@ enum MemoryCommand {
         namespace: String,
         #[arg(long, default_value_t = 20)]
         limit: usize,
+        /// Rank by embedding similarity, falling back to lexical search
+        #[arg(long, default_value_t = false)]
+        semantic: bool,
+        #[arg(long, default_value = DEFAULT_EMBEDDING_MODEL)]
+        embedding_model: String,
     },
     Status {
         #[arg(long, default_value = "default")]
@@ -1282,6 +1386,11 @@ enum MemoryCommand {
         #[arg(long, default_value_t = false)]
         all_namespaces: bool,
     },
+    Remove {
+        path: String,
+        #[arg(long, default_value = "default")]
+        namespace: String,
+    },
     Clear {
         #[arg(long, default_value = "default")]
         namespace: String,
@@ -1477,14 +1586,22 @@ enum SecurityCommand {
         max_files: usize,
         #[arg(long, default_value_t = 262_144)]
         max_file_size: usize,
-        #[arg(long)]
-        baseline: Option<String>,
+        #[arg(long, value_name = "PATH")]
+        cache: Option<String>,
+        #[arg(long, value_name = "REF")]
+        changed_since: Option<String>,
+        #[arg(long, default_value_t = 2)]
+        context_lines: usize,
+        #[arg(long = "baseline", action = ArgAction::Append)]
+        baselines: Vec<String>,
         #[arg(long)]
         no_baseline: bool,
         #[arg(long)]
         update_baseline: bool,
         #[arg(long)]
         sarif: bool,
+        #[arg(long, value_enum, conflicts_with = "sarif")]
+        format: Option<SecurityReportFormatArg>,
         #[arg(long)]
         sarif_output: Option<String>,
         #[arg(long, value_enum)]
@@ -1500,6 +1617,12 @@ enum SecurityCommand {
     },
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum SecurityReportFormatArg {
+    Json,
+    Sarif,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum SecuritySeverityArg {
     Low,
@@ -1547,7 +1670,10 @@ struct AgentsArgs {
 
 #[derive(Subcommand, Debug, Clone)]
 enum AgentsCommand {
-    List,
+    List {
+        #[arg(long, value_enum, default_value_t = ListFormatArg::Plain)]
+        format: ListFormatArg,
+    },
     Current {
         #[arg(long)]
         agent: Option<String>,
@@ -1576,6 +1702,10 @@ enum AgentsCommand {
         #[arg(long, value_enum)]
         guard_mode: Option<GuardModeArg>,
         #[arg(long)]
+        system_prompt: Option<String>,
+        #[arg(long = "allow-tool", action = ArgAction::Append)]
+        allowed_tools: Vec<String>,
+        #[arg(long)]
         set_default: bool,
         #[arg(long = "route")]
         route_keys: Vec<String>,
@@ -1611,6 +1741,14 @@ enum AgentsCommand {
         #[arg(long)]
         clear_guard_mode: bool,
         #[arg(long)]
+        system_prompt: Option<String>,
+        #[arg(long)]
+        clear_system_prompt: bool,
+        #[arg(long = "allow-tool", action = ArgAction::Append)]
+        allowed_tools: Vec<String>,
+        #[arg(long)]
+        clear_allowed_tools: bool,
+        #[arg(long)]
         set_default: bool,
         #[arg(long = "route")]
         route_keys: Vec<String>,
@@ -1618,12 +1756,29 @@ enum AgentsCommand {
     Show {
         agent_id: String,
     },
+    Clone {
+        source_id: String,
+        new_id: String,
+        #[arg(long)]
+        name: Option<String>,
+    },
     Remove {
         agent_id: String,
     },
     Default {
         agent_id: Option<String>,
     },
+    Check,
+    Export {
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    Import {
+        #[arg(long)]
+        file: PathBuf,
+        #[arg(long)]
+        replace: bool,
+    },
     Route {
         #[command(subcommand)]
         command: AgentsRouteCommand,
@@ -1657,18 +1812,37 @@ enum PluginsCommand {
     List {
         #[arg(long, value_enum, default_value_t = ExtensionSourceFilterArg::All)]
         source: ExtensionSourceFilterArg,
+        #[arg(long, value_enum, default_value_t = ListFormatArg::Plain)]
+        format: ListFormatArg,
     },
     Info {
         plugin_id: String,
     },
+    Search {
+        query: String,
+    },
     Check {
         plugin_id: Option<String>,
     },
     Install {
-        #[arg(long)]
-        path: String,
+        #[arg(long, required_unless_present = "url", conflicts_with = "url")]
+        path: Option<String>,
+        /// `.tar.gz`/`.tgz` archive or git repository (`git+<url>` or `*.git`,
+        /// shallow-cloned with the system `git`).
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
         #[arg(long)]
         force: bool,
+        /// Expected sha256: of the archive bytes (as `sha256sum` prints it) for
+        /// archive URLs, of the source tree (as --dry-run reports it) otherwise.
+        #[arg(long, value_name = "HEX")]
+        sha256: Option<String>,
+        /// Require checksums.txt in the plugin and verify every listed file.
+        #[arg(long)]
+        verify: bool,
+        /// Validate and report the outcome without installing anything.
+        #[arg(long)]
+        dry_run: bool,
     },
     Enable {
         plugin_id: String,
@@ -1708,18 +1882,32 @@ enum SkillsCommand {
     List {
         #[arg(long, value_enum, default_value_t = ExtensionSourceFilterArg::All)]
         source: ExtensionSourceFilterArg,
+        #[arg(long, value_enum, default_value_t = ListFormatArg::Plain)]
+        format: ListFormatArg,
     },
     Info {
         skill_id: String,
     },
+    Search {
+        query: String,
+    },
     Check {
         skill_id: Option<String>,
     },
+    Enable {
+        skill_id: String,
+    },
+    Disable {
+        skill_id: String,
+    },
     Install {
         #[arg(long)]
         path: String,
         #[arg(long)]
         force: bool,
+        /// Validate and report the outcome without installing anything.
+        #[arg(long)]
+        dry_run: bool,
     },
     Remove {
         skill_id: String,
@@ -1727,9 +1915,13 @@ enum SkillsCommand {
 }
 
 #[derive(Args, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 struct CompletionArgs {
     #[command(subcommand)]
-    command: CompletionCommand,
+    command: Option<CompletionCommand>,
+    /// Print the completion script for this shell (same as `shell <SHELL>`).
+    #[arg(value_enum)]
+    shell: Option<CompletionShellArg>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -1764,6 +1956,13 @@ struct UpdateArgs {
     timeout_ms: u64,
 }
 
+#[derive(Args, Debug, Clone)]
+struct DoctorArgs {
+    /// Apply safe repairs (missing state dirs, channels migration, dangling agent routes)
+    #[arg(long)]
+    fix: bool,
+}
+
 #[derive(Args, Debug, Clone)]
 struct DocsArgs {
     topic: Option<String>,
@@ -1862,6 +2061,12 @@ enum QrRenderArg {
     Png,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ListFormatArg {
+    Plain,
+    Table,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 enum ExtensionSourceFilterArg {
     All,
@@ -2310,14 +2515,6 @@ impl From<ApprovalModeArg> for ApprovalMode {
     }
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-#[value(rename_all = "snake_case")]
-enum SandboxProfileArg {
-    Restricted,
-    Standard,
-    Elevated,
-}
-
 #[derive(Debug, Clone, Copy, ValueEnum)]
 #[value(rename_all = "snake_case")]
 enum PairingStatusArg {
@@ -2356,15 +2553,6 @@ impl From<PairingStatusArg> for PairingStatus {
     }
 }
 
-impl From<SandboxProfileArg> for SandboxProfile {
-    fn from(value: SandboxProfileArg) -> Self {
-        match value {
-            SandboxProfileArg::Restricted => Self::Restricted,
-            SandboxProfileArg::Standard => Self::Standard,
-            SandboxProfileArg::Elevated => Self::Elevated,
-        }
-    }
-}
 
 impl From<WebhookMethodArg> for WebhookMethod {
     fn from(value: WebhookMethodArg) -> Self {
@@ -22,7 +22,13 @@ use super::{
 };
 
 pub(super) fn handle_completion(cli: &Cli, args: CompletionArgs) -> Result<()> {
-    match args.command {
+    let command = args
+        .command
+        .or_else(|| args.shell.map(|shell| CompletionCommand::Shell { shell }))
+        .ok_or_else(|| {
+            MosaicError::Validation("completion requires a shell or subcommand".to_string())
+        })?;
+    match command {
         CompletionCommand::Shell { shell } => {
             let mut command = Cli::command();
             let mut output = Vec::new();
@@ -344,7 +350,7 @@ pub(super) fn handle_dashboard(cli: &Cli) -> Result<()> {
                 },
                 "policy": {
                     "approval_mode": approval_policy.as_ref().map(|policy| format!("{:?}", policy.mode).to_lowercase()),
-                    "approval_allowlist_size": approval_policy.as_ref().map(|policy| policy.allowlist.len()),
+                    "approval_allowlist_size": approval_policy.as_ref().map(|policy| policy.allowlist().len()),
                     "sandbox_profile": sandbox_policy.as_ref().map(|policy| format!("{:?}", policy.profile).to_lowercase()),
                 },
                 "memory": {
@@ -393,7 +399,7 @@ pub(super) fn handle_dashboard(cli: &Cli) -> Result<()> {
             println!(
                 "- approvals: {:?} (allowlist={})",
                 policy.mode,
-                policy.allowlist.len()
+                policy.allowlist().len()
             );
         }
         if let Some(policy) = sandbox_policy {
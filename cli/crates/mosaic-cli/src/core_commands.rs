@@ -7,24 +7,32 @@ use serde_json::{Value, json};
 
 use mosaic_agent::{AgentEvent, AgentRunOptions};
 use mosaic_agents::{AgentStore, agent_routes_path, agents_file_path};
-use mosaic_core::config::{ConfigFile, ConfigManager, ProfileConfig, RunGuardMode, StateConfig};
+use mosaic_core::config::{
+    ConfigFieldChange, ConfigFile, ConfigManager, ProfileConfig, RunGuardMode, StateConfig,
+};
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::models::ModelRoutingStore;
+use mosaic_core::provider::TokenUsage;
 use mosaic_core::session::SessionStore;
 
 use super::{
     ChatArgs, Cli, ConfigureArgs, ConfigureCommand, ConfigurePatchArgs, ConfigureTemplateArgs,
     ConfigureTemplateFormatArg, ModelAliasesCommand, ModelFallbacksCommand, ModelsArgs,
-    ModelsCommand, PROJECT_STATE_DIR, SessionArgs, SessionCommand, SetupArgs, build_runtime,
-    print_json, print_json_line, resolve_effective_model, resolve_state_paths,
+    ModelsCommand, SessionArgs, SessionCommand, SetupArgs, attach_json_error_details,
+    build_runtime, print_json, print_json_line, project_state_dir, resolve_effective_model,
+    resolve_state_paths,
 };
+use crate::runtime_context::{RuntimeContext, RuntimeSelector, build_runtime_from_selector};
 
 pub(super) fn handle_setup(cli: &Cli, args: SetupArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
     let manager = ConfigManager::new(paths.config_path.clone());
-    let mut config = manager.load_or_default(paths.mode)?;
+    let mut config = manager.load_raw_or_default(paths.mode)?;
     let profile = config.profiles.entry(cli.profile.clone()).or_default();
+    if args.base_url.is_some() || args.model.is_some() || args.api_key_env.is_some() {
+        profile.ensure_inline_provider()?;
+    }
     if let Some(base_url) = args.base_url {
         profile.provider.base_url = base_url;
     }
@@ -49,7 +57,7 @@ pub(super) fn handle_setup(cli: &Cli, args: SetupArgs) -> Result<()> {
     config.active_profile = cli.profile.clone();
     config.state = StateConfig {
         mode: paths.mode,
-        project_dir: PROJECT_STATE_DIR.to_string(),
+        project_dir: project_state_dir().to_string(),
     };
     manager.save(&config)?;
 
@@ -80,21 +88,39 @@ pub(super) fn handle_configure(cli: &Cli, args: ConfigureArgs) -> Result<()> {
         max_turns,
         tools_enabled,
         guard_mode,
+        copy_from,
     } = args;
+    if let Some(ConfigureCommand::Diff { left, right }) = &command {
+        return handle_configure_diff(cli, left, right);
+    }
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
     let manager = ConfigManager::new(paths.config_path.clone());
-    let mut config = manager.load()?;
+    if let Some(ConfigureCommand::Validate) = &command {
+        return handle_configure_validate(cli, &manager);
+    }
+    // Edits must keep `${VAR}` tokens intact, so work on the file as written.
+    let mut config = manager.load_raw()?;
+
+    let has_legacy_flags = show
+        || base_url.is_some()
+        || model.is_some()
+        || api_key_env.is_some()
+        || temperature.is_some()
+        || max_turns.is_some()
+        || tools_enabled.is_some()
+        || guard_mode.is_some();
+    if let Some(from) = copy_from {
+        if command.is_some() || has_legacy_flags {
+            return Err(MosaicError::Validation(
+                "--copy-from cannot be combined with other configure flags or subcommands"
+                    .to_string(),
+            ));
+        }
+        return handle_configure_copy(cli, &manager, &mut config, &from);
+    }
 
     if let Some(command) = command {
-        let has_legacy_flags = show
-            || base_url.is_some()
-            || model.is_some()
-            || api_key_env.is_some()
-            || temperature.is_some()
-            || max_turns.is_some()
-            || tools_enabled.is_some()
-            || guard_mode.is_some();
         if has_legacy_flags {
             return Err(MosaicError::Validation(
                 "configure subcommands cannot be combined with legacy configure flags".to_string(),
@@ -106,6 +132,9 @@ pub(super) fn handle_configure(cli: &Cli, args: ConfigureArgs) -> Result<()> {
     let mut changed = false;
     {
         let profile = config.profiles.entry(cli.profile.clone()).or_default();
+        if base_url.is_some() || model.is_some() || api_key_env.is_some() {
+            profile.ensure_inline_provider()?;
+        }
         if let Some(base_url) = base_url {
             profile.provider.base_url = base_url;
             changed = true;
@@ -284,6 +313,140 @@ impl From<ConfigureTemplateFormatArg> for ConfigureTemplateFormat {
     }
 }
 
+fn handle_configure_diff(cli: &Cli, left: &str, right: &str) -> Result<()> {
+    let before = ConfigManager::new(left.into()).load()?;
+    let after = ConfigManager::new(right.into()).load()?;
+    let diff = before.diff(&after);
+    if cli.json {
+        print_json(&json!({
+            "ok": true,
+            "action": "diff",
+            "left": left,
+            "right": right,
+            "identical": diff.is_empty(),
+            "diff": diff,
+        }));
+        return Ok(());
+    }
+
+    println!("config diff: {left} -> {right}");
+    if diff.is_empty() {
+        println!("no differences");
+        return Ok(());
+    }
+    for change in &diff.settings {
+        println!("~ {}", format_config_change(change));
+    }
+    for name in &diff.added_profiles {
+        println!("+ profile {name}");
+    }
+    for name in &diff.removed_profiles {
+        println!("- profile {name}");
+    }
+    for profile in &diff.changed_profiles {
+        println!("~ profile {}", profile.name);
+        for change in &profile.changes {
+            println!("    {}", format_config_change(change));
+        }
+    }
+    Ok(())
+}
+
+/// Copies a profile to `--profile` without switching the active profile.
+fn handle_configure_copy(
+    cli: &Cli,
+    manager: &ConfigManager,
+    config: &mut ConfigFile,
+    from: &str,
+) -> Result<()> {
+    config.copy_profile(from, &cli.profile)?;
+    manager.save(config)?;
+    if cli.json {
+        print_json(&json!({
+            "ok": true,
+            "action": "copy",
+            "from": from,
+            "to": cli.profile,
+            "active_profile": config.active_profile,
+            "config_path": manager.path().display().to_string(),
+        }));
+    } else {
+        println!("Copied profile {from} -> {}", cli.profile);
+        println!("Active profile: {}", config.active_profile);
+    }
+    Ok(())
+}
+
+/// Reports every invalid profile instead of failing on the first one, and
+/// never writes the config back.
+/// Fails with a validation error when any issue is found; `--json` output keeps
+/// the report next to the error.
+fn handle_configure_validate(cli: &Cli, manager: &ConfigManager) -> Result<()> {
+    let report = manager.load_unvalidated()?.validation_report();
+    let invalid = || {
+        let issues = report.issues.len()
+            + report
+                .profiles
+                .iter()
+                .map(|profile| profile.issues.len())
+                .sum::<usize>();
+        MosaicError::Validation(format!(
+            "config {} is invalid ({issues} issue(s))",
+            manager.path().display()
+        ))
+    };
+    if cli.json {
+        let payload = json!({
+            "ok": report.ok,
+            "action": "validate",
+            "config_path": manager.path().display().to_string(),
+            "valid": report.ok,
+            "report": report,
+        });
+        if !report.ok {
+            attach_json_error_details(payload);
+            return Err(invalid());
+        }
+        print_json(&payload);
+        return Ok(());
+    }
+
+    println!(
+        "config {}: {}",
+        manager.path().display(),
+        if report.ok { "valid" } else { "invalid" }
+    );
+    for issue in &report.issues {
+        println!("- {issue}");
+    }
+    for profile in &report.profiles {
+        let status = if profile.ok { "ok" } else { "invalid" };
+        println!("profile {}: {status}", profile.name);
+        for issue in &profile.issues {
+            println!("    - {issue}");
+        }
+    }
+    if !report.ok {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+fn format_config_change(change: &ConfigFieldChange) -> String {
+    let render = |value: &Option<Value>| {
+        value
+            .as_ref()
+            .map(Value::to_string)
+            .unwrap_or_else(|| "<unset>".to_string())
+    };
+    format!(
+        "{}: {} -> {}",
+        change.field,
+        render(&change.before),
+        render(&change.after)
+    )
+}
+
 fn handle_configure_subcommand(
     cli: &Cli,
     manager: &ConfigManager,
@@ -383,7 +546,7 @@ fn handle_configure_subcommand(
         }
         ConfigureCommand::Unset { key } => {
             let key = parse_configure_key(&key)?;
-            let configured = unset_configure_value(profile, key);
+            let configured = unset_configure_value(profile, key)?;
             (
                 "unset",
                 configure_key_name(key).to_string(),
@@ -394,7 +557,9 @@ fn handle_configure_subcommand(
         ConfigureCommand::Keys
         | ConfigureCommand::Patch(_)
         | ConfigureCommand::Preview(_)
-        | ConfigureCommand::Template(_) => unreachable!(),
+        | ConfigureCommand::Template(_)
+        | ConfigureCommand::Diff { .. }
+        | ConfigureCommand::Validate => unreachable!(),
     };
 
     config.active_profile = cli.profile.clone();
@@ -497,6 +662,9 @@ fn set_configure_value_from_json(
     key: ConfigureKey,
     value: &Value,
 ) -> Result<Value> {
+    if configure_key_group(key) == "provider" {
+        profile.ensure_inline_provider()?;
+    }
     match key {
         ConfigureKey::ProviderBaseUrl => {
             let parsed = require_string_value(value, "provider.base_url")?;
@@ -546,9 +714,12 @@ fn set_configure_value_from_json(
     }
 }
 
-fn unset_configure_value(profile: &mut ProfileConfig, key: ConfigureKey) -> Value {
+fn unset_configure_value(profile: &mut ProfileConfig, key: ConfigureKey) -> Result<Value> {
+    if configure_key_group(key) == "provider" {
+        profile.ensure_inline_provider()?;
+    }
     let defaults = ProfileConfig::default();
-    match key {
+    Ok(match key {
         ConfigureKey::ProviderBaseUrl => {
             profile.provider.base_url = defaults.provider.base_url;
             json!(profile.provider.base_url)
@@ -577,7 +748,7 @@ fn unset_configure_value(profile: &mut ProfileConfig, key: ConfigureKey) -> Valu
             profile.tools.run.guard_mode = defaults.tools.run.guard_mode;
             json!(guard_mode_name(&profile.tools.run.guard_mode))
         }
-    }
+    })
 }
 
 fn handle_configure_patch(
@@ -1088,6 +1259,9 @@ pub(super) async fn handle_models(cli: &Cli, args: ModelsArgs) -> Result<()> {
                     } else {
                         println!("{}", model.id);
                     }
+                    for warning in &model.warnings {
+                        println!("  warning: {warning}");
+                    }
                 }
                 println!("Total models: {total_models}");
                 if let Some(query) = query {
@@ -1232,7 +1406,7 @@ pub(super) async fn handle_models(cli: &Cli, args: ModelsArgs) -> Result<()> {
             let paths = resolve_state_paths(cli.project_state)?;
             paths.ensure_dirs()?;
             let manager = ConfigManager::new(paths.config_path.clone());
-            let mut config = manager.load()?;
+            let mut config = manager.load_raw()?;
             let model_store = ModelRoutingStore::new(paths.models_path.clone());
             let profile_models = model_store.profile(&cli.profile)?;
             let (effective_model, used_alias) =
@@ -1241,6 +1415,7 @@ pub(super) async fn handle_models(cli: &Cli, args: ModelsArgs) -> Result<()> {
             let profile = config.profiles.get_mut(&cli.profile).ok_or_else(|| {
                 MosaicError::Config(format!("profile '{}' not found", cli.profile))
             })?;
+            profile.ensure_inline_provider()?;
             let previous_model = profile.provider.model.clone();
             profile.provider.model = effective_model.clone();
             config.active_profile = cli.profile.clone();
@@ -1341,6 +1516,21 @@ pub(super) async fn handle_models(cli: &Cli, args: ModelsArgs) -> Result<()> {
     Ok(())
 }
 
+fn normalize_run_label(label: Option<String>) -> Result<Option<String>> {
+    match label {
+        Some(label) => {
+            let trimmed = label.trim();
+            if trimmed.is_empty() {
+                return Err(MosaicError::Validation(
+                    "--label cannot be empty".to_string(),
+                ));
+            }
+            Ok(Some(trimmed.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
 pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
     let runtime = build_runtime(
         cli,
@@ -1349,12 +1539,14 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
         args.session.as_deref(),
     )?;
     let session_metadata = runtime.session_metadata();
+    let label = normalize_run_label(args.label)?;
     let mut session_id = args.session;
 
     if let Some(script_path) = args.script {
         let prompts = resolve_script_prompts(script_path)?;
         let mut run_results = Vec::with_capacity(prompts.len());
         let mut total_turns = 0u32;
+        let mut total_usage: Option<TokenUsage> = None;
         for (index, prompt) in prompts.into_iter().enumerate() {
             let result = runtime
                 .agent
@@ -1368,16 +1560,22 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
                         yes: cli.yes,
                         interactive: false,
                         event_callback: None,
+                        label: label.clone(),
                     },
                 )
                 .await?;
             session_id = Some(result.session_id.clone());
             total_turns = total_turns.saturating_add(result.turns);
+            if let Some(usage) = &result.usage {
+                total_usage.get_or_insert_default().accumulate(usage);
+            }
             run_results.push(json!({
                 "index": index + 1,
                 "prompt": prompt,
                 "response": result.response,
                 "turns": result.turns,
+                "usage": result.usage,
+                "estimated_cost_usd": result.estimated_cost_usd,
                 "session_id": result.session_id,
             }));
         }
@@ -1390,6 +1588,7 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
                 "runs": run_results,
                 "run_count": run_results.len(),
                 "total_turns": total_turns,
+                "total_usage": total_usage,
                 "agent_id": runtime.active_agent_id,
                 "profile": runtime.active_profile_name,
             }));
@@ -1408,6 +1607,9 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
             if let Some(agent_id) = &runtime.active_agent_id {
                 println!("agent: {agent_id}");
             }
+            if let Some(usage) = &total_usage {
+                println!("{}", format_token_usage(usage));
+            }
         }
         return Ok(());
     }
@@ -1424,6 +1626,7 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
                 yes: cli.yes,
                 interactive: false,
                 event_callback: None,
+                label,
             },
         )
         .await?;
@@ -1433,6 +1636,8 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
             "session_id": result.session_id,
             "response": result.response,
             "turns": result.turns,
+            "usage": result.usage,
+            "estimated_cost_usd": result.estimated_cost_usd,
             "agent_id": runtime.active_agent_id,
             "profile": runtime.active_profile_name,
         }));
@@ -1442,10 +1647,20 @@ pub(super) async fn handle_ask(cli: &Cli, args: super::AskArgs) -> Result<()> {
         if let Some(agent_id) = &runtime.active_agent_id {
             println!("agent: {agent_id}");
         }
+        if let Some(usage) = &result.usage {
+            println!("{}", format_token_usage(usage));
+        }
     }
     Ok(())
 }
 
+fn format_token_usage(usage: &TokenUsage) -> String {
+    format!(
+        "tokens: prompt={} completion={} total={}",
+        usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
+    )
+}
+
 pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
     if args.emit_events && cli.json {
         return Err(MosaicError::Validation(
@@ -1498,6 +1713,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                     yes: cli.yes,
                     interactive: true,
                     event_callback: Some(callback),
+                    label: None,
                 },
             )
             .await;
@@ -1530,6 +1746,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
         let prompts = resolve_script_prompts(script_path)?;
         let mut run_results = Vec::with_capacity(prompts.len());
         let mut total_turns = 0u32;
+        let mut total_usage: Option<TokenUsage> = None;
         for (index, prompt) in prompts.into_iter().enumerate() {
             let result = runtime
                 .agent
@@ -1543,16 +1760,22 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                         yes: cli.yes,
                         interactive: true,
                         event_callback: None,
+                        label: None,
                     },
                 )
                 .await?;
             session_id = Some(result.session_id.clone());
             total_turns = total_turns.saturating_add(result.turns);
+            if let Some(usage) = &result.usage {
+                total_usage.get_or_insert_default().accumulate(usage);
+            }
             run_results.push(json!({
                 "index": index + 1,
                 "prompt": prompt,
                 "response": result.response,
                 "turns": result.turns,
+                "usage": result.usage,
+                "estimated_cost_usd": result.estimated_cost_usd,
                 "session_id": result.session_id,
             }));
         }
@@ -1565,6 +1788,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                 "runs": run_results,
                 "run_count": run_results.len(),
                 "total_turns": total_turns,
+                "total_usage": total_usage,
                 "agent_id": runtime.active_agent_id,
                 "profile": runtime.active_profile_name,
             }));
@@ -1600,6 +1824,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                     yes: cli.yes,
                     interactive: true,
                     event_callback: None,
+                    label: None,
                 },
             )
             .await?;
@@ -1610,6 +1835,8 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                 "session_id": result.session_id,
                 "response": result.response,
                 "turns": result.turns,
+                "usage": result.usage,
+                "estimated_cost_usd": result.estimated_cost_usd,
                 "agent_id": runtime.active_agent_id,
                 "profile": runtime.active_profile_name,
             }));
@@ -1633,6 +1860,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
     if let Some(agent_id) = &runtime.active_agent_id {
         println!("Using agent: {agent_id}");
     }
+    let mut model_override: Option<String> = None;
     loop {
         print!("you> ");
         io::stdout()
@@ -1657,6 +1885,8 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                 println!("/agent    Show active agent");
                 println!("/agent ID Switch active agent");
                 println!("/agents   List configured agents");
+                println!("/model    Show active model");
+                println!("/model ID Switch model for later turns");
                 println!("/session  Show current session id");
                 println!("/new      Start a new chat session");
                 println!("/exit     Exit chat");
@@ -1680,6 +1910,7 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                     "agent: {}",
                     format_chat_agent(runtime.active_agent_id.as_deref())
                 );
+                println!("model: {}", runtime.active_model);
                 println!("session: {}", format_chat_session(session_id.as_deref()));
                 continue;
             }
@@ -1707,14 +1938,14 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                     continue;
                 }
 
-                let switched_runtime = match build_runtime(cli, Some(agent_id), Some("chat"), None)
-                {
-                    Ok(runtime) => runtime,
-                    Err(err) => {
-                        println!("error: {err}");
-                        continue;
-                    }
-                };
+                let switched_runtime =
+                    match build_chat_runtime(cli, Some(agent_id), model_override.as_deref()) {
+                        Ok(runtime) => runtime,
+                        Err(err) => {
+                            println!("error: {err}");
+                            continue;
+                        }
+                    };
                 runtime = switched_runtime;
                 session_metadata = runtime.session_metadata();
                 println!(
@@ -1730,10 +1961,51 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                 }
                 continue;
             }
+            ChatReplCommand::Model => {
+                println!("model: {}", runtime.active_model);
+                continue;
+            }
+            ChatReplCommand::ModelSet(model) => {
+                let switched_runtime = match build_chat_runtime(
+                    cli,
+                    runtime.active_agent_id.as_deref(),
+                    Some(model),
+                ) {
+                    Ok(runtime) => runtime,
+                    Err(err) => {
+                        println!("error: {err}");
+                        continue;
+                    }
+                };
+                match switched_runtime.provider.list_models().await {
+                    Ok(models)
+                        if models
+                            .iter()
+                            .any(|item| item.id == switched_runtime.active_model) => {}
+                    Ok(_) => {
+                        println!(
+                            "error: model '{}' is not offered by the provider; run `mosaic models list`",
+                            switched_runtime.active_model
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        println!("error: failed to verify model '{model}': {err}");
+                        continue;
+                    }
+                }
+                runtime = switched_runtime;
+                model_override = Some(model.to_string());
+                println!("model switched: {}", runtime.active_model);
+                continue;
+            }
             ChatReplCommand::Prompt(prompt) => {
+                // The label is printed with the first chunk so tool confirmation
+                // prompts raised earlier in the run stay on their own lines.
+                let mut streamed = false;
                 let result = runtime
                     .agent
-                    .ask(
+                    .ask_streaming(
                         prompt,
                         AgentRunOptions {
                             session_id: session_id.clone(),
@@ -1743,11 +2015,26 @@ pub(super) async fn handle_chat(cli: &Cli, args: ChatArgs) -> Result<()> {
                             yes: cli.yes,
                             interactive: true,
                             event_callback: None,
+                            label: None,
+                        },
+                        &mut |chunk| {
+                            if !streamed {
+                                print!("assistant> ");
+                                streamed = true;
+                            }
+                            print!("{chunk}");
+                            let _ = io::stdout().flush();
                         },
                     )
-                    .await?;
+                    .await;
+                if streamed {
+                    println!();
+                }
+                let result = result?;
+                if !streamed {
+                    println!("assistant> {}", result.response.trim());
+                }
                 session_id = Some(result.session_id.clone());
-                println!("assistant> {}", result.response.trim());
             }
         }
     }
@@ -1764,21 +2051,17 @@ enum ChatReplCommand<'a> {
     Agent,
     Agents,
     AgentSet(&'a str),
+    Model,
+    ModelSet(&'a str),
     Prompt(&'a str),
 }
 
 fn parse_chat_repl_command(prompt: &str) -> ChatReplCommand<'_> {
-    if let Some(rest) = prompt.strip_prefix("/agent") {
-        if rest.is_empty() {
-            return ChatReplCommand::Agent;
-        }
-        if rest.chars().next().is_some_and(char::is_whitespace) {
-            let requested = rest.trim();
-            if requested.is_empty() {
-                return ChatReplCommand::Agent;
-            }
-            return ChatReplCommand::AgentSet(requested);
-        }
+    if let Some(argument) = parse_chat_repl_argument(prompt, "/agent") {
+        return argument.map_or(ChatReplCommand::Agent, ChatReplCommand::AgentSet);
+    }
+    if let Some(argument) = parse_chat_repl_argument(prompt, "/model") {
+        return argument.map_or(ChatReplCommand::Model, ChatReplCommand::ModelSet);
     }
     match prompt {
         "/exit" | "exit" | "quit" => ChatReplCommand::Exit,
@@ -1791,6 +2074,31 @@ fn parse_chat_repl_command(prompt: &str) -> ChatReplCommand<'_> {
     }
 }
 
+/// Returns `Some(None)` for a bare `command`, `Some(Some(arg))` when an argument follows.
+fn parse_chat_repl_argument<'a>(prompt: &'a str, command: &str) -> Option<Option<&'a str>> {
+    let rest = prompt.strip_prefix(command)?;
+    if rest.is_empty() {
+        return Some(None);
+    }
+    if !rest.chars().next().is_some_and(char::is_whitespace) {
+        return None;
+    }
+    let requested = rest.trim();
+    Some((!requested.is_empty()).then_some(requested))
+}
+
+fn build_chat_runtime(
+    cli: &Cli,
+    agent_id: Option<&str>,
+    model: Option<&str>,
+) -> Result<RuntimeContext> {
+    let selector = RuntimeSelector {
+        model: model.map(ToOwned::to_owned),
+        ..RuntimeSelector::from(cli)
+    };
+    build_runtime_from_selector(&selector, agent_id, Some("chat"), None)
+}
+
 fn format_chat_session(session_id: Option<&str>) -> String {
     session_id
         .map(ToOwned::to_owned)
@@ -1968,67 +2276,6 @@ fn resolve_script_prompts(path: String) -> Result<Vec<String>> {
     Ok(prompts)
 }
 
-#[cfg(test)]
-mod repl_tests {
-    use super::*;
-
-    #[test]
-    fn parse_chat_repl_commands() {
-        assert!(matches!(
-            parse_chat_repl_command("/exit"),
-            ChatReplCommand::Exit
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/help"),
-            ChatReplCommand::Help
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/session"),
-            ChatReplCommand::Session
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/new"),
-            ChatReplCommand::New
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/status"),
-            ChatReplCommand::Status
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/agent"),
-            ChatReplCommand::Agent
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/agents"),
-            ChatReplCommand::Agents
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/agent writer"),
-            ChatReplCommand::AgentSet("writer")
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("/agent   reviewer"),
-            ChatReplCommand::AgentSet("reviewer")
-        ));
-        assert!(matches!(
-            parse_chat_repl_command("hello"),
-            ChatReplCommand::Prompt("hello")
-        ));
-    }
-
-    #[test]
-    fn chat_display_helpers_return_fallback_labels() {
-        assert_eq!(format_chat_session(None), "<new session>");
-        assert_eq!(format_chat_agent(None), "<none>");
-    }
-
-    #[test]
-    fn resolve_prompt_input_validates_non_empty_prompt() {
-        let err = resolve_prompt_input("   ".to_string()).expect_err("expected validation");
-        assert!(matches!(err, MosaicError::Validation(_)));
-    }
-}
-
 pub(super) async fn handle_session(cli: &Cli, args: SessionArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     let store = SessionStore::new(paths.sessions_dir.clone());
@@ -2056,9 +2303,14 @@ pub(super) async fn handle_session(cli: &Cli, args: SessionArgs) -> Result<()> {
                             )
                         },
                     );
+                    let title = session
+                        .title
+                        .as_deref()
+                        .map(|title| format!(" title={title:?}"))
+                        .unwrap_or_default();
                     println!(
-                        "{} events={} last={} {}",
-                        session.session_id, session.event_count, last, runtime
+                        "{} events={} last={} {}{}",
+                        session.session_id, session.event_count, last, runtime, title
                     );
                 }
             }
@@ -2107,6 +2359,48 @@ pub(super) async fn handle_session(cli: &Cli, args: SessionArgs) -> Result<()> {
             )
             .await?;
         }
+        SessionCommand::Fork { session_id } => {
+            let fork_id = store.fork(&session_id)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "source_session_id": session_id,
+                    "session_id": fork_id,
+                }));
+            } else {
+                println!("Forked session {session_id} -> {fork_id}");
+            }
+        }
+        SessionCommand::Rename { session_id, title } => {
+            store.rename(&session_id, &title)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "session_id": session_id,
+                    "title": title.trim(),
+                }));
+            } else {
+                println!("Renamed session {session_id} to {:?}", title.trim());
+            }
+        }
+        SessionCommand::Search { query } => {
+            let matches = store.search(&query)?;
+            if cli.json {
+                print_json(&json!({ "ok": true, "query": query, "matches": matches }));
+            } else if matches.is_empty() {
+                println!("No sessions match {query:?}.");
+            } else {
+                for item in matches {
+                    let title = item
+                        .title
+                        .as_deref()
+                        .map(|title| format!(" title={title:?}"))
+                        .unwrap_or_default();
+                    println!("{} hits={}{}", item.session_id, item.hits, title);
+                    println!("  {}", item.snippet);
+                }
+            }
+        }
         SessionCommand::Clear { session_id, all } => {
             if all {
                 let removed = store.clear_all()?;
@@ -2132,3 +2426,80 @@ pub(super) async fn handle_session(cli: &Cli, args: SessionArgs) -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod repl_tests {
+    use super::*;
+
+    #[test]
+    fn parse_chat_repl_commands() {
+        assert!(matches!(
+            parse_chat_repl_command("/exit"),
+            ChatReplCommand::Exit
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/help"),
+            ChatReplCommand::Help
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/session"),
+            ChatReplCommand::Session
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/new"),
+            ChatReplCommand::New
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/status"),
+            ChatReplCommand::Status
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/agent"),
+            ChatReplCommand::Agent
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/agents"),
+            ChatReplCommand::Agents
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/agent writer"),
+            ChatReplCommand::AgentSet("writer")
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/agent   reviewer"),
+            ChatReplCommand::AgentSet("reviewer")
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/model"),
+            ChatReplCommand::Model
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/model  "),
+            ChatReplCommand::Model
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/model gpt-4o-mini"),
+            ChatReplCommand::ModelSet("gpt-4o-mini")
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("/models"),
+            ChatReplCommand::Prompt("/models")
+        ));
+        assert!(matches!(
+            parse_chat_repl_command("hello"),
+            ChatReplCommand::Prompt("hello")
+        ));
+    }
+
+    #[test]
+    fn chat_display_helpers_return_fallback_labels() {
+        assert_eq!(format_chat_session(None), "<new session>");
+        assert_eq!(format_chat_agent(None), "<none>");
+    }
+
+    #[test]
+    fn resolve_prompt_input_validates_non_empty_prompt() {
+        let err = resolve_prompt_input("   ".to_string()).expect_err("expected validation");
+        assert!(matches!(err, MosaicError::Validation(_)));
+    }
+}
@@ -1,21 +1,26 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
 
 use serde_json::{Value, json};
 
-use mosaic_agents::{AgentStore, agent_routes_path, agents_file_path};
-use mosaic_channels::{ChannelRepository, channels_events_dir, channels_file_path};
+use mosaic_agents::{AgentRoutes, AgentStore, agent_routes_path, agents_file_path};
+use mosaic_channels::{
+    CHANNELS_SCHEMA_VERSION, ChannelRepository, channels_events_dir, channels_file_path,
+};
 use mosaic_core::config::ConfigManager;
 use mosaic_core::error::Result;
-use mosaic_core::provider::Provider;
 use mosaic_core::session::SessionStore;
 use mosaic_mcp::{McpStore, mcp_servers_file_path};
 use mosaic_memory::{MemoryStore, memory_index_path, memory_status_path};
 use mosaic_ops::{ApprovalStore, SandboxStore};
 use mosaic_plugins::{ExtensionRegistry, RegistryRoots};
-use mosaic_provider_openai::OpenAiCompatibleProvider;
-use mosaic_security::{SecurityAuditOptions, SecurityAuditor, SecurityBaselineConfig};
+use mosaic_security::{
+    DEFAULT_CONTEXT_LINES, SecurityAuditOptions, SecurityAuditor, SecurityBaselineConfig,
+};
 
-use super::{Cli, binary_in_path, print_json, resolve_state_paths};
+use super::{
+    Cli, DoctorArgs, binary_in_path, print_json, provider_from_profile, resolve_state_paths,
+};
 
 pub(super) fn run_check(
     name: impl Into<String>,
@@ -144,7 +149,7 @@ pub(super) async fn handle_health(cli: &Cli) -> Result<()> {
         let config = manager.load()?;
         checks.push(run_check("config", true, "config valid"));
         let resolved = config.resolve_profile(Some(&cli.profile))?;
-        let provider = OpenAiCompatibleProvider::from_profile(&resolved.profile)?;
+        let provider = provider_from_profile(&resolved.profile)?;
         let health = provider.health().await?;
         checks.push(run_check(
             "provider",
@@ -202,7 +207,7 @@ pub(super) async fn collect_doctor_checks(cli: &Cli) -> Result<Vec<BTreeMap<Stri
         ));
 
         if api_key_exists {
-            let provider = OpenAiCompatibleProvider::from_profile(&resolved.profile)?;
+            let provider = provider_from_profile(&resolved.profile)?;
             let provider_health = provider.health().await?;
             checks.push(run_check(
                 "provider_connectivity",
@@ -325,7 +330,7 @@ pub(super) async fn collect_doctor_checks(cli: &Cli) -> Result<Vec<BTreeMap<Stri
                 format!(
                     "mode={:?} allowlist_size={} path={}",
                     policy.mode,
-                    policy.allowlist.len(),
+                    policy.allowlist().len(),
                     approval_store.path().display()
                 ),
             ));
@@ -411,8 +416,7 @@ pub(super) async fn collect_doctor_checks(cli: &Cli) -> Result<Vec<BTreeMap<Stri
         }
     }
 
-    let extension_registry =
-        ExtensionRegistry::new(RegistryRoots::from_state_root(paths.root_dir.clone()));
+    let extension_registry = ExtensionRegistry::new(RegistryRoots::from_state_paths(&paths));
     match extension_registry.check_plugins(None) {
         Ok(report) => {
             checks.push(run_check(
@@ -483,6 +487,9 @@ pub(super) async fn collect_doctor_checks(cli: &Cli) -> Result<Vec<BTreeMap<Stri
         deep: false,
         max_files: 200,
         max_file_size: 131_072,
+        cache_path: None,
+        changed_since: None,
+        context_lines: DEFAULT_CONTEXT_LINES,
     });
     match security_report {
         Ok(report) => {
@@ -511,7 +518,117 @@ pub(super) async fn collect_doctor_checks(cli: &Cli) -> Result<Vec<BTreeMap<Stri
     Ok(checks)
 }
 
-pub(super) async fn handle_doctor(cli: &Cli) -> Result<()> {
+pub(super) async fn handle_doctor(cli: &Cli, args: DoctorArgs) -> Result<()> {
+    if !args.fix {
+        let checks = collect_doctor_checks(cli).await?;
+        return emit_checks(cli.json, "doctor", checks);
+    }
+
+    let fixes = apply_doctor_fixes(cli)?;
     let checks = collect_doctor_checks(cli).await?;
-    emit_checks(cli.json, "doctor", checks)
+    if cli.json {
+        print_json(&json!({
+            "ok": true,
+            "type": "doctor",
+            "checks": checks,
+            "fixes": fixes,
+        }));
+        return Ok(());
+    }
+    emit_checks(false, "doctor", checks)?;
+    if fixes.is_empty() {
+        println!("fixes: none needed");
+    } else {
+        println!("fixes:");
+        for fix in fixes {
+            let field = |key: &str| fix.get(key).and_then(Value::as_str).unwrap_or("-");
+            println!(
+                "[FIXED] {}: {} -> {}",
+                field("name"),
+                field("before"),
+                field("after")
+            );
+        }
+    }
+    Ok(())
+}
+
+fn fix_entry(
+    name: impl Into<String>,
+    before: impl Into<String>,
+    after: impl Into<String>,
+) -> BTreeMap<String, Value> {
+    let mut map = BTreeMap::new();
+    map.insert("name".to_string(), Value::String(name.into()));
+    map.insert("before".to_string(), Value::String(before.into()));
+    map.insert("after".to_string(), Value::String(after.into()));
+    map
+}
+
+/// Applies repairs that cannot lose user data. Anything riskier stays a warning
+/// in the regular checks.
+fn apply_doctor_fixes(cli: &Cli) -> Result<Vec<BTreeMap<String, Value>>> {
+    let paths = resolve_state_paths(cli.project_state)?;
+    let mut fixes = vec![];
+
+    let missing_dirs = [
+        paths.config_path.parent().map(Path::to_path_buf),
+        Some(paths.data_dir.clone()),
+        Some(paths.policy_dir.clone()),
+        Some(paths.sessions_dir.clone()),
+        Some(paths.audit_dir.clone()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|dir| !dir.exists())
+    .map(|dir| dir.display().to_string())
+    .collect::<BTreeSet<_>>();
+    if !missing_dirs.is_empty() {
+        paths.ensure_dirs()?;
+        let missing = missing_dirs.into_iter().collect::<Vec<_>>().join(", ");
+        fixes.push(fix_entry(
+            "state_dirs",
+            format!("missing {missing}"),
+            format!("created {missing}"),
+        ));
+    }
+
+    let channels_repo = ChannelRepository::new(
+        channels_file_path(&paths.data_dir),
+        channels_events_dir(&paths.data_dir),
+    );
+    if let Some(previous_version) = channels_repo.migrate()? {
+        fixes.push(fix_entry(
+            "channels_file",
+            format!("schema v{previous_version}"),
+            format!("schema v{CHANNELS_SCHEMA_VERSION}"),
+        ));
+    }
+
+    let agent_store = AgentStore::new(
+        agents_file_path(&paths.data_dir),
+        agent_routes_path(&paths.data_dir),
+    );
+    if let Some((before, after)) = agent_store.prune_dangling_routes()? {
+        fixes.push(fix_entry(
+            "agent_routes",
+            describe_routes(&before),
+            describe_routes(&after),
+        ));
+    }
+
+    Ok(fixes)
+}
+
+fn describe_routes(routes: &AgentRoutes) -> String {
+    let entries = routes
+        .routes
+        .iter()
+        .map(|(route, agent_id)| format!("{route}={agent_id}"))
+        .collect::<Vec<_>>();
+    format!(
+        "routes=[{}] default={}",
+        entries.join(", "),
+        routes.default_agent_id.as_deref().unwrap_or("-")
+    )
 }
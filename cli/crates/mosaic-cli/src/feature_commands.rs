@@ -9,31 +9,35 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use serde_json::{Value, json};
 
+use mosaic_core::config::ConfigManager;
 use mosaic_core::error::MosaicError;
-use mosaic_core::privacy::{append_sanitized_jsonl, write_pretty_state_json_file};
+use mosaic_core::privacy::append_sanitized_jsonl;
 use mosaic_memory::{
     MemoryCleanupPolicyStore, MemoryIndexOptions, MemoryPruneOptions, MemoryStore,
-    list_memory_namespace_statuses, memory_cleanup_policy_path, memory_index_path_for_namespace,
-    memory_status_path_for_namespace, prune_memory_namespaces,
+    ProviderEmbedder, list_memory_namespace_statuses, memory_cleanup_policy_path,
+    memory_index_path_for_namespace, memory_status_path_for_namespace, prune_memory_namespaces,
 };
 use mosaic_ops::{
-    ApprovalDecision, ApprovalStore, SandboxProfile, SandboxStore, evaluate_approval,
-    evaluate_sandbox,
+    ApprovalDecision, ApprovalStore, SandboxPolicy, SandboxProfile, SandboxStore,
+    evaluate_approval, evaluate_sandbox_policy,
+};
+use mosaic_plugins::{
+    ExtensionKind, ExtensionRegistry, ExtensionSource, InstallOptions, PluginEntry, RegistryRoots,
+    extension_state_path,
 };
-use mosaic_plugins::{ExtensionRegistry, ExtensionSource, PluginEntry, RegistryRoots};
 
 use super::{
-    BrowserArgs, BrowserCommand, Cli, ExtensionSourceFilterArg, MemoryArgs, MemoryCommand,
-    MemoryPolicyCommand, PluginHookArg, PluginsArgs, PluginsCommand, Result, SkillsArgs,
-    SkillsCommand, browser_history_file_path, browser_open_visit, browser_state_file_path,
-    load_browser_history_or_default, load_browser_state_or_default, print_json,
-    resolve_output_path, resolve_state_paths, save_browser_history, save_browser_state,
+    BrowserArgs, BrowserCommand, Cli, ExtensionSourceFilterArg, ListFormatArg, MemoryArgs,
+    MemoryCommand, MemoryPolicyCommand, PluginHookArg, PluginsArgs, PluginsCommand, Result,
+    SkillsArgs, SkillsCommand, browser_history_file_path, browser_open_visit,
+    browser_state_file_path, load_browser_history_or_default, load_browser_state_or_default,
+    print_json, provider_from_profile, render_table, resolve_output_path, resolve_state_paths,
+    save_browser_history, save_browser_state,
 };
 
-const PLUGIN_STATE_VERSION: u32 = 1;
 const DEFAULT_PLUGIN_HOOK_TIMEOUT_MS: u64 = 15_000;
 const DEFAULT_PLUGIN_MAX_OUTPUT_BYTES: u64 = 262_144;
 const MAX_PLUGIN_MAX_OUTPUT_BYTES: u64 = 16 * 1024 * 1024;
@@ -51,54 +55,6 @@ const PLUGIN_CPU_WATCHDOG_MIN_BUDGET_MS: u64 = 200;
 ))]
 const MIN_PLUGIN_MEMORY_RLIMIT_BYTES: u64 = 16 * 1024 * 1024;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct PluginStateFile {
-    version: u32,
-    disabled_plugins: Vec<String>,
-}
-
-impl Default for PluginStateFile {
-    fn default() -> Self {
-        Self {
-            version: PLUGIN_STATE_VERSION,
-            disabled_plugins: Vec::new(),
-        }
-    }
-}
-
-impl PluginStateFile {
-    fn is_enabled(&self, plugin_id: &str) -> bool {
-        !self.disabled_plugins.iter().any(|item| item == plugin_id)
-    }
-
-    fn set_enabled(&mut self, plugin_id: &str, enabled: bool) -> bool {
-        let normalized = plugin_id.trim();
-        if normalized.is_empty() {
-            return false;
-        }
-        let mut disabled = self
-            .disabled_plugins
-            .iter()
-            .map(|item| item.trim().to_string())
-            .filter(|item| !item.is_empty())
-            .collect::<BTreeSet<_>>();
-        let changed = if enabled {
-            disabled.remove(normalized)
-        } else {
-            disabled.insert(normalized.to_string())
-        };
-        self.disabled_plugins = disabled.into_iter().collect();
-        changed
-    }
-}
-
-#[derive(Debug, Clone, Serialize)]
-struct PluginView {
-    #[serde(flatten)]
-    plugin: PluginEntry,
-    enabled: bool,
-}
-
 pub(super) async fn handle_browser(cli: &Cli, args: BrowserArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
@@ -549,7 +505,7 @@ pub(super) async fn handle_browser(cli: &Cli, args: BrowserArgs) -> Result<()> {
                         }
                     }
                     history = deduped_by_id.into_values().collect::<Vec<_>>();
-                    history.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+                    history.sort_by_key(|lhs| lhs.ts);
                     history_changed = true;
                     actions.push(json!({
                         "kind": "dedupe_visit_ids",
@@ -558,7 +514,7 @@ pub(super) async fn handle_browser(cli: &Cli, args: BrowserArgs) -> Result<()> {
                         "detail": "duplicate visit ids removed (kept latest entry per id)",
                     }));
                 } else if !history_was_sorted {
-                    history.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+                    history.sort_by_key(|lhs| lhs.ts);
                     history_changed = true;
                     actions.push(json!({
                         "kind": "sort_visit_history",
@@ -1061,7 +1017,7 @@ fn sorted_tail_visits(
     mut visits: Vec<super::BrowserVisitRecord>,
     tail: usize,
 ) -> Vec<super::BrowserVisitRecord> {
-    visits.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
+    visits.sort_by_key(|lhs| lhs.ts);
     if visits.len() > tail {
         let keep_from = visits.len() - tail;
         visits.split_off(keep_from)
@@ -1167,7 +1123,7 @@ fn sample_string_items(values: &[String], limit: usize) -> Vec<String> {
     values.iter().take(limit).cloned().collect()
 }
 
-pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
+pub(super) async fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
 
@@ -1178,9 +1134,12 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
             incremental,
             stale_after_hours,
             retain_missing,
+            no_ignore,
             max_files,
             max_file_size,
             max_content_bytes,
+            embed,
+            embedding_model,
         } => {
             let namespace = normalize_memory_namespace(&namespace)?;
             let store = memory_store_for_namespace(&paths.data_dir, &namespace);
@@ -1198,16 +1157,27 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
                 incremental,
                 stale_after_hours,
                 retain_missing,
+                respect_ignore_files: !no_ignore,
                 max_files,
                 max_file_size,
                 max_content_bytes,
             })?;
+            let embedded_documents = if embed {
+                let embedder = memory_embedder(cli, &paths.config_path, &embedding_model)?;
+                Some(store.embed_documents(&embedder).await?)
+            } else {
+                None
+            };
             if cli.json {
-                print_json(&json!({
+                let mut payload = json!({
                     "ok": true,
                     "namespace": namespace,
                     "index": result,
-                }));
+                });
+                if let Some(embedded_documents) = embedded_documents {
+                    payload["embedded_documents"] = json!(embedded_documents);
+                }
+                print_json(&payload);
             } else {
                 println!("memory namespace: {namespace}");
                 println!("memory indexed documents: {}", result.indexed_documents);
@@ -1226,6 +1196,9 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
                     );
                 }
                 println!("memory skipped files: {}", result.skipped_files);
+                if let Some(embedded_documents) = embedded_documents {
+                    println!("memory embedded documents: {embedded_documents}");
+                }
                 println!("index path: {}", result.index_path);
             }
         }
@@ -1233,10 +1206,24 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
             query,
             namespace,
             limit,
+            semantic,
+            embedding_model,
         } => {
             let namespace = normalize_memory_namespace(&namespace)?;
             let store = memory_store_for_namespace(&paths.data_dir, &namespace);
-            let result = store.search(&query, Some(limit))?;
+            let result = if semantic {
+                // Without a usable provider the search degrades to lexical ranking.
+                let embedder = memory_embedder(cli, &paths.config_path, &embedding_model).ok();
+                store
+                    .search_semantic(
+                        &query,
+                        Some(limit),
+                        embedder.as_ref().map(|embedder| embedder as _),
+                    )
+                    .await?
+            } else {
+                store.search(&query, Some(limit))?
+            };
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -1248,8 +1235,9 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
             } else {
                 println!("memory namespace: {namespace}");
                 println!(
-                    "memory search hits: {} (showing {})",
+                    "memory search hits: {} matches: {} (showing {})",
                     result.total_hits,
+                    result.total_matches,
                     result.hits.len()
                 );
                 for hit in result.hits {
@@ -1309,6 +1297,22 @@ pub(super) fn handle_memory(cli: &Cli, args: MemoryArgs) -> Result<()> {
                 println!("index path: {}", status.index_path);
             }
         }
+        MemoryCommand::Remove { path, namespace } => {
+            let namespace = normalize_memory_namespace(&namespace)?;
+            let store = memory_store_for_namespace(&paths.data_dir, &namespace);
+            let removed = store.remove(&path)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "namespace": namespace,
+                    "path": path,
+                    "removed_documents": removed,
+                }));
+            } else {
+                println!("memory namespace: {namespace}");
+                println!("memory removed documents: {removed}");
+            }
+        }
         MemoryCommand::Clear { namespace } => {
             let namespace = normalize_memory_namespace(&namespace)?;
             let store = memory_store_for_namespace(&paths.data_dir, &namespace);
@@ -1656,6 +1660,13 @@ fn normalize_memory_namespace(raw: &str) -> Result<String> {
     }
 }
 
+fn memory_embedder(cli: &Cli, config_path: &Path, model: &str) -> Result<ProviderEmbedder> {
+    let config = ConfigManager::new(config_path.to_path_buf()).load()?;
+    let resolved = config.resolve_profile(Some(&cli.profile))?;
+    let provider = provider_from_profile(&resolved.profile)?;
+    Ok(ProviderEmbedder::new(provider, model))
+}
+
 fn memory_store_for_namespace(data_dir: &Path, namespace: &str) -> MemoryStore {
     MemoryStore::new(
         memory_index_path_for_namespace(data_dir, namespace),
@@ -1667,85 +1678,131 @@ fn memory_cleanup_policy_store(policy_dir: &Path) -> MemoryCleanupPolicyStore {
     MemoryCleanupPolicyStore::new(memory_cleanup_policy_path(policy_dir))
 }
 
-pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
+pub(super) async fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
-    let registry = ExtensionRegistry::new(RegistryRoots::from_state_root(paths.root_dir.clone()));
-    let plugin_state_path = paths.data_dir.join("plugins-state.json");
-    let mut plugin_state = load_plugin_state(&plugin_state_path)?;
+    let registry = ExtensionRegistry::new(RegistryRoots::from_state_paths(&paths));
+    let plugin_state_path = extension_state_path(&paths.data_dir);
 
     match args.command {
-        PluginsCommand::List { source } => {
+        PluginsCommand::List { source, format } => {
             let requested_source = parse_extension_source_filter(source);
-            let plugins = registry
-                .list_plugins()?
-                .into_iter()
-                .filter(|entry| source_matches(requested_source, entry.source))
-                .collect::<Vec<_>>();
-            let plugin_views = plugin_views_with_state(plugins, &plugin_state);
+            let plugins = registry.list_plugins(requested_source)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
                     "source_filter": extension_source_filter_name(source),
-                    "count": plugin_views.len(),
-                    "plugins": plugin_views,
+                    "count": plugins.len(),
+                    "plugins": plugins,
                 }));
-            } else if plugin_views.is_empty() {
+            } else if plugins.is_empty() {
                 println!("No plugins found.");
+            } else if format == ListFormatArg::Table {
+                let rows = plugins
+                    .into_iter()
+                    .map(|plugin| {
+                        vec![
+                            plugin.id,
+                            plugin.name,
+                            plugin.source.as_str().to_string(),
+                            plugin.enabled.to_string(),
+                            plugin.version.unwrap_or_else(|| "-".to_string()),
+                            plugin.manifest_valid.to_string(),
+                            plugin
+                                .shadowed_by
+                                .map(|winner| winner.as_str().to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                print!(
+                    "{}",
+                    render_table(
+                        &[
+                            "ID",
+                            "NAME",
+                            "SOURCE",
+                            "ENABLED",
+                            "VERSION",
+                            "MANIFEST_VALID",
+                            "SHADOWED_BY",
+                        ],
+                        &rows,
+                    )
+                );
             } else {
-                println!("plugins: {}", plugin_views.len());
+                println!("plugins: {}", plugins.len());
                 println!("source filter: {}", extension_source_filter_name(source));
-                for plugin in plugin_views {
+                for plugin in plugins {
                     println!(
-                        "- {} ({}) source={:?} enabled={} version={} manifest_valid={}",
-                        plugin.plugin.id,
-                        plugin.plugin.name,
-                        plugin.plugin.source,
+                        "- {} ({}) source={:?} enabled={} version={} manifest_valid={}{}",
+                        plugin.id,
+                        plugin.name,
+                        plugin.source,
                         plugin.enabled,
-                        plugin.plugin.version.unwrap_or_else(|| "-".to_string()),
-                        plugin.plugin.manifest_valid
+                        plugin.version.unwrap_or_else(|| "-".to_string()),
+                        plugin.manifest_valid,
+                        plugin
+                            .shadowed_by
+                            .map(|winner| format!(" shadowed_by={}", winner.as_str()))
+                            .unwrap_or_default()
                     );
                 }
             }
         }
         PluginsCommand::Info { plugin_id } => {
             let plugin = registry.plugin_info(&plugin_id)?;
-            let plugin_view = PluginView {
-                enabled: plugin_state.is_enabled(&plugin.id),
-                plugin,
-            };
             if cli.json {
                 print_json(&json!({
                     "ok": true,
-                    "plugin": plugin_view,
+                    "plugin": plugin,
                 }));
             } else {
-                println!("id: {}", plugin_view.plugin.id);
-                println!("name: {}", plugin_view.plugin.name);
-                println!("source: {:?}", plugin_view.plugin.source);
-                println!("enabled: {}", plugin_view.enabled);
+                println!("id: {}", plugin.id);
+                println!("name: {}", plugin.name);
+                println!("source: {:?}", plugin.source);
+                println!("enabled: {}", plugin.enabled);
                 println!(
                     "version: {}",
-                    plugin_view
-                        .plugin
-                        .version
-                        .unwrap_or_else(|| "-".to_string())
+                    plugin.version.unwrap_or_else(|| "-".to_string())
                 );
                 println!(
                     "description: {}",
-                    plugin_view
-                        .plugin
-                        .description
-                        .unwrap_or_else(|| "-".to_string())
+                    plugin.description.unwrap_or_else(|| "-".to_string())
                 );
-                println!("path: {}", plugin_view.plugin.path);
-                println!("manifest path: {}", plugin_view.plugin.manifest_path);
-                println!("manifest valid: {}", plugin_view.plugin.manifest_valid);
-                if let Some(error) = plugin_view.plugin.manifest_error {
+                println!("path: {}", plugin.path);
+                println!("manifest path: {}", plugin.manifest_path);
+                println!("manifest valid: {}", plugin.manifest_valid);
+                if let Some(error) = plugin.manifest_error {
                     println!("manifest error: {error}");
                 }
             }
         }
+        PluginsCommand::Search { query } => {
+            let plugins = registry.search_plugins(&query)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "query": query,
+                    "count": plugins.len(),
+                    "plugins": plugins,
+                }));
+            } else if plugins.is_empty() {
+                println!("No plugins match '{query}'.");
+            } else {
+                println!("plugins matching '{query}': {}", plugins.len());
+                for plugin in plugins {
+                    println!(
+                        "- {} ({}) source={:?} enabled={} {}",
+                        plugin.id,
+                        plugin.name,
+                        plugin.source,
+                        plugin.enabled,
+                        plugin.description.unwrap_or_default()
+                    );
+                }
+            }
+        }
         PluginsCommand::Check { plugin_id } => {
             let report = registry.check_plugins(plugin_id.as_deref())?;
             if cli.json {
@@ -1760,8 +1817,11 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                 );
                 for result in report.results {
                     println!(
-                        "- {} source={:?} ok={}",
-                        result.id, result.source, result.ok
+                        "- {} source={:?} ok={}{}",
+                        result.id,
+                        result.source,
+                        result.ok,
+                        if result.enabled { "" } else { " (disabled)" }
                     );
                     for check in result.checks {
                         let status = if check.ok { "OK" } else { "WARN" };
@@ -1770,26 +1830,70 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                 }
             }
         }
-        PluginsCommand::Install { path, force } => {
-            let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
-            let source = {
-                let value = PathBuf::from(path);
-                if value.is_absolute() {
-                    value
-                } else {
-                    cwd.join(value)
+        PluginsCommand::Install {
+            path,
+            url,
+            force,
+            sha256,
+            verify,
+            dry_run,
+        } => {
+            let options = InstallOptions {
+                force,
+                expected_sha256: sha256,
+                verify,
+                dry_run,
+            };
+            let outcome = match (url, path) {
+                (Some(url), _) => registry.install_plugin_from_url(&url, &options).await?,
+                (None, Some(path)) => {
+                    let cwd =
+                        std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
+                    let source = {
+                        let value = PathBuf::from(path);
+                        if value.is_absolute() {
+                            value
+                        } else {
+                            cwd.join(value)
+                        }
+                    };
+                    registry.install_plugin_from_path(&source, &options)?
+                }
+                (None, None) => {
+                    return Err(MosaicError::Validation(
+                        "plugins install requires --path or --url".to_string(),
+                    ));
                 }
             };
-            let outcome = registry.install_plugin_from_path(&source, force)?;
-            let state_changed = plugin_state.set_enabled(&outcome.id, true);
-            if state_changed {
-                save_plugin_state(&plugin_state_path, &plugin_state)?;
+            if outcome.dry_run {
+                if cli.json {
+                    print_json(&json!({
+                        "ok": true,
+                        "dry_run": true,
+                        "installed": outcome,
+                    }));
+                } else {
+                    println!(
+                        "Would install plugin {} -> {}",
+                        outcome.id, outcome.installed_path
+                    );
+                    if outcome.replaced {
+                        println!("would replace existing plugin package");
+                    }
+                    if let Some(digest) = &outcome.sha256 {
+                        println!("sha256: {digest}");
+                    }
+                }
+                return Ok(());
             }
+            let state_changed =
+                registry.clear_enabled_override(ExtensionKind::Plugin, &outcome.id)?;
+            let enabled = registry.plugin_info(&outcome.id)?.enabled;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
                     "installed": outcome,
-                    "enabled": true,
+                    "enabled": enabled,
                     "state_changed": state_changed,
                     "state_path": plugin_state_path.display().to_string(),
                 }));
@@ -1801,17 +1905,18 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                 if outcome.replaced {
                     println!("replaced existing plugin package");
                 }
+                if let Some(digest) = &outcome.sha256 {
+                    println!("sha256: {digest}");
+                }
+                println!("enabled: {enabled}");
                 if state_changed {
-                    println!("plugin enabled in state: {}", plugin_state_path.display());
+                    println!("plugin state reset: {}", plugin_state_path.display());
                 }
             }
         }
         PluginsCommand::Enable { plugin_id } => {
             let plugin = registry.plugin_info(&plugin_id)?;
-            let changed = plugin_state.set_enabled(&plugin.id, true);
-            if changed {
-                save_plugin_state(&plugin_state_path, &plugin_state)?;
-            }
+            let changed = registry.set_enabled(ExtensionKind::Plugin, &plugin.id, true)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -1829,10 +1934,7 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
         }
         PluginsCommand::Disable { plugin_id } => {
             let plugin = registry.plugin_info(&plugin_id)?;
-            let changed = plugin_state.set_enabled(&plugin.id, false);
-            if changed {
-                save_plugin_state(&plugin_state_path, &plugin_state)?;
-            }
+            let changed = registry.set_enabled(ExtensionKind::Plugin, &plugin.id, false)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -1849,16 +1951,16 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
             }
         }
         PluginsCommand::Doctor => {
-            let plugins = registry.list_plugins()?;
+            let plugins = registry.list_plugins(None)?;
             let report = registry.check_plugins(None)?;
             let enabled_plugins_list = plugins
                 .iter()
-                .filter(|plugin| plugin_state.is_enabled(&plugin.id))
+                .filter(|plugin| plugin.enabled)
                 .cloned()
                 .collect::<Vec<_>>();
             let disabled_plugins = plugins
                 .iter()
-                .filter(|plugin| !plugin_state.is_enabled(&plugin.id))
+                .filter(|plugin| !plugin.enabled)
                 .map(|plugin| plugin.id.clone())
                 .collect::<Vec<_>>();
             let enabled_plugins = plugins.len().saturating_sub(disabled_plugins.len());
@@ -1876,8 +1978,9 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                 .into_iter()
                 .map(|plugin| plugin.id)
                 .collect::<BTreeSet<_>>();
-            let stale_disabled_ids = plugin_state
-                .disabled_plugins
+            let stale_disabled_ids = registry
+                .extension_state()?
+                .disabled_ids(ExtensionKind::Plugin)
                 .iter()
                 .filter(|plugin_id| !installed_plugin_ids.contains(*plugin_id))
                 .cloned()
@@ -1953,7 +2056,7 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
             args,
         } => {
             let plugin = registry.plugin_info(&plugin_id)?;
-            if !plugin_state.is_enabled(&plugin.id) {
+            if !plugin.enabled {
                 return Err(MosaicError::Validation(format!(
                     "plugin '{}' is disabled. run `mosaic plugins enable {}` first",
                     plugin.id, plugin.id
@@ -1968,11 +2071,12 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
             let cpu_watchdog_ms = resolve_plugin_cpu_watchdog_ms(&plugin)?;
             validate_plugin_resource_limits_platform(&plugin, resource_limits)?;
             let sandbox_store = SandboxStore::new(paths.sandbox_policy_path.clone());
-            let default_profile = sandbox_store.load_or_default()?.profile;
-            let sandbox_profile = resolve_plugin_sandbox_profile(&plugin, default_profile)?;
-            validate_plugin_hook_sandbox(&plugin, &hook_path, sandbox_profile)?;
+            let sandbox_policy =
+                resolve_plugin_sandbox_policy(&plugin, sandbox_store.load_or_default()?)?;
+            let sandbox_profile = sandbox_policy.profile_name();
+            validate_plugin_hook_sandbox(&plugin, &hook_path, &sandbox_policy)?;
             let command = build_plugin_hook_command(&hook_path, &args)?;
-            if let Some(reason) = evaluate_sandbox(&command.rendered, sandbox_profile) {
+            if let Some(reason) = evaluate_sandbox_policy(&command.rendered, &sandbox_policy) {
                 return Err(MosaicError::SandboxDenied(reason));
             }
             let approval_store = ApprovalStore::new(paths.approvals_policy_path.clone());
@@ -2032,7 +2136,7 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                     timeout_ms: resolved_timeout_ms,
                     output_limit_bytes: execution.output_limit_bytes,
                     timed_out: execution.timed_out,
-                    sandbox_profile: sandbox_profile_label(sandbox_profile).to_string(),
+                    sandbox_profile: sandbox_profile.clone(),
                     approved_by: approved_by.clone(),
                     resource_limits: resource_limits.as_option(),
                     resource_metrics: execution.resources.clone(),
@@ -2081,7 +2185,7 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                     "timeout_ms": resolved_timeout_ms,
                     "output_limit_bytes": execution.output_limit_bytes,
                     "timed_out": execution.timed_out,
-                    "sandbox_profile": sandbox_profile_label(sandbox_profile),
+                    "sandbox_profile": sandbox_profile,
                     "approved_by": approved_by,
                     "resource_limits": resource_limits.as_option(),
                     "resource_metrics": execution.resources.clone(),
@@ -2101,10 +2205,7 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
                 println!("hook: {hook_display}");
                 println!("hook_path: {}", hook_path.display());
                 println!("timeout_ms: {resolved_timeout_ms}");
-                println!(
-                    "sandbox_profile: {}",
-                    sandbox_profile_label(sandbox_profile)
-                );
+                println!("sandbox_profile: {}", sandbox_profile);
                 println!("approved_by: {approved_by}");
                 println!("timed_out: {}", execution.timed_out);
                 println!("exit_code: {exit_code}");
@@ -2174,16 +2275,11 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
             }
         }
         PluginsCommand::Remove { plugin_id } => {
+            let had_override = registry
+                .extension_state()?
+                .has_override(ExtensionKind::Plugin, &plugin_id);
             let removed = registry.remove_project_plugin(&plugin_id)?;
-            let state_changed = if removed {
-                let changed = plugin_state.set_enabled(&plugin_id, true);
-                if changed {
-                    save_plugin_state(&plugin_state_path, &plugin_state)?;
-                }
-                changed
-            } else {
-                false
-            };
+            let state_changed = removed && had_override;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -2207,16 +2303,12 @@ pub(super) fn handle_plugins(cli: &Cli, args: PluginsArgs) -> Result<()> {
 pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
-    let registry = ExtensionRegistry::new(RegistryRoots::from_state_root(paths.root_dir.clone()));
+    let registry = ExtensionRegistry::new(RegistryRoots::from_state_paths(&paths));
 
     match args.command {
-        SkillsCommand::List { source } => {
+        SkillsCommand::List { source, format } => {
             let requested_source = parse_extension_source_filter(source);
-            let skills = registry
-                .list_skills()?
-                .into_iter()
-                .filter(|entry| source_matches(requested_source, entry.source))
-                .collect::<Vec<_>>();
+            let skills = registry.list_skills(requested_source)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -2226,11 +2318,35 @@ pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
                 }));
             } else if skills.is_empty() {
                 println!("No skills found.");
+            } else if format == ListFormatArg::Table {
+                let rows = skills
+                    .into_iter()
+                    .map(|skill| {
+                        vec![
+                            skill.id,
+                            skill.title,
+                            skill.source.as_str().to_string(),
+                            skill.enabled.to_string(),
+                            if skill.tags.is_empty() {
+                                "-".to_string()
+                            } else {
+                                skill.tags.join(",")
+                            },
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                print!(
+                    "{}",
+                    render_table(&["ID", "TITLE", "SOURCE", "ENABLED", "TAGS"], &rows)
+                );
             } else {
                 println!("skills: {}", skills.len());
                 println!("source filter: {}", extension_source_filter_name(source));
                 for skill in skills {
-                    println!("- {} ({}) source={:?}", skill.id, skill.title, skill.source);
+                    println!(
+                        "- {} ({}) source={:?} enabled={}",
+                        skill.id, skill.title, skill.source, skill.enabled
+                    );
                 }
             }
         }
@@ -2248,11 +2364,40 @@ pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
                     "description: {}",
                     skill.description.unwrap_or_else(|| "-".to_string())
                 );
+                if !skill.tags.is_empty() {
+                    println!("tags: {}", skill.tags.join(", "));
+                }
                 println!("source: {:?}", skill.source);
+                println!("enabled: {}", skill.enabled);
                 println!("path: {}", skill.path);
                 println!("skill file: {}", skill.skill_file);
             }
         }
+        SkillsCommand::Search { query } => {
+            let skills = registry.search_skills(&query)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "query": query,
+                    "count": skills.len(),
+                    "skills": skills,
+                }));
+            } else if skills.is_empty() {
+                println!("No skills match '{query}'.");
+            } else {
+                println!("skills matching '{query}': {}", skills.len());
+                for skill in skills {
+                    println!(
+                        "- {} ({}) source={:?} enabled={} {}",
+                        skill.id,
+                        skill.title,
+                        skill.source,
+                        skill.enabled,
+                        skill.description.unwrap_or_default()
+                    );
+                }
+            }
+        }
         SkillsCommand::Check { skill_id } => {
             let report = registry.check_skills(skill_id.as_deref())?;
             if cli.json {
@@ -2267,8 +2412,11 @@ pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
                 );
                 for result in report.results {
                     println!(
-                        "- {} source={:?} ok={}",
-                        result.id, result.source, result.ok
+                        "- {} source={:?} ok={}{}",
+                        result.id,
+                        result.source,
+                        result.ok,
+                        if result.enabled { "" } else { " (disabled)" }
                     );
                     for check in result.checks {
                         let status = if check.ok { "OK" } else { "WARN" };
@@ -2277,7 +2425,13 @@ pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
                 }
             }
         }
-        SkillsCommand::Install { path, force } => {
+        SkillsCommand::Enable { skill_id } => set_skill_enabled(cli, &registry, &skill_id, true)?,
+        SkillsCommand::Disable { skill_id } => set_skill_enabled(cli, &registry, &skill_id, false)?,
+        SkillsCommand::Install {
+            path,
+            force,
+            dry_run,
+        } => {
             let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
             let source = {
                 let value = PathBuf::from(path);
@@ -2287,12 +2441,26 @@ pub(super) fn handle_skills(cli: &Cli, args: SkillsArgs) -> Result<()> {
                     cwd.join(value)
                 }
             };
-            let outcome = registry.install_skill_from_path(&source, force)?;
-            if cli.json {
+            let outcome = registry.install_skill_from_path(&source, force, dry_run)?;
+            if cli.json && outcome.dry_run {
                 print_json(&json!({
                     "ok": true,
+                    "dry_run": true,
                     "installed": outcome,
                 }));
+            } else if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "installed": outcome,
+                }));
+            } else if outcome.dry_run {
+                println!(
+                    "Would install skill {} -> {}",
+                    outcome.id, outcome.installed_path
+                );
+                if outcome.replaced {
+                    println!("would replace existing skill package");
+                }
             } else {
                 println!(
                     "Installed skill {} -> {}",
@@ -2339,63 +2507,27 @@ fn extension_source_filter_name(source: ExtensionSourceFilterArg) -> &'static st
     }
 }
 
-fn source_matches(requested: Option<ExtensionSource>, actual: ExtensionSource) -> bool {
-    match requested {
-        Some(expected) => expected == actual,
-        None => true,
-    }
-}
-
-fn plugin_views_with_state(
-    plugins: Vec<PluginEntry>,
-    plugin_state: &PluginStateFile,
-) -> Vec<PluginView> {
-    plugins
-        .into_iter()
-        .map(|plugin| PluginView {
-            enabled: plugin_state.is_enabled(&plugin.id),
-            plugin,
-        })
-        .collect()
-}
-
-fn load_plugin_state(path: &Path) -> Result<PluginStateFile> {
-    if !path.exists() {
-        return Ok(PluginStateFile::default());
-    }
-    let raw = std::fs::read_to_string(path).map_err(|err| {
-        MosaicError::Io(format!(
-            "failed to read plugin state file '{}': {err}",
-            path.display()
-        ))
-    })?;
-    let mut state: PluginStateFile = serde_json::from_str(&raw).map_err(|err| {
-        MosaicError::Validation(format!(
-            "failed to parse plugin state file '{}': {err}",
-            path.display()
-        ))
-    })?;
-    if state.version == 0 {
-        state.version = PLUGIN_STATE_VERSION;
+fn set_skill_enabled(
+    cli: &Cli,
+    registry: &ExtensionRegistry,
+    skill_id: &str,
+    enabled: bool,
+) -> Result<()> {
+    let skill = registry.skill_info(skill_id)?;
+    let changed = registry.set_enabled(ExtensionKind::Skill, &skill.id, enabled)?;
+    if cli.json {
+        print_json(&json!({
+            "ok": true,
+            "skill_id": skill.id,
+            "enabled": enabled,
+            "changed": changed,
+        }));
+    } else {
+        println!("skill: {}", skill.id);
+        println!("enabled: {enabled}");
+        println!("changed: {changed}");
     }
-    state.disabled_plugins = state
-        .disabled_plugins
-        .iter()
-        .map(|item| item.trim().to_string())
-        .filter(|item| !item.is_empty())
-        .collect::<BTreeSet<_>>()
-        .into_iter()
-        .collect();
-    Ok(state)
-}
-
-fn save_plugin_state(path: &Path, state: &PluginStateFile) -> Result<()> {
-    write_pretty_state_json_file(path, state, "plugin state").map_err(|err| {
-        MosaicError::Io(format!(
-            "failed to write plugin state file '{}': {err}",
-            path.display()
-        ))
-    })
+    Ok(())
 }
 
 #[derive(Clone, Copy)]
@@ -3211,42 +3343,42 @@ fn plugin_resource_metrics_supported() -> bool {
     false
 }
 
-fn resolve_plugin_sandbox_profile(
+/// The configured sandbox policy, with the plugin's own `sandbox_profile`
+/// (if any) replacing the policy's built-in or custom profile. Hosts and path
+/// roots from the policy still apply.
+fn resolve_plugin_sandbox_policy(
     plugin: &PluginEntry,
-    default_profile: SandboxProfile,
-) -> Result<SandboxProfile> {
+    mut policy: SandboxPolicy,
+) -> Result<SandboxPolicy> {
     let Some(value) = plugin
         .runtime
         .as_ref()
         .and_then(|runtime| runtime.sandbox_profile.as_deref())
     else {
-        return Ok(default_profile);
+        return Ok(policy);
     };
-    match value {
-        "restricted" => Ok(SandboxProfile::Restricted),
-        "standard" => Ok(SandboxProfile::Standard),
-        "elevated" => Ok(SandboxProfile::Elevated),
-        _ => Err(MosaicError::Validation(format!(
-            "plugin '{}' has invalid runtime sandbox_profile '{}'",
-            plugin.id, value
-        ))),
-    }
-}
-
-fn sandbox_profile_label(profile: SandboxProfile) -> &'static str {
-    match profile {
-        SandboxProfile::Restricted => "restricted",
-        SandboxProfile::Standard => "standard",
-        SandboxProfile::Elevated => "elevated",
-    }
+    policy.profile = match value {
+        "restricted" => SandboxProfile::Restricted,
+        "standard" => SandboxProfile::Standard,
+        "elevated" => SandboxProfile::Elevated,
+        _ => {
+            return Err(MosaicError::Validation(format!(
+                "plugin '{}' has invalid runtime sandbox_profile '{}'",
+                plugin.id, value
+            )));
+        }
+    };
+    policy.custom_profile = None;
+    policy.custom = None;
+    Ok(policy)
 }
 
 fn validate_plugin_hook_sandbox(
     plugin: &PluginEntry,
     hook_path: &Path,
-    profile: SandboxProfile,
+    policy: &SandboxPolicy,
 ) -> Result<()> {
-    if profile != SandboxProfile::Restricted {
+    if policy.profile != SandboxProfile::Restricted && policy.custom.is_none() {
         return Ok(());
     }
     let extension = hook_path
@@ -3275,7 +3407,7 @@ fn validate_plugin_hook_sandbox(
         if trimmed.is_empty() || trimmed.starts_with('#') {
             continue;
         }
-        if let Some(reason) = evaluate_sandbox(trimmed, profile) {
+        if let Some(reason) = evaluate_sandbox_policy(trimmed, policy) {
             return Err(MosaicError::SandboxDenied(format!(
                 "plugin '{}' hook '{}' line {} blocked: {}",
                 plugin.id,
@@ -3322,7 +3454,9 @@ mod tests {
             manifest_exists: true,
             manifest_valid: true,
             manifest_error: None,
+            enabled: true,
             runtime,
+            shadowed_by: None,
         }
     }
 
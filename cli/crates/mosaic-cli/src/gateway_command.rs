@@ -9,7 +9,7 @@ use uuid::Uuid;
 
 use mosaic_core::error::MosaicError;
 use mosaic_core::privacy::append_sanitized_jsonl;
-use mosaic_gateway::{GatewayClient, GatewayRequest};
+use mosaic_gateway::{GatewayClient, GatewayRequest, gateway_client_for_url};
 
 use super::{
     Cli, GatewayArgs, GatewayCommand, GatewayState, HttpGatewayClient, Result,
@@ -820,7 +820,11 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 );
             }
         }
-        GatewayCommand::Call { method, params } => {
+        GatewayCommand::Call {
+            method,
+            params,
+            url,
+        } => {
             let started = Instant::now();
             if gateway_test_mode() {
                 let state: Option<GatewayState> = load_json_file_opt(&gateway_path)?;
@@ -934,8 +938,12 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 return Ok(());
             }
 
-            let (host, port) = resolve_gateway_target(&gateway_path, &gateway_service_path)?;
-            let client = HttpGatewayClient::new(&host, port)?;
+            let GatewayTarget {
+                client,
+                host,
+                port,
+                mode,
+            } = gateway_target(url.as_deref(), &gateway_path, &gateway_service_path)?;
             let params = params
                 .as_deref()
                 .map(|value| parse_json_input(value, "gateway params"))
@@ -951,7 +959,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: Some(method.as_str()),
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: true,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: None,
@@ -968,7 +976,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: Some(method.as_str()),
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: false,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: Some(err.code().to_string()),
@@ -999,7 +1007,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 );
             }
         }
-        GatewayCommand::Probe => {
+        GatewayCommand::Probe { url } => {
             let started = Instant::now();
             if gateway_test_mode() {
                 let state: Option<GatewayState> = load_json_file_opt(&gateway_path)?;
@@ -1045,6 +1053,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             "endpoint": "test-mode://gateway/health",
                             "latency_ms": 0,
                             "detail": "gateway test mode",
+                            "attempts": 1,
                         },
                         "gateway": { "host": "127.0.0.1", "port": 8787 },
                     }));
@@ -1054,8 +1063,12 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 return Ok(());
             }
 
-            let (host, port) = resolve_gateway_target(&gateway_path, &gateway_service_path)?;
-            let client = HttpGatewayClient::new(&host, port)?;
+            let GatewayTarget {
+                client,
+                host,
+                port,
+                mode,
+            } = gateway_target(url.as_deref(), &gateway_path, &gateway_service_path)?;
             let probe = match client.probe().await {
                 Ok(value) => {
                     write_gateway_event(
@@ -1065,7 +1078,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: None,
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: true,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: None,
@@ -1082,7 +1095,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: None,
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: false,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: Some(err.code().to_string()),
@@ -1105,10 +1118,11 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 println!("gateway probe ok");
                 println!("endpoint: {}", probe.endpoint);
                 println!("latency: {}ms", probe.latency_ms);
+                println!("attempts: {}", probe.attempts);
                 println!("detail: {}", probe.detail);
             }
         }
-        GatewayCommand::Discover => {
+        GatewayCommand::Discover { url } => {
             let started = Instant::now();
             if gateway_test_mode() {
                 let methods = vec!["health", "status", "echo", "nodes.run", "nodes.invoke"];
@@ -1147,8 +1161,12 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                 return Ok(());
             }
 
-            let (host, port) = resolve_gateway_target(&gateway_path, &gateway_service_path)?;
-            let client = HttpGatewayClient::new(&host, port)?;
+            let GatewayTarget {
+                client,
+                host,
+                port,
+                mode,
+            } = gateway_target(url.as_deref(), &gateway_path, &gateway_service_path)?;
             let discovery = match client.discover().await {
                 Ok(value) => {
                     write_gateway_event(
@@ -1158,7 +1176,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: None,
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: true,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: None,
@@ -1175,7 +1193,7 @@ pub(super) async fn handle_gateway(cli: &Cli, args: GatewayArgs) -> Result<()> {
                             method: None,
                             host: host.as_str(),
                             port,
-                            mode: "http",
+                            mode,
                             success: false,
                             latency_ms: started.elapsed().as_millis(),
                             error_code: Some(err.code().to_string()),
@@ -1642,6 +1660,43 @@ fn validate_gateway_method_schema(method: &str, payload: &Value) -> (bool, Strin
     (true, format!("{method} schema profile: basic"))
 }
 
+struct GatewayTarget {
+    client: Box<dyn GatewayClient>,
+    host: String,
+    port: u16,
+    mode: &'static str,
+}
+
+/// Client for `--url`, with the transport picked by its scheme, or HTTP to
+/// the gateway recorded by `gateway start`/`install`.
+fn gateway_target(
+    url: Option<&str>,
+    gateway_path: &Path,
+    gateway_service_path: &Path,
+) -> Result<GatewayTarget> {
+    let Some(url) = url else {
+        let (host, port) = resolve_gateway_target(gateway_path, gateway_service_path)?;
+        return Ok(GatewayTarget {
+            client: Box::new(HttpGatewayClient::new(&host, port)?),
+            host,
+            port,
+            mode: "http",
+        });
+    };
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|err| MosaicError::Validation(format!("invalid gateway url '{url}': {err}")))?;
+    Ok(GatewayTarget {
+        client: gateway_client_for_url(url)?,
+        host: parsed.host_str().unwrap_or_default().to_string(),
+        port: parsed.port_or_known_default().unwrap_or_default(),
+        mode: if matches!(parsed.scheme(), "ws" | "wss") {
+            "ws"
+        } else {
+            "http"
+        },
+    })
+}
+
 #[derive(Debug)]
 struct GatewayEventInput<'a> {
     action: &'a str,
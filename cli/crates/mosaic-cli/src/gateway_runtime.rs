@@ -4,11 +4,13 @@ use std::time::Duration;
 
 use chrono::Utc;
 use serde_json::{Value, json};
-use tiny_http::{Method, Response, Server};
 
 use mosaic_core::error::MosaicError;
-use mosaic_gateway::{GatewayClient, GatewayRequest, HttpGatewayClient};
+use mosaic_gateway::{
+    GatewayClient, GatewayRequest, GatewayServer, HttpGatewayClient, MethodRegistry,
+};
 
+use crate::runtime_context::config_path_override;
 use crate::utils::{load_json_file_opt, save_state_json_file};
 
 use super::{
@@ -294,6 +296,12 @@ fn spawn_gateway_process(cli: &Cli, host: &str, port: u16) -> Result<u32> {
     if cli.project_state {
         cmd.arg("--project-state");
     }
+    if let Some(state_dir) = &cli.state_dir {
+        cmd.arg("--state-dir").arg(state_dir);
+    }
+    if let Some(config_path) = config_path_override() {
+        cmd.arg("--config").arg(config_path);
+    }
     if cli.debug {
         cmd.arg("--debug");
     }
@@ -401,156 +409,35 @@ async fn probe_gateway_health(host: &str, port: u16) -> bool {
 }
 
 pub(super) fn run_gateway_http_server(host: &str, port: u16) -> Result<()> {
-    let server = Server::http(format!("{host}:{port}"))
-        .map_err(|err| MosaicError::Network(format!("failed to bind gateway server: {err}")))?;
-    let started_at = Utc::now();
-    for mut request in server.incoming_requests() {
-        let method = request.method().clone();
-        let url = request.url().to_string();
-        let response = match (method, url.as_str()) {
-            (Method::Get, "/health") => Response::from_string(
-                json!({
-                    "ok": true,
-                    "service": "mosaic-gateway",
-                    "ts": Utc::now(),
-                })
-                .to_string(),
-            ),
-            (Method::Get, "/status") => Response::from_string(
-                json!({
-                    "ok": true,
-                    "service": "mosaic-gateway",
-                    "started_at": started_at,
-                    "uptime_seconds": (Utc::now() - started_at).num_seconds(),
-                })
-                .to_string(),
-            ),
-            (Method::Get, "/discover") => Response::from_string(
-                json!({
-                    "ok": true,
-                    "methods": ["health", "status", "echo", "nodes.run", "nodes.invoke"],
-                })
-                .to_string(),
-            ),
-            (Method::Post, "/call") => {
-                let mut body = String::new();
-                if request.as_reader().read_to_string(&mut body).is_err() {
-                    Response::from_string(
-                        json!({
-                            "ok": false,
-                            "error": {
-                                "code": "invalid_request",
-                                "message": "failed to read request body",
-                            }
-                        })
-                        .to_string(),
-                    )
-                    .with_status_code(400)
-                } else {
-                    let parsed = serde_json::from_str::<GatewayRequest>(&body);
-                    match parsed {
-                        Ok(payload) => match payload.method.as_str() {
-                            "health" => Response::from_string(
-                                json!({
-                                    "ok": true,
-                                    "result": {
-                                        "ok": true,
-                                        "service": "mosaic-gateway",
-                                        "ts": Utc::now(),
-                                    }
-                                })
-                                .to_string(),
-                            ),
-                            "status" => Response::from_string(
-                                json!({
-                                    "ok": true,
-                                    "result": {
-                                        "ok": true,
-                                        "service": "mosaic-gateway",
-                                        "started_at": started_at,
-                                        "uptime_seconds": (Utc::now() - started_at).num_seconds(),
-                                    }
-                                })
-                                .to_string(),
-                            ),
-                            "echo" => Response::from_string(
-                                json!({
-                                    "ok": true,
-                                    "result": {
-                                        "ok": true,
-                                        "echo": payload.params,
-                                    }
-                                })
-                                .to_string(),
-                            ),
-                            "nodes.run" => Response::from_string(
-                                json!({
-                                    "ok": true,
-                                    "result": {
-                                        "ok": true,
-                                        "status": "accepted",
-                                        "node_id": payload.params.get("node_id").cloned().unwrap_or(Value::Null),
-                                        "command": payload.params.get("command").cloned().unwrap_or(Value::Null),
-                                    }
-                                })
-                                .to_string(),
-                            ),
-                            "nodes.invoke" => Response::from_string(
-                                json!({
-                                    "ok": true,
-                                    "result": {
-                                        "ok": true,
-                                        "status": "accepted",
-                                        "node_id": payload.params.get("node_id").cloned().unwrap_or(Value::Null),
-                                        "method": payload.params.get("method").cloned().unwrap_or(Value::Null),
-                                        "params": payload.params.get("params").cloned().unwrap_or(Value::Null),
-                                    }
-                                })
-                                .to_string(),
-                            ),
-                            _ => Response::from_string(
-                                json!({
-                                    "ok": false,
-                                    "error": {
-                                        "code": "method_not_found",
-                                        "message": format!("unknown method '{}'", payload.method),
-                                    }
-                                })
-                                .to_string(),
-                            )
-                            .with_status_code(404),
-                        },
-                        Err(err) => Response::from_string(
-                            json!({
-                                "ok": false,
-                                "error": {
-                                    "code": "invalid_request",
-                                    "message": format!("invalid JSON request: {err}"),
-                                }
-                            })
-                            .to_string(),
-                        )
-                        .with_status_code(400),
-                    }
-                }
-            }
-            _ => Response::from_string(
-                json!({
-                    "ok": false,
-                    "error": "not_found",
-                })
-                .to_string(),
-            )
-            .with_status_code(404),
-        };
-        let response = response.with_header(
-            tiny_http::Header::from_bytes("Content-Type", "application/json").map_err(|err| {
-                MosaicError::Unknown(format!("failed to create response header: {err:?}"))
-            })?,
-        );
-        let _ = request.respond(response);
-    }
-    Ok(())
+    GatewayServer::bind(host, port, gateway_method_registry())?.run()
+}
+
+fn gateway_method_registry() -> MethodRegistry {
+    let mut registry = MethodRegistry::with_defaults();
+    registry.register("echo", |call| {
+        Ok(json!({
+            "ok": true,
+            "echo": call.params,
+        }))
+    });
+    registry.register("nodes.run", |call| {
+        Ok(json!({
+            "ok": true,
+            "status": "accepted",
+            "node_id": call.params.get("node_id").cloned().unwrap_or(Value::Null),
+            "command": call.params.get("command").cloned().unwrap_or(Value::Null),
+        }))
+    });
+    registry.register("nodes.invoke", |call| {
+        Ok(json!({
+            "ok": true,
+            "status": "accepted",
+            "node_id": call.params.get("node_id").cloned().unwrap_or(Value::Null),
+            "method": call.params.get("method").cloned().unwrap_or(Value::Null),
+            "params": call.params.get("params").cloned().unwrap_or(Value::Null),
+        }))
+    });
+    registry
 }
 
 pub(super) fn resolve_gateway_target(
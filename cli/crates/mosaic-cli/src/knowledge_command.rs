@@ -7,7 +7,7 @@ use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::append_sanitized_jsonl;
 use mosaic_mcp::{McpStore, mcp_servers_file_path};
 use mosaic_memory::{
-    MemoryIndexOptions, MemoryIndexResult, MemorySearchHit, MemoryStore,
+    MemoryIndexOptions, MemoryIndexResult, MemorySearchHit, MemorySearchOptions, MemoryStore,
     list_memory_namespace_statuses, memory_index_path_for_namespace,
     memory_status_path_for_namespace,
 };
@@ -185,6 +185,8 @@ pub(super) async fn handle_knowledge(cli: &Cli, args: KnowledgeArgs) -> Result<(
                 incremental,
                 stale_after_hours,
                 retain_missing,
+                // Staged chunks are generated by ingest, so source ignore rules do not apply.
+                respect_ignore_files: false,
                 max_files,
                 max_file_size,
                 max_content_bytes,
@@ -337,6 +339,7 @@ pub(super) async fn handle_knowledge(cli: &Cli, args: KnowledgeArgs) -> Result<(
                         yes: cli.yes,
                         interactive: false,
                         event_callback: None,
+                        label: None,
                     },
                 )
                 .await?;
@@ -1926,7 +1929,14 @@ fn search_hits_for_namespace(
         memory_index_path_for_namespace(data_dir, namespace),
         memory_status_path_for_namespace(data_dir, namespace),
     );
-    let result = store.search(query, Some(usize::MAX))?;
+    // Knowledge queries are often full questions, so any matching term counts.
+    let result = store.search_with_options(
+        query,
+        MemorySearchOptions {
+            limit: Some(usize::MAX),
+            require_all_terms: false,
+        },
+    )?;
     let mut hits = result
         .hits
         .into_iter()
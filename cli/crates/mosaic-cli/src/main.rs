@@ -73,11 +73,14 @@ use nodes_command::handle_nodes;
 use nodes_telemetry::{NodeTelemetryEventInput, nodes_events_file_path, write_nodes_event};
 use ops_command::{
     handle_approvals, handle_logs, handle_observability, handle_safety, handle_sandbox,
-    handle_system,
+    handle_system, record_presence,
 };
 #[cfg(test)]
 use runtime_context::ModelRoutingProvider;
-use runtime_context::{build_runtime, resolve_effective_model, resolve_state_paths};
+use runtime_context::{
+    build_runtime, config_path_override, init_config_path, init_project_state_dir,
+    project_state_dir, provider_from_profile, resolve_effective_model, resolve_state_paths,
+};
 use security_command::handle_security;
 use state_records::{
     browser_history_file_path, browser_state_file_path, cron_events_dir, cron_events_file_path,
@@ -94,9 +97,9 @@ use state_records::{
 use tts_voicecall_command::{handle_tts, handle_voicecall};
 use tui_command::handle_tui;
 use utils::{
-    binary_in_path, load_json_file_opt, normalize_non_empty_list, parse_json_input, preview_text,
-    print_json, print_json_line, remove_matching, resolve_baseline_path, resolve_output_path,
-    save_json_file,
+    attach_json_error_details, binary_in_path, load_json_file_opt, normalize_non_empty_list,
+    parse_json_input, preview_text, print_json, print_json_line, remove_matching, render_table,
+    resolve_baseline_path, resolve_output_path, save_json_file, take_json_error_details,
 };
 
 const PROJECT_STATE_DIR: &str = ".mosaic";
@@ -115,14 +118,22 @@ async fn main() {
     let result = run(cli).await;
     if let Err(err) = result {
         if json_mode {
-            print_json(&json!({
+            let mut payload = json!({
                 "ok": false,
                 "error": {
                     "code": err.code(),
                     "message": err.to_string(),
                     "exit_code": err.exit_code(),
                 }
-            }));
+            });
+            if let Some(Value::Object(details)) = take_json_error_details()
+                && let Some(object) = payload.as_object_mut()
+            {
+                for (key, value) in details {
+                    object.entry(key).or_insert(value);
+                }
+            }
+            print_json(&payload);
         } else {
             eprintln!("error [{}]: {}", err.code(), err);
         }
@@ -131,12 +142,21 @@ async fn main() {
 }
 
 async fn run(cli: Cli) -> Result<()> {
+    init_project_state_dir(cli.state_dir.as_deref())?;
+    init_config_path(cli.config.as_deref())?;
     if cli.debug {
         eprintln!(
-            "[debug] profile={} project_state={} json={}",
-            cli.profile, cli.project_state, cli.json
+            "[debug] profile={} project_state={} state_dir={} config={} json={}",
+            cli.profile,
+            cli.project_state,
+            project_state_dir(),
+            config_path_override()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            cli.json
         );
     }
+    record_presence(&cli);
     match cli.command.clone() {
         None => handle_tui(&cli, TuiArgs::default()).await,
         Some(Commands::Setup(args)) => handle_setup(&cli, args),
@@ -163,11 +183,11 @@ async fn run(cli: Cli) -> Result<()> {
         Some(Commands::Approvals(args)) => handle_approvals(&cli, args),
         Some(Commands::Sandbox(args)) => handle_sandbox(&cli, args),
         Some(Commands::Safety(args)) => handle_safety(&cli, args),
-        Some(Commands::Memory(args)) => handle_memory(&cli, args),
+        Some(Commands::Memory(args)) => handle_memory(&cli, args).await,
         Some(Commands::Knowledge(args)) => handle_knowledge(&cli, args).await,
         Some(Commands::Security(args)) => handle_security(&cli, args),
         Some(Commands::Agents(args)) => handle_agents(&cli, args),
-        Some(Commands::Plugins(args)) => handle_plugins(&cli, args),
+        Some(Commands::Plugins(args)) => handle_plugins(&cli, args).await,
         Some(Commands::Skills(args)) => handle_skills(&cli, args),
         Some(Commands::Completion(args)) => handle_completion(&cli, args),
         Some(Commands::Directory(args)) => handle_directory(&cli, args),
@@ -195,6 +215,7 @@ async fn run(cli: Cli) -> Result<()> {
                         script,
                         session,
                         agent,
+                        label: None,
                     },
                 )
                 .await
@@ -233,6 +254,7 @@ async fn run(cli: Cli) -> Result<()> {
                         script: None,
                         session,
                         agent,
+                        label: None,
                     },
                 )
                 .await
@@ -241,6 +263,6 @@ async fn run(cli: Cli) -> Result<()> {
         },
         Some(Commands::Status) => handle_status(&cli),
         Some(Commands::Health) => handle_health(&cli).await,
-        Some(Commands::Doctor) => handle_doctor(&cli).await,
+        Some(Commands::Doctor(args)) => handle_doctor(&cli, args).await,
     }
 }
@@ -4,11 +4,12 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use mosaic_core::error::{MosaicError, Result};
-use mosaic_core::state::{StateMode, StatePaths};
+use mosaic_core::state::{STATE_MARKER_FILE, StateMode, StatePaths};
 use reqwest::StatusCode;
 use serde_json::{Value, json};
 
-use super::runtime_context::resolve_state_paths;
+use super::PROJECT_STATE_DIR;
+use super::runtime_context::{project_state_dir, resolve_state_paths};
 use super::utils::print_json;
 use super::{Cli, UpdateArgs};
 
@@ -67,6 +68,7 @@ pub(super) async fn handle_update(cli: &Cli, args: UpdateArgs) -> Result<()> {
 }
 
 pub(super) fn handle_reset(cli: &Cli) -> Result<()> {
+    reject_config_override(cli, "reset")?;
     require_yes(cli, "reset")?;
     let paths = resolve_state_paths(cli.project_state)?;
     let summary = reset_state(&paths)?;
@@ -90,6 +92,7 @@ pub(super) fn handle_reset(cli: &Cli) -> Result<()> {
 }
 
 pub(super) fn handle_uninstall(cli: &Cli) -> Result<()> {
+    reject_config_override(cli, "uninstall")?;
     require_yes(cli, "uninstall")?;
     let paths = resolve_state_paths(cli.project_state)?;
     let targets = uninstall_targets(&paths)?;
@@ -122,6 +125,42 @@ pub(super) fn handle_uninstall(cli: &Cli) -> Result<()> {
     Ok(())
 }
 
+/// Entries mosaic keeps directly under a project state root.
+const STATE_ROOT_ENTRIES: &[&str] = &[
+    "config.toml",
+    "models.toml",
+    "data",
+    "policy",
+    "security",
+    "knowledge",
+    "plugins",
+    "skills",
+];
+
+fn is_mosaic_state_root(path: &Path) -> bool {
+    if path.join(STATE_MARKER_FILE).is_file() {
+        return true;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return false;
+    };
+    let mut has_state = false;
+    for entry in entries {
+        let Ok(entry) = entry else {
+            return false;
+        };
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            return false;
+        };
+        if !STATE_ROOT_ENTRIES.contains(&name) {
+            return false;
+        }
+        has_state |= matches!(name, "config.toml" | "data");
+    }
+    has_state
+}
+
 fn mode_name(mode: StateMode) -> &'static str {
     match mode {
         StateMode::Xdg => "xdg",
@@ -129,6 +168,17 @@ fn mode_name(mode: StateMode) -> &'static str {
     }
 }
 
+/// With `--config` the state lives beside an arbitrary file, so removing the
+/// state root could delete directories Mosaic does not own.
+fn reject_config_override(cli: &Cli, action: &str) -> Result<()> {
+    if cli.config.is_none() {
+        return Ok(());
+    }
+    Err(MosaicError::Validation(format!(
+        "{action} is not supported with --config; remove the state beside the config file manually"
+    )))
+}
+
 fn require_yes(cli: &Cli, action: &str) -> Result<()> {
     if cli.yes {
         return Ok(());
@@ -362,7 +412,12 @@ fn ensure_safe_uninstall_path(mode: StateMode, path: &Path) -> Result<()> {
             ))
         })?;
     let safe = match mode {
-        StateMode::Project => name == ".mosaic",
+        StateMode::Project if name == PROJECT_STATE_DIR => true,
+        // A custom state dir name can point at any existing directory, so it
+        // must be one mosaic created or hold nothing but state entries.
+        StateMode::Project => {
+            name == project_state_dir() && (!path.exists() || is_mosaic_state_root(path))
+        }
         StateMode::Xdg => name == "mosaic",
     };
     if safe {
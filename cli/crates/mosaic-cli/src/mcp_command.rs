@@ -205,6 +205,7 @@ pub(super) fn handle_mcp(cli: &Cli, args: McpArgs) -> Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_repair(
     cli: &Cli,
     store: &McpStore,
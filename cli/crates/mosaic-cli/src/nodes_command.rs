@@ -5,7 +5,7 @@ use serde_json::{Value, json};
 
 use mosaic_core::error::MosaicError;
 use mosaic_ops::{
-    ApprovalDecision, ApprovalStore, SandboxStore, evaluate_approval, evaluate_sandbox,
+    ApprovalDecision, ApprovalStore, SandboxStore, evaluate_approval, evaluate_sandbox_policy,
 };
 
 use super::{
@@ -487,7 +487,7 @@ pub(super) async fn handle_nodes(cli: &Cli, args: NodesArgs) -> Result<()> {
             let sandbox_store = SandboxStore::new(paths.sandbox_policy_path.clone());
             let approval_policy = approval_store.load_or_default()?;
             let sandbox_policy = sandbox_store.load_or_default()?;
-            if let Some(reason) = evaluate_sandbox(&command, sandbox_policy.profile) {
+            if let Some(reason) = evaluate_sandbox_policy(&command, &sandbox_policy) {
                 return Err(MosaicError::SandboxDenied(reason));
             }
             let approved_by = match evaluate_approval(&command, &approval_policy) {
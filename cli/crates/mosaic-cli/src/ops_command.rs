@@ -6,17 +6,18 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
 use mosaic_channels::{ChannelRepository, channels_events_dir, channels_file_path};
-use mosaic_core::audit::CommandAudit;
+use mosaic_core::audit::{AuditStore, CommandAudit};
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::{append_sanitized_jsonl, render_sanitized_jsonl};
 use mosaic_mcp::{McpStore, mcp_servers_file_path};
 use mosaic_ops::{
-    ApprovalDecision, ApprovalStore, SandboxStore, SystemEventStore, UnifiedLogEntry, collect_logs,
-    evaluate_approval, evaluate_sandbox, list_profiles, snapshot_presence, system_events_path,
+    ApprovalDecision, ApprovalStore, LogFollower, PresenceStore, SandboxStore, SystemEventStore,
+    UnifiedLogEntry, collect_logs, evaluate_approval, evaluate_sandbox, evaluate_sandbox_policy_in,
+    presence_log_path, system_events_path,
 };
 
 use super::{
-    AllowlistCommand, ApprovalsArgs, ApprovalsCommand, Cli, DeviceStatus, LogsArgs,
+    AllowlistCommand, ApprovalsArgs, ApprovalsCommand, Cli, Commands, DeviceStatus, LogsArgs,
     NodeRuntimeStatus, ObservabilityArgs, ObservabilityCommand, PairingStatus, SafetyArgs,
     SafetyCommand, SandboxArgs, SandboxCommand, SystemArgs, SystemCommand,
     collect_gateway_runtime_status, devices_file_path, dispatch_system_event,
@@ -30,8 +31,16 @@ pub(super) async fn handle_logs(cli: &Cli, args: LogsArgs) -> Result<()> {
     paths.ensure_dirs()?;
     let source_filter = args.source.as_deref();
 
-    if !args.follow {
-        let entries = filter_logs(collect_logs(&paths.data_dir, args.tail)?, source_filter);
+    // The follower resumes where the snapshot stopped reading, so no line is
+    // missed or printed twice.
+    let (entries, mut follower) = if args.follow {
+        let (entries, follower) = LogFollower::snapshot(&paths.data_dir, args.tail)?;
+        (entries, Some(follower))
+    } else {
+        (collect_logs(&paths.data_dir, args.tail)?, None)
+    };
+    let entries = filter_logs(entries, source_filter);
+    let Some(follower) = follower.as_mut() else {
         if cli.json {
             print_json(&json!({
                 "ok": true,
@@ -41,44 +50,41 @@ pub(super) async fn handle_logs(cli: &Cli, args: LogsArgs) -> Result<()> {
             println!("No logs found.");
         } else {
             for entry in entries {
-                println!(
-                    "{} [{}] {}",
-                    entry
-                        .ts
-                        .map(|value| value.to_rfc3339())
-                        .unwrap_or_else(|| "-".to_string()),
-                    entry.source,
-                    entry.payload
-                );
+                print_log_entry(&entry);
             }
         }
         return Ok(());
-    }
+    };
 
-    let mut printed = 0usize;
+    // Followed output is one JSON object per line in `--json` mode.
+    let emit = |entry: &UnifiedLogEntry| {
+        if cli.json {
+            println!("{}", serde_json::to_string(entry).unwrap_or_default());
+        } else {
+            print_log_entry(entry);
+        }
+    };
+    entries.iter().for_each(emit);
     loop {
-        let entries = filter_logs(
-            collect_logs(&paths.data_dir, args.tail.max(200))?,
-            source_filter,
-        );
-        if entries.len() > printed {
-            for entry in entries.iter().skip(printed) {
-                println!(
-                    "{} [{}] {}",
-                    entry
-                        .ts
-                        .map(|value| value.to_rfc3339())
-                        .unwrap_or_else(|| "-".to_string()),
-                    entry.source,
-                    entry.payload
-                );
-            }
-            printed = entries.len();
+        for entry in filter_logs(follower.poll()?, source_filter) {
+            emit(&entry);
         }
         tokio::time::sleep(Duration::from_millis(500)).await;
     }
 }
 
+fn print_log_entry(entry: &UnifiedLogEntry) {
+    println!(
+        "{} [{}] {}",
+        entry
+            .ts
+            .map(|value| value.to_rfc3339())
+            .unwrap_or_else(|| "-".to_string()),
+        entry.source,
+        entry.payload
+    );
+}
+
 fn filter_logs(entries: Vec<UnifiedLogEntry>, source_filter: Option<&str>) -> Vec<UnifiedLogEntry> {
     match source_filter {
         Some(source) => entries
@@ -558,7 +564,7 @@ async fn build_observability_report(
         options.audit_tail,
         options.compare_window,
         &approvals_policy,
-        sandbox_policy.profile,
+        &sandbox_policy,
     )?;
     let mut plugin_soak =
         build_plugin_soak_report(options.plugin_soak_report.as_deref(), &paths.root_dir);
@@ -723,8 +729,8 @@ async fn build_observability_report(
         },
         "policy": {
             "approvals_mode": approvals_policy.mode,
-            "approvals_allowlist_size": approvals_policy.allowlist.len(),
-            "sandbox_profile": sandbox_policy.profile,
+            "approvals_allowlist_size": approvals_policy.allowlist().len(),
+            "sandbox_profile": sandbox_policy.profile_name(),
         },
         "logs": logs,
         "system_events": system_events,
@@ -3911,7 +3917,7 @@ struct AuditWindowSummary {
 fn summarize_audit_window(
     entries: &[CommandAudit],
     approval_policy: &mosaic_ops::ApprovalPolicy,
-    sandbox_profile: mosaic_ops::SandboxProfile,
+    sandbox_policy: &mosaic_ops::SandboxPolicy,
 ) -> AuditWindowSummary {
     let total_entries = entries.len();
     let succeeded = entries.iter().filter(|entry| entry.exit_code == 0).count();
@@ -3935,7 +3941,12 @@ fn summarize_audit_window(
             .to_string();
         *command_prefix_counts.entry(prefix).or_default() += 1;
 
-        let evaluated = evaluate_safety(&entry.command, approval_policy, sandbox_profile);
+        let evaluated = evaluate_safety(
+            &entry.command,
+            Path::new(&entry.cwd),
+            approval_policy,
+            sandbox_policy,
+        );
         *current_decision_counts
             .entry(evaluated.decision.to_string())
             .or_default() += 1;
@@ -4032,7 +4043,7 @@ fn build_safety_audit(
     tail: usize,
     compare_window: usize,
     approval_policy: &mosaic_ops::ApprovalPolicy,
-    sandbox_profile: mosaic_ops::SandboxProfile,
+    sandbox_policy: &mosaic_ops::SandboxPolicy,
 ) -> Result<Value> {
     let (entries, parse_errors) = read_command_audit_entries(audit_log_path)?;
     let current_start = if tail == 0 {
@@ -4048,10 +4059,9 @@ fn build_safety_audit(
         entries[start..current_start].to_vec()
     };
 
-    let current_summary =
-        summarize_audit_window(&current_entries, approval_policy, sandbox_profile);
+    let current_summary = summarize_audit_window(&current_entries, approval_policy, sandbox_policy);
     let previous_summary =
-        summarize_audit_window(&previous_entries, approval_policy, sandbox_profile);
+        summarize_audit_window(&previous_entries, approval_policy, sandbox_policy);
     let comparison = build_audit_comparison(
         &current_summary,
         &previous_summary,
@@ -4064,7 +4074,12 @@ fn build_safety_audit(
         .rev()
         .take(10)
         .map(|entry| {
-            let evaluated = evaluate_safety(&entry.command, approval_policy, sandbox_profile);
+            let evaluated = evaluate_safety(
+                &entry.command,
+                Path::new(&entry.cwd),
+                approval_policy,
+                sandbox_policy,
+            );
             json!({
                 "id": entry.id,
                 "ts": entry.ts,
@@ -4138,12 +4153,15 @@ fn approval_decision_view(decision: ApprovalDecision) -> ApprovalDecisionView {
     }
 }
 
+/// `cwd` is where the command runs; relative paths are resolved against it
+/// for the sandbox path roots.
 fn evaluate_safety(
     command: &str,
+    cwd: &Path,
     policy: &mosaic_ops::ApprovalPolicy,
-    profile: mosaic_ops::SandboxProfile,
+    sandbox_policy: &mosaic_ops::SandboxPolicy,
 ) -> SafetyCheckView {
-    let sandbox_reason = evaluate_sandbox(command, profile);
+    let sandbox_reason = evaluate_sandbox_policy_in(command, sandbox_policy, cwd);
     let sandbox_decision = if sandbox_reason.is_some() {
         "deny"
     } else {
@@ -4171,10 +4189,35 @@ fn evaluate_safety(
         approval_decision: approval.decision,
         approval_reason: approval.reason,
         approval_mode: format!("{:?}", policy.mode).to_lowercase(),
-        sandbox_profile: format!("{:?}", profile).to_lowercase(),
+        sandbox_profile: sandbox_policy.profile_name(),
     }
 }
 
+/// Best-effort presence heartbeat for every command. It never creates state
+/// directories and never fails the command; `system presence` records on its own.
+pub(super) fn record_presence(cli: &Cli) {
+    if matches!(
+        cli.command,
+        Some(Commands::Reset)
+            | Some(Commands::Uninstall)
+            | Some(Commands::System(SystemArgs {
+                command: SystemCommand::Presence { .. }
+            }))
+    ) {
+        return;
+    }
+    let Ok(paths) = resolve_state_paths(cli.project_state) else {
+        return;
+    };
+    if !paths.data_dir.is_dir() {
+        return;
+    }
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    let _ = PresenceStore::new(presence_log_path(&paths.data_dir)).record_throttled(&cwd);
+}
+
 pub(super) fn handle_system(cli: &Cli, args: SystemArgs) -> Result<()> {
     let paths = resolve_state_paths(cli.project_state)?;
     paths.ensure_dirs()?;
@@ -4215,20 +4258,44 @@ pub(super) fn handle_system(cli: &Cli, args: SystemArgs) -> Result<()> {
                 }
             }
         }
-        SystemCommand::Presence => {
+        SystemCommand::Presence { history } => {
             let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
-            let presence = snapshot_presence(&cwd);
+            let presence_store = PresenceStore::new(presence_log_path(&paths.data_dir));
+            presence_store.record(&cwd)?;
+            let presence = presence_store
+                .latest()?
+                .ok_or_else(|| MosaicError::Unknown("presence log is empty".to_string()))?;
+            let history = history
+                .map(|limit| presence_store.presence_history(limit))
+                .transpose()?;
             if cli.json {
-                print_json(&json!({
+                let mut payload = json!({
                     "ok": true,
                     "presence": presence,
-                }));
+                });
+                if let Some(history) = &history {
+                    payload["history"] = json!(history);
+                    payload["path"] = json!(presence_store.path().display().to_string());
+                }
+                print_json(&payload);
             } else {
                 println!("presence:");
                 println!("hostname: {}", presence.hostname);
                 println!("pid: {}", presence.pid);
                 println!("cwd: {}", presence.cwd);
                 println!("ts: {}", presence.ts.to_rfc3339());
+                if let Some(history) = history {
+                    println!("history:");
+                    for entry in history {
+                        println!(
+                            "{} pid={} host={} cwd={}",
+                            entry.ts.to_rfc3339(),
+                            entry.pid,
+                            entry.hostname,
+                            entry.cwd
+                        );
+                    }
+                }
             }
         }
         SystemCommand::List { tail, name } => {
@@ -4250,6 +4317,61 @@ pub(super) fn handle_system(cli: &Cli, args: SystemArgs) -> Result<()> {
                 }
             }
         }
+        SystemCommand::Events {
+            name,
+            since_minutes,
+            limit,
+        } => {
+            let since =
+                super::automation_commands::resolve_logs_cutoff(since_minutes, "system events")?;
+            let events = store.query(name.as_deref(), since, limit)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "events": events,
+                    "since": since,
+                    "path": store.path().display().to_string(),
+                }));
+            } else if events.is_empty() {
+                println!("No system events.");
+            } else {
+                for event in events {
+                    println!("{} {} {}", event.ts.to_rfc3339(), event.name, event.data);
+                }
+            }
+        }
+        SystemCommand::Audit {
+            session,
+            since_minutes,
+            limit,
+        } => {
+            let since =
+                super::automation_commands::resolve_logs_cutoff(since_minutes, "system audit")?;
+            let audit_store =
+                AuditStore::new(paths.audit_dir.clone(), paths.audit_log_path.clone());
+            let entries = audit_store.query(session.as_deref(), since, limit)?;
+            if cli.json {
+                print_json(&json!({
+                    "ok": true,
+                    "entries": entries,
+                    "since": since,
+                    "path": audit_store.path().display().to_string(),
+                }));
+            } else if entries.is_empty() {
+                println!("No audited commands.");
+            } else {
+                for entry in entries {
+                    println!(
+                        "{} session={} exit={} approved_by={} {}",
+                        entry.ts.to_rfc3339(),
+                        entry.session_id,
+                        entry.exit_code,
+                        entry.approved_by,
+                        entry.command
+                    );
+                }
+            }
+        }
     }
     Ok(())
 }
@@ -4303,11 +4425,11 @@ pub(super) fn handle_approvals(cli: &Cli, args: ApprovalsArgs) -> Result<()> {
         }));
     } else {
         println!("approvals mode: {:?}", policy.mode);
-        if policy.allowlist.is_empty() {
+        if policy.allowlist().is_empty() {
             println!("allowlist: <empty>");
         } else {
             println!("allowlist:");
-            for item in policy.allowlist {
+            for item in policy.allowlist() {
                 println!("- {item}");
             }
         }
@@ -4330,12 +4452,21 @@ pub(super) fn handle_sandbox(cli: &Cli, args: SandboxArgs) -> Result<()> {
                     "path": store.path().display().to_string(),
                 }));
             } else {
-                println!("sandbox profile: {:?}", policy.profile);
+                println!("sandbox profile: {}", policy.profile_name());
+                if !policy.allowed_hosts.is_empty() {
+                    println!("allowed hosts: {}", policy.allowed_hosts.join(", "));
+                }
+                if !policy.writable_paths.is_empty() {
+                    println!("writable paths: {}", policy.writable_paths.join(", "));
+                }
+                if !policy.readable_paths.is_empty() {
+                    println!("readable paths: {}", policy.readable_paths.join(", "));
+                }
                 println!("path: {}", store.path().display());
             }
         }
         SandboxCommand::Set { profile } => {
-            let policy = store.set_profile(profile.into())?;
+            let policy = store.set_named_profile(&profile)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -4343,13 +4474,14 @@ pub(super) fn handle_sandbox(cli: &Cli, args: SandboxArgs) -> Result<()> {
                     "path": store.path().display().to_string(),
                 }));
             } else {
-                println!("sandbox profile set: {:?}", policy.profile);
+                println!("sandbox profile set: {}", policy.profile_name());
                 println!("path: {}", store.path().display());
             }
         }
         SandboxCommand::Check { command } => {
             let policy = store.load_or_default()?;
-            let reason = evaluate_sandbox(&command, policy.profile);
+            let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
+            let reason = evaluate_sandbox_policy_in(&command, &policy, &cwd);
             let decision = if reason.is_some() { "deny" } else { "allow" };
             if cli.json {
                 print_json(&json!({
@@ -4357,7 +4489,7 @@ pub(super) fn handle_sandbox(cli: &Cli, args: SandboxArgs) -> Result<()> {
                     "command": command,
                     "decision": decision,
                     "reason": reason,
-                    "profile": policy.profile,
+                    "profile": policy.profile_name(),
                     "path": store.path().display().to_string(),
                 }));
             } else {
@@ -4366,31 +4498,32 @@ pub(super) fn handle_sandbox(cli: &Cli, args: SandboxArgs) -> Result<()> {
                 if let Some(reason) = reason {
                     println!("reason: {reason}");
                 }
-                println!("sandbox profile: {:?}", policy.profile);
+                println!("sandbox profile: {}", policy.profile_name());
                 println!("path: {}", store.path().display());
             }
         }
         SandboxCommand::List => {
             let policy = store.load_or_default()?;
-            let profiles = list_profiles();
+            let profiles = store.list_profiles()?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
-                    "current": policy.profile,
+                    "current": policy.profile_name(),
                     "profiles": profiles,
                     "path": store.path().display().to_string(),
                 }));
             } else {
-                println!("current sandbox profile: {:?}", policy.profile);
+                println!("current sandbox profile: {}", policy.profile_name());
                 for profile in profiles {
-                    println!("- {:?}: {}", profile.profile, profile.description);
+                    let kind = if profile.custom { " (custom)" } else { "" };
+                    println!("- {}{kind}: {}", profile.profile, profile.description);
                 }
             }
         }
         SandboxCommand::Explain { profile } => {
             let policy = store.load_or_default()?;
-            let profile = profile.map(Into::into).unwrap_or(policy.profile);
-            let info = mosaic_ops::profile_info(profile);
+            let profile = profile.unwrap_or_else(|| policy.profile_name());
+            let info = store.profile_info(&profile)?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -4398,7 +4531,7 @@ pub(super) fn handle_sandbox(cli: &Cli, args: SandboxArgs) -> Result<()> {
                     "path": store.path().display().to_string(),
                 }));
             } else {
-                println!("sandbox profile: {:?}", info.profile);
+                println!("sandbox profile: {}", info.profile);
                 println!("{}", info.description);
                 if info.blocked_examples.is_empty() {
                     println!("blocked examples: <none>");
@@ -4438,21 +4571,22 @@ pub(super) fn handle_safety(cli: &Cli, args: SafetyArgs) -> Result<()> {
                 }));
             } else {
                 println!("approvals mode: {:?}", approval_policy.mode);
-                if approval_policy.allowlist.is_empty() {
+                if approval_policy.allowlist().is_empty() {
                     println!("approvals allowlist: <empty>");
                 } else {
                     println!("approvals allowlist:");
-                    for item in approval_policy.allowlist {
+                    for item in approval_policy.allowlist() {
                         println!("- {item}");
                     }
                 }
                 println!("approvals path: {}", approval_store.path().display());
-                println!("sandbox profile: {:?}", sandbox_policy.profile);
+                println!("sandbox profile: {}", sandbox_policy.profile_name());
                 println!("sandbox path: {}", sandbox_store.path().display());
             }
         }
         SafetyCommand::Check { command } => {
-            let check = evaluate_safety(&command, &approval_policy, sandbox_policy.profile);
+            let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
+            let check = evaluate_safety(&command, &cwd, &approval_policy, &sandbox_policy);
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -4505,18 +4639,19 @@ pub(super) fn handle_safety(cli: &Cli, args: SafetyArgs) -> Result<()> {
             audit_tail,
             compare_window,
         } => {
-            let profile = mosaic_ops::profile_info(sandbox_policy.profile);
+            let profile = sandbox_store.profile_info(&sandbox_policy.profile_name())?;
+            let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
             let check = command
                 .as_deref()
-                .map(|value| evaluate_safety(value, &approval_policy, sandbox_policy.profile));
+                .map(|value| evaluate_safety(value, &cwd, &approval_policy, &sandbox_policy));
             let audit = build_safety_audit(
                 &paths.audit_log_path,
                 audit_tail,
                 compare_window,
                 &approval_policy,
-                sandbox_policy.profile,
+                &sandbox_policy,
             )?;
-            let profiles = list_profiles();
+            let profiles = sandbox_store.list_profiles()?;
             if cli.json {
                 print_json(&json!({
                     "ok": true,
@@ -4553,9 +4688,9 @@ pub(super) fn handle_safety(cli: &Cli, args: SafetyArgs) -> Result<()> {
                 println!("approvals mode: {:?}", approval_policy.mode);
                 println!(
                     "approvals allowlist entries: {}",
-                    approval_policy.allowlist.len()
+                    approval_policy.allowlist().len()
                 );
-                println!("sandbox profile: {:?}", sandbox_policy.profile);
+                println!("sandbox profile: {}", sandbox_policy.profile_name());
                 println!("sandbox description: {}", profile.description);
                 println!(
                     "audit entries (tail={}): {}",
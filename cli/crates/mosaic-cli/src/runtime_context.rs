@@ -1,34 +1,78 @@
 use std::collections::BTreeMap;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
 use mosaic_agent::{AgentRunner, default_system_prompt};
 use mosaic_agents::{AgentStore, agent_routes_path, agents_file_path};
 use mosaic_core::audit::AuditStore;
-use mosaic_core::config::ConfigManager;
+use mosaic_core::config::{ConfigManager, ProfileConfig, ProviderKind};
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::models::{ModelProfileConfig, ModelRoutingStore};
-use mosaic_core::provider::{ChatRequest, ChatResponse, ModelInfo, Provider, ProviderHealth};
+use mosaic_core::provider::{
+    ChatChunkSink, ChatRequest, ChatResponse, EmbeddingRequest, ModelInfo, Provider, ProviderHealth,
+};
 use mosaic_core::session::{SessionRuntimeMetadata, SessionStore};
-use mosaic_core::state::{StateMode, StatePaths};
+use mosaic_core::state::{StateMode, StatePaths, validate_project_dir_name};
 use mosaic_ops::{ApprovalStore, RuntimePolicy, SandboxStore};
 use mosaic_plugins::{ExtensionRegistry, RegistryRoots, SkillEntry};
+use mosaic_provider_anthropic::AnthropicProvider;
 use mosaic_provider_openai::OpenAiCompatibleProvider;
 use mosaic_tools::ToolExecutor;
 
 use super::{Cli, PROJECT_STATE_DIR};
 
+static PROJECT_STATE_DIR_OVERRIDE: OnceLock<String> = OnceLock::new();
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+pub(super) fn init_project_state_dir(state_dir: Option<&str>) -> Result<()> {
+    let Some(state_dir) = state_dir else {
+        return Ok(());
+    };
+    validate_project_dir_name(state_dir)?;
+    let _ = PROJECT_STATE_DIR_OVERRIDE.set(state_dir.to_string());
+    Ok(())
+}
+
+pub(super) fn init_config_path(config: Option<&Path>) -> Result<()> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    if config.as_os_str().is_empty() || config.is_dir() {
+        return Err(MosaicError::Validation(format!(
+            "--config must point to a config file, got '{}'",
+            config.display()
+        )));
+    }
+    let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
+    let _ = CONFIG_PATH_OVERRIDE.set(cwd.join(config));
+    Ok(())
+}
+
+pub(super) fn config_path_override() -> Option<&'static Path> {
+    CONFIG_PATH_OVERRIDE.get().map(PathBuf::as_path)
+}
+
+pub(super) fn project_state_dir() -> &'static str {
+    PROJECT_STATE_DIR_OVERRIDE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(PROJECT_STATE_DIR)
+}
+
 pub(super) struct RuntimeContext {
     pub(super) provider: Arc<dyn Provider>,
     pub(super) agent: AgentRunner,
     pub(super) active_agent_id: Option<String>,
     pub(super) active_profile_name: String,
+    pub(super) active_model: String,
 }
 
 #[derive(Debug, Clone)]
 pub(super) struct RuntimeSelector {
     pub(super) profile: String,
     pub(super) project_state: bool,
+    /// Overrides the model chosen by the profile/agent; aliases still apply.
+    pub(super) model: Option<String>,
 }
 
 impl From<&Cli> for RuntimeSelector {
@@ -36,6 +80,7 @@ impl From<&Cli> for RuntimeSelector {
         Self {
             profile: value.profile.clone(),
             project_state: value.project_state,
+            model: None,
         }
     }
 }
@@ -86,7 +131,10 @@ impl Provider for ModelRoutingProvider {
             let mut retry_request = request.clone();
             retry_request.model = model.clone();
             match self.inner.chat(retry_request).await {
-                Ok(response) => return Ok(response),
+                Ok(mut response) => {
+                    response.model.get_or_insert_with(|| model.clone());
+                    return Ok(response);
+                }
                 Err(err) => {
                     if matches!(err, MosaicError::Auth(_)) {
                         return Err(err);
@@ -102,9 +150,28 @@ impl Provider for ModelRoutingProvider {
             .with_context(format!("chat failed across model chain [{chain}]")))
     }
 
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_chunk: &mut ChatChunkSink<'_>,
+    ) -> Result<ChatResponse> {
+        // Retrying a half-streamed reply would duplicate output, so streaming
+        // only bypasses the fallback chain when there is nothing to fall back to.
+        if self.fallback_models.is_empty() {
+            return self.inner.chat_stream(request, on_chunk).await;
+        }
+        let response = self.chat(request).await?;
+        on_chunk(&response.content);
+        Ok(response)
+    }
+
     async fn health(&self) -> Result<ProviderHealth> {
         self.inner.health().await
     }
+
+    async fn embed(&self, request: EmbeddingRequest) -> Result<Vec<Vec<f32>>> {
+        self.inner.embed(request).await
+    }
 }
 
 pub(super) fn resolve_effective_model(
@@ -118,14 +185,27 @@ pub(super) fn resolve_effective_model(
     (requested_model.trim().to_string(), None)
 }
 
+/// Builds the provider client selected by `provider.kind`.
+pub(super) fn provider_from_profile(profile: &ProfileConfig) -> Result<Arc<dyn Provider>> {
+    Ok(match profile.provider.kind {
+        ProviderKind::OpenAiCompatible | ProviderKind::AzureOpenAi => {
+            Arc::new(OpenAiCompatibleProvider::from_profile(profile)?)
+        }
+        ProviderKind::Anthropic => Arc::new(AnthropicProvider::from_profile(profile)?),
+    })
+}
+
 pub(super) fn resolve_state_paths(project_state: bool) -> Result<StatePaths> {
     let mode = if project_state {
         StateMode::Project
     } else {
         StateMode::Xdg
     };
+    if let Some(config_path) = config_path_override() {
+        return Ok(StatePaths::for_config_file(mode, config_path));
+    }
     let cwd = std::env::current_dir().map_err(|err| MosaicError::Io(err.to_string()))?;
-    StatePaths::resolve(mode, &cwd, PROJECT_STATE_DIR)
+    StatePaths::resolve(mode, &cwd, project_state_dir())
 }
 
 pub(super) fn build_runtime(
@@ -171,8 +251,14 @@ pub(super) fn build_runtime_from_selector(
         requested_agent_id.or(session_agent_id.as_deref()),
         route_hint,
     )?;
+    if let Some(agent_id) = resolved.agent_id.as_deref() {
+        agent_store.touch(agent_id)?;
+    }
     let model_store = ModelRoutingStore::new(state_paths.models_path.clone());
     let profile_models = model_store.profile(&resolved.profile_name)?;
+    if let Some(model) = selector.model.as_deref() {
+        resolved.profile.provider.model = model.to_string();
+    }
     resolved.profile.provider.model =
         profile_models.resolve_model_ref(&resolved.profile.provider.model);
     let fallback_models = profile_models
@@ -186,8 +272,7 @@ pub(super) fn build_runtime_from_selector(
             }
             acc
         });
-    let mut provider: Arc<dyn Provider> =
-        Arc::new(OpenAiCompatibleProvider::from_profile(&resolved.profile)?);
+    let mut provider = provider_from_profile(&resolved.profile)?;
     if !fallback_models.is_empty() {
         provider = Arc::new(ModelRoutingProvider::new(provider, fallback_models));
     }
@@ -195,7 +280,8 @@ pub(super) fn build_runtime_from_selector(
         state_paths.audit_dir.clone(),
         state_paths.audit_log_path.clone(),
     );
-    let approval_store = ApprovalStore::new(state_paths.approvals_policy_path.clone());
+    let approval_store = ApprovalStore::new(state_paths.approvals_policy_path.clone())
+        .with_audit_path(state_paths.approvals_audit_path.clone());
     let sandbox_store = SandboxStore::new(state_paths.sandbox_policy_path.clone());
     let tool_executor = ToolExecutor::new(
         resolved.profile.tools.run.guard_mode.clone(),
@@ -203,9 +289,16 @@ pub(super) fn build_runtime_from_selector(
             approval: approval_store.load_or_default()?,
             sandbox: sandbox_store.load_or_default()?,
         }),
+    )
+    .with_approval_audit(approval_store);
+    let agent_skills = load_agent_skills(&state_paths, &resolved.agent_skills)?;
+    let system_prompt = build_system_prompt(
+        resolved
+            .system_prompt
+            .as_deref()
+            .unwrap_or_else(|| default_system_prompt()),
+        &agent_skills,
     );
-    let agent_skills = load_agent_skills(&state_paths.root_dir, &resolved.agent_skills)?;
-    let system_prompt = build_system_prompt(&agent_skills);
     let agent = AgentRunner::with_system_prompt(
         provider.clone(),
         resolved.profile.clone(),
@@ -213,12 +306,14 @@ pub(super) fn build_runtime_from_selector(
         audit_store,
         tool_executor,
         system_prompt,
-    );
+    )
+    .with_pricing(config.pricing.clone());
     Ok(RuntimeContext {
         provider,
         agent,
         active_agent_id: resolved.agent_id,
         active_profile_name: resolved.profile_name,
+        active_model: resolved.profile.provider.model,
     })
 }
 
@@ -229,13 +324,16 @@ struct LoadedAgentSkill {
     content: String,
 }
 
-fn load_agent_skills(state_root: &Path, requested_ids: &[String]) -> Result<Vec<LoadedAgentSkill>> {
+fn load_agent_skills(
+    state_paths: &StatePaths,
+    requested_ids: &[String],
+) -> Result<Vec<LoadedAgentSkill>> {
     if requested_ids.is_empty() {
         return Ok(Vec::new());
     }
-    let registry = ExtensionRegistry::new(RegistryRoots::from_state_root(state_root.to_path_buf()));
+    let registry = ExtensionRegistry::new(RegistryRoots::from_state_paths(state_paths));
     let skill_map = registry
-        .list_skills()?
+        .list_skills(None)?
         .into_iter()
         .map(|skill| (skill.id.clone(), skill))
         .collect::<BTreeMap<String, SkillEntry>>();
@@ -253,6 +351,9 @@ fn load_agent_skills(state_root: &Path, requested_ids: &[String]) -> Result<Vec<
                 "agent skill '{id}' not found; run `mosaic skills list` first"
             ))
         })?;
+        if !skill.enabled {
+            continue;
+        }
         let content = std::fs::read_to_string(&skill.skill_file).map_err(|err| {
             MosaicError::Io(format!(
                 "failed to read skill '{id}' at {}: {err}",
@@ -273,8 +374,8 @@ fn load_agent_skills(state_root: &Path, requested_ids: &[String]) -> Result<Vec<
     Ok(loaded)
 }
 
-fn build_system_prompt(skills: &[LoadedAgentSkill]) -> String {
-    let mut rendered = default_system_prompt().trim().to_string();
+fn build_system_prompt(base: &str, skills: &[LoadedAgentSkill]) -> String {
+    let mut rendered = base.trim().to_string();
     if skills.is_empty() {
         return rendered;
     }
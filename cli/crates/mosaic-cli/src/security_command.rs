@@ -7,13 +7,13 @@ use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::state::StatePaths;
 use mosaic_security::{
     SecurityAuditOptions, SecurityAuditor, SecurityBaselineConfig, apply_baseline,
-    refresh_report_metadata, report_to_sarif,
+    refresh_report_metadata, report_to_json, report_to_sarif,
 };
 
 use super::{
-    Cli, SecurityArgs, SecurityBaselineCommand, SecurityCommand, SecuritySeverityArg,
-    normalize_non_empty_list, print_json, remove_matching, resolve_baseline_path,
-    resolve_output_path, resolve_state_paths,
+    Cli, SecurityArgs, SecurityBaselineCommand, SecurityCommand, SecurityReportFormatArg,
+    SecuritySeverityArg, normalize_non_empty_list, print_json, remove_matching,
+    resolve_baseline_path, resolve_output_path, resolve_state_paths,
 };
 
 pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
@@ -28,10 +28,14 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
             deep,
             max_files,
             max_file_size,
-            baseline,
+            cache,
+            changed_since,
+            context_lines,
+            baselines,
             no_baseline,
             update_baseline,
             sarif,
+            format,
             sarif_output,
             min_severity,
             categories,
@@ -42,6 +46,11 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                     "--no-baseline and --update-baseline cannot be used together".to_string(),
                 ));
             }
+            if update_baseline && baselines.len() > 1 {
+                return Err(MosaicError::Validation(
+                    "--update-baseline accepts a single --baseline".to_string(),
+                ));
+            }
             if top == Some(0) {
                 return Err(MosaicError::Validation(
                     "--top must be greater than 0".to_string(),
@@ -61,16 +70,34 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                 deep,
                 max_files,
                 max_file_size,
+                cache_path: cache.map(|raw| resolve_output_path(&cwd, &raw)),
+                changed_since,
+                context_lines,
             })?;
-            let baseline_path = resolve_baseline_path(&paths, &cwd, baseline);
-            let baseline_path_display = baseline_path.display().to_string();
+            let baseline_paths = if baselines.is_empty() {
+                vec![resolve_baseline_path(&paths, &cwd, None)]
+            } else {
+                baselines
+                    .into_iter()
+                    .map(|raw| resolve_baseline_path(&paths, &cwd, Some(raw)))
+                    .collect::<Vec<_>>()
+            };
+            let baseline_path_display = baseline_paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
             let mut baseline_added = 0usize;
             let mut baseline_enabled = false;
             let mut sarif_output_path = None;
 
             if !no_baseline || update_baseline {
-                let mut baseline_config =
-                    SecurityBaselineConfig::load_optional(&baseline_path)?.unwrap_or_default();
+                let mut baseline_config = SecurityBaselineConfig::default();
+                for path in &baseline_paths {
+                    if let Some(loaded) = SecurityBaselineConfig::load_optional(path)? {
+                        baseline_config.merge(&loaded);
+                    }
+                }
                 if !no_baseline {
                     baseline_enabled = true;
                     let applied = apply_baseline(report, &baseline_config);
@@ -80,7 +107,7 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                 if update_baseline {
                     baseline_enabled = true;
                     baseline_added = baseline_config.add_findings(&report.findings);
-                    baseline_config.save_to_path(&baseline_path)?;
+                    baseline_config.save_to_path(&baseline_paths[0])?;
                     if !report.findings.is_empty() {
                         report.summary.ignored += report.findings.len();
                         report.findings.clear();
@@ -96,11 +123,17 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
             let (report, filtered_out) =
                 apply_audit_filters(report, min_severity, &categories, top);
 
-            let sarif_value = if sarif || sarif_output.is_some() {
-                Some(report_to_sarif(&report))
+            let format = if sarif {
+                Some(SecurityReportFormatArg::Sarif)
             } else {
-                None
+                format
             };
+            let sarif_value =
+                if format == Some(SecurityReportFormatArg::Sarif) || sarif_output.is_some() {
+                    Some(report_to_sarif(&report))
+                } else {
+                    None
+                };
             if let Some(raw_path) = sarif_output {
                 let output_path = resolve_output_path(&cwd, &raw_path);
                 if let Some(parent) = output_path.parent() {
@@ -118,13 +151,20 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                 sarif_output_path = Some(output_path.display().to_string());
             }
 
-            if sarif {
-                print_json(
-                    sarif_value
-                        .as_ref()
-                        .ok_or_else(|| MosaicError::Unknown("sarif value missing".to_string()))?,
-                );
-                return Ok(());
+            match format {
+                Some(SecurityReportFormatArg::Sarif) => {
+                    print_json(
+                        sarif_value.as_ref().ok_or_else(|| {
+                            MosaicError::Unknown("sarif value missing".to_string())
+                        })?,
+                    );
+                    return Ok(());
+                }
+                Some(SecurityReportFormatArg::Json) => {
+                    print_json(&report_to_json(&report));
+                    return Ok(());
+                }
+                None => {}
             }
 
             if cli.json {
@@ -148,20 +188,33 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                         } else {
                             None
                         },
+                        "paths": if baseline_enabled {
+                            baseline_paths
+                                .iter()
+                                .map(|path| path.display().to_string())
+                                .collect::<Vec<_>>()
+                        } else {
+                            Vec::new()
+                        },
                     },
                     "sarif_output": sarif_output_path,
                 }));
             } else {
                 println!(
-                    "security audit summary: findings={} high={} medium={} low={} ignored={} scanned={} skipped={}",
+                    "security audit summary: findings={} high={} medium={} low={} ignored={} scanned={} skipped={} binary={} cached={}",
                     report.summary.findings,
                     report.summary.high,
                     report.summary.medium,
                     report.summary.low,
                     report.summary.ignored,
                     report.summary.scanned_files,
-                    report.summary.skipped_files
+                    report.summary.skipped_files,
+                    report.summary.binary_files,
+                    report.summary.cached_files
                 );
+                for warning in &report.summary.warnings {
+                    println!("warning: {warning}");
+                }
                 println!(
                     "risk: score={} level={:?}",
                     report.risk.score, report.risk.level
@@ -218,6 +271,18 @@ pub(super) fn handle_security(cli: &Cli, args: SecurityArgs) -> Result<()> {
                             finding.title,
                             finding.category
                         );
+                        if let (Some(line), Some(snippet)) = (finding.line, &finding.snippet) {
+                            let first = line - finding.context_before.len();
+                            for (offset, text) in finding
+                                .context_before
+                                .iter()
+                                .chain(std::iter::once(snippet))
+                                .chain(&finding.context_after)
+                                .enumerate()
+                            {
+                                println!("  {:>5} | {text}", first + offset);
+                            }
+                        }
                         if let Some(suggestion) = finding.suggestion {
                             println!("  suggestion: {suggestion}");
                         }
@@ -28,6 +28,9 @@ impl Provider for StubProvider {
         }
         Ok(ChatResponse {
             content: request.model,
+            usage: None,
+            tool_calls: Vec::new(),
+            model: None,
         })
     }
 
@@ -105,11 +108,16 @@ async fn model_routing_provider_falls_back_on_network_error() {
             model: "primary".to_string(),
             temperature: 0.2,
             messages: Vec::new(),
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: Vec::new(),
         })
         .await
         .expect("fallback succeeds");
 
     assert_eq!(response.content, "backup");
+    assert_eq!(response.model.as_deref(), Some("backup"));
     assert_eq!(
         calls.lock().expect("calls lock").as_slice(),
         &["primary".to_string(), "backup".to_string()]
@@ -131,6 +139,10 @@ async fn model_routing_provider_does_not_retry_auth_error() {
             model: "primary".to_string(),
             temperature: 0.2,
             messages: Vec::new(),
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: Vec::new(),
         })
         .await
         .expect_err("auth should fail without fallback");
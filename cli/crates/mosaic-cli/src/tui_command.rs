@@ -14,8 +14,9 @@ use mosaic_tui::{
 use serde_json::json;
 
 use crate::runtime_context::{RuntimeSelector, build_runtime, build_runtime_from_selector};
+use crate::runtime_context::{config_path_override, project_state_dir};
 use crate::utils::print_json;
-use crate::{Cli, TuiArgs, TuiFocusArg, resolve_state_paths};
+use crate::{Cli, PROJECT_STATE_DIR, TuiArgs, TuiFocusArg, resolve_state_paths};
 
 pub(super) async fn handle_tui(cli: &Cli, args: TuiArgs) -> Result<()> {
     let has_prompt = args.prompt.is_some();
@@ -77,6 +78,7 @@ pub(super) async fn handle_tui(cli: &Cli, args: TuiArgs) -> Result<()> {
                 yes: cli.yes,
                 interactive: false,
                 event_callback: None,
+                label: None,
             },
         )
         .await?;
@@ -228,6 +230,12 @@ fn run_nested_cli_command(
     if selector.project_state {
         args.push("--project-state".to_string());
     }
+    if project_state_dir() != PROJECT_STATE_DIR {
+        args.extend(["--state-dir".to_string(), project_state_dir().to_string()]);
+    }
+    if let Some(config_path) = config_path_override() {
+        args.extend(["--config".to_string(), config_path.display().to_string()]);
+    }
     match command {
         TuiLocalCommand::Models => {
             args.extend(["models", "status"].into_iter().map(str::to_string));
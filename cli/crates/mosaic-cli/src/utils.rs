@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use serde::Serialize;
 use serde::de::DeserializeOwned;
@@ -6,7 +7,7 @@ use serde_json::Value;
 
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::write_pretty_state_json_file;
-use mosaic_core::state::StatePaths;
+use mosaic_core::state::{StatePaths, write_atomic};
 
 pub(super) fn extract_html_title(body: &str) -> Option<String> {
     let lower = body.to_lowercase();
@@ -123,17 +124,13 @@ pub(super) fn save_json_file<T>(path: &std::path::Path, value: &T) -> Result<()>
 where
     T: Serialize,
 {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
     let raw = serde_json::to_string_pretty(value).map_err(|err| {
         MosaicError::Validation(format!(
             "failed to serialize JSON {}: {err}",
             path.display()
         ))
     })?;
-    std::fs::write(path, raw)?;
-    Ok(())
+    write_atomic(path, raw)
 }
 
 pub(super) fn save_state_json_file<T>(
@@ -147,6 +144,21 @@ where
     write_pretty_state_json_file(path, value, context)
 }
 
+static JSON_ERROR_DETAILS: Mutex<Option<Value>> = Mutex::new(None);
+
+/// Object whose fields are merged into the `{"ok": false, "error": ...}`
+/// payload printed in `--json` mode when the running command then fails, so a
+/// command can keep its report in the output while still exiting nonzero.
+pub(super) fn attach_json_error_details(details: Value) {
+    if let Ok(mut slot) = JSON_ERROR_DETAILS.lock() {
+        *slot = Some(details);
+    }
+}
+
+pub(super) fn take_json_error_details() -> Option<Value> {
+    JSON_ERROR_DETAILS.lock().ok()?.take()
+}
+
 pub(super) fn print_json(value: &Value) {
     let rendered = serde_json::to_string_pretty(value).unwrap_or_else(|_| "{}".to_string());
     println!("{rendered}");
@@ -157,6 +169,39 @@ pub(super) fn print_json_line(value: &Value) {
     println!("{rendered}");
 }
 
+/// Renders `rows` under `headers` as left-aligned columns separated by two
+/// spaces. Widths count characters, and trailing padding is trimmed.
+pub(super) fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render_row = |cells: &mut dyn Iterator<Item = &str>| {
+        let mut line = String::new();
+        for (index, (cell, width)) in cells.zip(&widths).enumerate() {
+            if index > 0 {
+                line.push_str("  ");
+            }
+            line.push_str(cell);
+            line.extend(std::iter::repeat_n(' ', width - cell.chars().count()));
+        }
+        let mut line = line.trim_end().to_string();
+        line.push('\n');
+        line
+    };
+    let mut rendered = render_row(&mut headers.iter().copied());
+    for row in rows {
+        rendered.push_str(&render_row(&mut row.iter().map(String::as_str)));
+    }
+    rendered
+}
+
 pub(super) fn binary_in_path(name: &str) -> bool {
     if PathBuf::from(name).is_absolute() {
         return PathBuf::from(name).exists();
@@ -170,3 +215,23 @@ pub(super) fn binary_in_path(name: &str) -> bool {
         })
         .is_some()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_table_aligns_columns_to_widest_cell() {
+        let rows = vec![
+            vec!["a".to_string(), "Long name".to_string(), "x".to_string()],
+            vec!["identifier".to_string(), "é".to_string(), String::new()],
+        ];
+        assert_eq!(
+            render_table(&["ID", "NAME", "NOTE"], &rows),
+            "ID          NAME       NOTE\n\
+             a           Long name  x\n\
+             identifier  é\n"
+        );
+        assert_eq!(render_table(&["ID", "NAME"], &[]), "ID  NAME\n");
+    }
+}
@@ -546,6 +546,178 @@ fn agents_show_missing_returns_validation_error() {
     assert_eq!(json["error"]["code"], "validation");
 }
 
+#[test]
+#[allow(deprecated)]
+fn agents_check_reports_dangling_default_route() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "agents",
+            "add",
+            "--id",
+            "writer",
+            "--name",
+            "Writer",
+            "--set-default",
+        ])
+        .assert()
+        .success();
+
+    let ok_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "agents", "check"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let ok_json: Value = serde_json::from_slice(&ok_output).expect("check json");
+    assert_eq!(ok_json["ok"], true);
+    assert_eq!(ok_json["report"]["ok"], true);
+    assert_eq!(ok_json["report"]["agents_count"], 1);
+
+    std::fs::write(
+        temp.path().join(".mosaic/data/agent-routes.json"),
+        r#"{"default_agent_id":"ghost","routes":{"ask":"ghost"}}"#,
+    )
+    .expect("write dangling routes");
+
+    let failed_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "agents", "check"])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let failed_json: Value = serde_json::from_slice(&failed_output).expect("check error json");
+    assert_eq!(failed_json["ok"], false);
+    let message = failed_json["error"]["message"].as_str().expect("message");
+    assert!(message.contains("default_agent_id 'ghost' does not exist"));
+    assert!(message.contains("route 'ask' points to missing agent 'ghost'"));
+
+    let text_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "agents", "check"])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let text = String::from_utf8(text_output).expect("utf8");
+    assert!(text.contains("ok=false"));
+    assert!(text.contains("- default_agent_id 'ghost' does not exist"));
+}
+
+#[test]
+#[allow(deprecated)]
+fn doctor_fix_removes_dangling_agent_route() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "agents",
+            "add",
+            "--id",
+            "writer",
+            "--name",
+            "Writer",
+            "--set-default",
+        ])
+        .assert()
+        .success();
+    std::fs::write(
+        temp.path().join(".mosaic/data/agent-routes.json"),
+        r#"{"default_agent_id":"writer","routes":{"ask":"writer","chat":"ghost"}}"#,
+    )
+    .expect("write dangling route");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "doctor", "--fix"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("doctor json");
+    assert_eq!(json["ok"], true);
+    let fixes = json["fixes"].as_array().expect("fixes");
+    let route_fix = fixes
+        .iter()
+        .find(|fix| fix["name"] == "agent_routes")
+        .expect("agent_routes fix");
+    assert!(
+        route_fix["before"]
+            .as_str()
+            .expect("before")
+            .contains("chat=ghost")
+    );
+    assert_eq!(route_fix["after"], "routes=[ask=writer] default=writer");
+
+    let check_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "agents", "check"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let check_json: Value = serde_json::from_slice(&check_output).expect("check json");
+    assert_eq!(check_json["report"]["ok"], true);
+    assert_eq!(check_json["report"]["routes_count"], 1);
+
+    let rerun = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "doctor", "--fix"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let text = String::from_utf8(rerun).expect("utf8");
+    assert!(text.contains("fixes: none needed"));
+}
+
 #[test]
 #[allow(deprecated)]
 fn agents_update_conflicting_flags_returns_validation_error() {
@@ -737,6 +909,79 @@ fn agents_with_skill_injects_skill_prompt() {
     assert!(system_prompt.contains("Always answer with concise bullet points"));
 }
 
+#[test]
+#[allow(deprecated)]
+fn agents_skip_skills_disabled_in_state() {
+    let temp = tempdir().expect("tempdir");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+
+    let skill_dir = temp.path().join(".mosaic").join("skills").join("writer");
+    std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+    std::fs::write(
+        skill_dir.join("SKILL.md"),
+        "# Writer\nAlways answer with concise bullet points.",
+    )
+    .expect("write skill");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "agents",
+            "add",
+            "--id",
+            "writer",
+            "--name",
+            "Writer",
+            "--skill",
+            "writer",
+            "--set-default",
+            "--route",
+            "ask",
+        ])
+        .assert()
+        .success();
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "skills", "disable", "writer"])
+        .assert()
+        .success();
+
+    let capture_path = temp.path().join("mock-chat-request.json");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .env("MOSAIC_MOCK_CHAT_RESPONSE", "agent-skill-off")
+        .env("MOSAIC_MOCK_CHAT_CAPTURE_PATH", &capture_path)
+        .args(["--project-state", "--json", "ask", "hello from skill agent"])
+        .assert()
+        .success();
+
+    let captured: Value =
+        serde_json::from_slice(&std::fs::read(&capture_path).expect("read capture"))
+            .expect("capture json");
+    let system_prompt = captured["messages"][0]["content"]
+        .as_str()
+        .expect("system prompt");
+    assert!(!system_prompt.contains("BEGIN AGENT SKILL: writer"));
+    assert!(!system_prompt.contains("Always answer with concise bullet points"));
+}
+
 #[test]
 #[allow(deprecated)]
 fn ask_session_resume_keeps_original_agent_when_default_changes() {
@@ -843,3 +1088,272 @@ fn ask_session_resume_keeps_original_agent_when_default_changes() {
     assert_eq!(show_json["runtime"]["agent_id"], "writer");
     assert_eq!(show_json["runtime"]["profile_name"], "default");
 }
+
+#[test]
+#[allow(deprecated)]
+fn agents_clone_copies_overrides_and_rejects_existing_id() {
+    let temp = tempdir().expect("tempdir");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "agents",
+            "add",
+            "--id",
+            "writer",
+            "--name",
+            "Writer",
+            "--temperature",
+            "0.5",
+        ])
+        .assert()
+        .success();
+
+    let clone_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "agents",
+            "clone",
+            "writer",
+            "editor",
+            "--name",
+            "Editor",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let clone_json: Value = serde_json::from_slice(&clone_output).expect("clone json");
+    assert_eq!(clone_json["ok"], true);
+    assert_eq!(clone_json["source_id"], "writer");
+    assert_eq!(clone_json["agent"]["id"], "editor");
+    assert_eq!(clone_json["agent"]["name"], "Editor");
+    assert_eq!(clone_json["agent"]["temperature"], 0.5);
+
+    let duplicate = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "agents",
+            "clone",
+            "writer",
+            "editor",
+        ])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let duplicate_json: Value = serde_json::from_slice(&duplicate).expect("duplicate json");
+    assert_eq!(duplicate_json["error"]["code"], "validation");
+}
+
+#[test]
+#[allow(deprecated)]
+fn agents_system_prompt_override_replaces_default_prompt() {
+    let temp = tempdir().expect("tempdir");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "agents",
+            "add",
+            "--id",
+            "pirate",
+            "--name",
+            "Pirate",
+            "--system-prompt",
+            "You are a pirate. Answer like one.",
+            "--route",
+            "ask",
+        ])
+        .assert()
+        .success();
+
+    let capture_path = temp.path().join("mock-chat-request.json");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .env("MOSAIC_MOCK_CHAT_CAPTURE_PATH", &capture_path)
+        .args(["--project-state", "--json", "ask", "hello"])
+        .assert()
+        .success();
+    let captured: Value =
+        serde_json::from_slice(&std::fs::read(&capture_path).expect("read capture"))
+            .expect("capture json");
+    assert_eq!(captured["messages"][0]["role"], "system");
+    assert_eq!(
+        captured["messages"][0]["content"],
+        "You are a pirate. Answer like one."
+    );
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "agents",
+            "update",
+            "pirate",
+            "--clear-system-prompt",
+        ])
+        .assert()
+        .success();
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .env("MOSAIC_MOCK_CHAT_CAPTURE_PATH", &capture_path)
+        .args(["--project-state", "--json", "ask", "hello"])
+        .assert()
+        .success();
+    let captured: Value =
+        serde_json::from_slice(&std::fs::read(&capture_path).expect("read capture"))
+            .expect("capture json");
+    let system_prompt = captured["messages"][0]["content"]
+        .as_str()
+        .expect("system prompt");
+    assert!(system_prompt.starts_with("You are Mosaic CLI agent."));
+}
+
+#[test]
+#[allow(deprecated)]
+fn agents_export_import_round_trip_between_projects() {
+    let source = tempdir().expect("source tempdir");
+    let target = tempdir().expect("target tempdir");
+
+    for dir in [source.path(), target.path()] {
+        Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(dir)
+            .args([
+                "--project-state",
+                "setup",
+                "--base-url",
+                "mock://mock-model",
+                "--model",
+                "mock-model",
+            ])
+            .assert()
+            .success();
+    }
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(source.path())
+        .args([
+            "--project-state",
+            "agents",
+            "add",
+            "--id",
+            "writer",
+            "--name",
+            "Writer",
+            "--set-default",
+            "--route",
+            "ask",
+        ])
+        .assert()
+        .success();
+
+    let bundle_path = source.path().join("agents-bundle.json");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(source.path())
+        .args(["--project-state", "agents", "export", "--out"])
+        .arg(&bundle_path)
+        .assert()
+        .success();
+
+    let import_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(target.path())
+        .args(["--project-state", "--json", "agents", "import", "--file"])
+        .arg(&bundle_path)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let import_json: Value = serde_json::from_slice(&import_output).expect("import json");
+    assert_eq!(import_json["summary"]["imported"], 1);
+    assert_eq!(import_json["summary"]["integrity"]["ok"], true);
+
+    let show_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(target.path())
+        .args(["--project-state", "--json", "agents", "show", "writer"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let show_json: Value = serde_json::from_slice(&show_output).expect("show json");
+    assert_eq!(show_json["agent"]["name"], "Writer");
+    assert_eq!(show_json["is_default"], true);
+    assert_eq!(show_json["route_keys"], serde_json::json!(["ask"]));
+
+    let mut bundle: Value =
+        serde_json::from_slice(&std::fs::read(&bundle_path).expect("read bundle"))
+            .expect("bundle json");
+    bundle["version"] = serde_json::json!(2);
+    std::fs::write(&bundle_path, bundle.to_string()).expect("write bundle");
+    let rejected = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(target.path())
+        .args(["--project-state", "--json", "agents", "import", "--file"])
+        .arg(&bundle_path)
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let rejected_json: Value = serde_json::from_slice(&rejected).expect("rejected json");
+    assert!(
+        rejected_json["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("unsupported agents bundle version 2")
+    );
+}
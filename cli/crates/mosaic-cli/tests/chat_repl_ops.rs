@@ -539,3 +539,44 @@ fn chat_repl_switch_agent_rejects_unknown_agent() {
     assert!(stdout.contains("error: validation error: agent 'missing' not found"));
     assert!(stdout.contains("agent: <none>"));
 }
+
+#[test]
+#[allow(deprecated)]
+fn chat_repl_switches_model_and_rejects_unknown_model() {
+    let temp = tempdir().expect("tempdir");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .env("MOSAIC_MOCK_CHAT_RESPONSE", "chat-model-ok")
+        .args(["--project-state", "chat"])
+        .write_stdin(
+            "/model\n/model ghost-model\n/agent ghost\n/model mock-model\n/status\nhello\n/exit\n",
+        )
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).expect("stdout utf8");
+    assert!(stdout.contains("model: mock-model"));
+    assert!(stdout.contains("error: model 'ghost-model' is not offered by the provider"));
+    assert!(stdout.contains("error: validation error: agent 'ghost' not found"));
+    assert!(stdout.contains("model switched: mock-model"));
+    assert!(stdout.contains("assistant> chat-model-ok"));
+    assert!(stdout.contains("Bye."));
+}
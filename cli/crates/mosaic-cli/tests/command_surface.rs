@@ -183,6 +183,8 @@ fn channels_help_includes_operational_commands() {
         "login",
         "send",
         "test",
+        "metrics",
+        "deadletter",
         "logs",
         "replay",
         "capabilities",
@@ -207,6 +209,14 @@ fn channels_help_includes_operational_commands() {
         "channels logs --help missing expected option --summary:\n{logs_help}"
     );
 
+    let send_help = run_help(&["channels", "send", "--help"]);
+    for option in ["--var", "--no-strict-vars", "--dead-letter"] {
+        assert!(
+            send_help.contains(option),
+            "channels send --help missing expected option: {option}\n{send_help}"
+        );
+    }
+
     let replay_help = run_help(&["channels", "replay", "--help"]);
     for option in [
         "--tail",
@@ -377,6 +387,8 @@ fn configure_help_includes_keys_get_set_unset_patch_preview_template_commands()
         "patch",
         "preview",
         "template",
+        "validate",
+        "--copy-from",
     ] {
         assert!(
             help.contains(token),
@@ -783,7 +795,9 @@ fn browser_diagnose_help_includes_repair_and_stale_threshold() {
 #[allow(deprecated)]
 fn memory_help_includes_index_and_search_commands() {
     let help = run_help(&["memory", "--help"]);
-    let expected = ["index", "search", "status", "clear", "prune", "policy"];
+    let expected = [
+        "index", "search", "status", "remove", "clear", "prune", "policy",
+    ];
 
     for name in expected {
         assert!(
@@ -802,6 +816,7 @@ fn memory_index_help_includes_incremental_option() {
         "--namespace",
         "--stale-after-hours",
         "--retain-missing",
+        "--no-ignore",
     ];
     for option in expected {
         assert!(
@@ -892,7 +907,8 @@ fn security_audit_help_includes_filter_options() {
 fn plugins_help_includes_management_commands() {
     let help = run_help(&["plugins", "--help"]);
     let expected = [
-        "list", "info", "check", "install", "enable", "disable", "doctor", "run", "remove",
+        "list", "info", "search", "check", "install", "enable", "disable", "doctor", "run",
+        "remove",
     ];
 
     for name in expected {
@@ -907,13 +923,30 @@ fn plugins_help_includes_management_commands() {
         list_help.contains("--source"),
         "plugins list --help missing expected option --source:\n{list_help}"
     );
+
+    let install_help = run_help(&["plugins", "install", "--help"]);
+    for option in [
+        "--path",
+        "--url",
+        "--force",
+        "--sha256",
+        "--verify",
+        "--dry-run",
+    ] {
+        assert!(
+            install_help.contains(option),
+            "plugins install --help missing expected option {option}:\n{install_help}"
+        );
+    }
 }
 
 #[test]
 #[allow(deprecated)]
 fn skills_help_includes_management_commands() {
     let help = run_help(&["skills", "--help"]);
-    let expected = ["list", "info", "check", "install", "remove"];
+    let expected = [
+        "list", "info", "search", "check", "enable", "disable", "install", "remove",
+    ];
 
     for name in expected {
         assert!(
@@ -969,7 +1002,7 @@ fn observability_help_includes_report_and_export_commands() {
 #[allow(deprecated)]
 fn system_help_includes_event_and_presence_commands() {
     let help = run_help(&["system", "--help"]);
-    let expected = ["event", "presence", "list"];
+    let expected = ["event", "presence", "list", "events", "audit"];
 
     for name in expected {
         assert!(
@@ -20,6 +20,23 @@ fn completion_shell_outputs_script() {
     assert!(script.contains("complete"));
 }
 
+#[test]
+#[allow(deprecated)]
+fn completions_alias_prints_zsh_script() {
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .args(["completions", "zsh"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let script = String::from_utf8(output).expect("utf8");
+    assert!(script.starts_with("#compdef mosaic"));
+    assert!(script.contains("channels"));
+    assert!(script.contains("--dead-letter"));
+}
+
 #[test]
 #[allow(deprecated)]
 fn completion_install_writes_script_file() {
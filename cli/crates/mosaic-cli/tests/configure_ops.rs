@@ -589,3 +589,471 @@ fn configure_preview_template_and_target_profile_flow() {
     assert!(template_toml_text.contains("model = \"gpt-4o-mini\""));
     assert!(template_toml_text.contains("guard_mode = \"confirm_dangerous\""));
 }
+
+#[test]
+#[allow(deprecated)]
+fn state_dir_override_relocates_project_state() {
+    let temp = tempdir().expect("tempdir");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--state-dir",
+            ".agent-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    assert!(temp.path().join(".agent-state/config.toml").exists());
+    assert!(!temp.path().join(".mosaic").exists());
+
+    let ask = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .env("MOSAIC_PROJECT_DIR", ".agent-state")
+        .args(["--project-state", "--json", "ask", "hello"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let ask: Value = serde_json::from_slice(&ask).expect("ask json");
+    let session_id = ask["session_id"].as_str().expect("session id");
+    assert!(
+        temp.path()
+            .join(".agent-state/data/sessions")
+            .join(format!("{session_id}.jsonl"))
+            .exists()
+    );
+    assert!(!temp.path().join(".mosaic").exists());
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--state-dir", "../escape", "status"])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[allow(deprecated)]
+fn uninstall_with_custom_state_dir_only_removes_mosaic_state() {
+    let temp = tempdir().expect("tempdir");
+    std::fs::create_dir_all(temp.path().join("src")).expect("create src");
+    std::fs::write(temp.path().join("src/main.rs"), "fn main() {}\n").expect("write source");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--state-dir", "src", "status"])
+        .assert()
+        .success();
+    let refused = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--state-dir",
+            "src",
+            "--json",
+            "--yes",
+            "uninstall",
+        ])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let refused: Value = serde_json::from_slice(&refused).expect("uninstall json");
+    assert_eq!(refused["error"]["code"], "validation");
+    assert!(temp.path().join("src/main.rs").exists());
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--state-dir", ".agent-state", "status"])
+        .assert()
+        .success();
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--state-dir",
+            ".agent-state",
+            "--yes",
+            "uninstall",
+        ])
+        .assert()
+        .success();
+    assert!(!temp.path().join(".agent-state").exists());
+}
+
+#[test]
+#[allow(deprecated)]
+fn configure_diff_reports_changed_profile_model() {
+    let temp = tempdir().expect("tempdir");
+    let left = r#"version = 1
+active_profile = "default"
+
+[state]
+mode = "project"
+project_dir = ".mosaic"
+
+[profiles.default.provider]
+kind = "openai_compatible"
+base_url = "mock://mock-model"
+api_key_env = "OPENAI_API_KEY"
+model = "mock-model"
+
+[profiles.default.agent]
+temperature = 0.2
+max_turns = 8
+
+[profiles.default.tools]
+enabled = true
+
+[profiles.default.tools.run]
+guard_mode = "confirm_dangerous"
+"#;
+    fs::write(temp.path().join("a.toml"), left).expect("write a.toml");
+    fs::write(
+        temp.path().join("b.toml"),
+        left.replace("model = \"mock-model\"", "model = \"mock-model-2\""),
+    )
+    .expect("write b.toml");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--json", "config", "diff", "a.toml", "b.toml"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("diff json");
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["identical"], false);
+    let changed = json["diff"]["changed_profiles"]
+        .as_array()
+        .expect("changed profiles");
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0]["name"], "default");
+    assert_eq!(changed[0]["changes"][0]["field"], "provider.model");
+    assert_eq!(changed[0]["changes"][0]["before"], "mock-model");
+    assert_eq!(changed[0]["changes"][0]["after"], "mock-model-2");
+
+    let text = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["config", "diff", "a.toml", "b.toml"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let text = String::from_utf8(text).expect("utf8");
+    assert!(text.contains("~ profile default"));
+    assert!(text.contains("provider.model: \"mock-model\" -> \"mock-model-2\""));
+}
+
+#[test]
+#[allow(deprecated)]
+fn configure_validate_reports_invalid_profile_without_writing() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    let config_path = temp.path().join(".mosaic/config.toml");
+    let mut config = fs::read_to_string(&config_path).expect("read config");
+    config.push_str(
+        r#"
+[profiles.broken.provider]
+kind = "openai_compatible"
+base_url = "mock://mock-model"
+api_key_env = "OPENAI_API_KEY"
+model = "mock-model"
+
+[profiles.broken.agent]
+temperature = 4.0
+max_turns = 8
+
+[profiles.broken.tools]
+enabled = true
+
+[profiles.broken.tools.run]
+guard_mode = "confirm_dangerous"
+"#,
+    );
+    fs::write(&config_path, &config).expect("write config");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "configure", "validate"])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("validate json");
+    assert_eq!(json["ok"], false);
+    assert_eq!(json["error"]["code"], "validation");
+    assert_eq!(json["valid"], false);
+    let profiles = json["report"]["profiles"].as_array().expect("profiles");
+    let broken = profiles
+        .iter()
+        .find(|profile| profile["name"] == "broken")
+        .expect("broken profile");
+    assert_eq!(broken["ok"], false);
+    assert!(
+        broken["issues"][0]
+            .as_str()
+            .expect("issue")
+            .contains("agent.temperature")
+    );
+    let default = profiles
+        .iter()
+        .find(|profile| profile["name"] == "default")
+        .expect("default profile");
+    assert_eq!(default["ok"], true);
+
+    let text = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "configure", "validate"])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let text = String::from_utf8(text).expect("utf8");
+    assert!(text.contains("invalid"));
+    assert!(text.contains("profile broken: invalid"));
+    assert_eq!(
+        fs::read_to_string(&config_path).expect("reread config"),
+        config
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn configure_copy_from_clones_profile_without_switching_active() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "--profile",
+            "staging",
+            "configure",
+            "--copy-from",
+            "default",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("copy json");
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["action"], "copy");
+    assert_eq!(json["from"], "default");
+    assert_eq!(json["to"], "staging");
+    assert_eq!(json["active_profile"], "default");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "--profile",
+            "staging",
+            "configure",
+            "get",
+            "provider.model",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("get json");
+    assert_eq!(json["value"], "mock-model");
+
+    let config = fs::read_to_string(temp.path().join(".mosaic/config.toml")).expect("config");
+    assert!(config.contains("active_profile = \"default\""));
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--profile",
+            "staging",
+            "configure",
+            "--copy-from",
+            "default",
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+#[allow(deprecated)]
+fn config_flag_overrides_project_state_paths() {
+    let temp = tempdir().expect("tempdir");
+    let config_path = temp.path().join("custom").join("mosaic.toml");
+    let config_arg = config_path.to_str().expect("config path");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--config",
+            config_arg,
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "override-model",
+        ])
+        .assert()
+        .success();
+
+    let raw = fs::read_to_string(&config_path).expect("overridden config written");
+    assert!(raw.contains("override-model"));
+    assert!(temp.path().join("custom").join("data").is_dir());
+    assert!(!temp.path().join(".mosaic").join("config.toml").exists());
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--config",
+            config_arg,
+            "--json",
+            "configure",
+            "get",
+            "provider.model",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("get json");
+    assert_eq!(json["value"], "override-model");
+}
+
+#[test]
+#[allow(deprecated)]
+fn provider_edits_fail_on_profiles_with_provider_ref() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    let config_path = temp.path().join(".mosaic").join("config.toml");
+    let raw = fs::read_to_string(&config_path).expect("read config");
+    let raw = raw.replacen(
+        "[profiles.default.agent]\n",
+        "[profiles.default]\nprovider_ref = \"shared\"\n\n[profiles.default.agent]\n",
+        1,
+    );
+    fs::write(
+        &config_path,
+        format!(
+            "{raw}\n[providers.shared]\nkind = \"openai_compatible\"\nbase_url = \"mock://mock-model\"\napi_key_env = \"OPENAI_API_KEY\"\nmodel = \"shared-model\"\n"
+        ),
+    )
+    .expect("write config");
+
+    for args in [
+        vec!["configure", "set", "provider.model", "other-model"],
+        vec!["configure", "unset", "provider.model"],
+        vec!["models", "set", "other-model"],
+    ] {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args(["--project-state", "--json"])
+            .args(&args)
+            .assert()
+            .failure()
+            .get_output()
+            .stdout
+            .clone();
+        let json: Value = serde_json::from_slice(&output).expect("error json");
+        assert_eq!(json["error"]["code"], "validation", "{args:?}");
+        assert!(
+            json["error"]["message"]
+                .as_str()
+                .expect("message")
+                .contains("provider_ref 'shared'"),
+            "{args:?}"
+        );
+    }
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "configure",
+            "set",
+            "agent.max_turns",
+            "3",
+        ])
+        .assert()
+        .success();
+    let saved = fs::read_to_string(&config_path).expect("read saved config");
+    assert!(saved.contains("provider_ref = \"shared\""));
+    assert!(!saved.contains("[profiles.default.provider]"));
+}
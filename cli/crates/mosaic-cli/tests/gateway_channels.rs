@@ -2349,6 +2349,105 @@ fn gateway_probe_discover_call_flow() {
         .success();
 }
 
+#[test]
+#[allow(deprecated)]
+fn gateway_commands_use_websocket_transport_for_ws_urls() {
+    let temp = tempdir().expect("tempdir");
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port")
+        .port();
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin("mosaic"))
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "gateway",
+            "serve",
+            "--host",
+            "127.0.0.1",
+            "--port",
+            &port.to_string(),
+        ])
+        .spawn()
+        .expect("spawn gateway server");
+    let url = format!("ws://127.0.0.1:{port}/ws");
+
+    let mut probe_json = Value::Null;
+    for _ in 0..50 {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args([
+                "--project-state",
+                "--json",
+                "gateway",
+                "probe",
+                "--url",
+                &url,
+            ])
+            .output()
+            .expect("probe");
+        if output.status.success() {
+            probe_json = serde_json::from_slice(&output.stdout).expect("probe json");
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let call_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "gateway",
+            "call",
+            "echo",
+            "--params",
+            r#"{"text":"hi"}"#,
+            "--url",
+            &url,
+        ])
+        .output()
+        .expect("call");
+    let discover_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "gateway",
+            "discover",
+            "--url",
+            &url,
+        ])
+        .output()
+        .expect("discover");
+    let _ = server.kill();
+    let _ = server.wait();
+
+    assert_eq!(probe_json["probe"]["ok"], true);
+    assert_eq!(probe_json["probe"]["endpoint"], url.as_str());
+    assert_eq!(probe_json["gateway"]["port"], port);
+    assert!(call_output.status.success());
+    let call_json: Value = serde_json::from_slice(&call_output.stdout).expect("call json");
+    assert_eq!(call_json["data"]["echo"]["text"], "hi");
+    let discover_json: Value =
+        serde_json::from_slice(&discover_output.stdout).expect("discover json");
+    let methods = discover_json["discovery"]["methods"]
+        .as_array()
+        .expect("methods");
+    assert!(
+        methods
+            .iter()
+            .any(|value| value.as_str() == Some("nodes.invoke"))
+    );
+
+    let events = std::fs::read_to_string(temp.path().join(".mosaic/data/gateway-events.jsonl"))
+        .expect("gateway events");
+    assert!(events.lines().all(|line| line.contains("\"mode\":\"ws\"")));
+}
+
 #[test]
 #[allow(deprecated)]
 fn channels_ops_commands_flow() {
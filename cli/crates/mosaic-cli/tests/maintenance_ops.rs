@@ -109,6 +109,63 @@ fn uninstall_requires_yes() {
     assert_eq!(json["error"]["code"], "approval_required");
 }
 
+#[test]
+#[allow(deprecated)]
+fn reset_refuses_config_override_and_keeps_sibling_files() {
+    let temp = tempdir().expect("tempdir");
+    fs::create_dir_all(temp.path().join("data")).expect("data dir");
+    fs::write(temp.path().join("data").join("keep.txt"), "mine").expect("keep file");
+    fs::write(temp.path().join("models.toml"), "# mine").expect("models file");
+    let config_path = temp.path().join("mosaic.toml");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .args([
+            "--config",
+            config_path.to_str().expect("config path"),
+            "--yes",
+            "--json",
+            "reset",
+        ])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("json");
+    assert_eq!(json["error"]["code"], "validation");
+    assert!(temp.path().join("data").join("keep.txt").exists());
+    assert!(temp.path().join("models.toml").exists());
+}
+
+#[test]
+#[allow(deprecated)]
+fn uninstall_refuses_config_override_and_keeps_parent_dir() {
+    let temp = tempdir().expect("tempdir");
+    let parent = temp.path().join("mosaic");
+    fs::create_dir_all(&parent).expect("parent dir");
+    fs::write(parent.join("Cargo.toml"), "[workspace]").expect("checkout file");
+    let config_path = parent.join("mosaic.toml");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .args([
+            "--config",
+            config_path.to_str().expect("config path"),
+            "--yes",
+            "--json",
+            "uninstall",
+        ])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("json");
+    assert_eq!(json["error"]["code"], "validation");
+    assert!(parent.join("Cargo.toml").exists());
+}
+
 #[test]
 #[allow(deprecated)]
 fn reset_with_yes_clears_state_and_reinitializes() {
@@ -2,6 +2,71 @@ use assert_cmd::Command;
 use serde_json::Value;
 use tempfile::tempdir;
 
+#[test]
+#[allow(deprecated)]
+fn memory_semantic_search_uses_stored_embeddings() {
+    let temp = tempdir().expect("tempdir");
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "setup",
+            "--base-url",
+            "mock://mock-model",
+            "--model",
+            "mock-model",
+        ])
+        .assert()
+        .success();
+    std::fs::write(
+        temp.path().join("pets.md"),
+        "kitten visits the vet every spring",
+    )
+    .expect("write pets");
+    std::fs::write(temp.path().join("garage.md"), "truck engine needs fuel").expect("write garage");
+
+    let index_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "memory",
+            "index",
+            "--path",
+            ".",
+            "--embed",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let index_json: Value = serde_json::from_slice(&index_output).expect("index json");
+    assert_eq!(index_json["embedded_documents"], 2);
+
+    let search_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "memory",
+            "search",
+            "vet kitten",
+            "--semantic",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let search_json: Value = serde_json::from_slice(&search_output).expect("search json");
+    assert_eq!(search_json["result"]["semantic"], true);
+    assert_eq!(search_json["result"]["hits"][0]["path"], "pets.md");
+}
+
 #[test]
 #[allow(deprecated)]
 fn memory_index_search_status_flow() {
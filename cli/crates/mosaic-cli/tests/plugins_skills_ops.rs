@@ -318,6 +318,102 @@ fn plugins_and_skills_list_info_check_flow() {
     assert_eq!(skills_check["report"]["checked"], 1);
 }
 
+#[test]
+#[allow(deprecated)]
+fn plugins_manifest_disabled_by_default_until_enabled() {
+    let temp = tempdir().expect("tempdir");
+    let plugin_dir = temp.path().join(".mosaic").join("plugins").join("quiet");
+    std::fs::create_dir_all(&plugin_dir).expect("create plugin dir");
+    std::fs::write(
+        plugin_dir.join("plugin.toml"),
+        "[plugin]\nid = \"quiet\"\nname = \"Quiet\"\nenabled = false\n\n[runtime]\nrun = \"run.sh\"\n",
+    )
+    .expect("write plugin manifest");
+    std::fs::write(plugin_dir.join("run.sh"), "#!/bin/sh\necho quiet\n").expect("write hook");
+
+    let list = |temp: &std::path::Path| -> Value {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp)
+            .args(["--project-state", "--json", "plugins", "list"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&output).expect("plugins list json")
+    };
+    let listed = list(temp.path());
+    assert_eq!(listed["plugins"][0]["id"], "quiet");
+    assert_eq!(listed["plugins"][0]["enabled"], false);
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "plugins", "run", "quiet"])
+        .assert()
+        .failure()
+        .code(7);
+
+    let enable = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "plugins", "enable", "quiet"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let enable: Value = serde_json::from_slice(&enable).expect("enable json");
+    assert_eq!(enable["changed"], true);
+    assert_eq!(list(temp.path())["plugins"][0]["enabled"], true);
+}
+
+#[test]
+#[allow(deprecated)]
+fn skills_disable_persists_until_enabled() {
+    let temp = tempdir().expect("tempdir");
+    let skill_dir = temp.path().join(".mosaic").join("skills").join("writer");
+    std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+    std::fs::write(skill_dir.join("SKILL.md"), "# Writer\nShort notes.\n").expect("write skill");
+
+    let run = |args: &[&str]| -> Value {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args(["--project-state", "--json", "skills"])
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&output).expect("skills json")
+    };
+    let disable = run(&["disable", "writer"]);
+    assert_eq!(disable["enabled"], false);
+    assert_eq!(disable["changed"], true);
+    assert_eq!(run(&["list"])["skills"][0]["enabled"], false);
+    let state: Value = serde_json::from_str(
+        &std::fs::read_to_string(
+            temp.path()
+                .join(".mosaic")
+                .join("data")
+                .join("plugins-state.json"),
+        )
+        .expect("read shared state"),
+    )
+    .expect("state json");
+    assert_eq!(state["disabled_skills"], serde_json::json!(["writer"]));
+    assert_eq!(state["disabled_plugins"], serde_json::json!([]));
+    let check = run(&["check", "writer"]);
+    assert_eq!(check["report"]["ok"], true);
+    assert_eq!(check["report"]["results"][0]["enabled"], false);
+
+    assert_eq!(run(&["enable", "writer"])["changed"], true);
+    assert_eq!(run(&["list"])["skills"][0]["enabled"], true);
+}
+
 #[test]
 #[allow(deprecated)]
 fn plugins_info_missing_returns_validation_error() {
@@ -356,6 +452,29 @@ fn plugins_and_skills_install_remove_flow() {
     )
     .expect("write skill file");
 
+    let dry_run = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "plugins",
+            "install",
+            "--path",
+            "sample-plugin",
+            "--dry-run",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let dry_run: Value = serde_json::from_slice(&dry_run).expect("dry run json");
+    assert_eq!(dry_run["dry_run"], true);
+    assert_eq!(dry_run["installed"]["id"], "sample_plugin");
+    assert!(!temp.path().join(".mosaic/plugins/sample_plugin").exists());
+    assert!(!temp.path().join(".mosaic/data/plugins-state.json").exists());
+
     let plugin_install = Command::cargo_bin("mosaic")
         .expect("binary")
         .current_dir(temp.path())
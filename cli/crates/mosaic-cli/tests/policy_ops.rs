@@ -487,3 +487,118 @@ fn plugins_run_honors_restricted_sandbox_policy() {
     assert_eq!(payload["ok"], false);
     assert_eq!(payload["error"]["code"], "sandbox_denied");
 }
+
+#[test]
+#[allow(deprecated)]
+fn plugins_run_applies_custom_sandbox_profile() {
+    let temp = tempdir().expect("tempdir");
+    let state_root = temp.path().join(".mosaic");
+    let policy_dir = state_root.join("policy");
+    fs::create_dir_all(&policy_dir).expect("create policy dir");
+    fs::write(
+        policy_dir.join("sandbox-profiles.toml"),
+        "[profiles.ci]\nnetwork = \"allow\"\nblocked_commands = [\"git push\"]\n",
+    )
+    .expect("write sandbox profiles");
+    let plugin_dir = state_root.join("plugins").join("pusher");
+    let hooks_dir = plugin_dir.join("hooks");
+    fs::create_dir_all(&hooks_dir).expect("create hooks dir");
+    fs::write(
+        plugin_dir.join("plugin.toml"),
+        "[plugin]\nid = \"pusher\"\nname = \"Pusher\"\nversion = \"0.1.0\"\n\n[runtime]\nrun = \"hooks/run.sh\"\n",
+    )
+    .expect("write plugin manifest");
+    fs::write(
+        hooks_dir.join("run.sh"),
+        "#!/bin/sh\ngit push origin main\n",
+    )
+    .expect("write hook script");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "sandbox", "set", "ci"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--yes",
+            "--json",
+            "plugins",
+            "run",
+            "pusher",
+        ])
+        .assert()
+        .failure()
+        .code(12)
+        .get_output()
+        .stdout
+        .clone();
+    let payload: Value = serde_json::from_slice(&output).expect("plugins sandbox deny output");
+    assert_eq!(payload["error"]["code"], "sandbox_denied");
+    assert!(
+        payload["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("sandbox profile 'ci' blocks command 'git push'")
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn sandbox_custom_profile_can_be_selected_and_checked() {
+    let temp = tempdir().expect("tempdir");
+    let policy_dir = temp.path().join(".mosaic").join("policy");
+    fs::create_dir_all(&policy_dir).expect("create policy dir");
+    fs::write(
+        policy_dir.join("sandbox-profiles.toml"),
+        "[profiles.ci]\ndescription = \"CI runners\"\nnetwork = \"deny\"\nblocked_commands = [\"git push\"]\n",
+    )
+    .expect("write sandbox profiles");
+
+    let run = |args: &[&str]| -> Value {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args(["--project-state", "--json"])
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&output).expect("json output")
+    };
+
+    let set = run(&["sandbox", "set", "ci"]);
+    assert_eq!(set["policy"]["custom_profile"], "ci");
+
+    let list = run(&["sandbox", "list"]);
+    assert_eq!(list["current"], "ci");
+    let profiles = list["profiles"].as_array().expect("profiles");
+    assert!(
+        profiles
+            .iter()
+            .any(|profile| profile["profile"] == "ci" && profile["custom"] == true)
+    );
+
+    let check = run(&["sandbox", "check", "--command", "git push origin main"]);
+    assert_eq!(check["decision"], "deny");
+    assert_eq!(check["profile"], "ci");
+    let check = run(&["sandbox", "check", "--command", "cargo test"]);
+    assert_eq!(check["decision"], "allow");
+
+    let explain = run(&["sandbox", "explain"]);
+    assert_eq!(explain["profile"]["description"], "CI runners");
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "sandbox", "set", "missing"])
+        .assert()
+        .failure();
+}
@@ -128,6 +128,80 @@ fn security_audit_can_update_and_apply_baseline() {
     );
 }
 
+#[test]
+#[allow(deprecated)]
+fn security_audit_merges_repeated_baselines() {
+    let temp = tempdir().expect("tempdir");
+    std::fs::write(
+        temp.path().join("secrets.env"),
+        "API_KEY = \"sk-live-secret-value-123456\"\n",
+    )
+    .expect("write secrets");
+    std::fs::write(
+        temp.path().join("client.py"),
+        "BASE_URL = 'http://example.com'\n",
+    )
+    .expect("write client");
+    std::fs::write(
+        temp.path().join("repo.toml"),
+        "version = 1\nignored_fingerprints = []\nignored_paths = []\nignored_categories = [\"credential_exposure\"]\n",
+    )
+    .expect("write repo baseline");
+    std::fs::write(
+        temp.path().join("team.toml"),
+        "version = 1\nignored_fingerprints = []\nignored_paths = [\"client.py\"]\nignored_categories = []\n",
+    )
+    .expect("write team baseline");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "security",
+            "audit",
+            "--path",
+            ".",
+            "--baseline",
+            "repo.toml",
+            "--baseline",
+            "team.toml",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("audit json");
+    assert_eq!(json["report"]["summary"]["findings"], 0);
+    assert_eq!(json["report"]["summary"]["ignored"], 2);
+    assert_eq!(json["baseline"]["paths"].as_array().map(Vec::len), Some(2));
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "--json",
+            "security",
+            "audit",
+            "--baseline",
+            "repo.toml",
+            "--baseline",
+            "team.toml",
+            "--update-baseline",
+        ])
+        .assert()
+        .failure()
+        .code(7)
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("error json");
+    assert_eq!(json["error"]["code"], "validation");
+}
+
 #[test]
 #[allow(deprecated)]
 fn security_baseline_manage_commands_flow() {
@@ -311,6 +385,52 @@ fn security_audit_sarif_output_flow() {
     assert_eq!(sarif_file["version"], "2.1.0");
 }
 
+#[test]
+#[allow(deprecated)]
+fn security_audit_format_json_and_sarif() {
+    let temp = tempdir().expect("tempdir");
+    std::fs::write(
+        temp.path().join("secrets.env"),
+        "API_KEY = \"sk-live-secret-value-123456\"\n",
+    )
+    .expect("write secrets");
+
+    let run = |format: &str| {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args([
+                "--project-state",
+                "security",
+                "audit",
+                "--path",
+                ".",
+                "--format",
+                format,
+            ])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice::<Value>(&output).expect("report json")
+    };
+
+    let json_report = run("json");
+    assert_eq!(json_report["schema"], 1);
+    assert_eq!(json_report["summary"]["findings"], 1);
+    assert_eq!(
+        json_report["findings"]
+            .as_array()
+            .expect("findings array")
+            .len(),
+        1
+    );
+
+    let sarif_report = run("sarif");
+    assert_eq!(sarif_report["version"], "2.1.0");
+}
+
 #[test]
 #[allow(deprecated)]
 fn security_audit_supports_filter_dimensions() {
@@ -197,3 +197,133 @@ fn session_clear_supports_single_and_all() {
     let stdout = String::from_utf8(final_list_output).expect("stdout utf8");
     assert!(stdout.contains("No sessions found."));
 }
+
+#[test]
+#[allow(deprecated)]
+fn session_fork_copies_history_into_new_session() {
+    let temp = tempdir().expect("tempdir");
+    setup_project(&temp);
+
+    let session_id = ask_once(&temp, "first-response", "first prompt");
+
+    let fork_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "session", "fork", &session_id])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let fork_json: Value = serde_json::from_slice(&fork_output).expect("fork json");
+    assert_eq!(fork_json["source_session_id"], session_id);
+    let fork_id = fork_json["session_id"]
+        .as_str()
+        .expect("fork id")
+        .to_string();
+    assert_ne!(fork_id, session_id);
+
+    let show = |id: &str| -> Value {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args(["--project-state", "--json", "session", "show", id])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice(&output).expect("session show json")
+    };
+    let original = show(&session_id);
+    let forked = show(&fork_id);
+    assert_eq!(
+        original["events"].as_array().expect("events").len(),
+        forked["events"].as_array().expect("events").len()
+    );
+    assert_eq!(forked["runtime"], original["runtime"]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn session_rename_updates_listed_title() {
+    let temp = tempdir().expect("tempdir");
+    setup_project(&temp);
+
+    let session_id = ask_once(&temp, "first-response", "summarize the release checklist");
+
+    let list_titles = || -> Vec<Value> {
+        let output = Command::cargo_bin("mosaic")
+            .expect("binary")
+            .current_dir(temp.path())
+            .args(["--project-state", "--json", "session", "list"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let json: Value = serde_json::from_slice(&output).expect("session list json");
+        json["sessions"]
+            .as_array()
+            .expect("sessions")
+            .iter()
+            .map(|session| session["title"].clone())
+            .collect()
+    };
+    assert_eq!(
+        list_titles(),
+        vec![Value::from("summarize the release checklist")]
+    );
+
+    Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args([
+            "--project-state",
+            "session",
+            "rename",
+            &session_id,
+            "Release prep",
+        ])
+        .assert()
+        .success();
+    assert_eq!(list_titles(), vec![Value::from("Release prep")]);
+
+    let list_text_output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "session", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(list_text_output).expect("stdout utf8");
+    assert!(stdout.contains("title=\"Release prep\""));
+}
+
+#[test]
+#[allow(deprecated)]
+fn session_search_finds_matching_session() {
+    let temp = tempdir().expect("tempdir");
+    setup_project(&temp);
+
+    let retry_session = ask_once(&temp, "use exponential backoff", "how do retries back off?");
+    ask_once(&temp, "see the changelog", "what changed in the release?");
+
+    let output = Command::cargo_bin("mosaic")
+        .expect("binary")
+        .current_dir(temp.path())
+        .args(["--project-state", "--json", "session", "search", "BACKOFF"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: Value = serde_json::from_slice(&output).expect("session search json");
+    let matches = json["matches"].as_array().expect("matches");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["session_id"], retry_session);
+    assert_eq!(matches[0]["hits"], 1);
+    assert_eq!(matches[0]["snippet"], "use exponential backoff");
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::error::{MosaicError, Result};
 use crate::privacy::append_sanitized_jsonl;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +17,10 @@ pub struct CommandAudit {
     pub approved_by: String,
     pub exit_code: i32,
     pub duration_ms: u128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_keys: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +50,43 @@ impl AuditStore {
     pub fn path(&self) -> &Path {
         &self.audit_log_path
     }
+
+    /// Returns up to `limit` audits for `session_id` recorded at or after
+    /// `since`, newest first. A malformed line fails the query with a
+    /// validation error naming its line number.
+    pub fn query(
+        &self,
+        session_id: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<CommandAudit>> {
+        if !self.audit_log_path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = fs::read_to_string(&self.audit_log_path)?;
+        let session_id = session_id.map(str::trim).filter(|id| !id.is_empty());
+        let entries = raw
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<CommandAudit>(line).map_err(|err| {
+                    MosaicError::Validation(format!(
+                        "invalid command audit format {} line {}: {err}",
+                        self.audit_log_path.display(),
+                        index + 1
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(entries
+            .into_iter()
+            .rev()
+            .filter(|entry| session_id.is_none_or(|id| entry.session_id == id))
+            .filter(|entry| since.is_none_or(|since| entry.ts >= since))
+            .take(limit)
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -72,12 +113,89 @@ mod tests {
                 approved_by: "flag_yes".to_string(),
                 exit_code: 0,
                 duration_ms: 1,
+                label: None,
+                env_keys: Vec::new(),
             })
             .expect("append");
         let raw = std::fs::read_to_string(store.path()).expect("read audit log");
         assert!(raw.contains("[REDACTED_OPENAI_KEY]"));
     }
 
+    fn audit(session_id: &str, command: &str, ts: DateTime<Utc>) -> CommandAudit {
+        CommandAudit {
+            id: Uuid::new_v4().to_string(),
+            ts,
+            session_id: session_id.to_string(),
+            command: command.to_string(),
+            cwd: "/tmp".to_string(),
+            approved_by: "policy_allowlist".to_string(),
+            exit_code: 0,
+            duration_ms: 1,
+            label: None,
+            env_keys: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn query_filters_by_session_and_since_newest_first() {
+        let temp = tempdir().expect("tempdir");
+        let store = AuditStore::new(
+            temp.path().join("audit"),
+            temp.path().join("audit").join("commands.jsonl"),
+        );
+        let now = Utc::now();
+        for entry in [
+            audit("s1", "ls", now - chrono::Duration::hours(2)),
+            audit("s2", "pwd", now - chrono::Duration::minutes(30)),
+            audit("s1", "cargo test", now - chrono::Duration::minutes(10)),
+            audit("s1", "git status", now),
+        ] {
+            store.append_command(&entry).expect("append");
+        }
+
+        let commands = |entries: Vec<CommandAudit>| {
+            entries
+                .into_iter()
+                .map(|entry| entry.command)
+                .collect::<Vec<_>>()
+        };
+        let s1 = store.query(Some("s1"), None, 10).expect("query session");
+        assert_eq!(commands(s1), vec!["git status", "cargo test", "ls"]);
+
+        let recent = store
+            .query(Some("s1"), Some(now - chrono::Duration::hours(1)), 10)
+            .expect("query since");
+        assert_eq!(commands(recent), vec!["git status", "cargo test"]);
+
+        let latest = store.query(None, None, 2).expect("query limit");
+        assert_eq!(commands(latest), vec!["git status", "cargo test"]);
+        assert!(
+            store
+                .query(Some("missing"), None, 10)
+                .expect("none")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn query_fails_on_malformed_line() {
+        let temp = tempdir().expect("tempdir");
+        let store = AuditStore::new(
+            temp.path().join("audit"),
+            temp.path().join("audit").join("commands.jsonl"),
+        );
+        store
+            .append_command(&audit("s1", "ls", Utc::now()))
+            .expect("append");
+        let mut raw = std::fs::read_to_string(store.path()).expect("read audit log");
+        raw.push_str("{not json\n");
+        std::fs::write(store.path(), raw).expect("write audit log");
+
+        let err = store.query(None, None, 10).expect_err("malformed line");
+        assert!(matches!(err, MosaicError::Validation(_)));
+        assert!(err.to_string().contains("line 2"));
+    }
+
     #[test]
     fn append_command_blocks_private_key_material() {
         let temp = tempdir().expect("tempdir");
@@ -95,6 +213,8 @@ mod tests {
                 approved_by: "flag_yes".to_string(),
                 exit_code: 0,
                 duration_ms: 1,
+                label: None,
+                env_keys: Vec::new(),
             })
             .expect_err("should block");
         assert!(err.to_string().contains("private key material"));
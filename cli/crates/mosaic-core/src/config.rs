@@ -2,10 +2,13 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use serde::{Deserialize, Serialize};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
 
 use crate::error::{MosaicError, Result};
 use crate::privacy::write_pretty_state_toml_file;
+use crate::provider::TokenUsage;
 use crate::state::StateMode;
 
 pub const CURRENT_CONFIG_VERSION: u32 = 1;
@@ -13,11 +16,17 @@ pub const DEFAULT_PROFILE: &str = "default";
 pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
 pub const DEFAULT_BASE_URL: &str = "https://api.openai.com";
 pub const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+pub const MAX_PROVIDER_RETRIES: u32 = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProviderKind {
     #[serde(rename = "openai_compatible", alias = "open_ai_compatible")]
     OpenAiCompatible,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    /// OpenAI-compatible API served through Azure deployments.
+    #[serde(rename = "azure_openai")]
+    AzureOpenAi,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,12 +43,37 @@ pub struct ProviderConfig {
     pub base_url: String,
     pub api_key_env: String,
     pub model: String,
+    /// Retries for 429/5xx responses; the provider picks a default when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// `api-version` query parameter for `azure_openai`; ignored by other kinds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentConfig {
     pub temperature: f32,
     pub max_turns: u32,
+    /// Sampling limits forwarded to the provider only when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Limits on replayed session history; the oldest messages are dropped first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_budget: Option<ContextBudget>,
+}
+
+/// Caps applied to the messages replayed after the system prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextBudget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_chars: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,29 +85,132 @@ pub struct RunToolConfig {
 pub struct ToolsConfig {
     pub enabled: bool,
     pub run: RunToolConfig,
+    /// Tool names the agent may call; `None` or an empty list allows every tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<String>>,
+    /// Longer tool results are cut to this many characters before they are
+    /// recorded and replayed to the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_result_chars: Option<usize>,
+    /// Keeps the untruncated result in the session event as `full_result`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_full_tool_results: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Serialized by hand: a profile with `provider_ref` omits its inline
+/// `provider`, which would be ignored anyway.
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct ProfileConfig {
+    /// Name of an entry in the top-level `providers` table. When set it
+    /// replaces `provider`, which may then be omitted.
+    #[serde(default)]
+    pub provider_ref: Option<String>,
+    #[serde(default)]
     pub provider: ProviderConfig,
     pub agent: AgentConfig,
     pub tools: ToolsConfig,
 }
 
+impl Serialize for ProfileConfig {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut profile = serializer.serialize_struct("ProfileConfig", 4)?;
+        match &self.provider_ref {
+            Some(name) => {
+                profile.serialize_field("provider_ref", name)?;
+                profile.skip_field("provider")?;
+            }
+            None => {
+                profile.skip_field("provider_ref")?;
+                profile.serialize_field("provider", &self.provider)?;
+            }
+        }
+        profile.serialize_field("agent", &self.agent)?;
+        profile.serialize_field("tools", &self.tools)?;
+        profile.end()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateConfig {
     pub mode: StateMode,
     pub project_dir: String,
 }
 
+/// USD prices per 1,000 tokens for one model, keyed by model id in `pricing`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub prompt_per_1k: f64,
+    pub completion_per_1k: f64,
+}
+
+impl ModelPricing {
+    pub fn estimate_cost_usd(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt_per_1k
+            + usage.completion_tokens as f64 * self.completion_per_1k)
+            / 1000.0
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigFile {
     pub version: u32,
     pub active_profile: String,
     pub state: StateConfig,
+    /// Provider settings shared by name through `ProfileConfig::provider_ref`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub providers: BTreeMap<String, ProviderConfig>,
+    /// Per-model prices used to estimate the cost of agent runs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pricing: BTreeMap<String, ModelPricing>,
     pub profiles: BTreeMap<String, ProfileConfig>,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigDiff {
+    pub settings: Vec<ConfigFieldChange>,
+    pub added_profiles: Vec<String>,
+    pub removed_profiles: Vec<String>,
+    pub changed_profiles: Vec<ProfileDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileDiff {
+    pub name: String,
+    pub changes: Vec<ConfigFieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigFieldChange {
+    pub field: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Result of checking every profile without stopping at the first failure.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigValidationReport {
+    pub ok: bool,
+    /// Problems with the file itself rather than a single profile.
+    pub issues: Vec<String>,
+    pub profiles: Vec<ProfileValidationReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileValidationReport {
+    pub name: String,
+    pub ok: bool,
+    pub issues: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+            && self.added_profiles.is_empty()
+            && self.removed_profiles.is_empty()
+            && self.changed_profiles.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedConfig {
     pub profile_name: String,
@@ -88,6 +225,8 @@ impl Default for ProviderConfig {
             base_url: DEFAULT_BASE_URL.to_string(),
             api_key_env: DEFAULT_API_KEY_ENV.to_string(),
             model: DEFAULT_MODEL.to_string(),
+            max_retries: None,
+            api_version: None,
         }
     }
 }
@@ -97,6 +236,10 @@ impl Default for AgentConfig {
         Self {
             temperature: 0.2,
             max_turns: 8,
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            context_budget: None,
         }
     }
 }
@@ -114,6 +257,18 @@ impl Default for ToolsConfig {
         Self {
             enabled: true,
             run: RunToolConfig::default(),
+            allowed: None,
+            max_tool_result_chars: None,
+            keep_full_tool_results: false,
+        }
+    }
+}
+
+impl ToolsConfig {
+    pub fn is_tool_allowed(&self, name: &str) -> bool {
+        match &self.allowed {
+            Some(allowed) if !allowed.is_empty() => allowed.iter().any(|tool| tool == name),
+            _ => true,
         }
     }
 }
@@ -135,6 +290,8 @@ impl ConfigFile {
             version: CURRENT_CONFIG_VERSION,
             active_profile: DEFAULT_PROFILE.to_string(),
             state: StateConfig::from_mode(mode),
+            providers: BTreeMap::new(),
+            pricing: BTreeMap::new(),
             profiles,
         }
     }
@@ -151,21 +308,142 @@ impl ConfigFile {
                 "at least one profile must be configured".to_string(),
             ));
         }
-        if self.resolve_profile(Some(&self.active_profile)).is_err() {
+        if !self.profiles.contains_key(&self.active_profile) {
             return Err(MosaicError::Validation(format!(
                 "active_profile '{}' does not exist",
                 self.active_profile
             )));
         }
-        for (name, profile) in &self.profiles {
-            profile.validate().map_err(|err| {
-                MosaicError::Validation(format!("profile '{name}' validation failed: {err}"))
+        for (model, pricing) in &self.pricing {
+            let prices = [pricing.prompt_per_1k, pricing.completion_per_1k];
+            if prices
+                .iter()
+                .any(|price| !price.is_finite() || *price < 0.0)
+            {
+                return Err(MosaicError::Validation(format!(
+                    "pricing for model '{model}' must be finite and non-negative"
+                )));
+            }
+        }
+        for (name, provider) in &self.providers {
+            provider.validate().map_err(|err| {
+                MosaicError::Validation(format!("provider '{name}' validation failed: {err}"))
             })?;
         }
+        for (name, profile) in &self.profiles {
+            self.with_provider_ref(profile)
+                .and_then(|profile| profile.validate())
+                .map_err(|err| {
+                    MosaicError::Validation(format!("profile '{name}' validation failed: {err}"))
+                })?;
+        }
         Ok(())
     }
 
+    /// Runs the checks behind [`Self::validate`] on every profile, including
+    /// `${VAR}` interpolation, and collects the failures instead of returning
+    /// the first one.
+    pub fn validation_report(&self) -> ConfigValidationReport {
+        let mut issues = Vec::new();
+        if self.version != CURRENT_CONFIG_VERSION {
+            issues.push(format!(
+                "unsupported config version {}, expected {}",
+                self.version, CURRENT_CONFIG_VERSION
+            ));
+        }
+        if self.profiles.is_empty() {
+            issues.push("at least one profile must be configured".to_string());
+        } else if !self.profiles.contains_key(&self.active_profile) {
+            issues.push(format!(
+                "active_profile '{}' does not exist",
+                self.active_profile
+            ));
+        }
+        let profiles = self
+            .profiles
+            .iter()
+            .map(|(name, profile)| {
+                let issues = self
+                    .with_provider_ref(profile)
+                    .and_then(|mut resolved| {
+                        resolved.interpolate_env()?;
+                        resolved.validate()
+                    })
+                    .err()
+                    .map(|err| err.to_string())
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                ProfileValidationReport {
+                    name: name.clone(),
+                    ok: issues.is_empty(),
+                    issues,
+                }
+            })
+            .collect::<Vec<_>>();
+        ConfigValidationReport {
+            ok: issues.is_empty() && profiles.iter().all(|profile| profile.ok),
+            issues,
+            profiles,
+        }
+    }
+
+    /// Compares `self` (before) with `other` (after) field by field.
+    pub fn diff(&self, other: &ConfigFile) -> ConfigDiff {
+        let settings_of = |config: &ConfigFile| {
+            let mut fields = BTreeMap::new();
+            flatten_config_value(
+                "",
+                &serde_json::json!({
+                    "version": config.version,
+                    "active_profile": config.active_profile,
+                    "state": config.state,
+                    "providers": config.providers,
+                    "pricing": config.pricing,
+                }),
+                &mut fields,
+            );
+            fields
+        };
+        let mut diff = ConfigDiff {
+            settings: diff_fields(&settings_of(self), &settings_of(other)),
+            ..ConfigDiff::default()
+        };
+        for (name, profile) in &self.profiles {
+            match other.profiles.get(name) {
+                None => diff.removed_profiles.push(name.clone()),
+                Some(next) => {
+                    let changes = diff_fields(&profile_fields(profile), &profile_fields(next));
+                    if !changes.is_empty() {
+                        diff.changed_profiles.push(ProfileDiff {
+                            name: name.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+        diff.added_profiles = other
+            .profiles
+            .keys()
+            .filter(|name| !self.profiles.contains_key(*name))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Resolves `requested` (or the active profile) with its `provider_ref`
+    /// swapped in and `${VAR}` tokens expanded from the process environment.
+    /// Only this profile is expanded, so other profiles may reference unset
+    /// variables.
     pub fn resolve_profile(&self, requested: Option<&str>) -> Result<ResolvedConfig> {
+        self.resolve_profile_with(requested, |name| std::env::var(name).ok())
+    }
+
+    fn resolve_profile_with(
+        &self,
+        requested: Option<&str>,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<ResolvedConfig> {
         let profile_name = requested
             .map(ToOwned::to_owned)
             .unwrap_or_else(|| self.active_profile.clone());
@@ -173,31 +451,113 @@ impl ConfigFile {
             .profiles
             .get(&profile_name)
             .ok_or_else(|| MosaicError::Config(format!("profile '{profile_name}' not found")))?;
+        let mut profile = self
+            .with_provider_ref(profile)
+            .map_err(|err| MosaicError::Config(format!("profile '{profile_name}': {err}")))?;
+        profile.interpolate_with(lookup).map_err(|err| {
+            MosaicError::Config(format!(
+                "profile '{profile_name}' interpolation failed: {err}"
+            ))
+        })?;
+        profile.validate().map_err(|err| {
+            MosaicError::Validation(format!("profile '{profile_name}' validation failed: {err}"))
+        })?;
         Ok(ResolvedConfig {
+            profile,
             profile_name,
-            profile: profile.clone(),
             state: self.state.clone(),
         })
     }
+
+    /// Returns `profile` with its `provider_ref`, if any, swapped in for the
+    /// inline provider settings. The result no longer carries the reference.
+    fn with_provider_ref(&self, profile: &ProfileConfig) -> Result<ProfileConfig> {
+        let mut resolved = profile.clone();
+        if let Some(name) = resolved.provider_ref.take() {
+            resolved.provider = self.providers.get(&name).cloned().ok_or_else(|| {
+                MosaicError::Config(format!("provider_ref '{name}' is not defined in providers"))
+            })?;
+        }
+        Ok(resolved)
+    }
+
+    /// Adds `to` as a deep copy of `from`. The active profile is left alone.
+    pub fn copy_profile(&mut self, from: &str, to: &str) -> Result<()> {
+        let from = from.trim();
+        let to = to.trim();
+        if to.is_empty() {
+            return Err(MosaicError::Validation(
+                "target profile name cannot be empty".to_string(),
+            ));
+        }
+        let source = self
+            .profiles
+            .get(from)
+            .cloned()
+            .ok_or_else(|| MosaicError::Config(format!("profile '{from}' not found")))?;
+        if self.profiles.contains_key(to) {
+            return Err(MosaicError::Validation(format!(
+                "profile '{to}' already exists"
+            )));
+        }
+        self.profiles.insert(to.to_string(), source);
+        Ok(())
+    }
 }
 
-impl ProfileConfig {
+impl ProviderConfig {
     pub fn validate(&self) -> Result<()> {
-        if self.provider.base_url.trim().is_empty() {
+        if self.base_url.trim().is_empty() {
             return Err(MosaicError::Validation(
                 "provider.base_url cannot be empty".to_string(),
             ));
         }
-        if self.provider.api_key_env.trim().is_empty() {
+        if self.api_key_env.trim().is_empty() {
             return Err(MosaicError::Validation(
                 "provider.api_key_env cannot be empty".to_string(),
             ));
         }
-        if self.provider.model.trim().is_empty() {
+        if self.model.trim().is_empty() {
             return Err(MosaicError::Validation(
                 "provider.model cannot be empty".to_string(),
             ));
         }
+        if self
+            .max_retries
+            .is_some_and(|retries| retries > MAX_PROVIDER_RETRIES)
+        {
+            return Err(MosaicError::Validation(format!(
+                "provider.max_retries must be at most {MAX_PROVIDER_RETRIES}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn interpolate_with(&mut self, lookup: &impl Fn(&str) -> Option<String>) -> Result<()> {
+        expand_env_field("provider.base_url", &mut self.base_url, lookup)?;
+        expand_env_field("provider.api_key_env", &mut self.api_key_env, lookup)?;
+        expand_env_field("provider.model", &mut self.model, lookup)?;
+        if let Some(api_version) = &mut self.api_version {
+            expand_env_field("provider.api_version", api_version, lookup)?;
+        }
+        Ok(())
+    }
+}
+
+impl ProfileConfig {
+    /// Fails when the profile takes its provider from `provider_ref`, where
+    /// edits to the inline `provider` settings would have no effect.
+    pub fn ensure_inline_provider(&self) -> Result<()> {
+        match self.provider_ref.as_deref() {
+            Some(name) => Err(MosaicError::Validation(format!(
+                "profile uses provider_ref '{name}'; edit providers.{name} in the config file instead"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.provider.validate()?;
         if !(0.0..=2.0).contains(&self.agent.temperature) {
             return Err(MosaicError::Validation(
                 "agent.temperature must be in [0.0, 2.0]".to_string(),
@@ -208,8 +568,172 @@ impl ProfileConfig {
                 "agent.max_turns must be greater than 0".to_string(),
             ));
         }
+        if self.agent.max_tokens == Some(0) {
+            return Err(MosaicError::Validation(
+                "agent.max_tokens must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(top_p) = self.agent.top_p
+            && !(top_p > 0.0 && top_p <= 1.0)
+        {
+            return Err(MosaicError::Validation(
+                "agent.top_p must be in (0.0, 1.0]".to_string(),
+            ));
+        }
+        if let Some(budget) = self.agent.context_budget
+            && (budget.max_messages == Some(0) || budget.max_chars == Some(0))
+        {
+            return Err(MosaicError::Validation(
+                "agent.context_budget limits must be greater than 0".to_string(),
+            ));
+        }
+        if self.agent.stop.iter().any(|stop| stop.is_empty()) {
+            return Err(MosaicError::Validation(
+                "agent.stop cannot contain empty sequences".to_string(),
+            ));
+        }
+        if let Some(allowed) = &self.tools.allowed
+            && allowed.iter().any(|tool| tool.trim().is_empty())
+        {
+            return Err(MosaicError::Validation(
+                "tools.allowed cannot contain empty tool names".to_string(),
+            ));
+        }
         Ok(())
     }
+
+    /// Expands `${VAR}` and `${VAR:-default}` in every string field using the
+    /// process environment. `$$` yields a literal `$`.
+    pub fn interpolate_env(&mut self) -> Result<()> {
+        self.interpolate_with(|name| std::env::var(name).ok())
+    }
+
+    fn interpolate_with(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        self.provider.interpolate_with(&lookup)?;
+        for stop in &mut self.agent.stop {
+            expand_env_field("agent.stop", stop, &lookup)?;
+        }
+        for tool in self.tools.allowed.iter_mut().flatten() {
+            expand_env_field("tools.allowed", tool, &lookup)?;
+        }
+        Ok(())
+    }
+}
+
+fn expand_env_field(
+    field: &str,
+    value: &mut String,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<()> {
+    *value = interpolate_env_tokens(value, lookup)
+        .map_err(|err| MosaicError::Config(format!("{field}: {err}")))?;
+    Ok(())
+}
+
+/// Expands environment tokens in `raw`. Unset variables without a default are
+/// errors; a `$` not followed by `{` or `$` is kept as-is.
+fn interpolate_env_tokens(
+    raw: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(idx) = rest.find('$') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let Some(after) = rest.strip_prefix("${") else {
+            out.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated '${{' in '{raw}'"))?;
+        let token = &after[..end];
+        let (name, default) = match token.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (token, None),
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            return Err(format!("invalid environment variable name '{name}'"));
+        }
+        match (lookup(name).filter(|value| !value.is_empty()), default) {
+            (Some(value), _) => out.push_str(&value),
+            (None, Some(default)) => out.push_str(default),
+            (None, None) => {
+                return Err(format!("environment variable '{name}' is not set"));
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn profile_fields(profile: &ProfileConfig) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    if let Ok(value) = serde_json::to_value(profile) {
+        flatten_config_value("", &value, &mut fields);
+    }
+    fields
+}
+
+/// Flattens nested objects into dotted keys (`provider.model`). Floats are
+/// normalized through f32 so `0.2` does not render as `0.20000000298023224`.
+fn flatten_config_value(prefix: &str, value: &Value, fields: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_config_value(&path, child, fields);
+            }
+        }
+        Value::Number(number) if number.is_f64() => {
+            let normalized = number
+                .as_f64()
+                .and_then(|raw| (raw as f32).to_string().parse::<f64>().ok())
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .unwrap_or_else(|| value.clone());
+            fields.insert(prefix.to_string(), normalized);
+        }
+        _ => {
+            fields.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn diff_fields(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+) -> Vec<ConfigFieldChange> {
+    let mut keys = before.keys().chain(after.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let lhs = before.get(key);
+            let rhs = after.get(key);
+            (lhs != rhs).then(|| ConfigFieldChange {
+                field: key.clone(),
+                before: lhs.cloned(),
+                after: rhs.cloned(),
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -230,7 +754,23 @@ impl ConfigManager {
         self.path.exists()
     }
 
+    /// Loads and validates the config. `${VAR}` tokens are expanded lazily by
+    /// [`ConfigFile::resolve_profile`], for the selected profile only.
     pub fn load(&self) -> Result<ConfigFile> {
+        self.load_raw()
+    }
+
+    /// Loads the config exactly as written, keeping `${VAR}` tokens. Use this
+    /// for read-modify-write so saving does not bake in environment values.
+    pub fn load_raw(&self) -> Result<ConfigFile> {
+        let parsed = self.load_unvalidated()?;
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    /// Parses the config file without validating or interpolating it, for
+    /// callers that report problems rather than fail on them.
+    pub fn load_unvalidated(&self) -> Result<ConfigFile> {
         let raw = fs::read_to_string(&self.path).map_err(|err| {
             if err.kind() == std::io::ErrorKind::NotFound {
                 MosaicError::Config(format!(
@@ -241,9 +781,7 @@ impl ConfigManager {
                 MosaicError::Io(err.to_string())
             }
         })?;
-        let parsed: ConfigFile = toml::from_str(&raw)?;
-        parsed.validate()?;
-        Ok(parsed)
+        Ok(toml::from_str(&raw)?)
     }
 
     pub fn load_or_default(&self, mode: StateMode) -> Result<ConfigFile> {
@@ -253,6 +791,13 @@ impl ConfigManager {
         Ok(ConfigFile::default_for_mode(mode))
     }
 
+    pub fn load_raw_or_default(&self, mode: StateMode) -> Result<ConfigFile> {
+        if self.exists() {
+            return self.load_raw();
+        }
+        Ok(ConfigFile::default_for_mode(mode))
+    }
+
     pub fn save(&self, config: &ConfigFile) -> Result<()> {
         config.validate()?;
         write_pretty_state_toml_file(&self.path, config, "config state")
@@ -285,6 +830,153 @@ mod tests {
         assert_eq!(loaded.state.mode, StateMode::Project);
     }
 
+    #[test]
+    fn interpolate_expands_env_tokens_and_escapes() {
+        let lookup = |name: &str| match name {
+            "MOSAIC_BASE_URL" => Some("https://staging.example".to_string()),
+            "MODEL_SUFFIX" => Some("mini".to_string()),
+            "AZURE_API_VERSION" => Some("2024-10-21".to_string()),
+            _ => None,
+        };
+        let mut profile = ProfileConfig::default();
+        profile.provider.base_url = "${MOSAIC_BASE_URL}/v1".to_string();
+        profile.provider.model = "gpt-4o-${MODEL_SUFFIX}".to_string();
+        profile.provider.api_key_env = "KEY_$$HOME".to_string();
+        profile.provider.api_version = Some("${AZURE_API_VERSION}".to_string());
+        profile.agent.stop = vec!["${STOP_TOKEN:-END}".to_string(), "$5".to_string()];
+        profile.interpolate_with(lookup).unwrap();
+        assert_eq!(profile.provider.base_url, "https://staging.example/v1");
+        assert_eq!(profile.provider.model, "gpt-4o-mini");
+        assert_eq!(profile.provider.api_key_env, "KEY_$HOME");
+        assert_eq!(profile.provider.api_version.as_deref(), Some("2024-10-21"));
+        assert_eq!(profile.agent.stop, vec!["END", "$5"]);
+    }
+
+    #[test]
+    fn interpolate_rejects_unset_variables() {
+        let mut profile = ProfileConfig::default();
+        profile.provider.model = "${MOSAIC_TEST_UNSET_MODEL}".to_string();
+        let err = profile.interpolate_with(|_| None).unwrap_err();
+        assert!(matches!(err, MosaicError::Config(_)));
+        assert!(err.to_string().contains("provider.model"));
+        assert!(err.to_string().contains("MOSAIC_TEST_UNSET_MODEL"));
+
+        profile.provider.model = "${UNTERMINATED".to_string();
+        assert!(profile.interpolate_with(|_| None).is_err());
+    }
+
+    #[test]
+    fn resolve_profile_interpolates_only_the_selected_profile() {
+        let temp = tempdir().unwrap();
+        let manager = ConfigManager::new(temp.path().join("config.toml"));
+        let mut config = ConfigFile::default_for_mode(StateMode::Project);
+        config
+            .profiles
+            .get_mut(DEFAULT_PROFILE)
+            .unwrap()
+            .provider
+            .base_url = "${MOSAIC_TEST_CONFIG_BASE_URL}".to_string();
+        let mut shared = ProfileConfig::default().provider;
+        shared.model = "${MOSAIC_TEST_SHARED_MODEL}".to_string();
+        config.providers.insert("shared".to_string(), shared);
+        config.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                provider_ref: Some("shared".to_string()),
+                ..ProfileConfig::default()
+            },
+        );
+        manager.save(&config).unwrap();
+
+        let loaded = manager.load().unwrap();
+        assert_eq!(
+            loaded.profiles[DEFAULT_PROFILE].provider.base_url,
+            "${MOSAIC_TEST_CONFIG_BASE_URL}"
+        );
+        let lookup = |name: &str| match name {
+            "MOSAIC_TEST_CONFIG_BASE_URL" => Some("mock://interpolated".to_string()),
+            _ => None,
+        };
+        let resolved = loaded.resolve_profile_with(None, lookup).unwrap();
+        assert_eq!(resolved.profile.provider.base_url, "mock://interpolated");
+
+        let err = loaded
+            .resolve_profile_with(Some("work"), lookup)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Config(_)));
+        assert!(err.to_string().contains("MOSAIC_TEST_SHARED_MODEL"));
+        let resolved = loaded
+            .resolve_profile_with(Some("work"), |name| {
+                (name == "MOSAIC_TEST_SHARED_MODEL").then(|| "shared-model".to_string())
+            })
+            .unwrap();
+        assert_eq!(resolved.profile.provider.model, "shared-model");
+        assert_eq!(
+            manager.load_raw().unwrap().providers["shared"].model,
+            "${MOSAIC_TEST_SHARED_MODEL}"
+        );
+    }
+
+    #[test]
+    fn validation_report_flags_invalid_profiles() {
+        let mut config = ConfigFile::default_for_mode(StateMode::Project);
+        let mut bad = ProfileConfig::default();
+        bad.agent.temperature = 3.5;
+        config.profiles.insert("bad".to_string(), bad);
+        assert!(config.validate().is_err());
+
+        let report = config.validation_report();
+        assert!(!report.ok);
+        assert!(report.issues.is_empty());
+        let by_name = |name: &str| {
+            report
+                .profiles
+                .iter()
+                .find(|profile| profile.name == name)
+                .unwrap()
+        };
+        assert!(by_name(DEFAULT_PROFILE).ok);
+        let bad = by_name("bad");
+        assert!(!bad.ok);
+        assert_eq!(bad.issues.len(), 1);
+        assert!(bad.issues[0].contains("agent.temperature"));
+
+        config.active_profile = "missing".to_string();
+        let report = config.validation_report();
+        assert!(report.issues[0].contains("active_profile 'missing'"));
+    }
+
+    #[test]
+    fn diff_reports_changed_profile_fields() {
+        let before = ConfigFile::default_for_mode(StateMode::Project);
+        let mut after = before.clone();
+        after
+            .profiles
+            .get_mut(DEFAULT_PROFILE)
+            .unwrap()
+            .provider
+            .model = "gpt-4.1".to_string();
+        after
+            .profiles
+            .insert("staging".to_string(), ProfileConfig::default());
+
+        assert!(before.diff(&before).is_empty());
+        let diff = before.diff(&after);
+        assert!(diff.settings.is_empty());
+        assert!(diff.removed_profiles.is_empty());
+        assert_eq!(diff.added_profiles, vec!["staging".to_string()]);
+        assert_eq!(diff.changed_profiles.len(), 1);
+        assert_eq!(diff.changed_profiles[0].name, DEFAULT_PROFILE);
+        assert_eq!(
+            diff.changed_profiles[0].changes,
+            vec![ConfigFieldChange {
+                field: "provider.model".to_string(),
+                before: Some(Value::from(DEFAULT_MODEL)),
+                after: Some(Value::from("gpt-4.1")),
+            }]
+        );
+    }
+
     #[test]
     fn resolve_profile_uses_active_by_default() {
         let mut config = ConfigFile::default_for_mode(StateMode::Xdg);
@@ -292,4 +984,87 @@ mod tests {
         let resolved = config.resolve_profile(None).unwrap();
         assert_eq!(resolved.profile_name, "default");
     }
+
+    #[test]
+    fn resolve_profile_expands_provider_ref_and_keeps_inline_providers() {
+        let mut config = ConfigFile::default_for_mode(StateMode::Xdg);
+        config.providers.insert(
+            "azure".to_string(),
+            ProviderConfig {
+                base_url: "https://example.openai.azure.com".to_string(),
+                api_key_env: "AZURE_OPENAI_API_KEY".to_string(),
+                model: "gpt-4o".to_string(),
+                ..ProviderConfig::default()
+            },
+        );
+        config.copy_profile(DEFAULT_PROFILE, "work").unwrap();
+        config.profiles.get_mut("work").unwrap().provider_ref = Some("azure".to_string());
+
+        let temp = tempdir().unwrap();
+        let manager = ConfigManager::new(temp.path().join("config.toml"));
+        manager.save(&config).unwrap();
+        let raw = std::fs::read_to_string(manager.path()).unwrap();
+        assert!(!raw.contains("[profiles.work.provider]"));
+        assert!(raw.contains("[profiles.default.provider]"));
+        let loaded = manager.load().unwrap();
+        assert!(
+            loaded.profiles["work"]
+                .ensure_inline_provider()
+                .unwrap_err()
+                .to_string()
+                .contains("edit providers.azure")
+        );
+        assert!(
+            loaded.profiles[DEFAULT_PROFILE]
+                .ensure_inline_provider()
+                .is_ok()
+        );
+
+        let work = loaded.resolve_profile(Some("work")).unwrap();
+        assert_eq!(
+            work.profile.provider.base_url,
+            "https://example.openai.azure.com"
+        );
+        assert_eq!(work.profile.provider.api_key_env, "AZURE_OPENAI_API_KEY");
+        assert_eq!(work.profile.provider.model, "gpt-4o");
+
+        let inline = loaded.resolve_profile(None).unwrap();
+        assert_eq!(inline.profile.provider.model, DEFAULT_MODEL);
+        assert!(inline.profile.provider_ref.is_none());
+    }
+
+    #[test]
+    fn unknown_provider_ref_fails_resolution_and_validation() {
+        let mut config = ConfigFile::default_for_mode(StateMode::Xdg);
+        config
+            .profiles
+            .get_mut(DEFAULT_PROFILE)
+            .unwrap()
+            .provider_ref = Some("missing".to_string());
+
+        let err = config.resolve_profile(None).unwrap_err();
+        assert!(matches!(err, MosaicError::Config(_)));
+        assert!(err.to_string().contains("provider_ref 'missing'"));
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("provider_ref 'missing'"));
+    }
+
+    #[test]
+    fn copy_profile_creates_independent_clone() {
+        let mut config = ConfigFile::default_for_mode(StateMode::Xdg);
+        config.copy_profile(DEFAULT_PROFILE, "staging").unwrap();
+        assert_eq!(config.active_profile, DEFAULT_PROFILE);
+
+        let staging = config.profiles.get_mut("staging").unwrap();
+        staging.provider.model = "gpt-4.1".to_string();
+        staging.agent.stop.push("END".to_string());
+        let source = &config.profiles[DEFAULT_PROFILE];
+        assert_eq!(source.provider.model, DEFAULT_MODEL);
+        assert!(source.agent.stop.is_empty());
+
+        let err = config.copy_profile(DEFAULT_PROFILE, "staging").unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        let err = config.copy_profile("missing", "other").unwrap_err();
+        assert!(matches!(err, MosaicError::Config(_)));
+    }
 }
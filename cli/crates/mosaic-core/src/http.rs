@@ -0,0 +1,165 @@
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+
+use crate::error::{MosaicError, Result};
+
+/// Proxy for every scheme; when set, the standard variables are ignored.
+pub const MOSAIC_PROXY_ENV: &str = "MOSAIC_HTTP_PROXY";
+/// Proxy for `https://` requests; the first non-empty one wins.
+pub const HTTPS_PROXY_ENVS: [&str; 2] = ["HTTPS_PROXY", "https_proxy"];
+/// Proxy for `http://` requests; the first non-empty one wins.
+pub const HTTP_PROXY_ENVS: [&str; 2] = ["HTTP_PROXY", "http_proxy"];
+/// Hosts that never go through a proxy, on top of `NO_PROXY`.
+const LOOPBACK_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyScope {
+    All,
+    Https,
+    Http,
+}
+
+/// Routes requests from `builder` through [`MOSAIC_PROXY_ENV`], or else through
+/// [`HTTPS_PROXY_ENVS`] and [`HTTP_PROXY_ENVS`] per scheme. Loopback hosts and
+/// hosts in `NO_PROXY` bypass the proxy. Leaves the builder unchanged when no
+/// proxy is configured.
+pub fn with_env_proxy(builder: ClientBuilder) -> Result<ClientBuilder> {
+    with_proxy_from(builder, |name| std::env::var(name).ok())
+}
+
+/// Same as [`with_env_proxy`] with variables read through `lookup`.
+pub fn with_proxy_from(
+    builder: ClientBuilder,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<ClientBuilder> {
+    let mut builder = builder;
+    for (scope, name, url) in configured_proxies(&lookup) {
+        let proxy = match scope {
+            ProxyScope::All => Proxy::all(url.as_str()),
+            ProxyScope::Https => Proxy::https(url.as_str()),
+            ProxyScope::Http => Proxy::http(url.as_str()),
+        }
+        .map_err(|err| MosaicError::Network(format!("invalid proxy URL in {name} '{url}': {err}")))?
+        .no_proxy(NoProxy::from_string(&no_proxy_list(&lookup)));
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+fn configured_proxies(
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Vec<(ProxyScope, &'static str, String)> {
+    let first = |names: &[&'static str]| {
+        names.iter().find_map(|name| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|value| (*name, value))
+        })
+    };
+    if let Some((name, url)) = first(&[MOSAIC_PROXY_ENV]) {
+        return vec![(ProxyScope::All, name, url)];
+    }
+    let mut proxies = Vec::new();
+    if let Some((name, url)) = first(&HTTPS_PROXY_ENVS) {
+        proxies.push((ProxyScope::Https, name, url));
+    }
+    if let Some((name, url)) = first(&HTTP_PROXY_ENVS) {
+        proxies.push((ProxyScope::Http, name, url));
+    }
+    proxies
+}
+
+fn no_proxy_list(lookup: &impl Fn(&str) -> Option<String>) -> String {
+    match lookup("NO_PROXY")
+        .or_else(|| lookup("no_proxy"))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        Some(extra) => format!("{LOOPBACK_HOSTS},{extra}"),
+        None => LOOPBACK_HOSTS.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use reqwest::Client;
+
+    use super::*;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn proxy_env_builds_client_and_rejects_bad_urls() {
+        let builder = with_proxy_from(
+            Client::builder(),
+            lookup(&[
+                ("HTTPS_PROXY", "http://proxy.internal:3128"),
+                ("NO_PROXY", "localhost,127.0.0.1"),
+            ]),
+        )
+        .expect("valid proxy");
+        builder.build().expect("client with proxy");
+
+        let err = with_proxy_from(
+            Client::builder(),
+            lookup(&[
+                ("MOSAIC_HTTP_PROXY", "http://[::1"),
+                ("HTTPS_PROXY", "http://proxy.internal:3128"),
+            ]),
+        )
+        .map(|_| ())
+        .expect_err("malformed proxy");
+        assert!(matches!(err, MosaicError::Network(_)));
+        assert!(err.to_string().contains("MOSAIC_HTTP_PROXY"));
+
+        with_proxy_from(Client::builder(), lookup(&[("HTTPS_PROXY", "  ")]))
+            .expect("blank proxy is ignored")
+            .build()
+            .expect("client without proxy");
+    }
+
+    #[test]
+    fn proxies_are_scoped_by_variable() {
+        let scopes = |vars: &[(&str, &str)]| {
+            configured_proxies(&lookup(vars))
+                .into_iter()
+                .map(|(scope, name, _)| (scope, name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            scopes(&[
+                ("https_proxy", "http://secure.internal:3128"),
+                ("HTTP_PROXY", "http://plain.internal:3128"),
+            ]),
+            vec![
+                (ProxyScope::Https, "https_proxy"),
+                (ProxyScope::Http, "HTTP_PROXY")
+            ]
+        );
+        assert_eq!(
+            scopes(&[
+                ("MOSAIC_HTTP_PROXY", "http://mosaic.internal:3128"),
+                ("HTTPS_PROXY", "http://secure.internal:3128"),
+            ]),
+            vec![(ProxyScope::All, "MOSAIC_HTTP_PROXY")]
+        );
+        assert!(scopes(&[]).is_empty());
+    }
+
+    #[test]
+    fn loopback_always_bypasses_the_proxy() {
+        assert_eq!(no_proxy_list(&lookup(&[])), "localhost,127.0.0.1,::1");
+        assert_eq!(
+            no_proxy_list(&lookup(&[("no_proxy", "corp.internal")])),
+            "localhost,127.0.0.1,::1,corp.internal"
+        );
+    }
+}
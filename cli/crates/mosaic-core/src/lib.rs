@@ -1,6 +1,7 @@
 pub mod audit;
 pub mod config;
 pub mod error;
+pub mod http;
 pub mod models;
 pub mod privacy;
 pub mod provider;
@@ -7,6 +7,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::error::{MosaicError, Result};
+use crate::state::write_atomic;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct SanitizationSummary {
@@ -105,14 +106,10 @@ pub fn write_pretty_state_json_file<T: Serialize>(
         MosaicError::Validation(format!("failed to encode {context} JSON value: {err}"))
     })?;
     validate_value_for_state_persistence(&encoded, context)?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
     let raw = serde_json::to_string_pretty(value).map_err(|err| {
         MosaicError::Validation(format!("failed to encode {context} JSON: {err}"))
     })?;
-    std::fs::write(path, raw)?;
-    Ok(())
+    write_atomic(path, raw)
 }
 
 pub fn write_pretty_state_toml_file<T: Serialize>(
@@ -124,14 +121,10 @@ pub fn write_pretty_state_toml_file<T: Serialize>(
         MosaicError::Validation(format!("failed to encode {context} TOML value: {err}"))
     })?;
     validate_value_for_state_persistence(&encoded, context)?;
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
     let raw = toml::to_string_pretty(value).map_err(|err| {
         MosaicError::Validation(format!("failed to encode {context} TOML: {err}"))
     })?;
-    std::fs::write(path, raw)?;
-    Ok(())
+    write_atomic(path, raw)
 }
 
 fn sanitize_value_recursive(
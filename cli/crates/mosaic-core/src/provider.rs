@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::error::{MosaicError, Result};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -22,17 +22,82 @@ pub struct ChatRequest {
     pub model: String,
     pub temperature: f32,
     pub messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Function schemas offered for native tool calling; empty disables it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema for the tool arguments.
+    pub parameters: serde_json::Value,
+}
+
+/// A structured tool call returned by providers that support native calling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NativeToolCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub arguments: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<NativeToolCall>,
+    /// Model that produced the reply, when it may differ from the requested one
+    /// (for example after a fallback).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn accumulate(&mut self, other: &TokenUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub owned_by: Option<String>,
+    #[serde(default)]
+    pub context_window: Option<u32>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    /// Fields of the provider entry that could not be parsed and were dropped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub inputs: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,9 +107,34 @@ pub struct ProviderHealth {
     pub detail: String,
 }
 
+/// Receives incremental reply text from [`Provider::chat_stream`].
+pub type ChatChunkSink<'a> = dyn FnMut(&str) + Send + 'a;
+
 #[async_trait]
 pub trait Provider: Send + Sync {
     async fn list_models(&self) -> Result<Vec<ModelInfo>>;
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse>;
+
+    /// Streams the reply through `on_chunk` as it arrives and returns the full
+    /// response. Providers without streaming support deliver it as one chunk.
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_chunk: &mut ChatChunkSink<'_>,
+    ) -> Result<ChatResponse> {
+        let response = self.chat(request).await?;
+        on_chunk(&response.content);
+        Ok(response)
+    }
+
     async fn health(&self) -> Result<ProviderHealth>;
+
+    /// Returns one vector per input, in input order. Providers without an
+    /// embeddings endpoint report a validation error so callers can fall back.
+    async fn embed(&self, request: EmbeddingRequest) -> Result<Vec<Vec<f32>>> {
+        let _ = request;
+        Err(MosaicError::Validation(
+            "provider does not support embeddings".to_string(),
+        ))
+    }
 }
@@ -10,6 +10,10 @@ use uuid::Uuid;
 
 use crate::error::{MosaicError, Result};
 use crate::privacy::append_sanitized_jsonl;
+use crate::state::write_atomic;
+
+const DEFAULT_TITLE_MAX_CHARS: usize = 40;
+const SEARCH_SNIPPET_CONTEXT_CHARS: usize = 40;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,6 +49,18 @@ pub struct SessionSummary {
     pub runtime: Option<SessionRuntimeMetadata>,
 }
 
+/// A session whose user or assistant messages contain a search query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMatch {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub hits: usize,
+    /// Text around the first occurrence of the query.
+    pub snippet: String,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SessionRuntimeMetadata {
     pub agent_id: Option<String>,
@@ -126,6 +142,51 @@ impl SessionStore {
         Ok(sessions)
     }
 
+    /// Case-insensitive substring search over user and assistant message text.
+    /// Sessions are ordered by hit count, then by most recent activity.
+    pub fn search(&self, query: &str) -> Result<Vec<SessionMatch>> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Err(MosaicError::Validation(
+                "search query cannot be empty".to_string(),
+            ));
+        }
+        let mut matches = Vec::new();
+        for summary in self.list_sessions()? {
+            let events = self.read_events(&summary.session_id)?;
+            let mut hits = 0usize;
+            let mut snippet = None;
+            for event in &events {
+                if !matches!(event.kind, EventKind::User | EventKind::Assistant) {
+                    continue;
+                }
+                let Some(text) = event.payload.get("text").and_then(Value::as_str) else {
+                    continue;
+                };
+                let haystack = text.to_lowercase();
+                let count = haystack.matches(&needle).count();
+                if count == 0 {
+                    continue;
+                }
+                hits += count;
+                if snippet.is_none() {
+                    snippet = Some(search_snippet(text, &needle));
+                }
+            }
+            if let Some(snippet) = snippet {
+                matches.push(SessionMatch {
+                    session_id: summary.session_id,
+                    title: summary.title,
+                    hits,
+                    snippet,
+                    last_updated: summary.last_updated,
+                });
+            }
+        }
+        matches.sort_by_key(|item| (Reverse(item.hits), Reverse(item.last_updated)));
+        Ok(matches)
+    }
+
     pub fn latest_runtime_metadata(
         &self,
         session_id: &str,
@@ -143,6 +204,44 @@ impl SessionStore {
         Ok(sessions.first().map(|summary| summary.session_id.clone()))
     }
 
+    /// Sets the display title of an existing session. The latest title wins.
+    pub fn rename(&self, session_id: &str, title: &str) -> Result<()> {
+        if !self.session_path(session_id).exists() {
+            return Err(MosaicError::Config(format!(
+                "session '{session_id}' was not found"
+            )));
+        }
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(MosaicError::Validation(
+                "session title cannot be empty".to_string(),
+            ));
+        }
+        self.append_event(&Self::build_title_event(session_id, title))
+    }
+
+    /// Copies every event of `session_id` into a new session and returns its id.
+    /// The fork shares no state with the source afterwards.
+    pub fn fork(&self, session_id: &str) -> Result<String> {
+        let events = self.read_events(session_id)?;
+        let fork_id = self.create_session_id();
+        let mut lines = String::new();
+        for event in events {
+            let event = SessionEvent {
+                id: Uuid::new_v4().to_string(),
+                session_id: fork_id.clone(),
+                ..event
+            };
+            let line = serde_json::to_string(&event).map_err(|err| {
+                MosaicError::Validation(format!("failed to encode session event JSON: {err}"))
+            })?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        write_atomic(&self.session_path(&fork_id), lines)?;
+        Ok(fork_id)
+    }
+
     pub fn clear_session(&self, session_id: &str) -> Result<()> {
         let path = self.session_path(session_id);
         if !path.exists() {
@@ -192,21 +291,41 @@ impl SessionStore {
         )
     }
 
+    pub fn build_title_event(session_id: &str, title: &str) -> SessionEvent {
+        Self::build_event(
+            session_id,
+            EventKind::System,
+            serde_json::json!({
+                "category": "session_title",
+                "title": title,
+            }),
+        )
+    }
+
     pub fn latest_runtime_metadata_from_events(
         events: &[SessionEvent],
     ) -> Option<SessionRuntimeMetadata> {
         events.iter().rev().find_map(Self::parse_runtime_metadata)
     }
 
+    /// Latest explicit title, falling back to the first line of the first
+    /// user prompt truncated to [`DEFAULT_TITLE_MAX_CHARS`].
     fn extract_session_title(events: &[SessionEvent]) -> Option<String> {
+        if let Some(title) = events.iter().rev().find_map(Self::parse_title) {
+            return Some(title);
+        }
         events
             .iter()
             .find(|event| event.kind == EventKind::User)
             .and_then(|event| event.payload.get("text")?.as_str().map(str::to_string))
             .map(|text| {
                 let single_line = text.lines().next().unwrap_or("").trim().to_string();
-                if single_line.chars().count() > 60 {
-                    format!("{}…", single_line.chars().take(60).collect::<String>())
+                if single_line.chars().count() > DEFAULT_TITLE_MAX_CHARS {
+                    let truncated = single_line
+                        .chars()
+                        .take(DEFAULT_TITLE_MAX_CHARS)
+                        .collect::<String>();
+                    format!("{}…", truncated.trim_end())
                 } else {
                     single_line
                 }
@@ -214,6 +333,17 @@ impl SessionStore {
             .filter(|s| !s.is_empty())
     }
 
+    fn parse_title(event: &SessionEvent) -> Option<String> {
+        if event.kind != EventKind::System {
+            return None;
+        }
+        if event.payload.get("category")?.as_str()? != "session_title" {
+            return None;
+        }
+        let title = event.payload.get("title")?.as_str()?.trim();
+        (!title.is_empty()).then(|| title.to_string())
+    }
+
     fn read_events_from_path(path: &Path) -> Result<Vec<SessionEvent>> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
@@ -256,6 +386,43 @@ impl SessionStore {
     }
 }
 
+/// Single-line excerpt of `text` centred on the first match of `needle`.
+/// `haystack` is the lowercased `text`; offsets are mapped back by char count
+/// because lowercasing can change byte lengths.
+fn search_snippet(text: &str, needle: &str) -> String {
+    // Lowercasing can change how many chars (and bytes) a character takes, so
+    // keep the original char index for every lowercased char and map the match
+    // back to the original text.
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut haystack = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(chars.len());
+    for (index, ch) in chars.iter().enumerate() {
+        for lower in ch.to_lowercase() {
+            haystack.push(lower);
+            origin.push(index);
+        }
+    }
+    let (start_char, end_char) = match haystack.find(needle) {
+        Some(match_start) => {
+            let first = haystack[..match_start].chars().count();
+            let last = first + needle.chars().count().max(1) - 1;
+            (origin[first], origin[last.min(origin.len() - 1)] + 1)
+        }
+        None => (0, 0),
+    };
+    let from = start_char.saturating_sub(SEARCH_SNIPPET_CONTEXT_CHARS);
+    let to = (end_char + SEARCH_SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let excerpt = chars[from.min(chars.len())..to]
+        .iter()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prefix = if from > 0 { "…" } else { "" };
+    let suffix = if to < chars.len() { "…" } else { "" };
+    format!("{prefix}{excerpt}{suffix}")
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -278,6 +445,46 @@ mod tests {
         assert_eq!(events[1].kind, EventKind::Assistant);
     }
 
+    #[test]
+    fn fork_copies_events_into_independent_session() {
+        let temp = tempdir().unwrap();
+        let store = SessionStore::new(temp.path().join("sessions"));
+        let sid = store.create_session_id();
+        store
+            .append_event(&SessionStore::build_event(
+                &sid,
+                EventKind::User,
+                json!({ "text": "hello" }),
+            ))
+            .unwrap();
+        store
+            .append_event(&SessionStore::build_event(
+                &sid,
+                EventKind::Assistant,
+                json!({ "text": "hi" }),
+            ))
+            .unwrap();
+
+        let fork_id = store.fork(&sid).unwrap();
+        assert_ne!(fork_id, sid);
+        store
+            .append_event(&SessionStore::build_event(
+                &fork_id,
+                EventKind::User,
+                json!({ "text": "alternative" }),
+            ))
+            .unwrap();
+
+        let original = store.read_events(&sid).unwrap();
+        let forked = store.read_events(&fork_id).unwrap();
+        assert_eq!(original.len(), 2);
+        assert_eq!(forked.len(), 3);
+        assert!(forked.iter().all(|event| event.session_id == fork_id));
+        assert_eq!(forked[1].payload, original[1].payload);
+        assert_ne!(forked[0].id, original[0].id);
+        assert!(store.fork("missing").is_err());
+    }
+
     #[test]
     fn clear_all_sessions() {
         let temp = tempdir().unwrap();
@@ -377,6 +584,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn list_sessions_reports_default_and_renamed_titles() {
+        let temp = tempdir().unwrap();
+        let store = SessionStore::new(temp.path().join("sessions"));
+        let sid = store.create_session_id();
+        store
+            .append_event(&SessionStore::build_event(
+                &sid,
+                EventKind::User,
+                json!({ "text": "Explain how retry backoff works in the gateway client\nthanks" }),
+            ))
+            .unwrap();
+
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(
+            sessions[0].title.as_deref(),
+            Some("Explain how retry backoff works in the g…")
+        );
+
+        store.rename(&sid, "  Gateway retries  ").unwrap();
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(sessions[0].title.as_deref(), Some("Gateway retries"));
+
+        store.rename(&sid, "Backoff notes").unwrap();
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(sessions[0].title.as_deref(), Some("Backoff notes"));
+
+        assert!(store.rename(&sid, "   ").is_err());
+        assert!(store.rename("missing", "title").is_err());
+    }
+
+    #[test]
+    fn search_snippet_maps_match_back_to_original_text() {
+        // 'İ' lowercases to two chars, which shifts offsets in the lowercased text.
+        let text = format!("{} Retry Backoff tail", "İ".repeat(60));
+        let snippet = search_snippet(&text, "retry backoff");
+        assert!(snippet.contains("Retry Backoff tail"), "{snippet}");
+        assert!(snippet.starts_with('…'));
+        assert_eq!(search_snippet("ÄÖÜ retry", "retry"), "ÄÖÜ retry");
+    }
+
+    #[test]
+    fn search_finds_sessions_containing_query() {
+        let temp = tempdir().unwrap();
+        let store = SessionStore::new(temp.path().join("sessions"));
+        let retry = store.create_session_id();
+        let other = store.create_session_id();
+        for (sid, kind, text) in [
+            (&retry, EventKind::User, "How should Retry Backoff work?"),
+            (
+                &retry,
+                EventKind::Assistant,
+                "Use exponential retry backoff with a cap.",
+            ),
+            (&retry, EventKind::ToolResult, "retry backoff"),
+            (&other, EventKind::User, "Summarize the changelog"),
+        ] {
+            store
+                .append_event(&SessionStore::build_event(
+                    sid,
+                    kind,
+                    json!({ "text": text }),
+                ))
+                .unwrap();
+        }
+
+        let matches = store.search("retry backoff").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].session_id, retry);
+        assert_eq!(matches[0].hits, 2);
+        assert_eq!(matches[0].snippet, "How should Retry Backoff work?");
+        assert!(store.search("missing term").unwrap().is_empty());
+        assert!(store.search("  ").is_err());
+    }
+
     #[test]
     fn append_event_redacts_secret_like_payload_before_persist() {
         let temp = tempdir().unwrap();
@@ -1,10 +1,22 @@
-use std::fs;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
 use crate::error::{MosaicError, Result};
 
+/// Written into a state root that mosaic created itself, so `uninstall` can
+/// tell it apart from an existing directory that was reused as state.
+pub const STATE_MARKER_FILE: &str = ".mosaic-state";
+const STATE_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const STATE_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+static ATOMIC_WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum StateMode {
@@ -26,13 +38,17 @@ pub struct StatePaths {
     pub sessions_dir: PathBuf,
     pub audit_dir: PathBuf,
     pub audit_log_path: PathBuf,
+    pub approvals_audit_path: PathBuf,
 }
 
 impl StatePaths {
     pub fn resolve(mode: StateMode, cwd: &Path, project_dir_name: &str) -> Result<Self> {
         match mode {
             StateMode::Xdg => Self::resolve_xdg(),
-            StateMode::Project => Ok(Self::resolve_project(cwd, project_dir_name)),
+            StateMode::Project => {
+                validate_project_dir_name(project_dir_name)?;
+                Ok(Self::resolve_project(cwd, project_dir_name))
+            }
         }
     }
 
@@ -55,6 +71,7 @@ impl StatePaths {
         let sessions_dir = data_dir.join("sessions");
         let audit_dir = data_dir.join("audit");
         let audit_log_path = audit_dir.join("commands.jsonl");
+        let approvals_audit_path = audit_dir.join("approvals.jsonl");
 
         Ok(Self {
             mode: StateMode::Xdg,
@@ -69,12 +86,27 @@ impl StatePaths {
             sessions_dir,
             audit_dir,
             audit_log_path,
+            approvals_audit_path,
         })
     }
 
     fn resolve_project(cwd: &Path, project_dir_name: &str) -> Self {
         let root_dir = cwd.join(project_dir_name);
         let config_path = root_dir.join("config.toml");
+        Self::rooted(StateMode::Project, root_dir, config_path)
+    }
+
+    /// Uses `config_path` as the config file and keeps every other state file
+    /// beside it, laid out like project state (`data/`, `policy/`, `models.toml`).
+    pub fn for_config_file(mode: StateMode, config_path: &Path) -> Self {
+        let root_dir = config_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Self::rooted(mode, root_dir, config_path.to_path_buf())
+    }
+
+    fn rooted(mode: StateMode, root_dir: PathBuf, config_path: PathBuf) -> Self {
         let models_path = root_dir.join("models.toml");
         let data_dir = root_dir.join("data");
         let policy_dir = root_dir.join("policy");
@@ -84,9 +116,10 @@ impl StatePaths {
         let sessions_dir = data_dir.join("sessions");
         let audit_dir = data_dir.join("audit");
         let audit_log_path = audit_dir.join("commands.jsonl");
+        let approvals_audit_path = audit_dir.join("approvals.jsonl");
 
         Self {
-            mode: StateMode::Project,
+            mode,
             root_dir,
             config_path,
             models_path,
@@ -98,10 +131,12 @@ impl StatePaths {
             sessions_dir,
             audit_dir,
             audit_log_path,
+            approvals_audit_path,
         }
     }
 
     pub fn ensure_dirs(&self) -> Result<()> {
+        let created_root = !self.root_dir.exists();
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent)?;
         }
@@ -109,6 +144,10 @@ impl StatePaths {
         fs::create_dir_all(&self.policy_dir)?;
         fs::create_dir_all(&self.sessions_dir)?;
         fs::create_dir_all(&self.audit_dir)?;
+        if created_root {
+            fs::create_dir_all(&self.root_dir)?;
+            fs::write(self.root_dir.join(STATE_MARKER_FILE), b"")?;
+        }
         Ok(())
     }
 
@@ -121,6 +160,123 @@ impl StatePaths {
     }
 }
 
+/// Project state lives directly under the workspace, so the directory name must be a
+/// single relative path component.
+pub fn validate_project_dir_name(name: &str) -> Result<()> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(MosaicError::Config(
+            "project state directory name cannot be empty".to_string(),
+        ));
+    }
+    if trimmed != name
+        || name == "."
+        || name == ".."
+        || name.contains('/')
+        || name.contains('\\')
+        || Path::new(name).is_absolute()
+    {
+        return Err(MosaicError::Config(format!(
+            "invalid project state directory name '{name}': expected a simple relative directory name"
+        )));
+    }
+    Ok(())
+}
+
+/// Exclusive advisory lock on a state file, held across a load/modify/save
+/// sequence so concurrent `mosaic` processes cannot drop each other's updates.
+/// Released on drop.
+#[derive(Debug)]
+pub struct StateFileLock {
+    _file: File,
+}
+
+/// Locks `path` via a sibling `<file>.lock`, waiting up to five seconds.
+pub fn lock_state_file(path: &Path) -> Result<StateFileLock> {
+    lock_state_file_with_timeout(path, STATE_LOCK_TIMEOUT)
+}
+
+pub fn lock_state_file_with_timeout(path: &Path, timeout: Duration) -> Result<StateFileLock> {
+    let lock_path = state_lock_path(path);
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(StateFileLock { _file: file }),
+            Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                thread::sleep(STATE_LOCK_POLL_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(MosaicError::Io(format!(
+                    "timed out after {}ms waiting for lock on {}; another mosaic command may be writing it",
+                    timeout.as_millis(),
+                    path.display()
+                )));
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(MosaicError::Io(format!(
+                    "failed to lock {}: {err}",
+                    lock_path.display()
+                )));
+            }
+        }
+    }
+}
+
+fn state_lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Replaces `path` with `bytes` so that readers and crashes only ever observe
+/// the old or the new contents: the data goes to a temp file in the same
+/// directory, is fsynced, then renamed over the target.
+pub fn write_atomic(path: &Path, bytes: impl AsRef<[u8]>) -> Result<()> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let temp_path = atomic_temp_path(path);
+    let written = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(bytes.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(MosaicError::Io(format!(
+            "failed to write {}: {err}",
+            path.display()
+        )));
+    }
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(parent) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+fn atomic_temp_path(path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        ATOMIC_WRITE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
@@ -134,4 +290,76 @@ mod tests {
         assert!(paths.config_path.ends_with(".mosaic/config.toml"));
         assert!(paths.sessions_dir.ends_with(".mosaic/data/sessions"));
     }
+
+    #[test]
+    fn project_mode_honors_custom_dir_name() {
+        let temp = tempdir().unwrap();
+        let paths = StatePaths::resolve(StateMode::Project, temp.path(), ".agent-state").unwrap();
+        assert_eq!(paths.root_dir, temp.path().join(".agent-state"));
+        assert!(paths.config_path.ends_with(".agent-state/config.toml"));
+    }
+
+    #[test]
+    fn ensure_dirs_marks_only_roots_it_creates() {
+        let temp = tempdir().unwrap();
+        let fresh = StatePaths::resolve(StateMode::Project, temp.path(), ".agent-state").unwrap();
+        fresh.ensure_dirs().unwrap();
+        assert!(fresh.root_dir.join(STATE_MARKER_FILE).is_file());
+
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        let reused = StatePaths::resolve(StateMode::Project, temp.path(), "src").unwrap();
+        reused.ensure_dirs().unwrap();
+        assert!(!reused.root_dir.join(STATE_MARKER_FILE).exists());
+    }
+
+    #[test]
+    fn project_mode_rejects_traversal_dir_names() {
+        let temp = tempdir().unwrap();
+        for name in ["", "..", "../escape", "nested/dir", "/abs"] {
+            let err = StatePaths::resolve(StateMode::Project, temp.path(), name).unwrap_err();
+            assert!(matches!(err, MosaicError::Config(_)), "{name}");
+        }
+    }
+
+    #[test]
+    fn state_file_lock_blocks_second_holder_until_dropped() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("data").join("agents.json");
+        let lock = lock_state_file(&path).unwrap();
+        assert!(temp.path().join("data").join("agents.json.lock").exists());
+
+        let err = lock_state_file_with_timeout(&path, Duration::from_millis(30)).unwrap_err();
+        assert!(matches!(err, MosaicError::Io(_)));
+        assert!(err.to_string().contains("waiting for lock"));
+
+        drop(lock);
+        lock_state_file_with_timeout(&path, Duration::from_millis(30)).unwrap();
+    }
+
+    #[test]
+    fn write_atomic_leaves_original_intact_after_partial_temp_write() {
+        let temp = tempdir().unwrap();
+        let path = temp.path().join("data").join("channels.json");
+        write_atomic(&path, br#"{"version":1,"channels":[]}"#).unwrap();
+
+        // A crash mid-write only ever truncates the temp file, never the target.
+        let partial = atomic_temp_path(&path);
+        fs::write(&partial, br#"{"version":1,"chan"#).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"{"version":1,"channels":[]}"#
+        );
+
+        write_atomic(&path, br#"{"version":1,"channels":[{"id":"a"}]}"#).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"{"version":1,"channels":[{"id":"a"}]}"#
+        );
+        let leftovers = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path() != path && entry.path() != partial)
+            .count();
+        assert_eq!(leftovers, 0);
+    }
 }
@@ -1,12 +1,31 @@
-use std::time::Instant;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeSet;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::http::with_env_proxy;
+
+mod server;
+
+pub use server::{GatewayMethodCall, GatewayMethodHandler, GatewayServer, MethodRegistry};
+
+const DEFAULT_GATEWAY_TIMEOUT: Duration = Duration::from_secs(4);
+const GATEWAY_TIMEOUT_ENV: &str = "MOSAIC_GATEWAY_TIMEOUT_MS";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GatewayRequest {
@@ -47,6 +66,13 @@ pub struct GatewayProbe {
     pub endpoint: String,
     pub latency_ms: u128,
     pub detail: String,
+    /// Requests sent before the probe succeeded, including retries.
+    #[serde(default = "default_probe_attempts")]
+    pub attempts: u32,
+}
+
+fn default_probe_attempts() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,17 +82,62 @@ pub struct GatewayDiscovery {
     pub methods: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayStatus {
+    pub service: String,
+    pub uptime_seconds: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_count: Option<usize>,
+}
+
 #[async_trait]
 pub trait GatewayClient: Send + Sync {
     async fn probe(&self) -> Result<GatewayProbe>;
     async fn discover(&self) -> Result<GatewayDiscovery>;
     async fn call(&self, request: GatewayRequest) -> Result<GatewayResponse>;
+
+    async fn status(&self) -> Result<GatewayStatus> {
+        let response = self.call(GatewayRequest::new("status", None)).await?;
+        parse_gateway_status(response.result.unwrap_or(Value::Null))
+    }
+}
+
+/// Bounded retry for transient gateway failures: connection errors and 5xx.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GatewayRetryPolicy {
+    /// Total requests per operation, including the first; 0 behaves like 1.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for GatewayRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl GatewayRetryPolicy {
+    /// Delay before retry number `retry` (0-based), doubling each time.
+    fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.min(16)))
+            .min(self.max_delay)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct HttpGatewayClient {
     base_url: String,
     client: reqwest::Client,
+    bearer_token: Option<String>,
+    retry: GatewayRetryPolicy,
 }
 
 impl HttpGatewayClient {
@@ -75,9 +146,13 @@ impl HttpGatewayClient {
         Self::with_base_url(base_url)
     }
 
+    /// Uses `MOSAIC_GATEWAY_TIMEOUT_MS` when set, otherwise a 4 second timeout.
     pub fn with_base_url(base_url: impl Into<String>) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(4))
+        Self::with_timeout(base_url, default_gateway_timeout())
+    }
+
+    pub fn with_timeout(base_url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let client = with_env_proxy(reqwest::Client::builder().timeout(timeout))?
             .build()
             .map_err(|err| {
                 MosaicError::GatewayUnavailable(format!("failed to build gateway client: {err}"))
@@ -85,21 +160,81 @@ impl HttpGatewayClient {
         Ok(Self {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             client,
+            bearer_token: None,
+            retry: GatewayRetryPolicy::default(),
         })
     }
 
+    pub fn with_retry_policy(mut self, retry: GatewayRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Builds a client that sends `Authorization: Bearer <token>`, reading the
+    /// token from the `token_env` environment variable.
+    pub fn with_auth(base_url: impl Into<String>, token_env: &str) -> Result<Self> {
+        let token = std::env::var(token_env)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                MosaicError::Auth(format!(
+                    "environment variable {token_env} is required for gateway auth"
+                ))
+            })?;
+        let mut client = Self::with_base_url(base_url)?;
+        client.bearer_token = Some(token);
+        Ok(client)
+    }
+
     fn endpoint(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 
-    async fn get_json(&self, path: &str) -> Result<(u16, Value)> {
+    /// Repeats `send` up to the retry policy's attempt limit. Requests that
+    /// never reached the gateway are always retried; timeouts and 5xx statuses
+    /// only when `idempotent`, since the gateway may already have acted on them.
+    /// Returns the last outcome together with the number of attempts made.
+    async fn send_with_retry<F, Fut>(&self, idempotent: bool, send: F) -> Result<(u16, Value, u32)>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = std::result::Result<(u16, Value), RequestFailure>>,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let outcome = send().await;
+            let retryable = match &outcome {
+                Ok((status, _)) => idempotent && *status >= 500,
+                Err(failure) => {
+                    failure.connect
+                        || (idempotent
+                            && matches!(failure.error, MosaicError::GatewayUnavailable(_)))
+                }
+            };
+            if !retryable || attempt >= max_attempts {
+                let (status, body) = outcome.map_err(|failure| failure.error)?;
+                return Ok((status, body, attempt));
+            }
+            tokio::time::sleep(self.retry.delay(attempt - 1)).await;
+            attempt += 1;
+        }
+    }
+
+    fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.bearer_token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    async fn get_json(&self, path: &str) -> std::result::Result<(u16, Value), RequestFailure> {
         let url = self.endpoint(path);
         let response = self
-            .client
-            .get(url)
+            .authorize(self.client.get(url))
             .send()
             .await
-            .map_err(map_network_error)?;
+            .map_err(RequestFailure::network)?;
         let status = response.status().as_u16();
         let body = response.text().await.map_err(|err| {
             MosaicError::GatewayProtocol(format!("failed to read gateway response body: {err}"))
@@ -114,15 +249,17 @@ impl HttpGatewayClient {
         Ok((status, parsed))
     }
 
-    async fn post_json(&self, path: &str, payload: &impl Serialize) -> Result<(u16, Value)> {
+    async fn post_json(
+        &self,
+        path: &str,
+        payload: &impl Serialize,
+    ) -> std::result::Result<(u16, Value), RequestFailure> {
         let url = self.endpoint(path);
         let response = self
-            .client
-            .post(url)
-            .json(payload)
+            .authorize(self.client.post(url).json(payload))
             .send()
             .await
-            .map_err(map_network_error)?;
+            .map_err(RequestFailure::network)?;
         let status = response.status().as_u16();
         let body = response.text().await.map_err(|err| {
             MosaicError::GatewayProtocol(format!("failed to read gateway response body: {err}"))
@@ -143,7 +280,9 @@ impl GatewayClient for HttpGatewayClient {
     async fn probe(&self) -> Result<GatewayProbe> {
         let started = Instant::now();
         let endpoint = self.endpoint("/health");
-        let (status, body) = self.get_json("/health").await?;
+        let (status, body, attempts) = self
+            .send_with_retry(true, || self.get_json("/health"))
+            .await?;
         if !(200..300).contains(&status) {
             let detail = gateway_error_detail(&body);
             return Err(MosaicError::GatewayUnavailable(format!(
@@ -160,12 +299,16 @@ impl GatewayClient for HttpGatewayClient {
                 .and_then(Value::as_str)
                 .map(|service| format!("service={service}"))
                 .unwrap_or_else(|| "gateway health endpoint reachable".to_string()),
+            attempts,
         })
     }
 
     async fn discover(&self) -> Result<GatewayDiscovery> {
         let endpoint = self.endpoint("/discover");
-        let (status, body) = self.get_json("/discover").await?;
+        let (status, body) = self
+            .get_json("/discover")
+            .await
+            .map_err(|failure| failure.error)?;
 
         if status == 404 {
             return Ok(GatewayDiscovery {
@@ -192,7 +335,9 @@ impl GatewayClient for HttpGatewayClient {
     }
 
     async fn call(&self, request: GatewayRequest) -> Result<GatewayResponse> {
-        let (status, body) = self.post_json("/call", &request).await?;
+        let (status, body, _) = self
+            .send_with_retry(false, || self.post_json("/call", &request))
+            .await?;
         if status == 404 {
             return call_legacy_method(self, &request).await;
         }
@@ -205,22 +350,228 @@ impl GatewayClient for HttpGatewayClient {
         }
 
         let response = parse_gateway_call_response(body, &request.method)?;
-        if !response.ok {
-            if let Some(err) = &response.error {
-                return Err(MosaicError::GatewayProtocol(format!(
-                    "gateway method '{}' failed: {} ({})",
-                    request.method, err.message, err.code
-                )));
-            }
-            return Err(MosaicError::GatewayProtocol(format!(
-                "gateway method '{}' failed without error payload",
-                request.method
+        ensure_call_succeeded(response, &request.method)
+    }
+}
+
+/// Gateway client speaking JSON frames over a WebSocket (`ws://`, or `wss://`
+/// verified against the webpki roots). Each call opens its own connection,
+/// sends the request and waits for the response frame whose `id` matches;
+/// unrelated frames are ignored.
+#[derive(Debug, Clone)]
+pub struct WsGatewayClient {
+    url: String,
+    timeout: Duration,
+    retry: GatewayRetryPolicy,
+}
+
+impl WsGatewayClient {
+    pub fn new(host: &str, port: u16) -> Result<Self> {
+        Self::with_url(format!("ws://{host}:{port}/ws"))
+    }
+
+    pub fn with_url(url: impl Into<String>) -> Result<Self> {
+        let url = url.into();
+        if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err(MosaicError::Validation(format!(
+                "websocket gateway url must start with ws:// or wss://, got '{url}'"
             )));
         }
-        Ok(response)
+        Ok(Self {
+            url,
+            timeout: default_gateway_timeout(),
+            retry: GatewayRetryPolicy::default(),
+        })
+    }
+
+    pub fn with_retry_policy(mut self, retry: GatewayRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    async fn round_trip(&self, request: &GatewayRequest) -> Result<Value> {
+        tokio::time::timeout(self.timeout, self.exchange(request))
+            .await
+            .map_err(|_| MosaicError::GatewayUnavailable("gateway request timed out".to_string()))?
+    }
+
+    async fn exchange(&self, request: &GatewayRequest) -> Result<Value> {
+        let connect_failed = |err: tokio_tungstenite::tungstenite::Error| {
+            MosaicError::GatewayUnavailable(format!("gateway websocket connect failed: {err}"))
+        };
+        if self.url.starts_with("wss://") {
+            let stream = self.connect_tls().await?;
+            let (socket, _) = tokio_tungstenite::client_async(self.url.as_str(), stream)
+                .await
+                .map_err(connect_failed)?;
+            return exchange_frames(socket, request).await;
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(self.url.as_str())
+            .await
+            .map_err(connect_failed)?;
+        exchange_frames(socket, request).await
+    }
+
+    async fn connect_tls(&self) -> Result<TlsStream<TcpStream>> {
+        let url = reqwest::Url::parse(&self.url).map_err(|err| {
+            MosaicError::Validation(format!("invalid gateway url '{}': {err}", self.url))
+        })?;
+        let host = url
+            .host_str()
+            .map(|host| {
+                host.trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string()
+            })
+            .ok_or_else(|| {
+                MosaicError::Validation(format!("gateway url '{}' has no host", self.url))
+            })?;
+        let port = url.port_or_known_default().unwrap_or(443);
+        let server_name = ServerName::try_from(host.clone()).map_err(|err| {
+            MosaicError::Validation(format!("invalid gateway host '{host}': {err}"))
+        })?;
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|err| {
+                    MosaicError::GatewayUnavailable(format!("failed to configure TLS: {err}"))
+                })?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        let tcp = TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(|err| {
+                MosaicError::GatewayUnavailable(format!("gateway websocket connect failed: {err}"))
+            })?;
+        TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|err| {
+                MosaicError::GatewayUnavailable(format!("gateway TLS handshake failed: {err}"))
+            })
     }
 }
 
+/// Sends `request` as one frame and returns the body of the response frame
+/// with the same `id`, without the `id` field.
+async fn exchange_frames<S>(
+    mut socket: WebSocketStream<S>,
+    request: &GatewayRequest,
+) -> Result<Value>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let frame = serde_json::to_string(request).map_err(|err| {
+        MosaicError::GatewayProtocol(format!("failed to encode gateway request: {err}"))
+    })?;
+    socket.send(Message::Text(frame)).await.map_err(|err| {
+        MosaicError::GatewayUnavailable(format!("gateway websocket send failed: {err}"))
+    })?;
+
+    while let Some(message) = socket.next().await {
+        let message = message.map_err(|err| {
+            MosaicError::GatewayUnavailable(format!("gateway websocket read failed: {err}"))
+        })?;
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Binary(bytes) => String::from_utf8(bytes).map_err(|err| {
+                MosaicError::GatewayProtocol(format!("gateway sent non-UTF-8 binary frame: {err}"))
+            })?,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let mut body = serde_json::from_str::<Value>(&text).map_err(|err| {
+            MosaicError::GatewayProtocol(format!("gateway returned invalid JSON: {err}"))
+        })?;
+        if body.get("id").and_then(Value::as_str) != Some(request.id.as_str()) {
+            continue;
+        }
+        if let Some(object) = body.as_object_mut() {
+            object.remove("id");
+        }
+        let _ = socket.close(None).await;
+        return Ok(body);
+    }
+    Err(MosaicError::GatewayUnavailable(format!(
+        "gateway websocket closed before responding to '{}'",
+        request.method
+    )))
+}
+
+#[async_trait]
+impl GatewayClient for WsGatewayClient {
+    /// Retries `health` while the gateway is unreachable, up to the retry
+    /// policy's attempt limit.
+    async fn probe(&self) -> Result<GatewayProbe> {
+        let started = Instant::now();
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempts = 1;
+        let response = loop {
+            match self.call(GatewayRequest::new("health", None)).await {
+                Err(MosaicError::GatewayUnavailable(_)) if attempts < max_attempts => {
+                    tokio::time::sleep(self.retry.delay(attempts - 1)).await;
+                    attempts += 1;
+                }
+                outcome => break outcome?,
+            }
+        };
+        let result = response.result.unwrap_or(Value::Null);
+        Ok(GatewayProbe {
+            ok: true,
+            endpoint: self.url.clone(),
+            latency_ms: started.elapsed().as_millis(),
+            detail: result
+                .get("service")
+                .and_then(Value::as_str)
+                .map(|service| format!("service={service}"))
+                .unwrap_or_else(|| "gateway websocket reachable".to_string()),
+            attempts,
+        })
+    }
+
+    async fn discover(&self) -> Result<GatewayDiscovery> {
+        let request = GatewayRequest::new("discover", None);
+        let body = self.round_trip(&request).await?;
+        let response = parse_gateway_call_response(body, &request.method)?;
+        let methods = match &response.error {
+            Some(err) if err.code == "method_not_found" => {
+                vec!["health".to_string(), "status".to_string()]
+            }
+            _ => {
+                let response = ensure_call_succeeded(response, &request.method)?;
+                parse_discovery_methods(&response.result.unwrap_or(Value::Null))?
+            }
+        };
+        Ok(GatewayDiscovery {
+            ok: true,
+            endpoint: self.url.clone(),
+            methods,
+        })
+    }
+
+    async fn call(&self, request: GatewayRequest) -> Result<GatewayResponse> {
+        let body = self.round_trip(&request).await?;
+        let response = parse_gateway_call_response(body, &request.method)?;
+        ensure_call_succeeded(response, &request.method)
+    }
+}
+
+/// Picks the gateway transport from the URL scheme: `ws://` and `wss://` use
+/// the WebSocket client, `http://` and `https://` go over HTTP.
+pub fn gateway_client_for_url(url: &str) -> Result<Box<dyn GatewayClient>> {
+    if url.starts_with("ws://") || url.starts_with("wss://") {
+        return Ok(Box::new(WsGatewayClient::with_url(url)?));
+    }
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Ok(Box::new(HttpGatewayClient::with_base_url(url)?));
+    }
+    Err(MosaicError::Validation(format!(
+        "gateway url must start with http://, https://, ws:// or wss://, got '{url}'"
+    )))
+}
+
 async fn call_legacy_method(
     client: &HttpGatewayClient,
     request: &GatewayRequest,
@@ -232,7 +583,10 @@ async fn call_legacy_method(
         )));
     };
 
-    let (status, body) = client.get_json(path).await?;
+    let (status, body) = client
+        .get_json(path)
+        .await
+        .map_err(|failure| failure.error)?;
     if !(200..300).contains(&status) {
         return Err(MosaicError::GatewayUnavailable(format!(
             "legacy gateway method '{}' failed with HTTP {status}",
@@ -255,6 +609,39 @@ fn legacy_method_path(method: &str) -> Option<&'static str> {
     }
 }
 
+fn default_gateway_timeout() -> Duration {
+    std::env::var(GATEWAY_TIMEOUT_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|millis| *millis > 0)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_GATEWAY_TIMEOUT)
+}
+
+/// A failed gateway request; `connect` is set when it never reached the server.
+struct RequestFailure {
+    error: MosaicError,
+    connect: bool,
+}
+
+impl RequestFailure {
+    fn network(err: reqwest::Error) -> Self {
+        Self {
+            connect: err.is_connect(),
+            error: map_network_error(err),
+        }
+    }
+}
+
+impl From<MosaicError> for RequestFailure {
+    fn from(error: MosaicError) -> Self {
+        Self {
+            error,
+            connect: false,
+        }
+    }
+}
+
 fn map_network_error(err: reqwest::Error) -> MosaicError {
     if err.is_timeout() {
         return MosaicError::GatewayUnavailable("gateway request timed out".to_string());
@@ -323,6 +710,27 @@ fn parse_gateway_call_response(body: Value, method: &str) -> Result<GatewayRespo
     Ok(response)
 }
 
+fn ensure_call_succeeded(response: GatewayResponse, method: &str) -> Result<GatewayResponse> {
+    if response.ok {
+        return Ok(response);
+    }
+    if let Some(err) = &response.error {
+        return Err(MosaicError::GatewayProtocol(format!(
+            "gateway method '{method}' failed: {} ({})",
+            err.message, err.code
+        )));
+    }
+    Err(MosaicError::GatewayProtocol(format!(
+        "gateway method '{method}' failed without error payload"
+    )))
+}
+
+fn parse_gateway_status(result: Value) -> Result<GatewayStatus> {
+    serde_json::from_value(result).map_err(|err| {
+        MosaicError::GatewayProtocol(format!("invalid gateway status response: {err}"))
+    })
+}
+
 fn gateway_error_detail(body: &Value) -> Option<String> {
     let object = body.as_object()?;
     if let Some(error) = object.get("error") {
@@ -369,10 +777,77 @@ fn format_gateway_detail_suffix(detail: Option<&str>) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::thread;
+
     use serde_json::json;
 
     use super::*;
 
+    fn spawn_test_server<F>(handler: F) -> String
+    where
+        F: Fn(&str, &str) -> (u16, Value) + Send + 'static,
+    {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("bind test server");
+        let addr = server.server_addr().to_ip().expect("ip addr");
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let (status, payload) = handler(request.url(), &body);
+                let response =
+                    tiny_http::Response::from_string(payload.to_string()).with_status_code(status);
+                let _ = request.respond(response);
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    /// Serves one socket per connection, answering each request frame after
+    /// first pushing an unrelated frame the client must skip.
+    async fn spawn_ws_test_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind ws server");
+        let addr = listener.local_addr().expect("ws addr");
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut socket = tokio_tungstenite::accept_async(stream)
+                        .await
+                        .expect("ws handshake");
+                    while let Some(Ok(Message::Text(text))) = socket.next().await {
+                        let request: GatewayRequest =
+                            serde_json::from_str(&text).expect("request frame");
+                        let noise = json!({ "id": "other", "ok": true, "result": null });
+                        let reply = match request.method.as_str() {
+                            "health" => json!({
+                                "id": request.id,
+                                "ok": true,
+                                "result": { "service": "mosaic-gateway" },
+                            }),
+                            "echo" => json!({
+                                "id": request.id,
+                                "ok": true,
+                                "result": { "echo": request.params },
+                            }),
+                            _ => json!({
+                                "id": request.id,
+                                "ok": false,
+                                "error": { "code": "method_not_found", "message": "unknown method" },
+                            }),
+                        };
+                        for frame in [noise, reply] {
+                            if socket.send(Message::Text(frame.to_string())).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        format!("ws://{addr}/ws")
+    }
+
     #[test]
     fn gateway_request_serializes() {
         let request = GatewayRequest::new("status", Some(json!({"verbose": true})));
@@ -415,4 +890,240 @@ mod tests {
         .expect_err("should fail");
         assert!(err.to_string().contains("returned malformed error payload"));
     }
+
+    #[tokio::test]
+    async fn status_deserializes_typed_payload() {
+        let base_url = spawn_test_server(|url, body| {
+            assert_eq!(url, "/call");
+            let request: GatewayRequest = serde_json::from_str(body).expect("request");
+            assert_eq!(request.method, "status");
+            (
+                200,
+                json!({
+                    "ok": true,
+                    "result": {
+                        "ok": true,
+                        "service": "mosaic-gateway",
+                        "uptime_seconds": 42,
+                        "version": "0.1.0",
+                        "method_count": 5,
+                    }
+                }),
+            )
+        });
+        let client = HttpGatewayClient::with_base_url(base_url).expect("client");
+        let status = client.status().await.expect("status");
+        assert_eq!(
+            status,
+            GatewayStatus {
+                service: "mosaic-gateway".to_string(),
+                uptime_seconds: 42,
+                version: Some("0.1.0".to_string()),
+                method_count: Some(5),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn ws_client_matches_responses_by_id() {
+        let url = spawn_ws_test_server().await;
+        let client = gateway_client_for_url(&url).expect("client");
+
+        let probe = client.probe().await.expect("probe");
+        assert!(probe.ok);
+        assert_eq!(probe.endpoint, url);
+        assert_eq!(probe.detail, "service=mosaic-gateway");
+
+        let response = client
+            .call(GatewayRequest::new("echo", Some(json!({ "text": "hi" }))))
+            .await
+            .expect("echo");
+        assert_eq!(response.result.expect("result")["echo"]["text"], "hi");
+
+        let discovery = client.discover().await.expect("discover");
+        assert_eq!(discovery.methods, vec!["health", "status"]);
+
+        let err = client
+            .call(GatewayRequest::new("missing", None))
+            .await
+            .expect_err("unknown method");
+        assert!(matches!(err, MosaicError::GatewayProtocol(_)));
+        assert!(err.to_string().contains("method_not_found"));
+    }
+
+    #[test]
+    fn ws_client_rejects_non_ws_url() {
+        let err = WsGatewayClient::with_url("http://127.0.0.1:1").expect_err("should fail");
+        assert!(matches!(err, MosaicError::Validation(_)));
+    }
+
+    #[test]
+    fn gateway_client_for_url_rejects_unknown_schemes() {
+        assert!(gateway_client_for_url("wss://gateway.example/ws").is_ok());
+        assert!(gateway_client_for_url("https://gateway.example").is_ok());
+        let result = gateway_client_for_url("ftp://gateway.example");
+        assert!(matches!(result, Err(MosaicError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn ws_probe_retries_until_the_gateway_accepts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind ws server");
+        let addr = listener.local_addr().expect("ws addr");
+        tokio::spawn(async move {
+            // Drop the first connection before the handshake completes.
+            let _ = listener.accept().await;
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut socket = tokio_tungstenite::accept_async(stream)
+                        .await
+                        .expect("ws handshake");
+                    while let Some(Ok(Message::Text(text))) = socket.next().await {
+                        let request: GatewayRequest =
+                            serde_json::from_str(&text).expect("request frame");
+                        let reply = json!({ "id": request.id, "ok": true, "result": {} });
+                        let _ = socket.send(Message::Text(reply.to_string())).await;
+                    }
+                });
+            }
+        });
+        let client = WsGatewayClient::with_url(format!("ws://{addr}/ws"))
+            .expect("client")
+            .with_retry_policy(GatewayRetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(1),
+            });
+
+        let probe = client.probe().await.expect("probe");
+        assert_eq!(probe.attempts, 2);
+        assert_eq!(probe.detail, "gateway websocket reachable");
+    }
+
+    #[tokio::test]
+    async fn with_auth_sends_bearer_token() {
+        let env = "MOSAIC_TEST_GATEWAY_AUTH_TOKEN";
+        // SAFETY: the variable name is unique to this test and nothing else reads it.
+        unsafe { std::env::set_var(env, "secret-token") };
+
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("bind test server");
+        let addr = server.server_addr().to_ip().expect("ip addr");
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let header = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Authorization"))
+                    .map(|header| header.value.to_string());
+                captured.lock().expect("lock").push(header);
+                let body = json!({ "ok": true, "service": "mosaic-gateway" }).to_string();
+                let _ = request.respond(tiny_http::Response::from_string(body));
+            }
+        });
+
+        let client =
+            HttpGatewayClient::with_auth(format!("http://{addr}"), env).expect("auth client");
+        client.probe().await.expect("probe");
+        client
+            .call(GatewayRequest::new("status", None))
+            .await
+            .expect("call");
+        let seen = seen.lock().expect("lock");
+        assert_eq!(seen.len(), 2);
+        assert!(
+            seen.iter()
+                .all(|header| header.as_deref() == Some("Bearer secret-token"))
+        );
+    }
+
+    #[tokio::test]
+    async fn with_timeout_reports_slow_gateway_as_unavailable() {
+        let base_url = spawn_test_server(|_, _| {
+            thread::sleep(Duration::from_millis(500));
+            (200, json!({ "ok": true, "service": "mosaic-gateway" }))
+        });
+        let client = HttpGatewayClient::with_timeout(base_url, Duration::from_millis(50))
+            .expect("client")
+            .with_retry_policy(GatewayRetryPolicy {
+                max_attempts: 1,
+                ..GatewayRetryPolicy::default()
+            });
+        let err = client.probe().await.expect_err("should time out");
+        assert!(matches!(err, MosaicError::GatewayUnavailable(_)));
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn probe_retries_transient_failures_but_call_does_not() {
+        let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hits.clone();
+        let base_url = spawn_test_server(move |url, _| {
+            let count = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            match (url, count) {
+                (_, 0) | (_, 2) => (503, json!({ "ok": false, "error": "warming up" })),
+                ("/health", _) => (200, json!({ "ok": true, "service": "mosaic-gateway" })),
+                ("/call", _) => (200, json!({ "ok": true, "result": { "pong": true } })),
+                _ => (400, json!({ "ok": false, "error": "bad request" })),
+            }
+        });
+        let client = HttpGatewayClient::with_base_url(base_url)
+            .expect("client")
+            .with_retry_policy(GatewayRetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(5),
+                max_delay: Duration::from_millis(20),
+            });
+
+        let probe = client.probe().await.expect("probe");
+        assert_eq!(probe.attempts, 2);
+        let err = client
+            .call(GatewayRequest::new("ping", None))
+            .await
+            .expect_err("call is not retried after a 5xx");
+        assert!(err.to_string().contains("HTTP 503"));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn probe_does_not_retry_client_errors() {
+        let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hits.clone();
+        let base_url = spawn_test_server(move |_, _| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            (403, json!({ "ok": false, "error": "forbidden" }))
+        });
+        let client = HttpGatewayClient::with_base_url(base_url).expect("client");
+        let err = client.probe().await.expect_err("should fail");
+        assert!(err.to_string().contains("HTTP 403"));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn with_auth_requires_token_env() {
+        let err =
+            HttpGatewayClient::with_auth("http://127.0.0.1:1", "MOSAIC_TEST_GATEWAY_NO_TOKEN")
+                .expect_err("missing env");
+        assert!(matches!(err, MosaicError::Auth(_)));
+        assert!(err.to_string().contains("MOSAIC_TEST_GATEWAY_NO_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn status_rejects_mismatched_shape() {
+        let base_url = spawn_test_server(|_, _| {
+            (
+                200,
+                json!({
+                    "ok": true,
+                    "result": { "uptime_seconds": "soon" }
+                }),
+            )
+        });
+        let client = HttpGatewayClient::with_base_url(base_url).expect("client");
+        let err = client.status().await.expect_err("should fail");
+        assert!(matches!(err, MosaicError::GatewayProtocol(_)));
+        assert!(err.to_string().contains("invalid gateway status response"));
+    }
 }
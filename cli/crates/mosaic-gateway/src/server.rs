@@ -0,0 +1,432 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread;
+
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, ReadWrite, Request, Response, Server};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::{Message, WebSocket};
+
+use mosaic_core::error::{MosaicError, Result};
+
+use crate::{GatewayErrorPayload, GatewayRequest, GatewayResponse};
+
+const GATEWAY_SERVICE_NAME: &str = "mosaic-gateway";
+
+/// Arguments passed to a registered method handler.
+pub struct GatewayMethodCall<'a> {
+    pub params: Value,
+    pub registry: &'a MethodRegistry,
+}
+
+pub type GatewayMethodHandler =
+    Box<dyn Fn(GatewayMethodCall<'_>) -> Result<Value> + Send + Sync + 'static>;
+
+/// Method name to handler mapping served by [`GatewayServer`] on `/call`
+/// and `/ws`, and listed on `/discover`.
+pub struct MethodRegistry {
+    started_at: DateTime<Utc>,
+    handlers: BTreeMap<String, GatewayMethodHandler>,
+}
+
+impl Default for MethodRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MethodRegistry {
+    pub fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            handlers: BTreeMap::new(),
+        }
+    }
+
+    /// Registry preloaded with the built-in `health` and `status` methods.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register("health", |_| {
+            Ok(json!({
+                "ok": true,
+                "service": GATEWAY_SERVICE_NAME,
+                "ts": Utc::now(),
+            }))
+        });
+        registry.register("status", |call| {
+            let started_at = call.registry.started_at();
+            Ok(json!({
+                "ok": true,
+                "service": GATEWAY_SERVICE_NAME,
+                "started_at": started_at,
+                "uptime_seconds": (Utc::now() - started_at).num_seconds(),
+                "version": env!("CARGO_PKG_VERSION"),
+                "method_count": call.registry.len(),
+            }))
+        });
+        registry
+    }
+
+    /// Registers `handler` under `name`, replacing any existing handler.
+    pub fn register<F>(&mut self, name: impl Into<String>, handler: F)
+    where
+        F: Fn(GatewayMethodCall<'_>) -> Result<Value> + Send + Sync + 'static,
+    {
+        self.handlers.insert(name.into(), Box::new(handler));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Registered method names in sorted order.
+    pub fn methods(&self) -> Vec<String> {
+        self.handlers.keys().cloned().collect()
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Runs the handler for `request.method`, reporting unknown methods as
+    /// `method_not_found` and handler failures by their error code.
+    pub fn dispatch(&self, request: GatewayRequest) -> GatewayResponse {
+        let Some(handler) = self.handlers.get(&request.method) else {
+            return error_response(
+                "method_not_found",
+                format!("unknown method '{}'", request.method),
+            );
+        };
+        match handler(GatewayMethodCall {
+            params: request.params,
+            registry: self,
+        }) {
+            Ok(result) => GatewayResponse {
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => error_response(&err.code().to_string(), err.to_string()),
+        }
+    }
+}
+
+/// Blocking HTTP server exposing a [`MethodRegistry`] over the gateway protocol.
+/// `/ws` upgrades to a WebSocket that answers each JSON request frame with a
+/// response frame carrying the same `id`.
+pub struct GatewayServer {
+    server: Server,
+    registry: Arc<MethodRegistry>,
+}
+
+impl GatewayServer {
+    pub fn bind(host: &str, port: u16, registry: MethodRegistry) -> Result<Self> {
+        let server = Server::http(format!("{host}:{port}"))
+            .map_err(|err| MosaicError::Network(format!("failed to bind gateway server: {err}")))?;
+        Ok(Self {
+            server,
+            registry: Arc::new(registry),
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Serves requests until the listener shuts down.
+    pub fn run(self) -> Result<()> {
+        let content_type =
+            Header::from_bytes("Content-Type", "application/json").map_err(|err| {
+                MosaicError::Unknown(format!("failed to create response header: {err:?}"))
+            })?;
+        for mut request in self.server.incoming_requests() {
+            let method = request.method().clone();
+            let url = request.url().to_string();
+            if method == Method::Get && url == "/ws" {
+                self.upgrade_websocket(request, &content_type);
+                continue;
+            }
+            let (status, body) = match (method, url.as_str()) {
+                (Method::Get, "/health") => self.get_method("health"),
+                (Method::Get, "/status") => self.get_method("status"),
+                (Method::Get, "/discover") => (
+                    200,
+                    json!({
+                        "ok": true,
+                        "methods": self.registry.methods(),
+                    }),
+                ),
+                (Method::Post, "/call") => {
+                    let mut body = String::new();
+                    if request.as_reader().read_to_string(&mut body).is_err() {
+                        (
+                            400,
+                            error_body("invalid_request", "failed to read request body"),
+                        )
+                    } else {
+                        match serde_json::from_str::<GatewayRequest>(&body) {
+                            Ok(payload) => self.call(payload),
+                            Err(err) => (
+                                400,
+                                error_body(
+                                    "invalid_request",
+                                    format!("invalid JSON request: {err}"),
+                                ),
+                            ),
+                        }
+                    }
+                }
+                _ => (404, json!({ "ok": false, "error": "not_found" })),
+            };
+            let response = Response::from_string(body.to_string())
+                .with_status_code(status)
+                .with_header(content_type.clone());
+            let _ = request.respond(response);
+        }
+        Ok(())
+    }
+
+    /// Completes the WebSocket handshake and serves the socket on its own
+    /// thread so the HTTP routes stay responsive.
+    fn upgrade_websocket(&self, request: Request, content_type: &Header) {
+        let key = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Sec-WebSocket-Key"))
+            .map(|header| derive_accept_key(header.value.as_str().trim().as_bytes()));
+        let accept = key.and_then(|key| Header::from_bytes("Sec-WebSocket-Accept", key).ok());
+        let Some(accept) = accept else {
+            let body = error_body("invalid_request", "websocket upgrade required on /ws");
+            let response = Response::from_string(body.to_string())
+                .with_status_code(400)
+                .with_header(content_type.clone());
+            let _ = request.respond(response);
+            return;
+        };
+        let stream = request.upgrade("websocket", Response::empty(101).with_header(accept));
+        let registry = Arc::clone(&self.registry);
+        thread::spawn(move || serve_websocket(stream, &registry));
+    }
+
+    fn get_method(&self, name: &str) -> (u16, Value) {
+        let response = self.registry.dispatch(GatewayRequest::new(name, None));
+        match response.result {
+            Some(result) if response.ok => (200, result),
+            _ => (
+                response_status(&response),
+                serde_json::to_value(&response).unwrap_or(Value::Null),
+            ),
+        }
+    }
+
+    fn call(&self, request: GatewayRequest) -> (u16, Value) {
+        let response = self.registry.dispatch(request);
+        (
+            response_status(&response),
+            serde_json::to_value(&response).unwrap_or(Value::Null),
+        )
+    }
+}
+
+fn serve_websocket(stream: Box<dyn ReadWrite + Send>, registry: &MethodRegistry) {
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    // Reading fails once the peer has closed the socket, which ends the loop.
+    while let Ok(message) = socket.read() {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            _ => continue,
+        };
+        let reply = websocket_reply(registry, &text);
+        if socket.send(Message::Text(reply.to_string())).is_err() {
+            return;
+        }
+    }
+}
+
+/// Answers one request frame. `discover` is built in unless a handler
+/// overrides it, mirroring the `/discover` route.
+fn websocket_reply(registry: &MethodRegistry, text: &str) -> Value {
+    let request = match serde_json::from_str::<GatewayRequest>(text) {
+        Ok(request) => request,
+        Err(err) => {
+            let mut body = error_body("invalid_request", format!("invalid JSON request: {err}"));
+            body["id"] = Value::Null;
+            return body;
+        }
+    };
+    let id = request.id.clone();
+    let response = if request.method == "discover" && !registry.contains("discover") {
+        GatewayResponse {
+            ok: true,
+            result: Some(json!({ "methods": registry.methods() })),
+            error: None,
+        }
+    } else {
+        registry.dispatch(request)
+    };
+    let mut body = serde_json::to_value(&response).unwrap_or(Value::Null);
+    body["id"] = Value::String(id);
+    body
+}
+
+/// Handler failures are answered with a 4xx status: the request was processed,
+/// so clients must not treat it as a transient server error and resend it.
+fn response_status(response: &GatewayResponse) -> u16 {
+    match response.error.as_ref().map(|err| err.code.as_str()) {
+        None => 200,
+        Some("validation") => 400,
+        Some("auth") => 401,
+        Some("approval_required" | "sandbox_denied") => 403,
+        Some("method_not_found") => 404,
+        Some(_) => 422,
+    }
+}
+
+fn error_response(code: &str, message: impl Into<String>) -> GatewayResponse {
+    GatewayResponse {
+        ok: false,
+        result: None,
+        error: Some(GatewayErrorPayload {
+            code: code.to_string(),
+            message: message.into(),
+        }),
+    }
+}
+
+fn error_body(code: &str, message: impl Into<String>) -> Value {
+    serde_json::to_value(error_response(code, message)).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{GatewayClient, HttpGatewayClient, WsGatewayClient};
+
+    use super::*;
+
+    fn spawn_server(registry: MethodRegistry) -> String {
+        let server = GatewayServer::bind("127.0.0.1", 0, registry).expect("bind");
+        let addr = server.local_addr().expect("addr");
+        thread::spawn(move || server.run());
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn registered_methods_are_callable_and_discoverable() {
+        let mut registry = MethodRegistry::with_defaults();
+        registry.register("math.add", |call| {
+            let a = call.params["a"].as_i64().unwrap_or(0);
+            let b = call.params["b"].as_i64().unwrap_or(0);
+            Ok(json!({ "sum": a + b }))
+        });
+        registry.register("math.fail", |_| {
+            Err(MosaicError::Validation("bad operands".to_string()))
+        });
+        let client = HttpGatewayClient::with_base_url(spawn_server(registry)).expect("client");
+
+        let response = client
+            .call(GatewayRequest::new(
+                "math.add",
+                Some(json!({ "a": 2, "b": 3 })),
+            ))
+            .await
+            .expect("call");
+        assert_eq!(response.result.expect("result")["sum"], 5);
+
+        let discovery = client.discover().await.expect("discover");
+        assert_eq!(
+            discovery.methods,
+            vec!["health", "math.add", "math.fail", "status"]
+        );
+
+        let status = client.status().await.expect("status");
+        assert_eq!(status.method_count, Some(4));
+        assert!(client.probe().await.expect("probe").ok);
+
+        let err = client
+            .call(GatewayRequest::new("math.fail", None))
+            .await
+            .expect_err("handler error");
+        assert!(err.to_string().contains("bad operands"));
+        let err = client
+            .call(GatewayRequest::new("missing", None))
+            .await
+            .expect_err("unknown method");
+        assert!(err.to_string().contains("method 'missing'"));
+    }
+
+    #[tokio::test]
+    async fn handler_errors_are_client_errors_and_run_once() {
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = runs.clone();
+        let mut registry = MethodRegistry::with_defaults();
+        registry.register("tool.fail", move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(MosaicError::Tool("tool exploded".to_string()))
+        });
+        let client = HttpGatewayClient::with_base_url(spawn_server(registry)).expect("client");
+
+        let err = client
+            .call(GatewayRequest::new("tool.fail", None))
+            .await
+            .expect_err("handler error");
+        assert!(err.to_string().contains("HTTP 422"));
+        assert!(err.to_string().contains("tool exploded"));
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn ws_route_serves_registered_methods() {
+        let mut registry = MethodRegistry::with_defaults();
+        registry.register("math.add", |call| {
+            let a = call.params["a"].as_i64().unwrap_or(0);
+            let b = call.params["b"].as_i64().unwrap_or(0);
+            Ok(json!({ "sum": a + b }))
+        });
+        let base_url = spawn_server(registry);
+        let url = format!("{}/ws", base_url.replacen("http://", "ws://", 1));
+        let client = WsGatewayClient::with_url(&url).expect("client");
+
+        let probe = client.probe().await.expect("probe");
+        assert_eq!(probe.detail, "service=mosaic-gateway");
+        assert_eq!(probe.attempts, 1);
+
+        let response = client
+            .call(GatewayRequest::new(
+                "math.add",
+                Some(json!({ "a": 2, "b": 3 })),
+            ))
+            .await
+            .expect("call");
+        assert_eq!(response.result.expect("result")["sum"], 5);
+
+        let discovery = client.discover().await.expect("discover");
+        assert_eq!(discovery.methods, vec!["health", "math.add", "status"]);
+
+        let err = client
+            .call(GatewayRequest::new("missing", None))
+            .await
+            .expect_err("unknown method");
+        assert!(err.to_string().contains("method_not_found"));
+
+        let status = HttpGatewayClient::with_base_url(base_url)
+            .expect("http client")
+            .status()
+            .await
+            .expect("http routes still served");
+        assert_eq!(status.method_count, Some(3));
+    }
+}
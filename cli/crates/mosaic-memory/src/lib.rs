@@ -1,21 +1,42 @@
 use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::{write_pretty_state_json_file, write_pretty_state_toml_file};
+use mosaic_core::provider::{EmbeddingRequest, Provider};
+use mosaic_core::state::write_atomic;
 
 const DEFAULT_MAX_FILES: usize = 500;
 const DEFAULT_MAX_FILE_SIZE: usize = 256 * 1024;
 const DEFAULT_MAX_CONTENT_BYTES: usize = 16 * 1024;
+// Consecutive chunks share 1/8 of their bytes so terms near a boundary stay searchable.
+const CHUNK_OVERLAP_DIVISOR: usize = 8;
 const DEFAULT_SEARCH_LIMIT: usize = 20;
+// BM25 term-frequency saturation and document-length normalization.
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+// Path matches count as extra occurrences so queries naming a file still route to it.
+const BM25_PATH_WEIGHT: f64 = 2.0;
+// Multi-term hits get up to this much extra weight when the terms sit close together.
+const SEARCH_PROXIMITY_WEIGHT: f64 = 0.5;
+// Raw BM25 scores are fractional; hits report them scaled to integers.
+const SEARCH_SCORE_SCALE: f64 = 10.0;
+// Cosine similarities fall in 0..=1 for useful hits; they report as per-mille integers.
+const SEMANTIC_SCORE_SCALE: f64 = 1000.0;
+const EMBEDDING_BATCH_SIZE: usize = 64;
 const CURRENT_MEMORY_CLEANUP_POLICY_VERSION: u32 = 1;
+const MEMORY_IGNORE_FILENAME: &str = ".mosaicignore";
 pub const MEMORY_DEFAULT_NAMESPACE: &str = "default";
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryStatus {
@@ -29,25 +50,119 @@ pub struct MemoryStatus {
 pub struct MemoryDocument {
     pub id: String,
     pub path: String,
+    /// Position of this chunk within the source file; files that fit within
+    /// `max_content_bytes` are stored as a single chunk 0.
+    #[serde(default)]
+    pub chunk_index: usize,
     pub content: String,
     pub size_bytes: u64,
     pub source_modified_unix_ms: Option<i64>,
     pub indexed_at: DateTime<Utc>,
+    /// Vector from [`MemoryStore::embed_documents`]; absent until embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// Model that produced `embedding`; vectors from another model are not
+    /// comparable and are re-embedded or skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+}
+
+impl MemoryDocument {
+    fn embedding_for(&self, model: &str) -> Option<&[f32]> {
+        self.embedding
+            .as_deref()
+            .filter(|_| self.embedding_model.as_deref() == Some(model))
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct MemorySearchHit {
     pub id: String,
     pub path: String,
+    pub chunk_index: usize,
+    /// BM25 relevance of the document to the query, scaled by 10, or cosine
+    /// similarity scaled by 1000 for semantic searches. Scores are only
+    /// comparable between hits of the same search.
     pub score: usize,
     pub snippet: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct MemorySearchOptions {
+    pub limit: Option<usize>,
+    /// When set, every bare term and quoted phrase must appear in a document.
+    /// Otherwise any matching term is enough, which suits natural-language prompts.
+    pub require_all_terms: bool,
+}
+
+impl Default for MemorySearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            require_all_terms: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct MemorySearchResult {
     pub query: String,
     pub total_hits: usize,
+    pub total_matches: usize,
     pub hits: Vec<MemorySearchHit>,
+    /// Set when hits were ranked by embedding similarity rather than BM25.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub semantic: bool,
+    /// Documents a semantic search could not rank by similarity because they
+    /// have no embedding from the query's model; their lexical hits follow
+    /// the semantic ones.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub not_embedded_documents: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Turns text into vectors for [`MemoryStore::search_semantic`].
+#[async_trait]
+pub trait MemoryEmbedder: Send + Sync {
+    /// Identifies the vector space; stored with each embedding.
+    fn model(&self) -> &str;
+
+    /// Returns one vector per input, in input order.
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embeds through [`Provider::embed`] with a fixed embeddings model.
+pub struct ProviderEmbedder {
+    provider: Arc<dyn Provider>,
+    model: String,
+}
+
+impl ProviderEmbedder {
+    pub fn new(provider: Arc<dyn Provider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl MemoryEmbedder for ProviderEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.provider
+            .embed(EmbeddingRequest {
+                model: self.model.clone(),
+                inputs: inputs.to_vec(),
+            })
+            .await
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -128,6 +243,8 @@ pub struct MemoryIndexOptions {
     pub incremental: bool,
     pub stale_after_hours: Option<u64>,
     pub retain_missing: bool,
+    /// Honor `.gitignore` and `.mosaicignore` files beneath `root`.
+    pub respect_ignore_files: bool,
     pub max_files: usize,
     pub max_file_size: usize,
     pub max_content_bytes: usize,
@@ -140,6 +257,7 @@ impl Default for MemoryIndexOptions {
             incremental: false,
             stale_after_hours: None,
             retain_missing: false,
+            respect_ignore_files: true,
             max_files: DEFAULT_MAX_FILES,
             max_file_size: DEFAULT_MAX_FILE_SIZE,
             max_content_bytes: DEFAULT_MAX_CONTENT_BYTES,
@@ -269,18 +387,30 @@ impl MemoryStore {
         } else {
             Vec::new()
         };
-        let existing_by_path = existing_documents
-            .into_iter()
-            .map(|doc| (doc.path.clone(), doc))
-            .collect::<HashMap<_, _>>();
+        let mut existing_by_path = HashMap::<String, Vec<MemoryDocument>>::new();
+        for doc in existing_documents {
+            existing_by_path
+                .entry(doc.path.clone())
+                .or_default()
+                .push(doc);
+        }
+        for chunks in existing_by_path.values_mut() {
+            chunks.sort_by_key(|doc| doc.chunk_index);
+        }
         let mut documents = Vec::new();
+        let mut indexed_files = 0usize;
         let mut skipped = 0usize;
         let mut reused = 0usize;
         let mut reindexed = 0usize;
         let mut stale_reindexed = 0usize;
+        let allowed_paths = if options.respect_ignore_files {
+            Some(unignored_files(&root))
+        } else {
+            None
+        };
 
         for entry in WalkDir::new(&root).into_iter().flatten() {
-            if documents.len() >= options.max_files {
+            if indexed_files >= options.max_files {
                 break;
             }
             let path = entry.path();
@@ -290,6 +420,13 @@ impl MemoryStore {
             if should_skip(path) {
                 continue;
             }
+            if allowed_paths
+                .as_ref()
+                .is_some_and(|allowed| !allowed.contains(path))
+            {
+                skipped += 1;
+                continue;
+            }
 
             let metadata = match std::fs::metadata(path) {
                 Ok(metadata) => metadata,
@@ -311,16 +448,22 @@ impl MemoryStore {
                 .map(|value| value.to_string_lossy().to_string())
                 .unwrap_or_else(|| path.to_string_lossy().to_string());
 
-            if options.incremental
-                && let Some(existing) = existing_by_path.get(&relative)
-                && existing.size_bytes == metadata.len()
-                && existing.source_modified_unix_ms == source_modified_unix_ms
+            let existing = if options.incremental {
+                existing_by_path.get(&relative)
+            } else {
+                None
+            };
+            if let Some(chunks) = existing
+                && let Some(first) = chunks.first()
+                && first.size_bytes == metadata.len()
+                && first.source_modified_unix_ms == source_modified_unix_ms
             {
-                if is_stale(existing, options.stale_after_hours) {
-                    stale_reindexed += 1;
+                if is_stale(first, options.stale_after_hours) {
+                    stale_reindexed += chunks.len();
                 } else {
-                    documents.push(existing.clone());
-                    reused += 1;
+                    documents.extend(chunks.iter().cloned());
+                    reused += chunks.len();
+                    indexed_files += 1;
                     continue;
                 }
             }
@@ -329,37 +472,56 @@ impl MemoryStore {
                 Ok(content) => content,
                 Err(_) => {
                     skipped += 1;
-                    if options.incremental
-                        && let Some(existing) = existing_by_path.get(&relative)
-                    {
-                        documents.push(existing.clone());
-                        reused += 1;
+                    if let Some(chunks) = existing {
+                        documents.extend(chunks.iter().cloned());
+                        reused += chunks.len();
+                        indexed_files += 1;
                     }
                     continue;
                 }
             };
             if content.trim().is_empty() {
                 skipped += 1;
-                if options.incremental
-                    && let Some(existing) = existing_by_path.get(&relative)
-                {
-                    documents.push(existing.clone());
-                    reused += 1;
+                if let Some(chunks) = existing {
+                    documents.extend(chunks.iter().cloned());
+                    reused += chunks.len();
+                    indexed_files += 1;
                 }
                 continue;
             }
 
-            let truncated = truncate_to_bytes(&content, options.max_content_bytes);
-            let existing_id = existing_by_path.get(&relative).map(|doc| doc.id.clone());
-            documents.push(MemoryDocument {
-                id: existing_id.unwrap_or_else(|| format!("mem_{}", uuid::Uuid::new_v4())),
-                path: relative,
-                content: truncated,
-                size_bytes: metadata.len(),
-                source_modified_unix_ms,
-                indexed_at: Utc::now(),
-            });
-            reindexed += 1;
+            let existing_ids = existing_by_path
+                .get(&relative)
+                .map(|chunks| {
+                    chunks
+                        .iter()
+                        .map(|doc| (doc.chunk_index, doc.id.clone()))
+                        .collect::<HashMap<_, _>>()
+                })
+                .unwrap_or_default();
+            let indexed_at = Utc::now();
+            for (chunk_index, chunk) in chunk_content(&content, options.max_content_bytes)
+                .into_iter()
+                .enumerate()
+            {
+                let id = existing_ids
+                    .get(&chunk_index)
+                    .cloned()
+                    .unwrap_or_else(|| format!("mem_{}", uuid::Uuid::new_v4()));
+                documents.push(MemoryDocument {
+                    id,
+                    path: relative.clone(),
+                    chunk_index,
+                    content: chunk,
+                    size_bytes: metadata.len(),
+                    source_modified_unix_ms,
+                    indexed_at,
+                    embedding: None,
+                    embedding_model: None,
+                });
+                reindexed += 1;
+            }
+            indexed_files += 1;
         }
 
         documents.sort_by_key(|doc| Reverse(doc.path.clone()));
@@ -369,26 +531,22 @@ impl MemoryStore {
                 .iter()
                 .map(|doc| doc.path.clone())
                 .collect::<HashSet<_>>();
-            let missing_paths = existing_by_path
-                .keys()
-                .filter(|path| !indexed_paths.contains(*path))
-                .cloned()
+            let missing_chunks = existing_by_path
+                .iter()
+                .filter(|(path, _)| !indexed_paths.contains(*path))
+                .flat_map(|(_, chunks)| chunks.iter().cloned())
                 .collect::<Vec<_>>();
             if options.retain_missing {
-                for path in &missing_paths {
-                    if let Some(existing) = existing_by_path.get(path) {
-                        documents.push(existing.clone());
-                        retained_missing_documents += 1;
-                    }
-                }
+                retained_missing_documents = missing_chunks.len();
+                documents.extend(missing_chunks);
                 0
             } else {
-                missing_paths.len()
+                missing_chunks.len()
             }
         } else {
             0
         };
-        documents.sort_by_key(|doc| Reverse(doc.path.clone()));
+        documents.sort_by_key(|doc| (Reverse(doc.path.clone()), doc.chunk_index));
         self.save_documents(&documents)?;
         let status = MemoryStatus {
             indexed_documents: documents.len(),
@@ -402,11 +560,7 @@ impl MemoryStore {
             incremental: options.incremental,
             indexed_documents: status.indexed_documents,
             reused_documents: if options.incremental { reused } else { 0 },
-            reindexed_documents: if options.incremental {
-                reindexed
-            } else {
-                status.indexed_documents
-            },
+            reindexed_documents: reindexed,
             stale_reindexed_documents: if options.incremental {
                 stale_reindexed
             } else {
@@ -442,6 +596,22 @@ impl MemoryStore {
     }
 
     pub fn search(&self, query: &str, limit: Option<usize>) -> Result<MemorySearchResult> {
+        self.search_with_options(
+            query,
+            MemorySearchOptions {
+                limit,
+                ..MemorySearchOptions::default()
+            },
+        )
+    }
+
+    /// Searches indexed documents. Quoted parts of `query` are matched as exact
+    /// phrases; the remaining words are matched as individual terms.
+    pub fn search_with_options(
+        &self,
+        query: &str,
+        options: MemorySearchOptions,
+    ) -> Result<MemorySearchResult> {
         let query = query.trim();
         if query.is_empty() {
             return Err(MosaicError::Validation(
@@ -449,26 +619,82 @@ impl MemoryStore {
             ));
         }
         let docs = self.load_documents()?;
-        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
-        let query_tokens = tokenize_query(query);
-
-        let mut hits = docs
-            .into_iter()
-            .filter_map(|doc| {
-                let score = relevance_score(&doc.content, &doc.path, query, &query_tokens);
-                if score == 0 {
+        let limit = options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        let parsed = parse_search_query(query);
+        let query_tokens = &parsed.tokens;
+        let unquoted = query.replace('"', "");
+        let snippet_needle = parsed
+            .phrases
+            .first()
+            .map(|phrase| phrase.join(" "))
+            .unwrap_or_else(|| unquoted.clone());
+
+        let term_docs = docs
+            .iter()
+            .map(|doc| TermDocument::new(&doc.content, &doc.path))
+            .collect::<Vec<_>>();
+        let idf = inverse_document_frequencies(&term_docs, query_tokens);
+        let average_len = average_document_len(&term_docs);
+
+        let mut scored = docs
+            .iter()
+            .zip(term_docs.iter())
+            .filter_map(|(doc, terms)| {
+                if !parsed.matches(terms, options.require_all_terms) {
                     return None;
                 }
-                let snippet = find_snippet(&doc.content, query);
-                Some(MemorySearchHit {
-                    id: doc.id,
-                    path: doc.path,
-                    score,
-                    snippet,
-                })
+                let mut score = bm25_score(terms, query_tokens, &idf, average_len);
+                if let Some(proximity) = terms.proximity(query_tokens) {
+                    score *= 1.0 + SEARCH_PROXIMITY_WEIGHT * proximity;
+                }
+                if score <= 0.0 {
+                    return None;
+                }
+                Some((doc, score))
             })
             .collect::<Vec<_>>();
+        if scored.is_empty() {
+            // Whole-token matching misses partial words and terms the tokenizer
+            // splits or keeps joined (`foo_bar`, `v1.2`); match substrings instead.
+            scored = docs
+                .iter()
+                .filter_map(|doc| {
+                    let score = substring_score(
+                        &doc.content,
+                        &doc.path,
+                        &unquoted,
+                        query_tokens,
+                        options.require_all_terms,
+                    );
+                    (score > 0).then(|| (doc, score as f64 / SEARCH_SCORE_SCALE))
+                })
+                .collect();
+        }
+        scored.sort_by(|lhs, rhs| {
+            rhs.1
+                .total_cmp(&lhs.1)
+                .then(lhs.0.path.cmp(&rhs.0.path))
+                .then(lhs.0.chunk_index.cmp(&rhs.0.chunk_index))
+        });
 
+        // Only the best chunk of each source file is reported.
+        let mut seen_paths = HashSet::new();
+        let mut total_matches = 0usize;
+        let mut hits = Vec::new();
+        for (doc, score) in scored {
+            if !seen_paths.insert(doc.path.clone()) {
+                continue;
+            }
+            total_matches += match_count(&doc.content, &unquoted, query_tokens);
+            let snippet = find_snippet(&doc.content, &snippet_needle);
+            hits.push(MemorySearchHit {
+                id: doc.id.clone(),
+                path: doc.path.clone(),
+                chunk_index: doc.chunk_index,
+                score: ((score * SEARCH_SCORE_SCALE).round() as usize).max(1),
+                snippet,
+            });
+        }
         hits.sort_by(|lhs, rhs| rhs.score.cmp(&lhs.score).then(lhs.path.cmp(&rhs.path)));
         let total_hits = hits.len();
         if hits.len() > limit {
@@ -478,10 +704,191 @@ impl MemoryStore {
         Ok(MemorySearchResult {
             query: query.to_string(),
             total_hits,
+            total_matches,
+            hits,
+            semantic: false,
+            not_embedded_documents: 0,
+        })
+    }
+
+    /// Embeds every indexed document that has no vector from the embedder's
+    /// model yet and stores the vectors in the index. Returns how many
+    /// documents were embedded.
+    pub async fn embed_documents(&self, embedder: &dyn MemoryEmbedder) -> Result<usize> {
+        let mut docs = self.load_documents()?;
+        let model = embedder.model();
+        let pending = docs
+            .iter()
+            .enumerate()
+            .filter(|(_, doc)| doc.embedding_for(model).is_none())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        for batch in pending.chunks(EMBEDDING_BATCH_SIZE) {
+            let inputs = batch
+                .iter()
+                .map(|index| docs[*index].content.clone())
+                .collect::<Vec<_>>();
+            let vectors = embedder.embed(&inputs).await?;
+            if vectors.len() != inputs.len() {
+                return Err(MosaicError::Validation(format!(
+                    "embedder returned {} vectors for {} documents",
+                    vectors.len(),
+                    inputs.len()
+                )));
+            }
+            for (index, vector) in batch.iter().zip(vectors) {
+                docs[*index].embedding = Some(vector);
+                docs[*index].embedding_model = Some(model.to_string());
+            }
+        }
+        self.save_documents(&docs)?;
+        Ok(pending.len())
+    }
+
+    /// Ranks embedded documents by cosine similarity to `query`. Documents
+    /// without an embedding from the embedder's model are matched lexically
+    /// and listed after the ranked ones. Falls back to lexical search matching
+    /// any term when no embedder is given, embedding the query fails, or no
+    /// document has been embedded with that model yet.
+    pub async fn search_semantic(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        embedder: Option<&dyn MemoryEmbedder>,
+    ) -> Result<MemorySearchResult> {
+        let lexical = || {
+            self.search_with_options(
+                query,
+                MemorySearchOptions {
+                    limit,
+                    require_all_terms: false,
+                },
+            )
+        };
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(MosaicError::Validation(
+                "memory search query cannot be empty".to_string(),
+            ));
+        }
+        let Some(embedder) = embedder else {
+            return lexical();
+        };
+        let model = embedder.model();
+        let docs = self.load_documents()?;
+        if docs.iter().all(|doc| doc.embedding_for(model).is_none()) {
+            return lexical();
+        }
+        let query_vector = match embedder.embed(&[query.to_string()]).await {
+            Ok(mut vectors) if vectors.len() == 1 => vectors.remove(0),
+            _ => return lexical(),
+        };
+
+        let document_count = docs.len();
+        let (embedded, not_embedded): (Vec<_>, Vec<_>) = docs
+            .into_iter()
+            .partition(|doc| doc.embedding_for(model).is_some());
+        let mut scored = embedded
+            .into_iter()
+            .filter_map(|doc| {
+                let similarity = cosine_similarity(&query_vector, doc.embedding_for(model)?);
+                (similarity > 0.0).then_some((doc, similarity))
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by(|lhs, rhs| {
+            rhs.1
+                .total_cmp(&lhs.1)
+                .then(lhs.0.path.cmp(&rhs.0.path))
+                .then(lhs.0.chunk_index.cmp(&rhs.0.chunk_index))
+        });
+
+        let query_tokens = parse_search_query(query).tokens;
+        let mut seen_paths = HashSet::new();
+        let mut total_matches = 0usize;
+        let mut hits = Vec::new();
+        for (doc, similarity) in scored {
+            if !seen_paths.insert(doc.path.clone()) {
+                continue;
+            }
+            total_matches += match_count(&doc.content, query, &query_tokens);
+            hits.push(MemorySearchHit {
+                snippet: find_snippet(&doc.content, query),
+                id: doc.id,
+                path: doc.path,
+                chunk_index: doc.chunk_index,
+                score: ((similarity * SEMANTIC_SCORE_SCALE).round() as usize).max(1),
+            });
+        }
+        if !not_embedded.is_empty() {
+            let not_embedded_ids = not_embedded
+                .iter()
+                .map(|doc| doc.id.as_str())
+                .collect::<HashSet<_>>();
+            let lexical = self.search_with_options(
+                query,
+                MemorySearchOptions {
+                    limit: Some(document_count),
+                    require_all_terms: false,
+                },
+            )?;
+            for hit in lexical.hits {
+                if !not_embedded_ids.contains(hit.id.as_str())
+                    || !seen_paths.insert(hit.path.clone())
+                {
+                    continue;
+                }
+                total_matches += not_embedded
+                    .iter()
+                    .find(|doc| doc.id == hit.id)
+                    .map_or(0, |doc| match_count(&doc.content, query, &query_tokens));
+                hits.push(hit);
+            }
+        }
+        let total_hits = hits.len();
+        hits.truncate(limit.unwrap_or(DEFAULT_SEARCH_LIMIT));
+
+        Ok(MemorySearchResult {
+            query: query.to_string(),
+            total_hits,
+            total_matches,
             hits,
+            semantic: true,
+            not_embedded_documents: not_embedded.len(),
         })
     }
 
+    /// Removes indexed documents whose path equals `path_prefix` or lies beneath
+    /// it, returning how many were dropped.
+    pub fn remove(&self, path_prefix: &str) -> Result<usize> {
+        let prefix = path_prefix
+            .trim()
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        if prefix.is_empty() {
+            return Err(MosaicError::Validation(
+                "memory remove path cannot be empty".to_string(),
+            ));
+        }
+        let docs = self.load_documents()?;
+        let before = docs.len();
+        let kept = docs
+            .into_iter()
+            .filter(|doc| !path_is_under(&doc.path, prefix))
+            .collect::<Vec<_>>();
+        let removed = before - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+        self.save_documents(&kept)?;
+        let mut status = self.status()?;
+        status.indexed_documents = kept.len();
+        self.save_status(&status)?;
+        Ok(removed)
+    }
+
     pub fn clear(&self) -> Result<MemoryClearResult> {
         let removed_index = remove_if_exists(&self.index_path)?;
         let removed_status = remove_if_exists(&self.status_path)?;
@@ -494,9 +901,6 @@ impl MemoryStore {
     }
 
     fn save_documents(&self, docs: &[MemoryDocument]) -> Result<()> {
-        if let Some(parent) = self.index_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
         let mut lines = String::new();
         for doc in docs {
             let line = serde_json::to_string(doc).map_err(|err| {
@@ -505,8 +909,7 @@ impl MemoryStore {
             lines.push_str(&line);
             lines.push('\n');
         }
-        std::fs::write(&self.index_path, lines)?;
-        Ok(())
+        write_atomic(&self.index_path, lines)
     }
 
     fn save_status(&self, status: &MemoryStatus) -> Result<()> {
@@ -744,6 +1147,24 @@ fn canonicalize_root(root: &Path) -> Result<PathBuf> {
     )))
 }
 
+/// Files under `root` that survive `.gitignore` and `.mosaicignore` rules found
+/// beneath it. Hidden files stay visible to match the plain directory walk.
+fn unignored_files(root: &Path) -> HashSet<PathBuf> {
+    WalkBuilder::new(root)
+        .hidden(false)
+        .parents(false)
+        .ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .require_git(false)
+        .add_custom_ignore_filename(MEMORY_IGNORE_FILENAME)
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|kind| !kind.is_dir()))
+        .map(ignore::DirEntry::into_path)
+        .collect()
+}
+
 fn should_skip(path: &Path) -> bool {
     let text = path.to_string_lossy();
     text.contains("/.git/")
@@ -766,6 +1187,45 @@ fn truncate_to_bytes(content: &str, max_bytes: usize) -> String {
     truncated
 }
 
+/// Splits `content` into pieces of at most `max_bytes`, each starting before
+/// the previous one ends.
+fn chunk_content(content: &str, max_bytes: usize) -> Vec<String> {
+    let max_bytes = max_bytes.max(1);
+    if content.len() <= max_bytes {
+        return vec![content.to_string()];
+    }
+    let step = (max_bytes - max_bytes / CHUNK_OVERLAP_DIVISOR).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+    loop {
+        let mut end = floor_char_boundary(content, (start + max_bytes).min(content.len()));
+        if end <= start {
+            end = ceil_char_boundary(content, start + 1);
+        }
+        chunks.push(content[start..end].to_string());
+        if end >= content.len() {
+            break;
+        }
+        let next = floor_char_boundary(content, start + step);
+        start = if next > start { next } else { end };
+    }
+    chunks
+}
+
+fn floor_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(content: &str, mut index: usize) -> usize {
+    while !content.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
 fn system_time_to_unix_ms(value: SystemTime) -> Option<i64> {
     value
         .duration_since(UNIX_EPOCH)
@@ -784,6 +1244,24 @@ fn is_stale(document: &MemoryDocument, stale_after_hours: Option<u64>) -> bool {
     elapsed.num_hours() >= limit
 }
 
+/// Cosine similarity of two vectors; mismatched lengths or zero vectors score 0.
+fn cosine_similarity(lhs: &[f32], rhs: &[f32]) -> f64 {
+    if lhs.len() != rhs.len() || lhs.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut lhs_norm, mut rhs_norm) = (0.0f64, 0.0f64, 0.0f64);
+    for (left, right) in lhs.iter().zip(rhs) {
+        let (left, right) = (f64::from(*left), f64::from(*right));
+        dot += left * right;
+        lhs_norm += left * left;
+        rhs_norm += right * right;
+    }
+    if lhs_norm == 0.0 || rhs_norm == 0.0 {
+        return 0.0;
+    }
+    dot / (lhs_norm.sqrt() * rhs_norm.sqrt())
+}
+
 fn find_snippet(content: &str, query: &str) -> String {
     let lower_query = query.to_lowercase();
     for line in content.lines() {
@@ -794,42 +1272,252 @@ fn find_snippet(content: &str, query: &str) -> String {
     truncate_to_bytes(content, 160)
 }
 
-fn tokenize_query(query: &str) -> Vec<String> {
-    let mut tokens = query
-        .split(|ch: char| !ch.is_alphanumeric() && ch != '_')
-        .map(|token| token.trim().to_lowercase())
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric() && ch != '_')
         .filter(|token| !token.is_empty())
-        .collect::<Vec<_>>();
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn tokenize_query(query: &str) -> Vec<String> {
+    let mut tokens = tokenize(query);
     tokens.sort();
     tokens.dedup();
     tokens
 }
 
-fn relevance_score(content: &str, path: &str, query: &str, query_tokens: &[String]) -> usize {
-    let lower_query = query.to_lowercase();
-    let lower_content = content.to_lowercase();
-    let lower_path = path.to_lowercase();
-    let mut score = 0usize;
+/// A search query split into bare terms and quoted phrases.
+struct SearchQuery {
+    terms: Vec<String>,
+    phrases: Vec<Vec<String>>,
+    /// Distinct tokens from both terms and phrases, used for scoring.
+    tokens: Vec<String>,
+}
 
-    // Exact phrase matches in content are the strongest signal.
-    let phrase_hits = lower_content.matches(&lower_query).count();
-    score += phrase_hits * 8;
-    // Path exact phrase match helps route to target files faster.
-    let path_phrase_hits = lower_path.matches(&lower_query).count();
-    score += path_phrase_hits * 12;
+impl SearchQuery {
+    fn matches(&self, doc: &TermDocument, require_all_terms: bool) -> bool {
+        if require_all_terms {
+            self.terms.iter().all(|term| doc.term_frequency(term) > 0.0)
+                && self
+                    .phrases
+                    .iter()
+                    .all(|phrase| doc.contains_phrase(phrase))
+        } else {
+            self.tokens
+                .iter()
+                .any(|token| doc.term_frequency(token) > 0.0)
+        }
+    }
+}
 
-    for token in query_tokens {
-        let content_hits = lower_content.matches(token).count();
-        if content_hits > 0 {
-            score += content_hits * 3;
+fn parse_search_query(query: &str) -> SearchQuery {
+    let mut bare = String::new();
+    let mut phrases = Vec::new();
+    // Odd segments sit between quotes; an unterminated quote runs to the end.
+    for (index, segment) in query.split('"').enumerate() {
+        let phrase = tokenize(segment);
+        if index % 2 == 1 && phrase.len() > 1 {
+            phrases.push(phrase);
+        } else {
+            bare.push(' ');
+            bare.push_str(segment);
+        }
+    }
+    let terms = tokenize_query(&bare);
+    let mut tokens = terms
+        .iter()
+        .chain(phrases.iter().flatten())
+        .cloned()
+        .collect::<Vec<_>>();
+    tokens.sort();
+    tokens.dedup();
+    SearchQuery {
+        terms,
+        phrases,
+        tokens,
+    }
+}
+
+/// Per-document term positions used for BM25 scoring and phrase matching.
+struct TermDocument {
+    content_positions: HashMap<String, Vec<usize>>,
+    path_terms: HashMap<String, usize>,
+    len: usize,
+}
+
+impl TermDocument {
+    fn new(content: &str, path: &str) -> Self {
+        let content_tokens = tokenize(content);
+        let len = content_tokens.len();
+        let mut content_positions = HashMap::<String, Vec<usize>>::new();
+        for (position, token) in content_tokens.into_iter().enumerate() {
+            content_positions.entry(token).or_default().push(position);
+        }
+        let mut path_terms = HashMap::new();
+        for token in tokenize(path) {
+            *path_terms.entry(token).or_insert(0) += 1;
+        }
+        Self {
+            content_positions,
+            path_terms,
+            len,
+        }
+    }
+
+    fn term_frequency(&self, term: &str) -> f64 {
+        let content = self.content_positions.get(term).map_or(0, Vec::len) as f64;
+        let path = self.path_terms.get(term).copied().unwrap_or(0) as f64;
+        content + path * BM25_PATH_WEIGHT
+    }
+
+    fn contains_phrase(&self, phrase: &[String]) -> bool {
+        let Some(starts) = self.content_positions.get(&phrase[0]) else {
+            return false;
+        };
+        starts.iter().any(|start| {
+            phrase.iter().enumerate().skip(1).all(|(offset, token)| {
+                self.content_positions
+                    .get(token)
+                    .is_some_and(|positions| positions.binary_search(&(start + offset)).is_ok())
+            })
+        })
+    }
+
+    /// Returns `tokens.len() / span` for the tightest window of content holding
+    /// every token, or `None` for single-token queries and partial matches.
+    fn proximity(&self, tokens: &[String]) -> Option<f64> {
+        if tokens.len() < 2 {
+            return None;
+        }
+        let mut occurrences = Vec::new();
+        for (index, token) in tokens.iter().enumerate() {
+            let positions = self.content_positions.get(token)?;
+            occurrences.extend(positions.iter().map(|position| (*position, index)));
+        }
+        occurrences.sort_unstable();
+
+        let mut counts = vec![0usize; tokens.len()];
+        let mut covered = 0usize;
+        let mut left = 0usize;
+        let mut best_span = usize::MAX;
+        for right in 0..occurrences.len() {
+            let (_, index) = occurrences[right];
+            if counts[index] == 0 {
+                covered += 1;
+            }
+            counts[index] += 1;
+            while covered == tokens.len() {
+                let span = occurrences[right].0 - occurrences[left].0 + 1;
+                best_span = best_span.min(span);
+                let (_, left_index) = occurrences[left];
+                counts[left_index] -= 1;
+                if counts[left_index] == 0 {
+                    covered -= 1;
+                }
+                left += 1;
+            }
         }
-        let path_hits = lower_path.matches(token).count();
-        if path_hits > 0 {
-            score += path_hits * 5;
+        Some(tokens.len() as f64 / best_span as f64)
+    }
+}
+
+fn inverse_document_frequencies(
+    docs: &[TermDocument],
+    query_tokens: &[String],
+) -> HashMap<String, f64> {
+    let total = docs.len() as f64;
+    query_tokens
+        .iter()
+        .map(|token| {
+            let containing = docs
+                .iter()
+                .filter(|doc| doc.term_frequency(token) > 0.0)
+                .count() as f64;
+            // BM25 idf with +1 inside the log so terms present in every document
+            // still contribute a small positive weight.
+            let idf = (1.0 + (total - containing + 0.5) / (containing + 0.5)).ln();
+            (token.clone(), idf)
+        })
+        .collect()
+}
+
+fn average_document_len(docs: &[TermDocument]) -> f64 {
+    if docs.is_empty() {
+        return 0.0;
+    }
+    docs.iter().map(|doc| doc.len).sum::<usize>() as f64 / docs.len() as f64
+}
+
+fn bm25_score(
+    doc: &TermDocument,
+    query_tokens: &[String],
+    idf: &HashMap<String, f64>,
+    average_len: f64,
+) -> f64 {
+    let length_ratio = if average_len > 0.0 {
+        doc.len as f64 / average_len
+    } else {
+        1.0
+    };
+    let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * length_ratio);
+    query_tokens
+        .iter()
+        .map(|token| {
+            let tf = doc.term_frequency(token);
+            if tf == 0.0 {
+                return 0.0;
+            }
+            let weight = idf.get(token).copied().unwrap_or(0.0);
+            weight * tf * (BM25_K1 + 1.0) / (tf + norm)
+        })
+        .sum()
+}
+
+/// Counts occurrences of the query phrase in content, falling back to individual
+/// token occurrences when the phrase itself does not appear.
+/// Case-insensitive substring relevance used when no document matches whole
+/// tokens: the full query weighs most, path hits more than content hits.
+fn substring_score(
+    content: &str,
+    path: &str,
+    query: &str,
+    query_tokens: &[String],
+    require_all_terms: bool,
+) -> usize {
+    let lower_query = query.trim().to_lowercase();
+    let lower_content = content.to_lowercase();
+    let lower_path = path.to_lowercase();
+    let phrase_score = lower_content.matches(&lower_query).count() * 8
+        + lower_path.matches(&lower_query).count() * 12;
+    let mut token_score = 0usize;
+    for token in query_tokens {
+        let hits = lower_content.matches(token.as_str()).count() * 3
+            + lower_path.matches(token.as_str()).count() * 5;
+        if hits == 0 && require_all_terms && phrase_score == 0 {
+            return 0;
         }
+        token_score += hits;
+    }
+    phrase_score + token_score
+}
+
+fn match_count(content: &str, query: &str, query_tokens: &[String]) -> usize {
+    let lower_content = content.to_lowercase();
+    let phrase_hits = lower_content.matches(&query.to_lowercase()).count();
+    if phrase_hits > 0 {
+        return phrase_hits;
     }
+    query_tokens
+        .iter()
+        .map(|token| lower_content.matches(token.as_str()).count())
+        .sum()
+}
 
-    score
+fn path_is_under(path: &str, prefix: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
 }
 
 fn remove_if_exists(path: &Path) -> Result<bool> {
@@ -882,23 +1570,73 @@ mod tests {
         assert!(status.last_indexed_at.is_some());
     }
 
-    #[test]
-    fn search_empty_query_fails() {
-        let temp = tempdir().expect("tempdir");
-        let store = MemoryStore::new(
-            temp.path().join("state/memory/index.jsonl"),
-            temp.path().join("state/memory/status.json"),
-        );
-        let err = store.search("", None).expect_err("expected err");
-        assert!(matches!(err, MosaicError::Validation(_)));
+    /// Maps words onto fixed topic axes so similarity is predictable.
+    struct TopicEmbedder;
+
+    /// Same vectors as [`TopicEmbedder`] under another model name.
+    struct OtherModelEmbedder;
+
+    #[async_trait]
+    impl MemoryEmbedder for OtherModelEmbedder {
+        fn model(&self) -> &str {
+            "topics-v2"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+            TopicEmbedder.embed(inputs).await
+        }
     }
 
-    #[test]
-    fn clear_removes_index_and_status_files() {
+    #[async_trait]
+    impl MemoryEmbedder for TopicEmbedder {
+        fn model(&self) -> &str {
+            "topics-v1"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+            const TOPICS: [&[&str]; 3] = [
+                &["dog", "kitten", "pets", "vet"],
+                &["truck", "engine", "fuel", "garage"],
+                &["bread", "oven", "flour", "bake"],
+            ];
+            Ok(inputs
+                .iter()
+                .map(|input| {
+                    let words = tokenize(input);
+                    TOPICS
+                        .iter()
+                        .map(|topic| {
+                            words
+                                .iter()
+                                .filter(|word| topic.contains(&word.as_str()))
+                                .count() as f32
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn semantic_search_ranks_nearest_document_first() {
         let temp = tempdir().expect("tempdir");
         let docs_dir = temp.path().join("docs");
         std::fs::create_dir_all(&docs_dir).expect("create docs dir");
-        std::fs::write(docs_dir.join("a.txt"), "memory clear sample").expect("write a.txt");
+        std::fs::write(
+            docs_dir.join("pets.txt"),
+            "The kitten visits the vet with other pets",
+        )
+        .expect("write pets");
+        std::fs::write(
+            docs_dir.join("garage.txt"),
+            "The truck engine needs fuel from the garage, and a kitten sleeps there",
+        )
+        .expect("write garage");
+        std::fs::write(
+            docs_dir.join("kitchen.txt"),
+            "Bake bread once the oven is hot",
+        )
+        .expect("write kitchen");
 
         let store = MemoryStore::new(
             temp.path().join("state/memory/index.jsonl"),
@@ -910,7 +1648,236 @@ mod tests {
                 ..MemoryIndexOptions::default()
             })
             .expect("index");
-        assert!(store.index_path.exists());
+
+        let fallback = store
+            .search_semantic("dog", Some(5), Some(&TopicEmbedder))
+            .await
+            .expect("fallback search");
+        assert!(!fallback.semantic);
+        assert_eq!(fallback.total_hits, 0);
+
+        assert_eq!(
+            store.embed_documents(&TopicEmbedder).await.expect("embed"),
+            3
+        );
+        assert_eq!(
+            store
+                .embed_documents(&TopicEmbedder)
+                .await
+                .expect("re-embed"),
+            0
+        );
+
+        let result = store
+            .search_semantic("dog", Some(5), Some(&TopicEmbedder))
+            .await
+            .expect("semantic search");
+        assert!(result.semantic);
+        assert_eq!(result.hits[0].path, "pets.txt");
+        assert_eq!(result.hits[1].path, "garage.txt");
+        assert_eq!(result.total_hits, 2);
+        assert!(result.hits[0].score > result.hits[1].score);
+
+        let lexical = store
+            .search_semantic("oven", Some(5), None)
+            .await
+            .expect("lexical search");
+        assert!(!lexical.semantic);
+        assert_eq!(lexical.hits[0].path, "kitchen.txt");
+
+        let other_model = store
+            .search_semantic("dog", Some(5), Some(&OtherModelEmbedder))
+            .await
+            .expect("other model search");
+        assert!(!other_model.semantic);
+    }
+
+    #[tokio::test]
+    async fn semantic_search_merges_documents_without_embeddings_lexically() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(docs_dir.join("pets.txt"), "The kitten visits the vet").expect("write pets");
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir.clone(),
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+        assert_eq!(
+            store.embed_documents(&TopicEmbedder).await.expect("embed"),
+            1
+        );
+        std::fs::write(docs_dir.join("walks.txt"), "Walk the dog at noon").expect("write walks");
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                incremental: true,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("reindex");
+
+        let result = store
+            .search_semantic("dog", Some(5), Some(&TopicEmbedder))
+            .await
+            .expect("semantic search");
+        assert!(result.semantic);
+        assert_eq!(result.not_embedded_documents, 1);
+        let paths = result
+            .hits
+            .iter()
+            .map(|hit| hit.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["pets.txt", "walks.txt"]);
+
+        assert_eq!(
+            store
+                .embed_documents(&OtherModelEmbedder)
+                .await
+                .expect("embed with another model"),
+            2
+        );
+        let index = std::fs::read_to_string(temp.path().join("state/memory/index.jsonl"))
+            .expect("read index");
+        assert_eq!(
+            index.matches("\"embedding_model\":\"topics-v2\"").count(),
+            2
+        );
+    }
+
+    #[test]
+    fn index_honors_mosaicignore_unless_disabled() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(docs_dir.join("vendor")).expect("create docs dir");
+        std::fs::write(docs_dir.join(".mosaicignore"), "vendor/\n").expect("write ignore");
+        std::fs::write(docs_dir.join("vendor/lib.txt"), "vendored memory text")
+            .expect("write vendored");
+        std::fs::write(docs_dir.join("notes.txt"), "project memory text").expect("write notes");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        let result = store
+            .index(MemoryIndexOptions {
+                root: docs_dir.clone(),
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+        assert_eq!(result.skipped_files, 1);
+        let paths = store
+            .load_documents()
+            .expect("load")
+            .into_iter()
+            .map(|doc| doc.path)
+            .collect::<Vec<_>>();
+        assert!(paths.contains(&"notes.txt".to_string()));
+        assert!(!paths.contains(&"vendor/lib.txt".to_string()));
+
+        let unfiltered = store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                respect_ignore_files: false,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index without ignore files");
+        assert_eq!(unfiltered.skipped_files, 0);
+        assert_eq!(
+            store
+                .search("vendored", Some(5))
+                .expect("search")
+                .total_hits,
+            1
+        );
+    }
+
+    #[test]
+    fn large_files_are_chunked_and_late_terms_are_searchable() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        let mut content = "filler line about nothing in particular\n".repeat(100);
+        content.push_str("the zeppelin appears only at the end\n");
+        std::fs::write(docs_dir.join("long.txt"), &content).expect("write long");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        let result = store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                max_content_bytes: 1024,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+        assert!(result.indexed_documents > 1);
+
+        let docs = store.load_documents().expect("load");
+        assert!(docs.iter().all(|doc| doc.path == "long.txt"));
+        assert!(docs.iter().all(|doc| doc.content.len() <= 1024));
+        let last_chunk = docs
+            .iter()
+            .map(|doc| doc.chunk_index)
+            .max()
+            .expect("chunks");
+        assert_eq!(last_chunk + 1, docs.len());
+
+        let search = store.search("zeppelin", Some(5)).expect("search");
+        assert_eq!(search.total_hits, 1);
+        assert_eq!(search.hits[0].path, "long.txt");
+        assert_eq!(search.hits[0].chunk_index, last_chunk);
+        assert!(search.hits[0].snippet.contains("zeppelin"));
+
+        let filler = store.search("filler", Some(5)).expect("search filler");
+        assert_eq!(filler.total_hits, 1);
+    }
+
+    #[test]
+    fn chunk_content_overlaps_and_respects_char_boundaries() {
+        let content = "é".repeat(40);
+        let chunks = chunk_content(&content, 17);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 17));
+        assert!(chunks[1].starts_with(&chunks[0][chunks[0].len() - 2..]));
+        assert!(chunks.concat().len() > content.len());
+        assert_eq!(chunk_content("short", 17), vec!["short".to_string()]);
+    }
+
+    #[test]
+    fn search_empty_query_fails() {
+        let temp = tempdir().expect("tempdir");
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        let err = store.search("", None).expect_err("expected err");
+        assert!(matches!(err, MosaicError::Validation(_)));
+    }
+
+    #[test]
+    fn clear_removes_index_and_status_files() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(docs_dir.join("a.txt"), "memory clear sample").expect("write a.txt");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+        assert!(store.index_path.exists());
         assert!(store.status_path.exists());
 
         let cleared = store.clear().expect("clear");
@@ -924,6 +1891,41 @@ mod tests {
         assert!(status.last_indexed_at.is_none());
     }
 
+    #[test]
+    fn remove_drops_documents_under_prefix() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(docs_dir.join("notes")).expect("create docs dir");
+        std::fs::write(docs_dir.join("notes/old.txt"), "obsolete memory entry").expect("write old");
+        std::fs::write(docs_dir.join("keep.txt"), "current memory entry").expect("write keep");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        assert_eq!(store.remove("note").expect("remove partial segment"), 0);
+        assert_eq!(store.remove("notes/").expect("remove notes"), 1);
+        assert_eq!(store.status().expect("status").indexed_documents, 1);
+
+        let search = store.search("memory entry", Some(10)).expect("search");
+        assert_eq!(search.total_hits, 1);
+        assert_eq!(search.hits[0].path, "keep.txt");
+        assert!(
+            store
+                .search("obsolete", Some(10))
+                .expect("search")
+                .hits
+                .is_empty()
+        );
+    }
+
     #[test]
     fn incremental_index_reuses_unchanged_and_tracks_removed() {
         let temp = tempdir().expect("tempdir");
@@ -967,6 +1969,133 @@ mod tests {
         assert_eq!(second.retained_missing_documents, 0);
     }
 
+    #[test]
+    fn incremental_index_only_refreshes_modified_documents() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(docs_dir.join("a.txt"), "alpha memory item").expect("write a");
+        std::fs::write(docs_dir.join("b.txt"), "beta memory item").expect("write b");
+        std::fs::write(docs_dir.join("c.txt"), "gamma memory item").expect("write c");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir.clone(),
+                ..MemoryIndexOptions::default()
+            })
+            .expect("first index");
+        let before = store
+            .load_documents()
+            .expect("load first")
+            .into_iter()
+            .map(|doc| (doc.path, doc.indexed_at))
+            .collect::<HashMap<_, _>>();
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(docs_dir.join("b.txt"), "beta memory item, revised").expect("rewrite b");
+
+        let second = store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                incremental: true,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("incremental index");
+        assert_eq!(second.indexed_documents, 3);
+        assert_eq!(second.reused_documents, 2);
+        assert_eq!(second.reindexed_documents, 1);
+
+        let after = store.load_documents().expect("load second");
+        for doc in after {
+            let previous = before[&doc.path];
+            if doc.path == "b.txt" {
+                assert!(doc.indexed_at > previous);
+                assert!(doc.content.contains("revised"));
+            } else {
+                assert_eq!(doc.indexed_at, previous);
+            }
+        }
+    }
+
+    #[test]
+    fn search_reports_total_matches_across_documents() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(
+            docs_dir.join("a.txt"),
+            "gateway retries and gateway timeouts",
+        )
+        .expect("write a.txt");
+        std::fs::write(
+            docs_dir.join("b.txt"),
+            "the Gateway talks to another gateway",
+        )
+        .expect("write b.txt");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        let search = store.search("gateway", Some(1)).expect("search");
+        assert_eq!(search.total_hits, 2);
+        assert_eq!(search.total_matches, 4);
+        assert_eq!(search.hits.len(), 1);
+    }
+
+    #[test]
+    fn search_falls_back_to_substrings_when_no_token_matches() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(
+            docs_dir.join("config.txt"),
+            "set max_foo_bar_size before the v1.2.3 release",
+        )
+        .expect("write config.txt");
+        std::fs::write(docs_dir.join("other.txt"), "unrelated notes").expect("write other.txt");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        for query in ["foo_bar", "v1.2", "relea"] {
+            let search = store.search(query, None).expect("search");
+            assert_eq!(search.total_hits, 1, "query {query}");
+            assert_eq!(search.hits[0].path, "config.txt");
+            assert!(search.hits[0].snippet.contains("max_foo_bar_size"));
+        }
+        let strict = store
+            .search_with_options(
+                "foo_bar missing",
+                MemorySearchOptions {
+                    require_all_terms: true,
+                    ..MemorySearchOptions::default()
+                },
+            )
+            .expect("search all terms");
+        assert_eq!(strict.total_hits, 0);
+        assert_eq!(store.search("absent", None).expect("search").total_hits, 0);
+    }
+
     #[test]
     fn search_scores_path_and_phrase_signals() {
         let temp = tempdir().expect("tempdir");
@@ -1001,6 +2130,124 @@ mod tests {
         assert!(first.score >= result.hits[1].score);
     }
 
+    #[test]
+    fn search_ranks_short_relevant_doc_above_long_repetitive_doc() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(
+            docs_dir.join("focused.md"),
+            "gateway retry uses exponential backoff",
+        )
+        .expect("write focused");
+        let filler = "the gateway forwards requests to upstream services. ".repeat(40);
+        std::fs::write(docs_dir.join("verbose.md"), filler).expect("write verbose");
+        std::fs::write(
+            docs_dir.join("other.md"),
+            "channel templates and formatting",
+        )
+        .expect("write other");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        let result = store
+            .search_with_options(
+                "gateway retry",
+                MemorySearchOptions {
+                    limit: Some(5),
+                    require_all_terms: false,
+                },
+            )
+            .expect("search");
+        assert_eq!(result.total_hits, 2);
+        assert_eq!(result.hits[0].path, "focused.md");
+        assert_eq!(result.hits[1].path, "verbose.md");
+        assert!(result.hits[0].score > result.hits[1].score);
+    }
+
+    #[test]
+    fn multi_term_search_requires_all_terms_and_prefers_both() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(docs_dir.join("both.md"), "a rust cli for agents").expect("write both");
+        std::fs::write(docs_dir.join("rust.md"), "rust services and rust tooling")
+            .expect("write rust");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        let all_terms = store.search("rust cli", Some(5)).expect("search");
+        assert_eq!(all_terms.total_hits, 1);
+        assert_eq!(all_terms.hits[0].path, "both.md");
+
+        let any_term = store
+            .search_with_options(
+                "rust cli",
+                MemorySearchOptions {
+                    limit: Some(5),
+                    require_all_terms: false,
+                },
+            )
+            .expect("search any");
+        assert_eq!(any_term.total_hits, 2);
+        assert_eq!(any_term.hits[0].path, "both.md");
+        assert!(any_term.hits[0].score > any_term.hits[1].score);
+    }
+
+    #[test]
+    fn phrase_search_excludes_distant_terms() {
+        let temp = tempdir().expect("tempdir");
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).expect("create docs dir");
+        std::fs::write(docs_dir.join("phrase.md"), "install the rust cli first")
+            .expect("write phrase");
+        std::fs::write(
+            docs_dir.join("apart.md"),
+            "rust is used across the project and the cli wraps it",
+        )
+        .expect("write apart");
+
+        let store = MemoryStore::new(
+            temp.path().join("state/memory/index.jsonl"),
+            temp.path().join("state/memory/status.json"),
+        );
+        store
+            .index(MemoryIndexOptions {
+                root: docs_dir,
+                ..MemoryIndexOptions::default()
+            })
+            .expect("index");
+
+        let unquoted = store.search("rust cli", Some(5)).expect("search terms");
+        assert_eq!(unquoted.total_hits, 2);
+        assert_eq!(unquoted.hits[0].path, "phrase.md");
+
+        let phrase = store
+            .search("\"rust cli\"", Some(5))
+            .expect("search phrase");
+        assert_eq!(phrase.total_hits, 1);
+        assert_eq!(phrase.hits[0].path, "phrase.md");
+        assert_eq!(phrase.hits[0].snippet, "install the rust cli first");
+    }
+
     #[test]
     fn incremental_retains_missing_when_requested() {
         let temp = tempdir().expect("tempdir");
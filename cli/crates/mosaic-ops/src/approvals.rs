@@ -1,12 +1,17 @@
 use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 
 use mosaic_core::error::{MosaicError, Result};
-use mosaic_core::privacy::write_pretty_state_toml_file;
+use mosaic_core::privacy::{append_sanitized_jsonl, write_pretty_state_toml_file};
+
+use crate::command_paths::is_simple_command;
 
 const CURRENT_APPROVAL_POLICY_VERSION: u32 = 1;
+const REGEX_ALLOWLIST_PREFIX: &str = "re:";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -16,12 +21,64 @@ pub enum ApprovalMode {
     Allowlist,
 }
 
+/// Allowlist entries are command prefixes, except entries starting with `re:`
+/// (a regex) or containing `*` (a glob); both must match the whole command.
+/// Entries are compiled by [`ApprovalPolicy::normalize`], so build policies
+/// through [`ApprovalPolicy::new`] or [`ApprovalStore::load_or_default`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApprovalPolicy {
     pub version: u32,
     pub mode: ApprovalMode,
     #[serde(default)]
-    pub allowlist: Vec<String>,
+    allowlist: Vec<String>,
+    #[serde(skip)]
+    patterns: Vec<AllowlistPattern>,
+}
+
+#[derive(Debug, Clone)]
+enum AllowlistPattern {
+    Prefix(String),
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl AllowlistPattern {
+    fn parse(entry: &str) -> Result<Self> {
+        if let Some(pattern) = entry.strip_prefix(REGEX_ALLOWLIST_PREFIX) {
+            let regex = RegexBuilder::new(&format!("^(?:{})$", pattern.trim()))
+                .case_insensitive(true)
+                .build()
+                .map_err(|err| {
+                    MosaicError::Validation(format!("invalid allowlist regex '{entry}': {err}"))
+                })?;
+            return Ok(Self::Regex(regex));
+        }
+        if entry.contains('*') {
+            let pattern = glob::Pattern::new(entry).map_err(|err| {
+                MosaicError::Validation(format!("invalid allowlist glob '{entry}': {err}"))
+            })?;
+            return Ok(Self::Glob(pattern));
+        }
+        Ok(Self::Prefix(entry.to_string()))
+    }
+
+    fn matches(&self, command: &str) -> bool {
+        // Entries describe a single command; never auto-approve one that chains,
+        // backgrounds, redirects or substitutes another.
+        if !is_simple_command(command) {
+            return false;
+        }
+        let normalized = command.trim().to_lowercase();
+        match self {
+            Self::Prefix(prefix) => {
+                normalized == *prefix
+                    || normalized.starts_with(&format!("{prefix} "))
+                    || normalized.starts_with(&format!("{prefix}/"))
+            }
+            Self::Glob(pattern) => pattern.matches(&normalized),
+            Self::Regex(regex) => regex.is_match(command.trim()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,9 +88,52 @@ pub enum ApprovalDecision {
     Deny { reason: String },
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalAuditDecision {
+    Auto,
+    Confirm,
+    Deny,
+}
+
+/// One approval outcome for a command, appended to the approvals audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalAudit {
+    pub ts: DateTime<Utc>,
+    pub command: String,
+    pub decision: ApprovalAuditDecision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+}
+
+impl ApprovalAudit {
+    pub fn approved(command: &str, decision: ApprovalAuditDecision, approved_by: &str) -> Self {
+        Self {
+            ts: Utc::now(),
+            command: command.to_string(),
+            decision,
+            reason: None,
+            approved_by: Some(approved_by.to_string()),
+        }
+    }
+
+    pub fn denied(command: &str, reason: &str) -> Self {
+        Self {
+            ts: Utc::now(),
+            command: command.to_string(),
+            decision: ApprovalAuditDecision::Deny,
+            reason: Some(reason.to_string()),
+            approved_by: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ApprovalStore {
     path: PathBuf,
+    audit_path: Option<PathBuf>,
 }
 
 impl Default for ApprovalPolicy {
@@ -42,29 +142,50 @@ impl Default for ApprovalPolicy {
             version: CURRENT_APPROVAL_POLICY_VERSION,
             mode: ApprovalMode::Confirm,
             allowlist: Vec::new(),
+            patterns: Vec::new(),
         }
     }
 }
 
 impl ApprovalPolicy {
+    /// Builds a validated policy with its allowlist entries normalized and compiled.
+    pub fn new(mode: ApprovalMode, allowlist: Vec<String>) -> Result<Self> {
+        let mut policy = Self {
+            mode,
+            allowlist,
+            ..Self::default()
+        };
+        policy.validate()?;
+        policy.normalize();
+        Ok(policy)
+    }
+
+    pub fn allowlist(&self) -> &[String] {
+        &self.allowlist
+    }
+
+    /// Dedupes the allowlist and compiles its entries; entries that fail to
+    /// compile are left out (see [`Self::validate`]).
     pub fn normalize(&mut self) {
         let mut deduped = BTreeSet::new();
-        for prefix in self.allowlist.drain(..) {
-            let normalized = normalize_prefix(&prefix);
+        for entry in self.allowlist.drain(..) {
+            let normalized = normalize_allowlist_entry(&entry);
             if !normalized.is_empty() {
                 deduped.insert(normalized);
             }
         }
         self.allowlist = deduped.into_iter().collect();
+        self.patterns = self
+            .allowlist
+            .iter()
+            .filter_map(|entry| AllowlistPattern::parse(entry).ok())
+            .collect();
     }
 
+    /// True when an allowlist entry matches `command` and the command does not
+    /// chain or substitute others (`;`, `&&`, `|`, `$(`, backticks, newlines).
     pub fn matches_allowlist(&self, command: &str) -> bool {
-        let normalized = command.trim().to_lowercase();
-        self.allowlist.iter().any(|prefix| {
-            normalized == *prefix
-                || normalized.starts_with(&format!("{prefix} "))
-                || normalized.starts_with(&format!("{prefix}/"))
-        })
+        self.patterns.iter().any(|pattern| pattern.matches(command))
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -74,19 +195,43 @@ impl ApprovalPolicy {
                 self.version, CURRENT_APPROVAL_POLICY_VERSION
             )));
         }
+        for entry in &self.allowlist {
+            AllowlistPattern::parse(entry)?;
+        }
         Ok(())
     }
 }
 
 impl ApprovalStore {
     pub fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            audit_path: None,
+        }
+    }
+
+    pub fn with_audit_path(mut self, audit_path: PathBuf) -> Self {
+        self.audit_path = Some(audit_path);
+        self
     }
 
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    pub fn audit_path(&self) -> Option<&Path> {
+        self.audit_path.as_deref()
+    }
+
+    /// Appends `audit` to the approvals audit log; a no-op when the store has
+    /// no audit path.
+    pub fn append_audit(&self, audit: &ApprovalAudit) -> Result<()> {
+        match &self.audit_path {
+            Some(path) => append_sanitized_jsonl(path, audit, "approvals audit persistence"),
+            None => Ok(()),
+        }
+    }
+
     pub fn load_or_default(&self) -> Result<ApprovalPolicy> {
         if !self.path.exists() {
             return Ok(ApprovalPolicy::default());
@@ -121,7 +266,7 @@ impl ApprovalStore {
 
     pub fn add_allowlist(&self, prefix: &str) -> Result<ApprovalPolicy> {
         let mut policy = self.load_or_default()?;
-        let normalized = normalize_prefix(prefix);
+        let normalized = normalize_allowlist_entry(prefix);
         if normalized.is_empty() {
             return Err(MosaicError::Validation(
                 "allowlist prefix cannot be empty".to_string(),
@@ -134,7 +279,7 @@ impl ApprovalStore {
 
     pub fn remove_allowlist(&self, prefix: &str) -> Result<ApprovalPolicy> {
         let mut policy = self.load_or_default()?;
-        let normalized = normalize_prefix(prefix);
+        let normalized = normalize_allowlist_entry(prefix);
         policy.allowlist.retain(|item| item != &normalized);
         self.save(&policy)?;
         self.load_or_default()
@@ -163,8 +308,14 @@ pub fn evaluate_approval(command: &str, policy: &ApprovalPolicy) -> ApprovalDeci
     }
 }
 
-fn normalize_prefix(prefix: &str) -> String {
-    prefix.trim().to_lowercase()
+/// Prefix and glob entries are lowercased; regex entries keep their case so
+/// escapes like `\S` survive, and are matched case-insensitively instead.
+fn normalize_allowlist_entry(entry: &str) -> String {
+    let entry = entry.trim();
+    match entry.strip_prefix(REGEX_ALLOWLIST_PREFIX) {
+        Some(pattern) => format!("{REGEX_ALLOWLIST_PREFIX}{}", pattern.trim()),
+        None => entry.to_lowercase(),
+    }
 }
 
 #[cfg(test)]
@@ -175,13 +326,70 @@ mod tests {
 
     #[test]
     fn allowlist_mode_matches_prefix() {
-        let policy = ApprovalPolicy {
-            version: CURRENT_APPROVAL_POLICY_VERSION,
-            mode: ApprovalMode::Allowlist,
-            allowlist: vec!["cargo test".to_string()],
-        };
+        let policy = ApprovalPolicy::new(ApprovalMode::Allowlist, vec!["cargo test".to_string()])
+            .expect("policy");
         assert!(policy.matches_allowlist("cargo test --workspace"));
         assert!(!policy.matches_allowlist("cargo run"));
+        assert!(!policy.matches_allowlist("cargo test && rm -rf target"));
+    }
+
+    #[test]
+    fn allowlist_regex_entry_permits_only_matching_subcommands() {
+        let policy = ApprovalPolicy::new(
+            ApprovalMode::Allowlist,
+            vec![r"re:git (log|status)(\s.*)?".to_string()],
+        )
+        .expect("policy");
+        assert!(matches!(
+            evaluate_approval("git log --oneline", &policy),
+            ApprovalDecision::Auto { .. }
+        ));
+        assert!(matches!(
+            evaluate_approval("GIT STATUS", &policy),
+            ApprovalDecision::Auto { .. }
+        ));
+        assert!(matches!(
+            evaluate_approval("git push origin main", &policy),
+            ApprovalDecision::NeedsConfirmation { .. }
+        ));
+        assert!(!policy.matches_allowlist("rm -rf build; git log"));
+        assert!(!policy.matches_allowlist("git log $(rm -rf build)"));
+        assert!(!policy.matches_allowlist("git log `rm -rf build`"));
+    }
+
+    #[test]
+    fn allowlist_glob_entry_matches_whole_command() {
+        let policy = ApprovalPolicy::new(
+            ApprovalMode::Allowlist,
+            vec!["git log*".to_string(), "cargo test".to_string()],
+        )
+        .expect("policy");
+        assert!(policy.matches_allowlist("git log -n 5"));
+        assert!(!policy.matches_allowlist("git log; rm -rf /"));
+        assert!(!policy.matches_allowlist("git log | sh"));
+        assert!(!policy.matches_allowlist("git log & rm -rf ~"));
+        assert!(!policy.matches_allowlist("git log > ~/.bashrc"));
+        assert!(!policy.matches_allowlist("git log >> ~/.bashrc"));
+        assert!(!policy.matches_allowlist("git log <(rm -rf ~)"));
+        assert!(!policy.matches_allowlist("git log $(rm -rf ~)"));
+        assert!(policy.matches_allowlist("git log --grep 'a; b'"));
+        assert!(!policy.matches_allowlist("git push"));
+        assert!(policy.matches_allowlist("cargo test --workspace"));
+    }
+
+    #[test]
+    fn store_rejects_invalid_regex_entry_and_keeps_regex_case() {
+        let temp = tempdir().expect("tempdir");
+        let store = ApprovalStore::new(temp.path().join("approvals.toml"));
+        let err = store
+            .add_allowlist("re:git (log")
+            .expect_err("invalid regex should fail");
+        assert!(err.to_string().contains("invalid allowlist regex"));
+
+        let policy = store
+            .add_allowlist(r" re: ls\S* ")
+            .expect("add regex entry");
+        assert_eq!(policy.allowlist(), vec![r"re:ls\S*".to_string()]);
     }
 
     #[test]
@@ -193,12 +401,46 @@ mod tests {
         assert_eq!(policy.mode, ApprovalMode::Allowlist);
 
         let policy = store.add_allowlist("cargo test").expect("add allowlist");
-        assert_eq!(policy.allowlist, vec!["cargo test".to_string()]);
+        assert_eq!(policy.allowlist(), vec!["cargo test".to_string()]);
 
         let policy = store
             .remove_allowlist("cargo test")
             .expect("remove allowlist");
-        assert!(policy.allowlist.is_empty());
+        assert!(policy.allowlist().is_empty());
+    }
+
+    #[test]
+    fn append_audit_writes_jsonl_records() {
+        let temp = tempdir().expect("tempdir");
+        let audit_path = temp.path().join("audit").join("approvals.jsonl");
+        let store = ApprovalStore::new(temp.path().join("approvals.toml"))
+            .with_audit_path(audit_path.clone());
+        store
+            .append_audit(&ApprovalAudit::approved(
+                "cargo test",
+                ApprovalAuditDecision::Auto,
+                "approval_allowlist",
+            ))
+            .expect("append auto");
+        store
+            .append_audit(&ApprovalAudit::denied(
+                "rm -rf build",
+                "approval mode is set to deny",
+            ))
+            .expect("append deny");
+
+        let raw = std::fs::read_to_string(audit_path).expect("read audit");
+        let records = raw
+            .lines()
+            .map(|line| serde_json::from_str::<ApprovalAudit>(line).expect("record"))
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].decision, ApprovalAuditDecision::Auto);
+        assert_eq!(records[1].decision, ApprovalAuditDecision::Deny);
+        assert_eq!(
+            records[1].reason.as_deref(),
+            Some("approval mode is set to deny")
+        );
     }
 
     #[test]
@@ -206,11 +448,11 @@ mod tests {
         let temp = tempdir().expect("tempdir");
         let path = temp.path().join("approvals.toml");
         let store = ApprovalStore::new(path);
-        let policy = ApprovalPolicy {
-            version: CURRENT_APPROVAL_POLICY_VERSION,
-            mode: ApprovalMode::Allowlist,
-            allowlist: vec!["echo sk-live-secret-12345678901234567890".to_string()],
-        };
+        let policy = ApprovalPolicy::new(
+            ApprovalMode::Allowlist,
+            vec!["echo sk-live-secret-12345678901234567890".to_string()],
+        )
+        .expect("policy");
 
         let err = store
             .save(&policy)
@@ -0,0 +1,260 @@
+/// Paths a shell command visibly reads from or writes to.
+///
+/// This is a heuristic over the command text: it understands redirects,
+/// `tee`, `cp`/`mv`/`install` destinations, `rm`-style deletions and
+/// `cat`-style readers, but not variables, subshells or anything a program
+/// decides at runtime.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CommandPaths {
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Separator,
+    RedirectOut,
+    RedirectIn,
+}
+
+pub(crate) fn command_paths(command: &str) -> CommandPaths {
+    let mut paths = CommandPaths::default();
+    for segment in tokenize(command).split(|token| *token == Token::Separator) {
+        collect_segment_paths(segment, &mut paths);
+    }
+    paths
+}
+
+/// Argument words of each simple command in `command`, with redirects and
+/// their targets removed.
+pub(crate) fn command_words(command: &str) -> Vec<Vec<String>> {
+    tokenize(command)
+        .split(|token| *token == Token::Separator)
+        .map(|segment| {
+            let mut words = Vec::new();
+            let mut tokens = segment.iter();
+            while let Some(token) = tokens.next() {
+                match token {
+                    Token::Word(word) => words.push(word.clone()),
+                    Token::RedirectOut | Token::RedirectIn => {
+                        tokens.next();
+                    }
+                    Token::Separator => {}
+                }
+            }
+            words
+        })
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+fn collect_segment_paths(segment: &[Token], paths: &mut CommandPaths) {
+    let mut argv = Vec::new();
+    let mut tokens = segment.iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(word) => argv.push(word.as_str()),
+            Token::RedirectOut => {
+                if let Some(Token::Word(target)) = tokens.next() {
+                    paths.writes.push(target.clone());
+                }
+            }
+            Token::RedirectIn => {
+                if let Some(Token::Word(target)) = tokens.next() {
+                    paths.reads.push(target.clone());
+                }
+            }
+            Token::Separator => {}
+        }
+    }
+
+    let argv = argv
+        .into_iter()
+        .skip_while(|word| is_env_assignment(word))
+        .collect::<Vec<_>>();
+    let Some((program, args)) = argv.split_first() else {
+        return;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let operands = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    match program {
+        "tee" | "rm" | "rmdir" | "unlink" => paths.writes.extend(operands),
+        "cat" | "head" | "tail" | "less" | "more" => paths.reads.extend(operands),
+        "cp" | "mv" | "install" => {
+            let target_dir = args.iter().enumerate().find_map(|(index, arg)| {
+                if *arg == "-t" {
+                    args.get(index + 1).map(|dir| dir.to_string())
+                } else {
+                    arg.strip_prefix("--target-directory=").map(str::to_string)
+                }
+            });
+            let (destination, sources) = match target_dir {
+                Some(dir) => {
+                    let sources = operands
+                        .iter()
+                        .filter(|operand| **operand != dir)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    (dir, sources)
+                }
+                None => match operands.split_last() {
+                    Some((destination, sources)) => (destination.clone(), sources.to_vec()),
+                    None => return,
+                },
+            };
+            paths.writes.push(destination);
+            // Moving a file also removes it from its source directory.
+            if program == "mv" {
+                paths.writes.extend(sources);
+            } else {
+                paths.reads.extend(sources);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether `command` is one simple command: no separators (`;`, `&`, `&&`,
+/// `|`, newlines), no redirects or process substitution, and no `$(...)` or
+/// backtick substitution anywhere, including inside quotes.
+pub(crate) fn is_simple_command(command: &str) -> bool {
+    if command.contains("$(") || command.contains('`') {
+        return false;
+    }
+    tokenize(command)
+        .iter()
+        .all(|token| matches!(token, Token::Word(_)))
+}
+
+pub(crate) fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    })
+}
+
+/// Splits `command` into words, command separators and redirects. File
+/// descriptor duplications such as `2>&1` are dropped.
+fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    fn flush(tokens: &mut Vec<Token>, word: &mut String, in_word: &mut bool) {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    }
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' => {
+                in_word = true;
+                for inner in chars.by_ref() {
+                    if inner == ch {
+                        break;
+                    }
+                    word.push(inner);
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            ch if ch.is_whitespace() => flush(&mut tokens, &mut word, &mut in_word),
+            ';' | '|' | '\n' => {
+                flush(&mut tokens, &mut word, &mut in_word);
+                if chars.peek() == Some(&'|') {
+                    chars.next();
+                }
+                tokens.push(Token::Separator);
+            }
+            '&' => {
+                flush(&mut tokens, &mut word, &mut in_word);
+                if chars.peek() == Some(&'>') {
+                    // `&>` and `&>>` redirect both stdout and stderr.
+                    continue;
+                }
+                if chars.peek() == Some(&'&') {
+                    chars.next();
+                }
+                tokens.push(Token::Separator);
+            }
+            '>' | '<' => {
+                // A word made only of digits directly before a redirect is a
+                // file descriptor number, not an argument.
+                if in_word && word.chars().all(|digit| digit.is_ascii_digit()) {
+                    word.clear();
+                    in_word = false;
+                }
+                flush(&mut tokens, &mut word, &mut in_word);
+                if ch == '>' && chars.peek() == Some(&'>') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&'&') {
+                    // `>&2` duplicates a descriptor instead of naming a file.
+                    chars.next();
+                    while chars
+                        .peek()
+                        .is_some_and(|next| next.is_ascii_digit() || *next == '-')
+                    {
+                        chars.next();
+                    }
+                    continue;
+                }
+                tokens.push(if ch == '>' {
+                    Token::RedirectOut
+                } else {
+                    Token::RedirectIn
+                });
+            }
+            _ => {
+                in_word = true;
+                word.push(ch);
+            }
+        }
+    }
+    flush(&mut tokens, &mut word, &mut in_word);
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_redirect_tee_and_copy_targets() {
+        let paths = command_paths(
+            "echo hi > out.txt 2>&1; cat < in.txt | tee -a log.txt && cp -r src 'dest dir'",
+        );
+        assert_eq!(paths.writes, vec!["out.txt", "log.txt", "dest dir"]);
+        assert_eq!(paths.reads, vec!["in.txt", "src"]);
+    }
+
+    #[test]
+    fn handles_descriptor_prefixes_and_target_directory() {
+        let paths = command_paths("cargo test 2>>/tmp/err.log &>/dev/null; mv -t /opt a b");
+        assert_eq!(
+            paths.writes,
+            vec!["/tmp/err.log", "/dev/null", "/opt", "a", "b"]
+        );
+        assert!(paths.reads.is_empty());
+    }
+
+    #[test]
+    fn treats_deletions_as_writes() {
+        let paths = command_paths("rm -rf -- build '/tmp/a b'; rmdir out && unlink link");
+        assert_eq!(paths.writes, vec!["build", "/tmp/a b", "out", "link"]);
+    }
+}
@@ -1,18 +1,22 @@
 mod approvals;
+mod command_paths;
 mod logs;
 mod sandbox;
 mod system;
 
 pub use approvals::{
-    ApprovalDecision, ApprovalMode, ApprovalPolicy, ApprovalStore, evaluate_approval,
+    ApprovalAudit, ApprovalAuditDecision, ApprovalDecision, ApprovalMode, ApprovalPolicy,
+    ApprovalStore, evaluate_approval,
 };
-pub use logs::{UnifiedLogEntry, collect_logs};
+pub use logs::{LogFollower, UnifiedLogEntry, collect_logs, follow_logs};
 pub use sandbox::{
-    SandboxPolicy, SandboxProfile, SandboxProfileInfo, SandboxStore, evaluate_sandbox,
+    CustomSandboxProfile, SandboxNetworkAccess, SandboxPolicy, SandboxProfile, SandboxProfileInfo,
+    SandboxStore, evaluate_sandbox, evaluate_sandbox_policy, evaluate_sandbox_policy_in,
     list_profiles, profile_info,
 };
 pub use system::{
-    PresenceSnapshot, SystemEvent, SystemEventStore, snapshot_presence, system_events_path,
+    PresenceSnapshot, PresenceStore, SystemEvent, SystemEventStore, presence_log_path,
+    snapshot_presence, system_events_path,
 };
 
 #[derive(Debug, Clone)]
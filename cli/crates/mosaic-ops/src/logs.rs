@@ -1,4 +1,9 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 use serde::Serialize;
@@ -15,111 +20,158 @@ pub struct UnifiedLogEntry {
 
 pub fn collect_logs(data_dir: &Path, tail: usize) -> Result<Vec<UnifiedLogEntry>> {
     let mut entries = Vec::new();
+    for (path, source) in log_sources(data_dir)? {
+        load_jsonl_file(&mut entries, &path, &source)?;
+    }
+    load_browser_history_file(&mut entries, &data_dir.join("browser-history.json"))?;
+    Ok(newest(entries, tail))
+}
 
-    load_jsonl_file(
-        &mut entries,
-        &data_dir.join("system-events.jsonl"),
-        "system",
-    )?;
-    load_jsonl_file(
-        &mut entries,
-        &data_dir.join("audit/commands.jsonl"),
-        "audit",
-    )?;
-
-    let channel_events_dir = data_dir.join("channel-events");
-    if channel_events_dir.exists() {
-        for entry in std::fs::read_dir(&channel_events_dir)? {
-            let path = entry?.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("jsonl") {
-                continue;
-            }
-            let source = format!(
-                "channel:{}",
-                path.file_stem()
-                    .and_then(|value| value.to_str())
-                    .unwrap_or("unknown")
-            );
-            load_jsonl_file(&mut entries, &path, &source)?;
+fn newest(mut entries: Vec<UnifiedLogEntry>, tail: usize) -> Vec<UnifiedLogEntry> {
+    entries.sort_by_key(|entry| entry.ts);
+    if entries.len() > tail {
+        let keep_from = entries.len() - tail;
+        entries = entries.split_off(keep_from);
+    }
+    entries
+}
+
+/// Tails the JSONL logs under a data directory. Files that exist when the
+/// follower starts are read from their current end; files created later are
+/// read from the beginning. Browser history is a rewritten JSON array rather
+/// than an appended log, so it is not followed.
+#[derive(Debug)]
+pub struct LogFollower {
+    data_dir: PathBuf,
+    offsets: HashMap<PathBuf, u64>,
+}
+
+impl LogFollower {
+    pub fn new(data_dir: &Path) -> Result<Self> {
+        let mut offsets = HashMap::new();
+        for (path, _) in log_sources(data_dir)? {
+            let len = std::fs::metadata(&path)?.len();
+            offsets.insert(path, len);
         }
+        Ok(Self {
+            data_dir: data_dir.to_path_buf(),
+            offsets,
+        })
     }
 
-    let hook_events_dir = data_dir.join("hook-events");
-    if hook_events_dir.exists() {
-        for entry in std::fs::read_dir(&hook_events_dir)? {
-            let path = entry?.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("jsonl") {
-                continue;
-            }
-            let source = format!(
-                "hook:{}",
-                path.file_stem()
-                    .and_then(|value| value.to_str())
-                    .unwrap_or("unknown")
-            );
-            load_jsonl_file(&mut entries, &path, &source)?;
+    /// Collects the newest `tail` entries like [`collect_logs`] and returns a
+    /// follower that resumes where that read stopped in each file, so a line
+    /// appended in between is reported exactly once. A trailing partial line
+    /// is left for the follower.
+    pub fn snapshot(data_dir: &Path, tail: usize) -> Result<(Vec<UnifiedLogEntry>, Self)> {
+        let mut entries = Vec::new();
+        let mut offsets = HashMap::new();
+        for (path, source) in log_sources(data_dir)? {
+            let raw = std::fs::read(&path)?;
+            let end = raw
+                .iter()
+                .rposition(|byte| *byte == b'\n')
+                .map_or(0, |last_newline| last_newline + 1);
+            push_jsonl_lines(&mut entries, &String::from_utf8_lossy(&raw[..end]), &source);
+            offsets.insert(path, end as u64);
         }
+        load_browser_history_file(&mut entries, &data_dir.join("browser-history.json"))?;
+        let follower = Self {
+            data_dir: data_dir.to_path_buf(),
+            offsets,
+        };
+        Ok((newest(entries, tail), follower))
     }
 
-    let cron_events_dir = data_dir.join("cron-events");
-    if cron_events_dir.exists() {
-        for entry in std::fs::read_dir(&cron_events_dir)? {
-            let path = entry?.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("jsonl") {
-                continue;
+    /// Returns complete lines appended since the last poll, ordered by
+    /// timestamp. A trailing partial line is left for the next poll.
+    pub fn poll(&mut self) -> Result<Vec<UnifiedLogEntry>> {
+        let mut entries = Vec::new();
+        for (path, source) in log_sources(&self.data_dir)? {
+            let offset = self.offsets.entry(path.clone()).or_insert(0);
+            let mut file = File::open(&path)?;
+            if file.metadata()?.len() < *offset {
+                // The file was truncated or replaced; start over.
+                *offset = 0;
             }
-            let source = format!(
-                "cron:{}",
-                path.file_stem()
-                    .and_then(|value| value.to_str())
-                    .unwrap_or("unknown")
-            );
-            load_jsonl_file(&mut entries, &path, &source)?;
+            file.seek(SeekFrom::Start(*offset))?;
+            let mut appended = Vec::new();
+            file.read_to_end(&mut appended)?;
+            let Some(last_newline) = appended.iter().rposition(|byte| *byte == b'\n') else {
+                continue;
+            };
+            *offset += last_newline as u64 + 1;
+            let complete = String::from_utf8_lossy(&appended[..=last_newline]);
+            push_jsonl_lines(&mut entries, &complete, &source);
         }
+        entries.sort_by_key(|entry| entry.ts);
+        Ok(entries)
     }
 
-    let webhook_events_dir = data_dir.join("webhook-events");
-    if webhook_events_dir.exists() {
-        for entry in std::fs::read_dir(&webhook_events_dir)? {
-            let path = entry?.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("jsonl") {
-                continue;
+    /// Sends newly appended log entries to `tx` every `interval` until the
+    /// receiver is dropped or reading the logs fails.
+    pub fn follow(mut self, interval: Duration, tx: Sender<UnifiedLogEntry>) -> Result<()> {
+        loop {
+            for entry in self.poll()? {
+                if tx.send(entry).is_err() {
+                    return Ok(());
+                }
             }
-            let source = format!(
-                "webhook:{}",
-                path.file_stem()
-                    .and_then(|value| value.to_str())
-                    .unwrap_or("unknown")
-            );
-            load_jsonl_file(&mut entries, &path, &source)?;
+            std::thread::sleep(interval);
+        }
+    }
+}
+
+/// Follows the logs under `data_dir` from their current end; see
+/// [`LogFollower::follow`].
+pub fn follow_logs(data_dir: &Path, interval: Duration, tx: Sender<UnifiedLogEntry>) -> Result<()> {
+    LogFollower::new(data_dir)?.follow(interval, tx)
+}
+
+/// Existing JSONL log files paired with their source label.
+fn log_sources(data_dir: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let mut sources = Vec::new();
+    for (relative, source) in [
+        ("system-events.jsonl", "system"),
+        ("audit/commands.jsonl", "audit"),
+        ("audit/approvals.jsonl", "approval"),
+    ] {
+        let path = data_dir.join(relative);
+        if path.exists() {
+            sources.push((path, source.to_string()));
         }
     }
 
-    let plugin_events_dir = data_dir.join("plugin-events");
-    if plugin_events_dir.exists() {
-        for entry in std::fs::read_dir(&plugin_events_dir)? {
+    for (dir_name, prefix) in [
+        ("channel-events", "channel"),
+        ("hook-events", "hook"),
+        ("cron-events", "cron"),
+        ("webhook-events", "webhook"),
+        ("plugin-events", "plugin"),
+    ] {
+        let dir = data_dir.join(dir_name);
+        if !dir.exists() {
+            continue;
+        }
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
             let path = entry?.path();
-            if path.extension().and_then(|value| value.to_str()) != Some("jsonl") {
-                continue;
+            if path.extension().and_then(|value| value.to_str()) == Some("jsonl") {
+                paths.push(path);
             }
+        }
+        paths.sort();
+        for path in paths {
             let source = format!(
-                "plugin:{}",
+                "{prefix}:{}",
                 path.file_stem()
                     .and_then(|value| value.to_str())
                     .unwrap_or("unknown")
             );
-            load_jsonl_file(&mut entries, &path, &source)?;
+            sources.push((path, source));
         }
     }
-
-    load_browser_history_file(&mut entries, &data_dir.join("browser-history.json"))?;
-
-    entries.sort_by(|lhs, rhs| lhs.ts.cmp(&rhs.ts));
-    if entries.len() > tail {
-        let keep_from = entries.len() - tail;
-        entries = entries.split_off(keep_from);
-    }
-    Ok(entries)
+    Ok(sources)
 }
 
 fn load_browser_history_file(entries: &mut Vec<UnifiedLogEntry>, path: &Path) -> Result<()> {
@@ -149,6 +201,11 @@ fn load_jsonl_file(entries: &mut Vec<UnifiedLogEntry>, path: &Path, source: &str
         return Ok(());
     }
     let raw = std::fs::read_to_string(path)?;
+    push_jsonl_lines(entries, &raw, source);
+    Ok(())
+}
+
+fn push_jsonl_lines(entries: &mut Vec<UnifiedLogEntry>, raw: &str, source: &str) {
     for line in raw.lines() {
         if line.trim().is_empty() {
             continue;
@@ -162,7 +219,6 @@ fn load_jsonl_file(entries: &mut Vec<UnifiedLogEntry>, path: &Path, source: &str
             payload,
         });
     }
-    Ok(())
 }
 
 fn parse_ts(value: &str) -> Option<DateTime<Utc>> {
@@ -174,6 +230,7 @@ fn parse_ts(value: &str) -> Option<DateTime<Utc>> {
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::io::Write;
 
     use serde_json::json;
     use tempfile::tempdir;
@@ -187,6 +244,101 @@ mod tests {
         assert!(logs.is_empty());
     }
 
+    #[test]
+    fn snapshot_follower_resumes_after_snapshot_without_repeats() {
+        let temp = tempdir().expect("tempdir");
+        let events_path = temp.path().join("system-events.jsonl");
+        fs::write(
+            &events_path,
+            format!(
+                "{}\n{{\"ts\":\"2026-02-22T00:00:01Z\",",
+                json!({ "ts": "2026-02-22T00:00:00Z", "name": "old" })
+            ),
+        )
+        .expect("write events");
+
+        let (entries, mut follower) = LogFollower::snapshot(temp.path(), 10).expect("snapshot");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].payload["name"], "old");
+        assert!(follower.poll().expect("poll").is_empty());
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&events_path)
+            .expect("open events");
+        writeln!(file, "\"name\":\"partial\"}}").expect("finish line");
+        let polled = follower.poll().expect("poll");
+        assert_eq!(polled.len(), 1);
+        assert_eq!(polled[0].payload["name"], "partial");
+    }
+
+    #[test]
+    fn log_follower_delivers_lines_appended_after_start() {
+        let temp = tempdir().expect("tempdir");
+        let events_path = temp.path().join("system-events.jsonl");
+        fs::write(
+            &events_path,
+            format!(
+                "{}\n",
+                json!({ "ts": "2026-02-22T00:00:00Z", "name": "old" })
+            ),
+        )
+        .expect("write existing event");
+
+        let follower = LogFollower::new(temp.path()).expect("follower");
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || follower.follow(Duration::from_millis(10), tx));
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&events_path)
+            .expect("open events");
+        writeln!(
+            file,
+            "{}",
+            json!({ "ts": "2026-02-22T00:00:01Z", "name": "new" })
+        )
+        .expect("append event");
+        let hooks_dir = temp.path().join("hook-events");
+        fs::create_dir_all(&hooks_dir).expect("create hook-events dir");
+        fs::write(
+            hooks_dir.join("hk-1.jsonl"),
+            format!(
+                "{}\n",
+                json!({ "ts": "2026-02-22T00:00:02Z", "hook_id": "hk-1" })
+            ),
+        )
+        .expect("write hook event");
+
+        let first = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("appended entry");
+        assert_eq!(first.source, "system");
+        assert_eq!(first.payload["name"], "new");
+        let second = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("new file entry");
+        assert_eq!(second.source, "hook:hk-1");
+    }
+
+    #[test]
+    fn log_follower_waits_for_complete_lines() {
+        let temp = tempdir().expect("tempdir");
+        let mut follower = LogFollower::new(temp.path()).expect("follower");
+        let path = temp.path().join("system-events.jsonl");
+        fs::write(&path, "{\"name\":\"par").expect("write partial line");
+        assert!(follower.poll().expect("poll").is_empty());
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open events");
+        file.write_all(b"tial\"}\n").expect("finish line");
+        let entries = follower.poll().expect("poll");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].payload["name"], "partial");
+    }
+
     #[test]
     fn collect_logs_includes_hook_events() {
         let temp = tempdir().expect("tempdir");
@@ -212,6 +364,31 @@ mod tests {
         assert_eq!(logs[0].source, "hook:hk-1");
     }
 
+    #[test]
+    fn collect_logs_includes_approval_audit() {
+        let temp = tempdir().expect("tempdir");
+        let audit_dir = temp.path().join("audit");
+        fs::create_dir_all(&audit_dir).expect("create audit dir");
+        fs::write(
+            audit_dir.join("approvals.jsonl"),
+            format!(
+                "{}\n",
+                json!({
+                    "ts": "2026-02-22T00:00:00Z",
+                    "command": "git push",
+                    "decision": "deny",
+                    "reason": "approval mode is set to deny",
+                })
+            ),
+        )
+        .expect("write approval audit");
+
+        let logs = collect_logs(temp.path(), 50).expect("collect logs");
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].source, "approval");
+        assert_eq!(logs[0].payload["decision"], "deny");
+    }
+
     #[test]
     fn collect_logs_includes_cron_events() {
         let temp = tempdir().expect("tempdir");
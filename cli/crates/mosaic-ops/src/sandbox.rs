@@ -1,11 +1,19 @@
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::write_pretty_state_toml_file;
 
+use crate::command_paths::{command_paths, command_words, is_env_assignment};
+
 const CURRENT_SANDBOX_POLICY_VERSION: u32 = 1;
+const CUSTOM_SANDBOX_PROFILES_FILE: &str = "sandbox-profiles.toml";
+const HTTP_FETCH_PATTERNS: [&str; 2] = ["curl ", "wget "];
+const NETWORK_COMMAND_PATTERNS: [&str; 5] = ["ssh ", "scp ", "nc ", "ncat ", "telnet "];
+const SYSTEM_COMMAND_PATTERNS: [&str; 5] =
+    ["docker ", "kubectl ", "sudo ", "brew install", "apt-get"];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -15,17 +23,88 @@ pub enum SandboxProfile {
     Elevated,
 }
 
+impl SandboxProfile {
+    pub const ALL: [Self; 3] = [Self::Restricted, Self::Standard, Self::Elevated];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Restricted => "restricted",
+            Self::Standard => "standard",
+            Self::Elevated => "elevated",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.as_str() == name.trim())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxNetworkAccess {
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// A user-defined profile from `sandbox-profiles.toml`, selected through
+/// [`SandboxPolicy::custom_profile`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomSandboxProfile {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub network: SandboxNetworkAccess,
+    /// Substrings that deny any command containing them.
+    #[serde(default)]
+    pub blocked_commands: Vec<String>,
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+    #[serde(default)]
+    pub readable_paths: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CustomSandboxProfilesFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, CustomSandboxProfile>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxPolicy {
     pub version: u32,
     pub profile: SandboxProfile,
+    /// Hosts that `curl`/`wget` may reach even under `restricted`. A leading
+    /// `*.` also matches subdomains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// Roots that commands may write under; empty means unrestricted.
+    /// Relative roots such as `.` are relative to the command's working
+    /// directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_paths: Vec<String>,
+    /// Roots that commands may read from; empty means unrestricted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub readable_paths: Vec<String>,
+    /// Custom profile to apply instead of `profile`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_profile: Option<String>,
+    /// Definition of `custom_profile`, filled in by [`SandboxStore`] on load.
+    #[serde(skip)]
+    pub custom: Option<CustomSandboxProfile>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SandboxProfileInfo {
-    pub profile: SandboxProfile,
+    pub profile: String,
     pub description: String,
     pub blocked_examples: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub custom: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -38,6 +117,11 @@ impl Default for SandboxPolicy {
         Self {
             version: CURRENT_SANDBOX_POLICY_VERSION,
             profile: SandboxProfile::Standard,
+            allowed_hosts: Vec::new(),
+            writable_paths: Vec::new(),
+            readable_paths: Vec::new(),
+            custom_profile: None,
+            custom: None,
         }
     }
 }
@@ -50,8 +134,96 @@ impl SandboxPolicy {
                 self.version, CURRENT_SANDBOX_POLICY_VERSION
             )));
         }
+        validate_entries("allowed_hosts", &self.allowed_hosts)?;
+        validate_entries("writable_paths", &self.writable_paths)?;
+        validate_entries("readable_paths", &self.readable_paths)?;
+        if let Some(name) = &self.custom_profile
+            && SandboxProfile::from_name(name).is_some()
+        {
+            return Err(MosaicError::Config(format!(
+                "custom_profile '{name}' names a built-in sandbox profile; set profile instead"
+            )));
+        }
         Ok(())
     }
+
+    /// Name of the effective profile: the custom profile when one is
+    /// selected, otherwise the built-in one.
+    pub fn profile_name(&self) -> String {
+        self.custom_profile
+            .clone()
+            .unwrap_or_else(|| self.profile.as_str().to_string())
+    }
+
+    fn custom_entries<'a>(
+        &'a self,
+        own: &'a [String],
+        field: fn(&CustomSandboxProfile) -> &Vec<String>,
+    ) -> Vec<String> {
+        own.iter()
+            .chain(self.custom.iter().flat_map(field))
+            .cloned()
+            .collect()
+    }
+
+    fn allows_host(&self, host: &str) -> bool {
+        let allowed_hosts =
+            self.custom_entries(&self.allowed_hosts, |custom| &custom.allowed_hosts);
+        allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.trim().to_lowercase();
+            match allowed.strip_prefix("*.") {
+                Some(domain) => host.ends_with(&format!(".{domain}")),
+                None => host == allowed,
+            }
+        })
+    }
+}
+
+impl CustomSandboxProfile {
+    fn validate(&self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(MosaicError::Config(
+                "custom sandbox profile name cannot be empty".to_string(),
+            ));
+        }
+        if SandboxProfile::from_name(name).is_some() {
+            return Err(MosaicError::Config(format!(
+                "custom sandbox profile '{name}' conflicts with a built-in profile"
+            )));
+        }
+        validate_entries("blocked_commands", &self.blocked_commands)?;
+        validate_entries("allowed_hosts", &self.allowed_hosts)?;
+        validate_entries("writable_paths", &self.writable_paths)?;
+        validate_entries("readable_paths", &self.readable_paths)
+    }
+
+    fn info(&self, name: &str) -> SandboxProfileInfo {
+        let mut blocked_examples = self.blocked_commands.clone();
+        if self.network == SandboxNetworkAccess::Deny {
+            blocked_examples.push("curl https://...".to_string());
+            blocked_examples.push("ssh user@host".to_string());
+        }
+        let description = if self.description.trim().is_empty() {
+            "User-defined sandbox profile".to_string()
+        } else {
+            self.description.clone()
+        };
+        SandboxProfileInfo {
+            profile: name.to_string(),
+            description,
+            blocked_examples,
+            custom: true,
+        }
+    }
+}
+
+fn validate_entries(field: &str, entries: &[String]) -> Result<()> {
+    if entries.iter().any(|entry| entry.trim().is_empty()) {
+        return Err(MosaicError::Config(format!(
+            "sandbox {field} entries cannot be empty"
+        )));
+    }
+    Ok(())
 }
 
 impl SandboxStore {
@@ -63,6 +235,56 @@ impl SandboxStore {
         &self.path
     }
 
+    /// Custom profiles live next to the policy file.
+    pub fn profiles_path(&self) -> PathBuf {
+        self.path.with_file_name(CUSTOM_SANDBOX_PROFILES_FILE)
+    }
+
+    pub fn load_custom_profiles(&self) -> Result<BTreeMap<String, CustomSandboxProfile>> {
+        let path = self.profiles_path();
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let raw = std::fs::read_to_string(&path)?;
+        let file: CustomSandboxProfilesFile = toml::from_str(&raw).map_err(|err| {
+            MosaicError::Config(format!(
+                "invalid sandbox profiles {}: {err}",
+                path.display()
+            ))
+        })?;
+        for (name, profile) in &file.profiles {
+            profile.validate(name)?;
+        }
+        Ok(file.profiles)
+    }
+
+    /// Built-in profiles followed by custom ones.
+    pub fn list_profiles(&self) -> Result<Vec<SandboxProfileInfo>> {
+        let mut profiles = list_profiles();
+        profiles.extend(
+            self.load_custom_profiles()?
+                .iter()
+                .map(|(name, profile)| profile.info(name)),
+        );
+        Ok(profiles)
+    }
+
+    pub fn profile_info(&self, name: &str) -> Result<SandboxProfileInfo> {
+        if let Some(profile) = SandboxProfile::from_name(name) {
+            return Ok(profile_info(profile));
+        }
+        Ok(self.custom_profile(name)?.info(name))
+    }
+
+    fn custom_profile(&self, name: &str) -> Result<CustomSandboxProfile> {
+        self.load_custom_profiles()?.remove(name).ok_or_else(|| {
+            MosaicError::Config(format!(
+                "sandbox profile '{name}' is not defined in {}",
+                self.profiles_path().display()
+            ))
+        })
+    }
+
     pub fn load_or_default(&self) -> Result<SandboxPolicy> {
         if !self.path.exists() {
             return Ok(SandboxPolicy::default());
@@ -75,6 +297,10 @@ impl SandboxStore {
             ))
         })?;
         policy.validate()?;
+        let mut policy = policy;
+        if let Some(name) = &policy.custom_profile {
+            policy.custom = Some(self.custom_profile(name)?);
+        }
         Ok(policy)
     }
 
@@ -88,45 +314,450 @@ impl SandboxStore {
     pub fn set_profile(&self, profile: SandboxProfile) -> Result<SandboxPolicy> {
         let mut policy = self.load_or_default()?;
         policy.profile = profile;
+        policy.custom_profile = None;
+        policy.custom = None;
+        self.save(&policy)?;
+        Ok(policy)
+    }
+
+    /// Selects a built-in profile by name, or a custom profile otherwise.
+    pub fn set_named_profile(&self, name: &str) -> Result<SandboxPolicy> {
+        let name = name.trim();
+        if let Some(profile) = SandboxProfile::from_name(name) {
+            return self.set_profile(profile);
+        }
+        let custom = self.custom_profile(name)?;
+        let mut policy = self.load_or_default()?;
+        policy.custom_profile = Some(name.to_string());
+        policy.custom = Some(custom);
         self.save(&policy)?;
         Ok(policy)
     }
 }
 
+/// Evaluates `command` against a bare profile with no allowed hosts.
 pub fn evaluate_sandbox(command: &str, profile: SandboxProfile) -> Option<String> {
-    if profile != SandboxProfile::Restricted {
+    evaluate_sandbox_policy(
+        command,
+        &SandboxPolicy {
+            profile,
+            ..SandboxPolicy::default()
+        },
+    )
+}
+
+/// Path roots apply to every profile. A custom profile then applies its own
+/// blocked commands and network setting; of the built-ins only `restricted`
+/// blocks anything. Without a working directory, relative paths are only
+/// compared with relative roots; see [`evaluate_sandbox_policy_in`].
+pub fn evaluate_sandbox_policy(command: &str, policy: &SandboxPolicy) -> Option<String> {
+    evaluate_sandbox_policy_at(command, policy, None)
+}
+
+/// Like [`evaluate_sandbox_policy`] for a command run in `cwd`: relative
+/// targets and roots are resolved against it, so `out.txt` is checked against
+/// an absolute root such as `/work`.
+pub fn evaluate_sandbox_policy_in(
+    command: &str,
+    policy: &SandboxPolicy,
+    cwd: &Path,
+) -> Option<String> {
+    evaluate_sandbox_policy_at(command, policy, Some(cwd))
+}
+
+fn evaluate_sandbox_policy_at(
+    command: &str,
+    policy: &SandboxPolicy,
+    cwd: Option<&Path>,
+) -> Option<String> {
+    if let Some(reason) = evaluate_sandbox_paths(command, policy, cwd) {
+        return Some(reason);
+    }
+    let normalized = command.trim().to_lowercase();
+
+    if let Some(custom) = &policy.custom {
+        let name = policy.profile_name();
+        if let Some(pattern) = custom
+            .blocked_commands
+            .iter()
+            .map(|pattern| pattern.trim().to_lowercase())
+            .find(|pattern| normalized.contains(pattern.as_str()))
+        {
+            return Some(format!(
+                "sandbox profile '{name}' blocks command '{pattern}'"
+            ));
+        }
+        if custom.network == SandboxNetworkAccess::Deny
+            && blocks_network(command, &normalized, policy)
+        {
+            return Some(format!("sandbox profile '{name}' blocks network commands"));
+        }
         return None;
     }
 
-    let normalized = command.trim().to_lowercase();
-    let blocked_patterns = [
-        "curl ",
-        "wget ",
-        "ssh ",
-        "scp ",
-        "nc ",
-        "ncat ",
-        "telnet ",
-        "docker ",
-        "kubectl ",
-        "sudo ",
-        "brew install",
-        "apt-get",
-    ];
-    if blocked_patterns
+    if policy.profile != SandboxProfile::Restricted {
+        return None;
+    }
+    if SYSTEM_COMMAND_PATTERNS
         .iter()
-        .any(|pattern| normalized.starts_with(pattern) || normalized.contains(pattern))
+        .any(|pattern| normalized.contains(pattern))
+        || blocks_network(command, &normalized, policy)
     {
         return Some("sandbox profile 'restricted' blocks network/system commands".to_string());
     }
+    None
+}
 
+/// Whether `command` uses the network other than to fetch from allowed
+/// hosts. Fetches pass only when every target in the command names an
+/// allowed host; without a recognizable target, or when a proxy or config
+/// file could redirect the fetch, the target is unknown.
+fn blocks_network(command: &str, normalized: &str, policy: &SandboxPolicy) -> bool {
+    if NETWORK_COMMAND_PATTERNS
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+    {
+        return true;
+    }
+    if !HTTP_FETCH_PATTERNS
+        .iter()
+        .any(|pattern| normalized.contains(pattern))
+    {
+        return false;
+    }
+    match command_fetch_hosts(command.trim()) {
+        Some(hosts) => hosts.is_empty() || !hosts.iter().all(|host| policy.allows_host(host)),
+        None => true,
+    }
+}
+
+fn evaluate_sandbox_paths(
+    command: &str,
+    policy: &SandboxPolicy,
+    cwd: Option<&Path>,
+) -> Option<String> {
+    let writable = policy.custom_entries(&policy.writable_paths, |custom| &custom.writable_paths);
+    let readable = policy.custom_entries(&policy.readable_paths, |custom| &custom.readable_paths);
+    if writable.is_empty() && readable.is_empty() {
+        return None;
+    }
+    let paths = command_paths(command);
+    for (access, field, roots, targets) in [
+        ("write", "writable_paths", &writable, &paths.writes),
+        ("read", "readable_paths", &readable, &paths.reads),
+    ] {
+        if roots.is_empty() {
+            continue;
+        }
+        if let Some(target) = targets
+            .iter()
+            .find(|target| !path_within_roots(target, roots, cwd))
+        {
+            return Some(format!(
+                "sandbox {field} do not allow {access} access to '{target}'"
+            ));
+        }
+    }
     None
 }
 
+/// Lexical containment check: absolute, `~`-relative and relative targets
+/// are each compared only with roots of the same kind. With a `cwd`, relative
+/// targets and roots are first made absolute against it. Targets built from
+/// shell expansions cannot be resolved here, so they count as outside.
+fn path_within_roots(target: &str, roots: &[String], cwd: Option<&Path>) -> bool {
+    const DEVICE_PATHS: [&str; 4] = ["/dev/null", "/dev/stdout", "/dev/stderr", "/dev/tty"];
+    if DEVICE_PATHS.contains(&target) {
+        return true;
+    }
+    if target.contains('$') || target.contains('`') {
+        return false;
+    }
+    let resolve = |path: &str| {
+        let path = Path::new(path);
+        match cwd {
+            Some(cwd) if !path.has_root() && !path.starts_with("~") => {
+                normalize_lexically(&cwd.join(path))
+            }
+            _ => normalize_lexically(path),
+        }
+    };
+    let Some(target) = resolve(target) else {
+        return false;
+    };
+    let anchor = |path: &Path| (path.has_root(), path.starts_with("~"));
+    roots.iter().any(|root| {
+        resolve(root.trim())
+            .is_some_and(|root| anchor(&root) == anchor(&target) && target.starts_with(&root))
+    })
+}
+
+/// Resolves `.` and `..` without touching the filesystem; `None` when a
+/// relative path climbs above its starting directory.
+fn normalize_lexically(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 {
+                    if normalized.has_root() {
+                        continue;
+                    }
+                    return None;
+                }
+                normalized.pop();
+                depth -= 1;
+            }
+            Component::Normal(part) => {
+                normalized.push(part);
+                depth += 1;
+            }
+            Component::RootDir | Component::Prefix(_) => normalized.push(component),
+        }
+    }
+    Some(normalized)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FetchTool {
+    Curl,
+    Wget,
+}
+
+impl FetchTool {
+    fn from_token(token: &str) -> Option<Self> {
+        match token.rsplit('/').next() {
+            Some("curl") => Some(Self::Curl),
+            Some("wget") => Some(Self::Wget),
+            _ => None,
+        }
+    }
+
+    /// Options that route the fetch through another host, follow redirects
+    /// to hosts not on the command line, or read further options (and
+    /// targets) from somewhere the sandbox cannot see.
+    fn denies_long(self, name: &str) -> bool {
+        match self {
+            Self::Curl => {
+                name.starts_with("proxy")
+                    || name.starts_with("preproxy")
+                    || name.starts_with("socks")
+                    || matches!(
+                        name,
+                        "config"
+                            | "next"
+                            | "connect-to"
+                            | "resolve"
+                            | "doh-url"
+                            | "location"
+                            | "location-trusted"
+                    )
+            }
+            Self::Wget => matches!(name, "execute" | "input-file" | "config"),
+        }
+    }
+
+    fn denies_short(self, flag: char) -> bool {
+        match self {
+            Self::Curl => matches!(flag, 'x' | 'K' | ':' | 'L'),
+            Self::Wget => matches!(flag, 'e' | 'i'),
+        }
+    }
+
+    fn long_takes_value(self, name: &str) -> bool {
+        match self {
+            Self::Curl => matches!(
+                name,
+                "output"
+                    | "output-dir"
+                    | "header"
+                    | "data"
+                    | "data-raw"
+                    | "data-binary"
+                    | "data-ascii"
+                    | "data-urlencode"
+                    | "request"
+                    | "user"
+                    | "user-agent"
+                    | "referer"
+                    | "cookie"
+                    | "cookie-jar"
+                    | "form"
+                    | "form-string"
+                    | "max-time"
+                    | "connect-timeout"
+                    | "write-out"
+                    | "range"
+                    | "retry"
+                    | "retry-delay"
+                    | "retry-max-time"
+                    | "upload-file"
+                    | "continue-at"
+                    | "limit-rate"
+                    | "max-filesize"
+                    | "cert"
+                    | "key"
+                    | "cacert"
+                    | "capath"
+                    | "oauth2-bearer"
+            ),
+            Self::Wget => matches!(
+                name,
+                "output-document"
+                    | "output-file"
+                    | "append-output"
+                    | "directory-prefix"
+                    | "user-agent"
+                    | "timeout"
+                    | "tries"
+                    | "wait"
+                    | "quota"
+                    | "header"
+                    | "user"
+                    | "password"
+                    | "http-user"
+                    | "http-password"
+                    | "method"
+                    | "body-data"
+                    | "post-data"
+                    | "post-file"
+                    | "referer"
+                    | "load-cookies"
+                    | "save-cookies"
+            ),
+        }
+    }
+
+    fn short_takes_value(self, flag: char) -> bool {
+        match self {
+            Self::Curl => matches!(
+                flag,
+                'o' | 'H'
+                    | 'd'
+                    | 'X'
+                    | 'u'
+                    | 'A'
+                    | 'e'
+                    | 'b'
+                    | 'c'
+                    | 'F'
+                    | 'm'
+                    | 'w'
+                    | 'r'
+                    | 'T'
+                    | 'C'
+                    | 'E'
+                    | 'Y'
+                    | 'y'
+                    | 'z'
+            ),
+            Self::Wget => matches!(flag, 'O' | 'o' | 'a' | 'P' | 'U' | 'T' | 't' | 'w' | 'Q'),
+        }
+    }
+}
+
+/// Hosts targeted by the `curl`/`wget` invocations in `command`. Every
+/// positional argument counts as a target (as does `--url URL`/`--url=URL`),
+/// so a bare `evil.com` next to an allowed URL is still checked. Returns
+/// `None` when an invocation uses a proxy, config-file, or `--next` option,
+/// or a `*_proxy=` environment assignment, or may follow a redirect (curl
+/// `-L`/`--location`, wget without `--max-redirect=0`), since the real
+/// target is then not on the command line.
+fn command_fetch_hosts(command: &str) -> Option<Vec<String>> {
+    let mut hosts = Vec::new();
+    for words in command_words(command) {
+        if words.iter().any(|word| {
+            is_env_assignment(word)
+                && word
+                    .split_once('=')
+                    .is_some_and(|(name, _)| name.to_ascii_lowercase().ends_with("_proxy"))
+        }) {
+            return None;
+        }
+        let mut words = words
+            .iter()
+            .map(|word| word.trim_matches(|ch| matches!(ch, '$' | '(' | ')' | '`')));
+        let Some(tool) = words.by_ref().find_map(FetchTool::from_token) else {
+            continue;
+        };
+        let mut skip_value = false;
+        let mut max_redirect_next = false;
+        // wget follows redirects unless told not to; curl only with -L.
+        let mut follows_redirects = tool == FetchTool::Wget;
+        for word in words.filter(|word| !word.is_empty()) {
+            if std::mem::take(&mut max_redirect_next) {
+                follows_redirects = word != "0";
+                continue;
+            }
+            if std::mem::take(&mut skip_value) {
+                continue;
+            }
+            if let Some(long) = word.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (long, None),
+                };
+                if tool.denies_long(name) {
+                    return None;
+                }
+                if name == "url" {
+                    if let Some(value) = value {
+                        hosts.push(url_host(value).unwrap_or_default());
+                    }
+                } else if tool == FetchTool::Wget && name == "max-redirect" {
+                    match value {
+                        Some(value) => follows_redirects = value != "0",
+                        None => max_redirect_next = true,
+                    }
+                } else if value.is_none() && tool.long_takes_value(name) {
+                    skip_value = true;
+                }
+            } else if let Some(flags) = word.strip_prefix('-')
+                && !flags.is_empty()
+            {
+                for (index, flag) in flags.char_indices() {
+                    if tool.denies_short(flag) {
+                        return None;
+                    }
+                    if tool.short_takes_value(flag) {
+                        skip_value = index + flag.len_utf8() == flags.len();
+                        break;
+                    }
+                }
+            } else {
+                hosts.push(url_host(word).unwrap_or_default());
+            }
+        }
+        if follows_redirects {
+            return None;
+        }
+    }
+    Some(hosts)
+}
+
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host_port.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => host_port.split(':').next()?,
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
 pub fn profile_info(profile: SandboxProfile) -> SandboxProfileInfo {
     match profile {
         SandboxProfile::Restricted => SandboxProfileInfo {
-            profile,
+            profile: profile.as_str().to_string(),
             description:
                 "Disallow network/system-impacting shell commands and require local-only execution"
                     .to_string(),
@@ -135,41 +766,245 @@ pub fn profile_info(profile: SandboxProfile) -> SandboxProfileInfo {
                 "ssh user@host".to_string(),
                 "docker build .".to_string(),
             ],
+            custom: false,
         },
         SandboxProfile::Standard => SandboxProfileInfo {
-            profile,
+            profile: profile.as_str().to_string(),
             description:
                 "Allow standard local development commands; high-risk actions still need approval"
                     .to_string(),
             blocked_examples: Vec::new(),
+            custom: false,
         },
         SandboxProfile::Elevated => SandboxProfileInfo {
-            profile,
+            profile: profile.as_str().to_string(),
             description: "Least restrictive profile for trusted controlled environments"
                 .to_string(),
             blocked_examples: Vec::new(),
+            custom: false,
         },
     }
 }
 
 pub fn list_profiles() -> Vec<SandboxProfileInfo> {
-    vec![
-        profile_info(SandboxProfile::Restricted),
-        profile_info(SandboxProfile::Standard),
-        profile_info(SandboxProfile::Elevated),
-    ]
+    SandboxProfile::ALL.into_iter().map(profile_info).collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use tempfile::tempdir;
+
     use super::*;
 
+    #[test]
+    fn custom_profile_is_loaded_listed_and_evaluated() {
+        let temp = tempdir().expect("tempdir");
+        let store = SandboxStore::new(temp.path().join("sandbox.toml"));
+        std::fs::write(
+            store.profiles_path(),
+            r#"
+[profiles.ci]
+description = "CI runners: internal network only"
+network = "deny"
+allowed_hosts = ["artifacts.internal"]
+writable_paths = [".", "/tmp"]
+blocked_commands = ["git push"]
+"#,
+        )
+        .expect("write profiles");
+
+        let policy = store
+            .set_named_profile("ci")
+            .expect("select custom profile");
+        assert_eq!(policy.profile_name(), "ci");
+        let policy = store.load_or_default().expect("reload policy");
+        assert_eq!(policy.custom_profile.as_deref(), Some("ci"));
+
+        let deny = |command: &str| evaluate_sandbox_policy(command, &policy);
+        assert!(deny("git push origin main").is_some_and(|reason| reason.contains("git push")));
+        assert!(deny("curl https://example.com").is_some_and(|reason| reason.contains("'ci'")));
+        assert!(deny("echo x > /etc/hosts").is_some());
+        assert_eq!(
+            deny("curl -o /tmp/a.tgz https://artifacts.internal/a.tgz"),
+            None
+        );
+        assert_eq!(deny("docker build ."), None);
+
+        let profiles = store.list_profiles().expect("list profiles");
+        assert_eq!(profiles.len(), 4);
+        assert_eq!(profiles[3].profile, "ci");
+        assert!(profiles[3].custom);
+        assert_eq!(
+            store.profile_info("ci").expect("info").description,
+            "CI runners: internal network only"
+        );
+
+        let policy = store
+            .set_named_profile("restricted")
+            .expect("select built-in profile");
+        assert!(policy.custom_profile.is_none());
+        assert!(store.set_named_profile("missing").is_err());
+    }
+
+    #[test]
+    fn custom_profile_cannot_shadow_builtin() {
+        let temp = tempdir().expect("tempdir");
+        let store = SandboxStore::new(temp.path().join("sandbox.toml"));
+        std::fs::write(store.profiles_path(), "[profiles.standard]\n").expect("write profiles");
+        let err = store.load_custom_profiles().expect_err("conflict");
+        assert!(err.to_string().contains("built-in"));
+    }
+
     #[test]
     fn restricted_profile_blocks_network_commands() {
         let reason = evaluate_sandbox("curl https://example.com", SandboxProfile::Restricted);
         assert!(reason.is_some());
     }
 
+    #[test]
+    fn restricted_profile_allows_fetches_to_allowed_hosts() {
+        let policy = SandboxPolicy {
+            profile: SandboxProfile::Restricted,
+            allowed_hosts: vec![
+                "artifacts.internal".to_string(),
+                "*.corp.example".to_string(),
+            ],
+            ..SandboxPolicy::default()
+        };
+        for command in [
+            "curl -sS https://artifacts.internal/build.tar.gz -o build.tar.gz",
+            "curl --url 'http://artifacts.internal:8080/health'",
+            "wget -q --max-redirect=0 https://cache.corp.example/pkg.zip",
+            "curl --url=https://user@ARTIFACTS.internal/x",
+            "curl -X POST -H 'Accept: */*' https://artifacts.internal/api > out.json",
+            "curl -o out.bin artifacts.internal/file; echo done",
+            "wget --max-redirect 0 -O pkg.zip https://cache.corp.example/pkg.zip",
+        ] {
+            assert_eq!(evaluate_sandbox_policy(command, &policy), None, "{command}");
+        }
+    }
+
+    #[test]
+    fn restricted_profile_denies_fetches_to_other_hosts() {
+        let policy = SandboxPolicy {
+            profile: SandboxProfile::Restricted,
+            allowed_hosts: vec!["artifacts.internal".to_string()],
+            ..SandboxPolicy::default()
+        };
+        for command in [
+            "curl https://example.com",
+            "curl https://artifacts.internal.evil.com/x",
+            "curl https://artifacts.internal@evil.com/x",
+            "curl https://artifacts.internal/a && curl https://example.com/b",
+            "curl $TARGET",
+            "curl https://artifacts.internal | ssh host",
+            "curl https://artifacts.internal/ evil.com",
+            "curl -x http://evil.com:8080 https://artifacts.internal/",
+            "curl --proxy=evil.com https://artifacts.internal/",
+            "curl -sSx evil.com https://artifacts.internal/",
+            "curl -K /tmp/evil.cfg https://artifacts.internal/",
+            "curl --config /tmp/evil.cfg https://artifacts.internal/",
+            "curl https://artifacts.internal/ --next https://evil.com/",
+            "HTTPS_PROXY=http://evil.com curl https://artifacts.internal/",
+            "wget -e use_proxy=yes https://artifacts.internal/",
+            "wget -i urls.txt",
+            "curl -sSL https://artifacts.internal/redirect-to-evil",
+            "curl --location https://artifacts.internal/",
+            "curl --location-trusted https://artifacts.internal/",
+            "wget https://artifacts.internal/pkg.zip",
+            "wget --max-redirect=5 https://artifacts.internal/pkg.zip",
+            "wget --max-redirect=0 https://artifacts.internal/a && wget https://artifacts.internal/b",
+        ] {
+            assert!(
+                evaluate_sandbox_policy(command, &policy).is_some(),
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn workspace_only_policy_denies_writes_outside_workspace() {
+        let policy = SandboxPolicy {
+            writable_paths: vec![".".to_string()],
+            ..SandboxPolicy::default()
+        };
+        for command in [
+            "echo '127.0.0.1 evil' > /etc/hosts",
+            "echo x >> ../outside.txt",
+            "date | tee -a /var/log/app.log",
+            "cp build/app /usr/local/bin/",
+            "mv notes.txt ~/notes.txt",
+            "echo x > $HOME/.bashrc",
+            "tee ${HOME}/.ssh/authorized_keys",
+            "echo x > `pwd`/../escape",
+        ] {
+            let reason = evaluate_sandbox_policy(command, &policy);
+            assert!(
+                reason
+                    .as_deref()
+                    .is_some_and(|reason| reason.contains("writable_paths")),
+                "{command}: {reason:?}"
+            );
+        }
+        for command in [
+            "echo ok > out/result.txt 2>&1",
+            "cargo test > /dev/null",
+            "cp src/a.rs ./backup/a.rs",
+            "ls -la",
+        ] {
+            assert_eq!(evaluate_sandbox_policy(command, &policy), None, "{command}");
+        }
+    }
+
+    #[test]
+    fn relative_targets_resolve_against_cwd_for_absolute_roots() {
+        let policy = SandboxPolicy {
+            writable_paths: vec!["/work/project".to_string(), "/tmp".to_string()],
+            ..SandboxPolicy::default()
+        };
+        let cwd = Path::new("/work/project");
+        for command in ["echo ok > out/result.txt", "cp a.rs ./backup/a.rs"] {
+            assert_eq!(
+                evaluate_sandbox_policy_in(command, &policy, cwd),
+                None,
+                "{command}"
+            );
+            assert!(
+                evaluate_sandbox_policy(command, &policy).is_some(),
+                "{command}"
+            );
+        }
+        assert!(evaluate_sandbox_policy_in("echo x > ../other/file", &policy, cwd).is_some());
+        assert!(
+            evaluate_sandbox_policy_in("echo x > out.txt", &policy, Path::new("/home/me"))
+                .is_some()
+        );
+
+        let relative = SandboxPolicy {
+            writable_paths: vec![".".to_string()],
+            ..SandboxPolicy::default()
+        };
+        assert_eq!(
+            evaluate_sandbox_policy_in("echo x > /work/project/out.txt", &relative, cwd),
+            None
+        );
+    }
+
+    #[test]
+    fn readable_paths_limit_input_redirects_and_readers() {
+        let policy = SandboxPolicy {
+            readable_paths: vec![".".to_string(), "/usr/share".to_string()],
+            ..SandboxPolicy::default()
+        };
+        assert!(evaluate_sandbox_policy("wc -l < /etc/shadow", &policy).is_some());
+        assert!(evaluate_sandbox_policy("cat ~/.ssh/id_rsa", &policy).is_some());
+        assert!(evaluate_sandbox_policy("cat $HOME/.ssh/id_rsa", &policy).is_some());
+        assert_eq!(
+            evaluate_sandbox_policy("cat README.md /usr/share/dict/words", &policy),
+            None
+        );
+    }
+
     #[test]
     fn standard_profile_allows_network_commands() {
         let reason = evaluate_sandbox("curl https://example.com", SandboxProfile::Standard);
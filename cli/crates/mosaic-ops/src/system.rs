@@ -1,11 +1,18 @@
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use mosaic_core::error::{MosaicError, Result};
 use mosaic_core::privacy::append_sanitized_jsonl;
+use mosaic_core::state::write_atomic;
+
+/// Snapshots kept in the presence log; older ones are dropped when recording.
+pub const PRESENCE_HISTORY_LIMIT: usize = 500;
+/// Minimum gap between automatic records from the same host and directory.
+pub const PRESENCE_RECORD_INTERVAL_SECS: i64 = 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemEvent {
@@ -54,34 +61,129 @@ impl SystemEventStore {
     }
 
     pub fn read_tail(&self, tail: usize) -> Result<Vec<SystemEvent>> {
-        if !self.path.exists() {
-            return Ok(Vec::new());
-        }
-        let raw = std::fs::read_to_string(&self.path)?;
-        let mut events = raw
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| {
-                serde_json::from_str::<SystemEvent>(line).map_err(|err| {
-                    MosaicError::Validation(format!(
-                        "invalid system event format {}: {err}",
-                        self.path.display()
-                    ))
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let mut events = self.read_all()?;
         if events.len() > tail {
             let keep_from = events.len() - tail;
             events = events.split_off(keep_from);
         }
         Ok(events)
     }
+
+    /// Returns up to `limit` events matching `name` exactly and recorded at or
+    /// after `since`, newest first.
+    pub fn query(
+        &self,
+        name: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<SystemEvent>> {
+        let name = name.map(str::trim).filter(|name| !name.is_empty());
+        Ok(self
+            .read_all()?
+            .into_iter()
+            .rev()
+            .filter(|event| name.is_none_or(|name| event.name == name))
+            .filter(|event| since.is_none_or(|since| event.ts >= since))
+            .take(limit)
+            .collect())
+    }
+
+    fn read_all(&self) -> Result<Vec<SystemEvent>> {
+        read_jsonl(&self.path, "system event")
+    }
+}
+
+/// Reads every non-blank line of `path` as one `T`, oldest first. A missing
+/// file is empty; a malformed line fails with a validation error naming `label`.
+fn read_jsonl<T: DeserializeOwned>(path: &Path, label: &str) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = std::fs::read_to_string(path)?;
+    raw.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str::<T>(line).map_err(|err| {
+                MosaicError::Validation(format!("invalid {label} format {}: {err}", path.display()))
+            })
+        })
+        .collect()
+}
+
+/// Append-only log of presence snapshots, one per recorded CLI activity.
+#[derive(Debug, Clone)]
+pub struct PresenceStore {
+    path: PathBuf,
+}
+
+impl PresenceStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a snapshot of the current process and returns the stored entry.
+    /// The log keeps at most [`PRESENCE_HISTORY_LIMIT`] entries.
+    pub fn record(&self, cwd: &Path) -> Result<PresenceSnapshot> {
+        let snapshot = snapshot_presence(cwd);
+        append_sanitized_jsonl(&self.path, &snapshot, "presence persistence")?;
+        self.trim_to_limit()?;
+        Ok(snapshot)
+    }
+
+    /// Like [`Self::record`], but skips the write when the newest entry comes
+    /// from the same host and directory less than
+    /// [`PRESENCE_RECORD_INTERVAL_SECS`] ago. Returns the entry when written.
+    pub fn record_throttled(&self, cwd: &Path) -> Result<Option<PresenceSnapshot>> {
+        let snapshot = snapshot_presence(cwd);
+        if let Some(latest) = self.latest()?
+            && latest.cwd == snapshot.cwd
+            && latest.hostname == snapshot.hostname
+            && (snapshot.ts - latest.ts).num_seconds() < PRESENCE_RECORD_INTERVAL_SECS
+        {
+            return Ok(None);
+        }
+        self.record(cwd).map(Some)
+    }
+
+    /// Returns up to `limit` recorded snapshots, newest first.
+    pub fn presence_history(&self, limit: usize) -> Result<Vec<PresenceSnapshot>> {
+        let mut history = read_jsonl::<PresenceSnapshot>(&self.path, "presence entry")?;
+        history.reverse();
+        history.truncate(limit);
+        Ok(history)
+    }
+
+    fn trim_to_limit(&self) -> Result<()> {
+        let raw = std::fs::read_to_string(&self.path)?;
+        let lines = raw
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        if lines.len() <= PRESENCE_HISTORY_LIMIT {
+            return Ok(());
+        }
+        let mut kept = lines[lines.len() - PRESENCE_HISTORY_LIMIT..].join("\n");
+        kept.push('\n');
+        write_atomic(&self.path, kept)
+    }
+
+    pub fn latest(&self) -> Result<Option<PresenceSnapshot>> {
+        Ok(self.presence_history(1)?.into_iter().next())
+    }
 }
 
 pub fn system_events_path(data_dir: &Path) -> PathBuf {
     data_dir.join("system-events.jsonl")
 }
 
+pub fn presence_log_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("presence.jsonl")
+}
+
 pub fn snapshot_presence(cwd: &Path) -> PresenceSnapshot {
     let hostname = std::env::var("HOSTNAME")
         .or_else(|_| std::env::var("COMPUTERNAME"))
@@ -113,6 +215,94 @@ mod tests {
         assert_eq!(events[0].name, "startup");
     }
 
+    #[test]
+    fn query_filters_by_name_and_since_newest_first() {
+        let temp = tempdir().expect("tempdir");
+        let store = SystemEventStore::new(temp.path().join("events.jsonl"));
+        let old = SystemEvent {
+            ts: Utc::now() - chrono::Duration::hours(2),
+            name: "deploy".to_string(),
+            data: json!({"seq": 0}),
+        };
+        append_sanitized_jsonl(store.path(), &old, "test").expect("append old");
+        store
+            .append_event("deploy", json!({"seq": 1}))
+            .expect("append");
+        store
+            .append_event("startup", json!({"seq": 2}))
+            .expect("append");
+        store
+            .append_event("deploy", json!({"seq": 3}))
+            .expect("append");
+
+        let deploys = store.query(Some("deploy"), None, 10).expect("query name");
+        let seqs = deploys
+            .iter()
+            .map(|event| event.data["seq"].as_i64().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(seqs, vec![3, 1, 0]);
+
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let recent = store
+            .query(Some("deploy"), Some(since), 10)
+            .expect("query since");
+        assert_eq!(recent.len(), 2);
+
+        let latest = store.query(None, Some(since), 1).expect("query limit");
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].data["seq"], 3);
+    }
+
+    #[test]
+    fn presence_records_build_history_newest_first() {
+        let temp = tempdir().expect("tempdir");
+        let store = PresenceStore::new(presence_log_path(temp.path()));
+        assert!(store.latest().expect("latest").is_none());
+
+        let first = store.record(temp.path()).expect("first record");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = store.record(temp.path()).expect("second record");
+
+        let history = store.presence_history(10).expect("history");
+        assert_eq!(history.len(), 2);
+        assert!(first.ts < second.ts);
+        assert_eq!(history[0].ts, second.ts);
+        assert_eq!(history[1].ts, first.ts);
+        assert_eq!(
+            store.latest().expect("latest").map(|s| s.ts),
+            Some(second.ts)
+        );
+        assert_eq!(store.presence_history(1).expect("limited").len(), 1);
+
+        assert!(
+            store
+                .record_throttled(temp.path())
+                .expect("throttled")
+                .is_none()
+        );
+        let elsewhere = temp.path().join("elsewhere");
+        assert!(
+            store
+                .record_throttled(&elsewhere)
+                .expect("other cwd")
+                .is_some()
+        );
+        assert_eq!(store.presence_history(10).expect("history").len(), 3);
+    }
+
+    #[test]
+    fn presence_log_keeps_only_the_newest_entries() {
+        let temp = tempdir().expect("tempdir");
+        let store = PresenceStore::new(presence_log_path(temp.path()));
+        for _ in 0..PRESENCE_HISTORY_LIMIT + 5 {
+            store.record(temp.path()).expect("record");
+        }
+        let history = store
+            .presence_history(PRESENCE_HISTORY_LIMIT * 2)
+            .expect("history");
+        assert_eq!(history.len(), PRESENCE_HISTORY_LIMIT);
+    }
+
     #[test]
     fn append_event_redacts_secret_like_content() {
         let temp = tempdir().expect("tempdir");
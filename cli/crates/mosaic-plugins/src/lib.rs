@@ -1,10 +1,26 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
 use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::http::with_env_proxy;
+use mosaic_core::privacy::write_pretty_state_json_file;
+use mosaic_core::state::StatePaths;
+
+/// Optional per-file sha256 list shipped inside a plugin directory.
+const PLUGIN_CHECKSUMS_FILE: &str = "checksums.txt";
+/// Upper bound for fetching a remote plugin archive.
+const PLUGIN_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const EXTENSION_STATE_VERSION: u32 = 1;
+const EXTENSION_STATE_FILE: &str = "plugins-state.json";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -14,6 +30,16 @@ pub enum ExtensionSource {
     UserHome,
 }
 
+impl ExtensionSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::CodexHome => "codex_home",
+            Self::UserHome => "user_home",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginRuntimeConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -46,8 +72,15 @@ pub struct PluginEntry {
     pub manifest_exists: bool,
     pub manifest_valid: bool,
     pub manifest_error: Option<String>,
+    /// Manifest default (`enabled = false` ships the plugin disabled); state overrides win.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub runtime: Option<PluginRuntimeConfig>,
+    /// Set when a source-filtered listing returns a plugin that an unfiltered
+    /// listing hides behind a higher-precedence source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadowed_by: Option<ExtensionSource>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +91,15 @@ pub struct SkillEntry {
     pub source: ExtensionSource,
     pub path: String,
     pub skill_file: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Front-matter default (`enabled: false` ships the skill disabled).
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +116,9 @@ pub struct ExtensionCheckResult {
     pub source: ExtensionSource,
     pub path: String,
     pub ok: bool,
+    /// Disabled extensions are still checked but reported separately.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     pub checks: Vec<ExtensionCheckItem>,
 }
 
@@ -94,6 +139,187 @@ pub struct InstallOutcome {
     pub source_path: String,
     pub installed_path: String,
     pub replaced: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Validation ran and the outcome is what an install would do; nothing was written.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// Options for [`ExtensionRegistry::install_plugin_from_path`] and
+/// [`ExtensionRegistry::install_plugin_from_url`].
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Replace an already installed plugin with the same id.
+    pub force: bool,
+    /// Required source digest, checked before anything is unpacked or copied: the
+    /// plain sha256 of the archive bytes (what `sha256sum` prints) for archive URLs,
+    /// the [`directory_sha256`] of the tree for directories and git repositories.
+    pub expected_sha256: Option<String>,
+    /// Require a `checksums.txt` in the source whose hashes all match.
+    pub verify: bool,
+    /// Stop after validation and report the would-be outcome without writing anything.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionKind {
+    Plugin,
+    Skill,
+}
+
+/// Enable/disable overrides for plugins and skills, keyed by kind and id.
+/// Ids listed here win over the manifest/front-matter default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionStateFile {
+    pub version: u32,
+    pub disabled_plugins: Vec<String>,
+    /// Plugins enabled explicitly even though their manifest ships them disabled.
+    #[serde(default)]
+    pub enabled_plugins: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_skills: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled_skills: Vec<String>,
+}
+
+impl Default for ExtensionStateFile {
+    fn default() -> Self {
+        Self {
+            version: EXTENSION_STATE_VERSION,
+            disabled_plugins: Vec::new(),
+            enabled_plugins: Vec::new(),
+            disabled_skills: Vec::new(),
+            enabled_skills: Vec::new(),
+        }
+    }
+}
+
+impl ExtensionStateFile {
+    pub fn disabled_ids(&self, kind: ExtensionKind) -> &[String] {
+        match kind {
+            ExtensionKind::Plugin => &self.disabled_plugins,
+            ExtensionKind::Skill => &self.disabled_skills,
+        }
+    }
+
+    fn lists_mut(&mut self, kind: ExtensionKind) -> (&mut Vec<String>, &mut Vec<String>) {
+        match kind {
+            ExtensionKind::Plugin => (&mut self.disabled_plugins, &mut self.enabled_plugins),
+            ExtensionKind::Skill => (&mut self.disabled_skills, &mut self.enabled_skills),
+        }
+    }
+
+    /// Whether `id` has an explicit enable or disable override.
+    pub fn has_override(&self, kind: ExtensionKind, id: &str) -> bool {
+        let (disabled, enabled) = match kind {
+            ExtensionKind::Plugin => (&self.disabled_plugins, &self.enabled_plugins),
+            ExtensionKind::Skill => (&self.disabled_skills, &self.enabled_skills),
+        };
+        disabled.iter().chain(enabled).any(|item| item == id)
+    }
+
+    /// State overrides win over `default_enabled`, the manifest default.
+    pub fn is_enabled(&self, kind: ExtensionKind, id: &str, default_enabled: bool) -> bool {
+        let (disabled, enabled) = match kind {
+            ExtensionKind::Plugin => (&self.disabled_plugins, &self.enabled_plugins),
+            ExtensionKind::Skill => (&self.disabled_skills, &self.enabled_skills),
+        };
+        if disabled.iter().any(|item| item == id) {
+            return false;
+        }
+        default_enabled || enabled.iter().any(|item| item == id)
+    }
+
+    /// Records an override only when `enabled` differs from `default_enabled`;
+    /// passing `enabled == default_enabled` clears any override. Returns
+    /// whether the state changed.
+    pub fn set_enabled(
+        &mut self,
+        kind: ExtensionKind,
+        id: &str,
+        enabled: bool,
+        default_enabled: bool,
+    ) -> bool {
+        let normalized = id.trim();
+        if normalized.is_empty() {
+            return false;
+        }
+        let normalize = |items: &[String]| {
+            items
+                .iter()
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect::<BTreeSet<_>>()
+        };
+        let (disabled_ids, enabled_ids) = self.lists_mut(kind);
+        let before = (normalize(disabled_ids), normalize(enabled_ids));
+        let (mut disabled, mut explicit) = before.clone();
+        disabled.remove(normalized);
+        explicit.remove(normalized);
+        if enabled != default_enabled {
+            let target = if enabled {
+                &mut explicit
+            } else {
+                &mut disabled
+            };
+            target.insert(normalized.to_string());
+        }
+        let changed = before != (disabled.clone(), explicit.clone());
+        *disabled_ids = disabled.into_iter().collect();
+        *enabled_ids = explicit.into_iter().collect();
+        changed
+    }
+}
+
+pub fn load_extension_state(path: &Path) -> Result<ExtensionStateFile> {
+    if !path.exists() {
+        return Ok(ExtensionStateFile::default());
+    }
+    let raw = std::fs::read_to_string(path).map_err(|err| {
+        MosaicError::Io(format!(
+            "failed to read plugin state file '{}': {err}",
+            path.display()
+        ))
+    })?;
+    let mut state: ExtensionStateFile = serde_json::from_str(&raw).map_err(|err| {
+        MosaicError::Validation(format!(
+            "failed to parse plugin state file '{}': {err}",
+            path.display()
+        ))
+    })?;
+    if state.version == 0 {
+        state.version = EXTENSION_STATE_VERSION;
+    }
+    for ids in [
+        &mut state.disabled_plugins,
+        &mut state.enabled_plugins,
+        &mut state.disabled_skills,
+        &mut state.enabled_skills,
+    ] {
+        *ids = ids
+            .iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+    }
+    Ok(state)
+}
+
+pub fn save_extension_state(path: &Path, state: &ExtensionStateFile) -> Result<()> {
+    write_pretty_state_json_file(path, state, "plugin state").map_err(|err| {
+        MosaicError::Io(format!(
+            "failed to write plugin state file '{}': {err}",
+            path.display()
+        ))
+    })
+}
+
+/// Enable/disable overrides for plugins and skills share one state file.
+pub fn extension_state_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(EXTENSION_STATE_FILE)
 }
 
 #[derive(Debug, Clone)]
@@ -101,14 +327,17 @@ pub struct RegistryRoots {
     pub state_root: PathBuf,
     pub codex_home: Option<PathBuf>,
     pub user_home: Option<PathBuf>,
+    /// Enable/disable override file; without one, manifest defaults apply.
+    pub state_file: Option<PathBuf>,
 }
 
 impl RegistryRoots {
-    pub fn from_state_root(state_root: PathBuf) -> Self {
+    pub fn from_state_paths(paths: &StatePaths) -> Self {
         Self {
-            state_root,
+            state_root: paths.root_dir.clone(),
             codex_home: std::env::var_os("CODEX_HOME").map(PathBuf::from),
             user_home: dirs::home_dir(),
+            state_file: Some(extension_state_path(&paths.data_dir)),
         }
     }
 }
@@ -123,47 +352,156 @@ impl ExtensionRegistry {
         Self { roots }
     }
 
-    pub fn list_plugins(&self) -> Result<Vec<PluginEntry>> {
+    pub fn state_file(&self) -> Option<&Path> {
+        self.roots.state_file.as_deref()
+    }
+
+    /// The persisted overrides, or an empty state when no state file is configured.
+    pub fn extension_state(&self) -> Result<ExtensionStateFile> {
+        match &self.roots.state_file {
+            Some(path) => load_extension_state(path),
+            None => Ok(ExtensionStateFile::default()),
+        }
+    }
+
+    /// Keeps the plugin or skill `id` installed but marks it enabled or disabled,
+    /// overriding its manifest default. Returns whether the state changed.
+    pub fn set_enabled(&self, kind: ExtensionKind, id: &str, enabled: bool) -> Result<bool> {
+        let default_enabled = match kind {
+            ExtensionKind::Plugin => self
+                .collect_plugins(None)
+                .into_iter()
+                .find(|plugin| plugin.id == id)
+                .map(|plugin| plugin.enabled)
+                .ok_or_else(|| MosaicError::Validation(format!("plugin '{id}' not found")))?,
+            ExtensionKind::Skill => self
+                .collect_skills(None)
+                .into_iter()
+                .find(|skill| skill.id == id)
+                .map(|skill| skill.enabled)
+                .ok_or_else(|| MosaicError::Validation(format!("skill '{id}' not found")))?,
+        };
+        self.update_state(|state| state.set_enabled(kind, id, enabled, default_enabled))
+    }
+
+    /// Drops any override for `id` so its manifest default applies again.
+    /// Returns whether the state changed.
+    pub fn clear_enabled_override(&self, kind: ExtensionKind, id: &str) -> Result<bool> {
+        self.update_state(|state| state.set_enabled(kind, id, true, true))
+    }
+
+    fn update_state(&self, apply: impl FnOnce(&mut ExtensionStateFile) -> bool) -> Result<bool> {
+        let Some(path) = &self.roots.state_file else {
+            return Err(MosaicError::Config(
+                "extension state file is not configured".to_string(),
+            ));
+        };
+        let mut state = load_extension_state(path)?;
+        let changed = apply(&mut state);
+        if changed {
+            save_extension_state(path, &state)?;
+        }
+        Ok(changed)
+    }
+
+    /// Lists plugins from every root, the first root (Project, CodexHome, UserHome)
+    /// winning on duplicate ids. With `source` only that root is scanned. `enabled`
+    /// reflects the state file on top of the manifest default.
+    pub fn list_plugins(&self, source: Option<ExtensionSource>) -> Result<Vec<PluginEntry>> {
+        let state = self.extension_state()?;
+        Ok(self
+            .collect_plugins(source)
+            .into_iter()
+            .map(|mut plugin| {
+                plugin.enabled =
+                    state.is_enabled(ExtensionKind::Plugin, &plugin.id, plugin.enabled);
+                plugin
+            })
+            .collect())
+    }
+
+    /// Plugins with their manifest `enabled` default.
+    fn collect_plugins(&self, source: Option<ExtensionSource>) -> Vec<PluginEntry> {
         let mut items = BTreeMap::<String, PluginEntry>::new();
-        for (source, root) in self.plugin_roots() {
-            if !root.is_dir() {
-                continue;
+        for discovered in self.discover_plugins(source) {
+            items.entry(discovered.id.clone()).or_insert(discovered);
+        }
+        if source.is_some() {
+            let all = self.discover_plugins(None);
+            for plugin in items.values_mut() {
+                plugin.shadowed_by = all
+                    .iter()
+                    .find(|other| other.id == plugin.id)
+                    .map(|other| other.source)
+                    .filter(|winner| *winner != plugin.source);
             }
-            let entries = match std::fs::read_dir(&root) {
-                Ok(value) => value,
-                Err(_) => continue,
+        }
+        items.into_values().collect()
+    }
+
+    /// Every plugin directory in root precedence order, duplicates included.
+    fn discover_plugins(&self, source: Option<ExtensionSource>) -> Vec<PluginEntry> {
+        let mut found = Vec::new();
+        for (source, root) in filter_roots(self.plugin_roots(), source) {
+            let Ok(entries) = std::fs::read_dir(&root) else {
+                continue;
             };
             for entry in entries.flatten() {
                 let path = entry.path();
-                if !path.is_dir() {
-                    continue;
+                if path.is_dir() {
+                    found.push(discover_plugin_entry(source, &path));
                 }
-                let discovered = discover_plugin_entry(source, &path);
-                items.entry(discovered.id.clone()).or_insert(discovered);
             }
         }
-        Ok(items.into_values().collect())
+        found
     }
 
     pub fn plugin_info(&self, plugin_id: &str) -> Result<PluginEntry> {
-        self.list_plugins()?
+        self.list_plugins(None)?
             .into_iter()
             .find(|item| item.id == plugin_id)
             .ok_or_else(|| MosaicError::Validation(format!("plugin '{plugin_id}' not found")))
     }
 
+    /// Plugins whose id, name or description contains `query` (case-insensitive),
+    /// best matches first: exact id, id prefix, id substring, name, then description.
+    pub fn search_plugins(&self, query: &str) -> Result<Vec<PluginEntry>> {
+        let query = normalize_search_query(query)?;
+        Ok(rank_search_matches(self.list_plugins(None)?, |plugin| {
+            search_rank(
+                &query,
+                &plugin.id,
+                &[Some(plugin.name.as_str()), plugin.description.as_deref()],
+            )
+        }))
+    }
+
     pub fn check_plugins(&self, plugin_id: Option<&str>) -> Result<ExtensionCheckReport> {
         let plugins = if let Some(plugin_id) = plugin_id {
             vec![self.plugin_info(plugin_id)?]
         } else {
-            self.list_plugins()?
+            self.list_plugins(None)?
         };
 
+        let discovered = self.discover_plugins(None);
         let mut failed = 0usize;
         let mut results = Vec::with_capacity(plugins.len());
         for plugin in plugins {
             let mut checks = Vec::new();
             let path = PathBuf::from(&plugin.path);
+            let shadowed = discovered
+                .iter()
+                .filter(|other| other.id == plugin.id && other.path != plugin.path)
+                .map(|other| format!("{} ({})", other.path, other.source.as_str()))
+                .collect::<Vec<_>>();
+            if !shadowed.is_empty() {
+                // Informational: precedence is intended, but users should know.
+                checks.push(check(
+                    "plugin_shadows",
+                    true,
+                    format!("overrides {}", shadowed.join(", ")),
+                ));
+            }
             checks.push(check(
                 "plugin_dir_exists",
                 path.is_dir(),
@@ -182,6 +520,13 @@ impl ExtensionRegistry {
                     .clone()
                     .unwrap_or_else(|| "manifest parsed".to_string()),
             ));
+            if path.join(PLUGIN_CHECKSUMS_FILE).is_file() {
+                let (ok, detail) = match verify_plugin_checksums(&path) {
+                    Ok(verified) => (true, format!("{verified} file(s) verified")),
+                    Err(err) => (false, err.to_string()),
+                };
+                checks.push(check("plugin_checksums", ok, detail));
+            }
             if let Some(runtime) = plugin.runtime.as_ref() {
                 if let Some(run_hook) = runtime.run.as_ref() {
                     let run_path = path.join(run_hook);
@@ -252,6 +597,7 @@ impl ExtensionRegistry {
                 source: plugin.source,
                 path: plugin.path,
                 ok,
+                enabled: plugin.enabled,
                 checks,
             });
         }
@@ -266,12 +612,21 @@ impl ExtensionRegistry {
         })
     }
 
+    /// Installs a plugin directory into the project state root, honoring `options`
+    /// (see [`InstallOptions`]). A `[integrity]` table in plugin.toml is always enforced.
     pub fn install_plugin_from_path(
         &self,
         source_path: &Path,
-        force: bool,
+        options: &InstallOptions,
     ) -> Result<InstallOutcome> {
+        let InstallOptions {
+            force,
+            ref expected_sha256,
+            verify,
+            dry_run,
+        } = *options;
         let source_dir = canonicalize_existing_dir(source_path, "plugin source path")?;
+        reject_symlinks(&source_dir)?;
         let discovered = discover_plugin_entry(ExtensionSource::Project, &source_dir);
         if !discovered.manifest_exists {
             return Err(MosaicError::Validation(format!(
@@ -286,6 +641,25 @@ impl ExtensionRegistry {
                     .unwrap_or_else(|| "plugin manifest is invalid".to_string()),
             ));
         }
+        let digest = directory_sha256(&source_dir)?;
+        if let Some(expected) = expected_sha256 {
+            let expected = normalize_sha256(expected, "expected sha256")?;
+            if expected != digest {
+                return Err(MosaicError::Validation(format!(
+                    "plugin source checksum mismatch: expected sha256 {expected}, got {digest}"
+                )));
+            }
+        }
+        verify_manifest_integrity(&source_dir)?;
+        if verify {
+            if !source_dir.join(PLUGIN_CHECKSUMS_FILE).is_file() {
+                return Err(MosaicError::Validation(format!(
+                    "plugin source {} is missing {PLUGIN_CHECKSUMS_FILE} (required for verification)",
+                    source_dir.display()
+                )));
+            }
+            verify_plugin_checksums(&source_dir)?;
+        }
         let plugin_id = normalize_package_id(&discovered.id, "plugin id")?;
         let destination_root = self.roots.state_root.join("plugins");
         let destination = destination_root.join(&plugin_id);
@@ -303,6 +677,8 @@ impl ExtensionRegistry {
                 source_path: source_real.display().to_string(),
                 installed_path: source_real.display().to_string(),
                 replaced: false,
+                sha256: Some(digest),
+                dry_run,
             });
         }
 
@@ -314,6 +690,17 @@ impl ExtensionRegistry {
                 destination.display()
             )));
         }
+        if dry_run {
+            return Ok(InstallOutcome {
+                kind: "plugin".to_string(),
+                id: plugin_id,
+                source_path: source_dir.display().to_string(),
+                installed_path: destination.display().to_string(),
+                replaced: existed,
+                sha256: Some(digest),
+                dry_run,
+            });
+        }
         if existed {
             std::fs::remove_dir_all(&destination)?;
         }
@@ -335,9 +722,74 @@ impl ExtensionRegistry {
             source_path: source_dir.display().to_string(),
             installed_path: destination.display().to_string(),
             replaced: existed,
+            sha256: Some(digest),
+            dry_run: false,
         })
     }
 
+    /// Fetches a plugin from `url` into a temporary directory and installs it with
+    /// [`Self::install_plugin_from_path`]. `.tar.gz`/`.tgz` URLs are unpacked (`file://`
+    /// is read locally, http(s) is downloaded with a bounded timeout);
+    /// `git+<url>` or `*.git` URLs are shallow-cloned with the system `git` (see
+    /// [`clone_git_repository`]). The plugin.toml may sit at the root or in a single
+    /// top-level directory. For archives `options.expected_sha256` is checked against
+    /// the archive bytes before unpacking and the archive digest is what the outcome
+    /// records; a clone is checked like a local directory. The other options apply
+    /// to the fetched source as they would to a local directory.
+    pub async fn install_plugin_from_url(
+        &self,
+        url: &str,
+        options: &InstallOptions,
+    ) -> Result<InstallOutcome> {
+        let url = url.trim();
+        if url.is_empty() {
+            return Err(MosaicError::Validation(
+                "plugin url cannot be empty".to_string(),
+            ));
+        }
+        let staging = tempfile::tempdir().map_err(|err| {
+            MosaicError::Io(format!("failed to create plugin staging directory: {err}"))
+        })?;
+        let fetched = staging.path().join("source");
+        let archive_digest = if let Some(repo) = git_repository_url(url) {
+            clone_git_repository(repo, &fetched)?;
+            None
+        } else if is_tar_gz_url(url) {
+            let archive = staging.path().join("plugin.tar.gz");
+            download_url(url, &archive).await?;
+            let archive_digest = file_sha256(&archive)?;
+            if let Some(expected) = &options.expected_sha256 {
+                let expected = normalize_sha256(expected, "expected sha256")?;
+                if expected != archive_digest {
+                    return Err(MosaicError::Validation(format!(
+                        "plugin archive checksum mismatch: expected sha256 {expected}, got {archive_digest}"
+                    )));
+                }
+            }
+            unpack_tar_gz(&archive, &fetched)?;
+            Some(archive_digest)
+        } else {
+            return Err(MosaicError::Validation(format!(
+                "unsupported plugin url '{url}' (expected a .tar.gz/.tgz archive or a git repository)"
+            )));
+        };
+        let plugin_dir = locate_plugin_root(&fetched)?;
+        // An archive digest was already checked; a clone is checked as a tree.
+        let source_options = InstallOptions {
+            expected_sha256: options
+                .expected_sha256
+                .clone()
+                .filter(|_| archive_digest.is_none()),
+            ..options.clone()
+        };
+        let mut outcome = self.install_plugin_from_path(&plugin_dir, &source_options)?;
+        outcome.source_path = url.to_string();
+        if archive_digest.is_some() {
+            outcome.sha256 = archive_digest;
+        }
+        Ok(outcome)
+    }
+
     pub fn remove_project_plugin(&self, plugin_id: &str) -> Result<bool> {
         let plugin_id = plugin_id.trim();
         if plugin_id.is_empty() {
@@ -346,7 +798,7 @@ impl ExtensionRegistry {
             ));
         }
         let target = self
-            .list_plugins()?
+            .list_plugins(None)?
             .into_iter()
             .find(|item| item.id == plugin_id && item.source == ExtensionSource::Project);
         let Some(target) = target else {
@@ -357,12 +809,30 @@ impl ExtensionRegistry {
             return Ok(false);
         }
         std::fs::remove_dir_all(path)?;
+        if self.roots.state_file.is_some() {
+            self.clear_enabled_override(ExtensionKind::Plugin, plugin_id)?;
+        }
         Ok(true)
     }
 
-    pub fn list_skills(&self) -> Result<Vec<SkillEntry>> {
+    /// Lists skills with the same root precedence, `source` filter and state
+    /// overrides as [`Self::list_plugins`].
+    pub fn list_skills(&self, source: Option<ExtensionSource>) -> Result<Vec<SkillEntry>> {
+        let state = self.extension_state()?;
+        Ok(self
+            .collect_skills(source)
+            .into_iter()
+            .map(|mut skill| {
+                skill.enabled = state.is_enabled(ExtensionKind::Skill, &skill.id, skill.enabled);
+                skill
+            })
+            .collect())
+    }
+
+    /// Skills with their front-matter `enabled` default.
+    fn collect_skills(&self, source: Option<ExtensionSource>) -> Vec<SkillEntry> {
         let mut items = BTreeMap::<String, SkillEntry>::new();
-        for (source, root) in self.skill_roots() {
+        for (source, root) in filter_roots(self.skill_roots(), source) {
             if !root.is_dir() {
                 continue;
             }
@@ -399,9 +869,16 @@ impl ExtensionRegistry {
                     .file_name()
                     .map(|value| value.to_string_lossy().to_string())
                     .unwrap_or_else(|| id.clone());
-                let (title, description) = match std::fs::read_to_string(skill_file) {
-                    Ok(content) => extract_skill_summary(&content, &fallback_title),
-                    Err(_) => (fallback_title, None),
+                let (title, description, tags, enabled) = match std::fs::read_to_string(skill_file)
+                {
+                    Ok(content) => {
+                        let front_matter = parse_skill_front_matter(&content);
+                        let (title, description) =
+                            extract_skill_summary(&content, &front_matter, &fallback_title);
+                        let enabled = front_matter.enabled.unwrap_or(true);
+                        (title, description, front_matter.tags, enabled)
+                    }
+                    Err(_) => (fallback_title, None, Vec::new(), true),
                 };
 
                 let discovered = SkillEntry {
@@ -411,25 +888,45 @@ impl ExtensionRegistry {
                     source,
                     path: skill_dir.display().to_string(),
                     skill_file: skill_file.display().to_string(),
+                    tags,
+                    enabled,
                 };
                 items.insert(id, discovered);
             }
         }
-        Ok(items.into_values().collect())
+        items.into_values().collect()
     }
 
     pub fn skill_info(&self, skill_id: &str) -> Result<SkillEntry> {
-        self.list_skills()?
+        self.list_skills(None)?
             .into_iter()
             .find(|item| item.id == skill_id)
             .ok_or_else(|| MosaicError::Validation(format!("skill '{skill_id}' not found")))
     }
 
+    /// Skills ranked like [`Self::search_plugins`], matching id, title, description
+    /// and tags.
+    pub fn search_skills(&self, query: &str) -> Result<Vec<SkillEntry>> {
+        let query = normalize_search_query(query)?;
+        Ok(rank_search_matches(self.list_skills(None)?, |skill| {
+            let tags = skill.tags.join(" ");
+            search_rank(
+                &query,
+                &skill.id,
+                &[
+                    Some(skill.title.as_str()),
+                    skill.description.as_deref(),
+                    Some(tags.as_str()),
+                ],
+            )
+        }))
+    }
+
     pub fn check_skills(&self, skill_id: Option<&str>) -> Result<ExtensionCheckReport> {
         let skills = if let Some(skill_id) = skill_id {
             vec![self.skill_info(skill_id)?]
         } else {
-            self.list_skills()?
+            self.list_skills(None)?
         };
 
         let mut failed = 0usize;
@@ -486,6 +983,7 @@ impl ExtensionRegistry {
                 source: skill.source,
                 path: skill.path,
                 ok,
+                enabled: skill.enabled,
                 checks,
             });
         }
@@ -500,10 +998,13 @@ impl ExtensionRegistry {
         })
     }
 
+    /// Copies a skill directory into the project state root; `dry_run` validates and
+    /// reports the would-be outcome without writing anything.
     pub fn install_skill_from_path(
         &self,
         source_path: &Path,
         force: bool,
+        dry_run: bool,
     ) -> Result<InstallOutcome> {
         let source_dir = canonicalize_existing_dir(source_path, "skill source path")?;
         let skill_file = source_dir.join("SKILL.md");
@@ -539,6 +1040,8 @@ impl ExtensionRegistry {
                 source_path: source_real.display().to_string(),
                 installed_path: source_real.display().to_string(),
                 replaced: false,
+                sha256: None,
+                dry_run,
             });
         }
 
@@ -550,6 +1053,17 @@ impl ExtensionRegistry {
                 destination.display()
             )));
         }
+        if dry_run {
+            return Ok(InstallOutcome {
+                kind: "skill".to_string(),
+                id: skill_id,
+                source_path: source_dir.display().to_string(),
+                installed_path: destination.display().to_string(),
+                replaced: existed,
+                sha256: None,
+                dry_run,
+            });
+        }
         if existed {
             std::fs::remove_dir_all(&destination)?;
         }
@@ -563,6 +1077,8 @@ impl ExtensionRegistry {
             source_path: source_dir.display().to_string(),
             installed_path: destination.display().to_string(),
             replaced: existed,
+            sha256: None,
+            dry_run: false,
         })
     }
 
@@ -574,7 +1090,7 @@ impl ExtensionRegistry {
             ));
         }
         let target = self
-            .list_skills()?
+            .list_skills(None)?
             .into_iter()
             .find(|item| item.id == skill_id && item.source == ExtensionSource::Project);
         let Some(target) = target else {
@@ -585,6 +1101,9 @@ impl ExtensionRegistry {
             return Ok(false);
         }
         std::fs::remove_dir_all(path)?;
+        if self.roots.state_file.is_some() {
+            self.clear_enabled_override(ExtensionKind::Skill, skill_id)?;
+        }
         Ok(true)
     }
 
@@ -623,6 +1142,54 @@ impl ExtensionRegistry {
     }
 }
 
+fn filter_roots(
+    roots: Vec<(ExtensionSource, PathBuf)>,
+    source: Option<ExtensionSource>,
+) -> Vec<(ExtensionSource, PathBuf)> {
+    roots
+        .into_iter()
+        .filter(|(root_source, _)| source.is_none_or(|source| source == *root_source))
+        .collect()
+}
+
+fn normalize_search_query(query: &str) -> Result<String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err(MosaicError::Validation(
+            "search query cannot be empty".to_string(),
+        ));
+    }
+    Ok(query)
+}
+
+/// Lower is better; `None` means no match. `fields` are ranked in order after the id.
+fn search_rank(query: &str, id: &str, fields: &[Option<&str>]) -> Option<usize> {
+    let id = id.to_lowercase();
+    if id == query {
+        return Some(0);
+    }
+    if id.starts_with(query) {
+        return Some(1);
+    }
+    if id.contains(query) {
+        return Some(2);
+    }
+    fields
+        .iter()
+        .position(|field| field.is_some_and(|value| value.to_lowercase().contains(query)))
+        .map(|index| index + 3)
+}
+
+/// Keeps matching items ordered by rank; equal ranks keep their listing order.
+fn rank_search_matches<T>(items: Vec<T>, rank: impl Fn(&T) -> Option<usize>) -> Vec<T> {
+    let mut ranked = items
+        .into_iter()
+        .filter_map(|item| rank(&item).map(|rank| (rank, item)))
+        .collect::<Vec<_>>();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, item)| item).collect()
+}
+
 fn check(name: impl Into<String>, ok: bool, detail: impl Into<String>) -> ExtensionCheckItem {
     ExtensionCheckItem {
         name: name.into(),
@@ -705,6 +1272,9 @@ fn copy_dir_recursive(source_dir: &Path, destination_dir: &Path) -> Result<()> {
             ))
         })?;
         let target_path = destination_dir.join(relative);
+        if entry.path_is_symlink() {
+            return Err(symlink_error(relative));
+        }
         if entry.file_type().is_dir() {
             std::fs::create_dir_all(&target_path)?;
             continue;
@@ -723,83 +1293,426 @@ fn copy_dir_recursive(source_dir: &Path, destination_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-#[derive(Debug, Default, Deserialize)]
-struct PluginManifest {
-    plugin: Option<PluginManifestPlugin>,
-    runtime: Option<PluginManifestRuntime>,
-    id: Option<String>,
-    name: Option<String>,
-    version: Option<String>,
-    description: Option<String>,
+/// Packages are copied as plain files; a link could point anywhere on the host.
+fn reject_symlinks(dir: &Path) -> Result<()> {
+    for entry in WalkDir::new(dir).min_depth(1) {
+        let entry = entry
+            .map_err(|err| MosaicError::Io(format!("failed to walk {}: {err}", dir.display())))?;
+        if entry.path_is_symlink() {
+            let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+            return Err(symlink_error(relative));
+        }
+    }
+    Ok(())
 }
 
-#[derive(Debug, Default, Clone, Deserialize)]
-struct PluginManifestRuntime {
-    run: Option<String>,
-    doctor: Option<String>,
-    timeout_ms: Option<u64>,
-    sandbox_profile: Option<String>,
-    max_cpu_ms: Option<u64>,
-    max_rss_kb: Option<u64>,
-    max_output_bytes: Option<u64>,
-    cpu_watchdog_ms: Option<u64>,
+fn symlink_error(relative: &Path) -> MosaicError {
+    MosaicError::Validation(format!(
+        "source entry '{}' is a symlink, which is not supported",
+        relative.display()
+    ))
 }
 
-#[derive(Debug, Default, Deserialize)]
-struct PluginManifestPlugin {
-    id: Option<String>,
-    name: Option<String>,
-    version: Option<String>,
-    description: Option<String>,
-    runtime: Option<PluginManifestRuntime>,
+fn git_repository_url(url: &str) -> Option<&str> {
+    if let Some(repo) = url.strip_prefix("git+") {
+        return Some(repo);
+    }
+    url.trim_end_matches('/').ends_with(".git").then_some(url)
 }
 
-impl PluginManifest {
-    fn plugin_id(&self) -> Option<String> {
-        self.plugin
-            .as_ref()
-            .and_then(|plugin| plugin.id.clone())
-            .or_else(|| self.id.clone())
+fn is_tar_gz_url(url: &str) -> bool {
+    let path = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(url)
+        .to_ascii_lowercase();
+    path.ends_with(".tar.gz") || path.ends_with(".tgz")
+}
+
+/// Shallow-clones the default branch of `repo` into `destination` with the
+/// system `git`, so every transport git knows (https, ssh, `git://`, `file://`,
+/// self-hosted forges) works. Prompts are disabled, and the `.git` directory is
+/// dropped so only the working tree is installed.
+fn clone_git_repository(repo: &str, destination: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", "--"])
+        .arg(repo)
+        .arg(destination)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| {
+            MosaicError::Io(format!(
+                "failed to run git to clone plugin repository '{repo}': {err}"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(MosaicError::Network(format!(
+            "failed to clone plugin repository '{repo}': {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let git_dir = destination.join(".git");
+    if git_dir.exists() {
+        std::fs::remove_dir_all(&git_dir).map_err(|err| {
+            MosaicError::Io(format!("failed to remove {}: {err}", git_dir.display()))
+        })?;
     }
+    Ok(())
+}
 
-    fn plugin_name(&self) -> Option<String> {
-        self.plugin
-            .as_ref()
-            .and_then(|plugin| plugin.name.clone())
-            .or_else(|| self.name.clone())
+async fn download_url(url: &str, destination: &Path) -> Result<()> {
+    if let Some(path) = url.strip_prefix("file://") {
+        std::fs::copy(path, destination).map_err(|err| {
+            MosaicError::Io(format!("failed to read plugin archive {path}: {err}"))
+        })?;
+        return Ok(());
+    }
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(MosaicError::Validation(format!(
+            "unsupported plugin url scheme in '{url}'"
+        )));
     }
+    let client = with_env_proxy(reqwest::Client::builder().timeout(PLUGIN_DOWNLOAD_TIMEOUT))?
+        .build()
+        .map_err(|err| MosaicError::Network(format!("failed to build HTTP client: {err}")))?;
+    let response = client.get(url).send().await.map_err(|err| {
+        MosaicError::Network(format!("failed to download plugin archive '{url}': {err}"))
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(MosaicError::Network(format!(
+            "failed to download plugin archive '{url}': HTTP {status}"
+        )));
+    }
+    let body = response.bytes().await.map_err(|err| {
+        MosaicError::Network(format!("failed to download plugin archive '{url}': {err}"))
+    })?;
+    std::fs::write(destination, &body).map_err(|err| {
+        MosaicError::Io(format!(
+            "failed to write plugin archive {}: {err}",
+            destination.display()
+        ))
+    })
+}
 
-    fn plugin_version(&self) -> Option<String> {
-        self.plugin
-            .as_ref()
-            .and_then(|plugin| plugin.version.clone())
-            .or_else(|| self.version.clone())
+/// Unpacks a gzip tarball, refusing links and entries that would land outside
+/// `destination`.
+fn unpack_tar_gz(archive_path: &Path, destination: &Path) -> Result<()> {
+    let file = File::open(archive_path).map_err(|err| {
+        MosaicError::Io(format!(
+            "failed to open plugin archive {}: {err}",
+            archive_path.display()
+        ))
+    })?;
+    std::fs::create_dir_all(destination)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let entries = archive
+        .entries()
+        .map_err(|err| MosaicError::Validation(format!("invalid plugin archive: {err}")))?;
+    for entry in entries {
+        let mut entry = entry
+            .map_err(|err| MosaicError::Validation(format!("invalid plugin archive: {err}")))?;
+        let entry_path = entry
+            .path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(MosaicError::Validation(format!(
+                "plugin archive entry '{entry_path}' is a link, which is not supported"
+            )));
+        }
+        let unpacked = entry.unpack_in(destination).map_err(|err| {
+            MosaicError::Validation(format!(
+                "failed to unpack plugin archive entry '{entry_path}': {err}"
+            ))
+        })?;
+        if !unpacked {
+            return Err(MosaicError::Validation(format!(
+                "plugin archive entry '{entry_path}' escapes the archive root"
+            )));
+        }
     }
+    Ok(())
+}
 
-    fn plugin_description(&self) -> Option<String> {
-        self.plugin
-            .as_ref()
-            .and_then(|plugin| plugin.description.clone())
-            .or_else(|| self.description.clone())
+/// Returns `dir` when it holds plugin.toml, otherwise its single top-level
+/// subdirectory when that one does (the usual `name-1.0/` archive layout).
+fn locate_plugin_root(dir: &Path) -> Result<PathBuf> {
+    if dir.join("plugin.toml").is_file() {
+        return Ok(dir.to_path_buf());
     }
+    let children = std::fs::read_dir(dir)
+        .map_err(|err| MosaicError::Io(format!("failed to read {}: {err}", dir.display())))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    if let [child] = children.as_slice()
+        && child.is_dir()
+        && child.join("plugin.toml").is_file()
+    {
+        return Ok(child.clone());
+    }
+    Err(MosaicError::Validation(
+        "plugin archive does not contain a plugin.toml at its root or in a single top-level directory"
+            .to_string(),
+    ))
+}
 
-    fn plugin_runtime(&self) -> Option<PluginManifestRuntime> {
-        self.plugin
-            .as_ref()
-            .and_then(|plugin| plugin.runtime.clone())
-            .or_else(|| self.runtime.clone())
+/// Deterministic sha256 over a directory: every file's relative path, size, and
+/// content, visited in sorted path order.
+pub fn directory_sha256(dir: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry
+            .map_err(|err| MosaicError::Io(format!("failed to walk {}: {err}", dir.display())))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(dir)
+            .map_err(|err| {
+                MosaicError::Io(format!(
+                    "failed to compute relative path for {}: {err}",
+                    entry.path().display()
+                ))
+            })?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let content = std::fs::read(entry.path())?;
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
     }
+    Ok(hex_digest(hasher))
 }
 
-fn discover_plugin_entry(source: ExtensionSource, plugin_dir: &Path) -> PluginEntry {
-    let fallback_id = plugin_dir
-        .file_name()
-        .map(|value| value.to_string_lossy().to_string())
-        .unwrap_or_else(|| "unknown".to_string());
-    let manifest_path = plugin_dir.join("plugin.toml");
-    let mut entry = PluginEntry {
-        id: fallback_id.clone(),
-        name: fallback_id,
+fn file_sha256(path: &Path) -> Result<String> {
+    let content = std::fs::read(path)
+        .map_err(|err| MosaicError::Io(format!("failed to read {}: {err}", path.display())))?;
+    let mut hasher = Sha256::new();
+    hasher.update(&content);
+    Ok(hex_digest(hasher))
+}
+
+fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn normalize_sha256(value: &str, field_name: &str) -> Result<String> {
+    let value = value.trim();
+    let value = value.strip_prefix("sha256:").unwrap_or(value);
+    if value.len() != 64 || !value.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(MosaicError::Validation(format!(
+            "{field_name} must be 64 hex characters"
+        )));
+    }
+    Ok(value.to_ascii_lowercase())
+}
+
+/// Checks the per-file hashes declared under `[integrity.files]` in plugin.toml.
+fn verify_manifest_integrity(plugin_dir: &Path) -> Result<()> {
+    let raw = std::fs::read_to_string(plugin_dir.join("plugin.toml"))?;
+    let manifest = toml::from_str::<PluginManifest>(&raw)
+        .map_err(|err| MosaicError::Validation(format!("failed to parse manifest: {err}")))?;
+    let Some(integrity) = manifest.integrity else {
+        return Ok(());
+    };
+    for (relative, expected) in integrity.files {
+        let relative_path = plugin_relative_path(&relative, "plugin integrity path")?;
+        let expected = normalize_sha256(
+            &expected,
+            &format!("plugin integrity hash for '{relative}'"),
+        )?;
+        let file_path = plugin_dir.join(relative_path);
+        if !file_path.is_file() {
+            return Err(MosaicError::Validation(format!(
+                "plugin integrity file '{relative}' is missing"
+            )));
+        }
+        let actual = file_sha256(&file_path)?;
+        if actual != expected {
+            return Err(MosaicError::Validation(format!(
+                "plugin integrity mismatch for '{relative}': expected sha256 {expected}, got {actual}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks every `<sha256>  <path>` line of the plugin's checksums.txt (the format
+/// `sha256sum` writes) and returns how many files were verified. Files in the
+/// plugin directory that the list does not cover fail verification.
+fn verify_plugin_checksums(plugin_dir: &Path) -> Result<usize> {
+    let raw = std::fs::read_to_string(plugin_dir.join(PLUGIN_CHECKSUMS_FILE))?;
+    let mut covered = HashSet::new();
+    for (index, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((expected, relative)) = line.split_once(char::is_whitespace) else {
+            return Err(MosaicError::Validation(format!(
+                "{PLUGIN_CHECKSUMS_FILE} line {} must be '<sha256>  <path>'",
+                index + 1
+            )));
+        };
+        // `sha256sum -b` marks binary-mode entries with a leading '*'.
+        let relative = relative.trim_start();
+        let relative = relative.strip_prefix('*').unwrap_or(relative);
+        let relative_path = plugin_relative_path(relative, "plugin checksum path")?;
+        let expected = normalize_sha256(expected, &format!("checksum for '{relative}'"))?;
+        let file_path = plugin_dir.join(relative_path);
+        if !file_path.is_file() {
+            return Err(MosaicError::Validation(format!(
+                "plugin checksum file '{relative}' is missing"
+            )));
+        }
+        let actual = file_sha256(&file_path)?;
+        if actual != expected {
+            return Err(MosaicError::Validation(format!(
+                "plugin checksum mismatch for '{relative}': expected sha256 {expected}, got {actual}"
+            )));
+        }
+        covered.insert(relative_path.to_path_buf());
+    }
+    // Every shipped file, including plugin.toml and hooks, must be listed so
+    // nothing can be added next to a checksummed package unnoticed.
+    for entry in WalkDir::new(plugin_dir).min_depth(1) {
+        let entry = entry.map_err(|err| MosaicError::Io(err.to_string()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(plugin_dir)
+            .map_err(|err| MosaicError::Io(err.to_string()))?;
+        if relative == Path::new(PLUGIN_CHECKSUMS_FILE) || covered.contains(relative) {
+            continue;
+        }
+        return Err(MosaicError::Validation(format!(
+            "plugin file '{}' is not listed in {PLUGIN_CHECKSUMS_FILE}",
+            relative.display()
+        )));
+    }
+    Ok(covered.len())
+}
+
+fn plugin_relative_path<'a>(relative: &'a str, label: &str) -> Result<&'a Path> {
+    let relative_path = Path::new(relative);
+    if relative_path.is_absolute()
+        || relative_path
+            .components()
+            .any(|component| !matches!(component, std::path::Component::Normal(_)))
+    {
+        return Err(MosaicError::Validation(format!(
+            "{label} '{relative}' must be relative to the plugin directory"
+        )));
+    }
+    Ok(relative_path)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginManifest {
+    plugin: Option<PluginManifestPlugin>,
+    runtime: Option<PluginManifestRuntime>,
+    integrity: Option<PluginManifestIntegrity>,
+    id: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+struct PluginManifestRuntime {
+    run: Option<String>,
+    doctor: Option<String>,
+    timeout_ms: Option<u64>,
+    sandbox_profile: Option<String>,
+    max_cpu_ms: Option<u64>,
+    max_rss_kb: Option<u64>,
+    max_output_bytes: Option<u64>,
+    cpu_watchdog_ms: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginManifestIntegrity {
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginManifestPlugin {
+    id: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    enabled: Option<bool>,
+    runtime: Option<PluginManifestRuntime>,
+}
+
+impl PluginManifest {
+    fn plugin_id(&self) -> Option<String> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.id.clone())
+            .or_else(|| self.id.clone())
+    }
+
+    fn plugin_name(&self) -> Option<String> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.name.clone())
+            .or_else(|| self.name.clone())
+    }
+
+    fn plugin_version(&self) -> Option<String> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.version.clone())
+            .or_else(|| self.version.clone())
+    }
+
+    fn plugin_description(&self) -> Option<String> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.description.clone())
+            .or_else(|| self.description.clone())
+    }
+
+    fn plugin_enabled(&self) -> Option<bool> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.enabled)
+            .or(self.enabled)
+    }
+
+    fn plugin_runtime(&self) -> Option<PluginManifestRuntime> {
+        self.plugin
+            .as_ref()
+            .and_then(|plugin| plugin.runtime.clone())
+            .or_else(|| self.runtime.clone())
+    }
+}
+
+fn discover_plugin_entry(source: ExtensionSource, plugin_dir: &Path) -> PluginEntry {
+    let fallback_id = plugin_dir
+        .file_name()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let manifest_path = plugin_dir.join("plugin.toml");
+    let mut entry = PluginEntry {
+        id: fallback_id.clone(),
+        name: fallback_id,
         version: None,
         description: None,
         source,
@@ -808,7 +1721,9 @@ fn discover_plugin_entry(source: ExtensionSource, plugin_dir: &Path) -> PluginEn
         manifest_exists: manifest_path.is_file(),
         manifest_valid: false,
         manifest_error: None,
+        enabled: true,
         runtime: None,
+        shadowed_by: None,
     };
 
     if !entry.manifest_exists {
@@ -848,6 +1763,7 @@ fn discover_plugin_entry(source: ExtensionSource, plugin_dir: &Path) -> PluginEn
         .plugin_description()
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
+    entry.enabled = parsed.plugin_enabled().unwrap_or(true);
     entry.runtime = parsed.plugin_runtime().and_then(|runtime| {
         let run = runtime
             .run
@@ -894,10 +1810,17 @@ fn discover_plugin_entry(source: ExtensionSource, plugin_dir: &Path) -> PluginEn
     entry
 }
 
-fn extract_skill_summary(content: &str, fallback_title: &str) -> (String, Option<String>) {
-    let mut title = None;
-    let mut description = None;
-    for line in content.lines() {
+/// Front-matter title/description win; otherwise the first `# ` heading and the
+/// first plain line of the body are used.
+fn extract_skill_summary(
+    content: &str,
+    front_matter: &SkillFrontMatter,
+    fallback_title: &str,
+) -> (String, Option<String>) {
+    let mut title = front_matter.title.clone();
+    let mut description = front_matter.description.clone();
+    let (_, body) = split_front_matter(content);
+    for line in body.lines() {
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
@@ -922,6 +1845,91 @@ fn extract_skill_summary(content: &str, fallback_title: &str) -> (String, Option
     )
 }
 
+/// Splits a leading `---` front-matter block from the markdown body.
+fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0usize;
+    for line in rest.split_inclusive('\n') {
+        if line.trim() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+/// SKILL.md front-matter keys. Only flat `key: value` scalars plus `[a, b]` or
+/// `- item` lists are understood, which covers what skills use in practice.
+#[derive(Debug, Default)]
+struct SkillFrontMatter {
+    title: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    enabled: Option<bool>,
+}
+
+fn parse_skill_front_matter(content: &str) -> SkillFrontMatter {
+    let mut parsed = SkillFrontMatter::default();
+    let (Some(front_matter), _) = split_front_matter(content) else {
+        return parsed;
+    };
+    let mut in_tags = false;
+    for line in front_matter.lines() {
+        let trimmed = line.trim();
+        if in_tags && let Some(item) = trimmed.strip_prefix("- ") {
+            push_front_matter_tag(&mut parsed.tags, item);
+            continue;
+        }
+        in_tags = false;
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "title" | "name" if parsed.title.is_none() => {
+                parsed.title = Some(unquote_front_matter(value)).filter(|value| !value.is_empty());
+            }
+            "description" => {
+                parsed.description =
+                    Some(unquote_front_matter(value)).filter(|value| !value.is_empty());
+            }
+            "tags" => {
+                if let Some(list) = value
+                    .strip_prefix('[')
+                    .and_then(|list| list.strip_suffix(']'))
+                {
+                    for item in list.split(',') {
+                        push_front_matter_tag(&mut parsed.tags, item);
+                    }
+                } else if value.is_empty() {
+                    in_tags = true;
+                } else {
+                    push_front_matter_tag(&mut parsed.tags, value);
+                }
+            }
+            "enabled" => parsed.enabled = unquote_front_matter(value).parse::<bool>().ok(),
+            _ => {}
+        }
+    }
+    parsed
+}
+
+fn push_front_matter_tag(tags: &mut Vec<String>, raw: &str) {
+    let tag = unquote_front_matter(raw.trim());
+    if !tag.is_empty() && !tags.contains(&tag) {
+        tags.push(tag);
+    }
+}
+
+fn unquote_front_matter(value: &str) -> String {
+    value.trim_matches(['"', '\'']).trim().to_string()
+}
+
 fn is_valid_sandbox_profile(value: &str) -> bool {
     matches!(value, "restricted" | "standard" | "elevated")
 }
@@ -958,14 +1966,53 @@ mod tests {
             state_root,
             codex_home: Some(codex_home),
             user_home: None,
+            state_file: None,
         });
-        let plugins = registry.list_plugins().expect("list plugins");
+        let plugins = registry.list_plugins(None).expect("list plugins");
         assert_eq!(plugins.len(), 1);
         assert_eq!(plugins[0].id, "demo");
         assert_eq!(plugins[0].name, "Demo Project Plugin");
         assert_eq!(plugins[0].source, ExtensionSource::Project);
     }
 
+    #[test]
+    fn manifests_can_ship_extensions_disabled() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let plugin_dir = state_root.join("plugins").join("quiet");
+        std::fs::create_dir_all(&plugin_dir).expect("create plugin dir");
+        std::fs::write(
+            plugin_dir.join("plugin.toml"),
+            "[plugin]\nid = \"quiet\"\nname = \"Quiet\"\nenabled = false\n",
+        )
+        .expect("write plugin manifest");
+        let skill_dir = state_root.join("skills").join("draft");
+        std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nenabled: false\n---\n# Draft\nWork in progress.\n",
+        )
+        .expect("write skill");
+
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root,
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let plugins = registry.list_plugins(None).expect("list plugins");
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].id, "quiet");
+        assert!(plugins[0].manifest_valid);
+        assert!(!plugins[0].enabled);
+
+        let skills = registry.list_skills(None).expect("list skills");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].title, "Draft");
+        assert_eq!(skills[0].description.as_deref(), Some("Work in progress."));
+        assert!(!skills[0].enabled);
+    }
+
     #[test]
     fn skill_discovery_and_check() {
         let temp = tempdir().expect("tempdir");
@@ -982,8 +2029,9 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
-        let skills = registry.list_skills().expect("list skills");
+        let skills = registry.list_skills(None).expect("list skills");
         assert_eq!(skills.len(), 1);
         assert_eq!(skills[0].id, "writer");
         assert_eq!(skills[0].title, "Writer");
@@ -994,6 +2042,301 @@ mod tests {
         assert_eq!(report.failed, 0);
     }
 
+    #[test]
+    fn list_source_filter_excludes_other_roots() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let codex_home = temp.path().join("codex");
+        for (root, id) in [
+            (state_root.join("plugins"), "shared"),
+            (codex_home.join("plugins"), "shared"),
+            (codex_home.join("plugins"), "codex_only"),
+        ] {
+            let dir = root.join(id);
+            std::fs::create_dir_all(&dir).expect("create plugin dir");
+            std::fs::write(
+                dir.join("plugin.toml"),
+                format!("[plugin]\nid = \"{id}\"\n"),
+            )
+            .expect("write manifest");
+        }
+        std::fs::create_dir_all(codex_home.join("skills").join("reviewer")).expect("skill dir");
+        std::fs::write(
+            codex_home.join("skills").join("reviewer").join("SKILL.md"),
+            "# Reviewer\n",
+        )
+        .expect("write skill");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root,
+            codex_home: Some(codex_home),
+            user_home: None,
+            state_file: None,
+        });
+
+        let all = registry.list_plugins(None).expect("list all");
+        assert_eq!(all.len(), 2);
+        let shared = all
+            .iter()
+            .find(|plugin| plugin.id == "shared")
+            .expect("shared");
+        assert_eq!(shared.source, ExtensionSource::Project);
+
+        let project = registry
+            .list_plugins(Some(ExtensionSource::Project))
+            .expect("list project");
+        assert_eq!(project.len(), 1);
+        assert!(
+            project
+                .iter()
+                .all(|plugin| plugin.source == ExtensionSource::Project)
+        );
+
+        let codex = registry
+            .list_plugins(Some(ExtensionSource::CodexHome))
+            .expect("list codex");
+        assert_eq!(codex.len(), 2);
+        assert!(
+            registry
+                .list_skills(Some(ExtensionSource::Project))
+                .expect("project skills")
+                .is_empty()
+        );
+        assert_eq!(registry.list_skills(None).expect("all skills").len(), 1);
+    }
+
+    #[test]
+    fn shadowed_plugins_are_reported() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let codex_home = temp.path().join("codex");
+        for root in [state_root.join("plugins"), codex_home.join("plugins")] {
+            std::fs::create_dir_all(root.join("shared")).expect("create plugin dir");
+            std::fs::write(
+                root.join("shared").join("plugin.toml"),
+                "[plugin]\nid = \"shared\"\n",
+            )
+            .expect("write manifest");
+        }
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root,
+            codex_home: Some(codex_home.clone()),
+            user_home: None,
+            state_file: None,
+        });
+
+        let winner = registry.plugin_info("shared").expect("plugin");
+        assert_eq!(winner.source, ExtensionSource::Project);
+        assert_eq!(winner.shadowed_by, None);
+        let hidden = registry
+            .list_plugins(Some(ExtensionSource::CodexHome))
+            .expect("list codex");
+        assert_eq!(hidden[0].shadowed_by, Some(ExtensionSource::Project));
+
+        let report = registry.check_plugins(Some("shared")).expect("check");
+        assert!(report.ok);
+        let item = report.results[0]
+            .checks
+            .iter()
+            .find(|item| item.name == "plugin_shadows")
+            .expect("shadow item");
+        assert!(item.ok);
+        assert!(item.detail.contains("codex_home"));
+        assert!(item.detail.contains(&codex_home.display().to_string()));
+    }
+
+    #[test]
+    fn search_ranks_id_matches_before_description_matches() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let plugins_root = state_root.join("plugins");
+        for (dir, manifest) in [
+            (
+                "formatter",
+                "[plugin]\nid = \"formatter\"\ndescription = \"Runs the project LINTER fixes\"\n",
+            ),
+            ("linter", "[plugin]\nid = \"linter\"\nname = \"Lint\"\n"),
+            (
+                "deploy",
+                "[plugin]\nid = \"deploy\"\ndescription = \"Ships builds\"\n",
+            ),
+        ] {
+            std::fs::create_dir_all(plugins_root.join(dir)).expect("create plugin dir");
+            std::fs::write(plugins_root.join(dir).join("plugin.toml"), manifest)
+                .expect("write manifest");
+        }
+        let skill_dir = state_root.join("skills").join("writer");
+        std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "# Writer\nDrafts release notes from merged changes.\n",
+        )
+        .expect("write skill");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root,
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+
+        let ids = registry
+            .search_plugins("Linter")
+            .expect("search plugins")
+            .into_iter()
+            .map(|plugin| plugin.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["linter", "formatter"]);
+        assert!(
+            registry
+                .search_plugins("missing")
+                .expect("search")
+                .is_empty()
+        );
+        assert!(registry.search_plugins("  ").is_err());
+
+        let skills = registry
+            .search_skills("release notes")
+            .expect("search skills");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].id, "writer");
+    }
+
+    #[test]
+    fn registry_applies_persisted_state_to_every_listing() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let plugin_dir = state_root.join("plugins").join("hello");
+        std::fs::create_dir_all(&plugin_dir).expect("create plugin dir");
+        std::fs::write(plugin_dir.join("plugin.toml"), "[plugin]\nid = \"hello\"\n")
+            .expect("write manifest");
+        let skill_dir = state_root.join("skills").join("writer");
+        std::fs::create_dir_all(&skill_dir).expect("create skill dir");
+        std::fs::write(skill_dir.join("SKILL.md"), "# Writer\nNotes.\n").expect("write skill");
+        let state_file = temp.path().join("data").join("plugins-state.json");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: Some(state_file.clone()),
+        });
+
+        assert!(
+            registry
+                .set_enabled(ExtensionKind::Plugin, "hello", false)
+                .expect("disable plugin")
+        );
+        assert!(
+            !registry
+                .set_enabled(ExtensionKind::Plugin, "hello", false)
+                .expect("disable again")
+        );
+        assert!(
+            registry
+                .set_enabled(ExtensionKind::Skill, "writer", false)
+                .expect("disable skill")
+        );
+        assert!(
+            registry
+                .set_enabled(ExtensionKind::Skill, "hello", false)
+                .is_err()
+        );
+
+        assert!(!registry.plugin_info("hello").expect("plugin").enabled);
+        assert!(!registry.list_skills(None).expect("skills")[0].enabled);
+        assert!(!registry.check_plugins(None).expect("check").results[0].enabled);
+        assert!(!registry.check_skills(None).expect("check").results[0].enabled);
+
+        assert!(
+            registry
+                .remove_project_skill("writer")
+                .expect("remove skill")
+        );
+        assert!(
+            !registry
+                .extension_state()
+                .expect("state")
+                .has_override(ExtensionKind::Skill, "writer")
+        );
+        std::fs::create_dir_all(&skill_dir).expect("recreate skill dir");
+        std::fs::write(skill_dir.join("SKILL.md"), "# Writer\nNotes.\n").expect("write skill");
+        assert!(registry.skill_info("writer").expect("reinstalled").enabled);
+    }
+
+    #[test]
+    fn load_extension_state_normalizes_every_list() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("plugins-state.json");
+        std::fs::write(
+            &path,
+            r#"{"version":1,"disabled_plugins":[" b","a","b"],"enabled_plugins":["z "," ","z"],"enabled_skills":["y","y "]}"#,
+        )
+        .expect("write state");
+        let state = load_extension_state(&path).expect("load state");
+        assert_eq!(state.disabled_plugins, ["a", "b"]);
+        assert_eq!(state.enabled_plugins, ["z"]);
+        assert_eq!(state.enabled_skills, ["y"]);
+    }
+
+    #[test]
+    fn extension_state_keys_overrides_by_kind() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("data").join("plugins-state.json");
+        let mut state = load_extension_state(&path).expect("missing state");
+        assert!(state.set_enabled(ExtensionKind::Skill, "shared", false, true));
+        assert!(!state.set_enabled(ExtensionKind::Skill, "shared", false, true));
+        assert!(state.set_enabled(ExtensionKind::Plugin, "optional", true, false));
+        save_extension_state(&path, &state).expect("save state");
+
+        let state = load_extension_state(&path).expect("load state");
+        assert!(!state.is_enabled(ExtensionKind::Skill, "shared", true));
+        assert!(state.is_enabled(ExtensionKind::Plugin, "shared", true));
+        assert!(state.is_enabled(ExtensionKind::Plugin, "optional", false));
+        assert!(!state.is_enabled(ExtensionKind::Skill, "optional", false));
+        assert_eq!(state.disabled_ids(ExtensionKind::Skill), ["shared"]);
+        assert!(state.disabled_ids(ExtensionKind::Plugin).is_empty());
+    }
+
+    #[test]
+    fn skill_front_matter_populates_summary_and_tags() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let skills_root = state_root.join("skills");
+        std::fs::create_dir_all(skills_root.join("writer")).expect("create writer");
+        std::fs::write(
+            skills_root.join("writer").join("SKILL.md"),
+            "---\ntitle: \"Release Writer\"\ndescription: Drafts release notes from merged PRs.\ntags: [docs, \"release\", docs]\n---\n# Writer\nBody text.\n",
+        )
+        .expect("write writer skill");
+        std::fs::create_dir_all(skills_root.join("triage")).expect("create triage");
+        std::fs::write(
+            skills_root.join("triage").join("SKILL.md"),
+            "---\ntags:\n  - issues\n  - 'support'\nenabled: false\n---\n# Triage\nSorts incoming issues.\n",
+        )
+        .expect("write triage skill");
+
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root,
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let writer = registry.skill_info("writer").expect("writer");
+        assert_eq!(writer.title, "Release Writer");
+        assert_eq!(
+            writer.description.as_deref(),
+            Some("Drafts release notes from merged PRs.")
+        );
+        assert_eq!(writer.tags, vec!["docs", "release"]);
+
+        let triage = registry.skill_info("triage").expect("triage");
+        assert_eq!(triage.title, "Triage");
+        assert_eq!(
+            triage.description.as_deref(),
+            Some("Sorts incoming issues.")
+        );
+        assert_eq!(triage.tags, vec!["issues", "support"]);
+        assert!(!triage.enabled);
+    }
+
     #[test]
     fn plugin_check_fails_without_manifest() {
         let temp = tempdir().expect("tempdir");
@@ -1005,6 +2348,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
         let report = registry.check_plugins(None).expect("check plugins");
         assert!(!report.ok);
@@ -1028,6 +2372,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
         let report = registry
             .check_plugins(Some("runtime_missing"))
@@ -1059,6 +2404,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
         let report = registry
             .check_plugins(Some("runtime_sandbox_bad"))
@@ -1090,6 +2436,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
         let report = registry
             .check_plugins(Some("runtime_output_bad"))
@@ -1121,6 +2468,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
         let report = registry
             .check_plugins(Some("runtime_watchdog_bad"))
@@ -1144,6 +2492,7 @@ mod tests {
             state_root: state_root.clone(),
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
 
         let source_dir = temp.path().join("plugin-source");
@@ -1156,7 +2505,7 @@ mod tests {
         std::fs::write(source_dir.join("README.md"), "hello plugin").expect("write readme");
 
         let installed = registry
-            .install_plugin_from_path(&source_dir, false)
+            .install_plugin_from_path(&source_dir, &InstallOptions::default())
             .expect("install plugin");
         assert_eq!(installed.kind, "plugin");
         assert_eq!(installed.id, "hello_plugin");
@@ -1166,10 +2515,486 @@ mod tests {
             .remove_project_plugin("hello_plugin")
             .expect("remove plugin");
         assert!(removed);
-        let after = registry.list_plugins().expect("list after remove");
+        let after = registry.list_plugins(None).expect("list after remove");
         assert!(after.is_empty());
     }
 
+    #[test]
+    fn install_dry_run_writes_nothing() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let plugin_source = temp.path().join("plugin-source");
+        std::fs::create_dir_all(&plugin_source).expect("create plugin source");
+        std::fs::write(
+            plugin_source.join("plugin.toml"),
+            "[plugin]\nid = \"hello_plugin\"\n",
+        )
+        .expect("write manifest");
+        let skill_source = temp.path().join("writer");
+        std::fs::create_dir_all(&skill_source).expect("create skill source");
+        std::fs::write(skill_source.join("SKILL.md"), "# Writer\n").expect("write skill");
+
+        let plugin = registry
+            .install_plugin_from_path(
+                &plugin_source,
+                &InstallOptions {
+                    dry_run: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect("plugin dry run");
+        assert!(plugin.dry_run);
+        assert!(!plugin.replaced);
+        assert_eq!(
+            PathBuf::from(&plugin.installed_path),
+            state_root.join("plugins").join("hello_plugin")
+        );
+        let skill = registry
+            .install_skill_from_path(&skill_source, false, true)
+            .expect("skill dry run");
+        assert!(skill.dry_run);
+        assert!(!state_root.exists());
+
+        registry
+            .install_plugin_from_path(&plugin_source, &InstallOptions::default())
+            .expect("install plugin");
+        std::fs::write(plugin_source.join("extra.txt"), "new").expect("write extra");
+        registry
+            .install_plugin_from_path(
+                &plugin_source,
+                &InstallOptions {
+                    dry_run: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect_err("dry run still requires force");
+        let replaced = registry
+            .install_plugin_from_path(
+                &plugin_source,
+                &InstallOptions {
+                    force: true,
+                    dry_run: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect("forced dry run");
+        assert!(replaced.replaced);
+        assert!(
+            !state_root
+                .join("plugins")
+                .join("hello_plugin")
+                .join("extra.txt")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn plugin_install_verifies_sha256() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let source_dir = temp.path().join("plugin-source");
+        std::fs::create_dir_all(&source_dir).expect("create source");
+        std::fs::write(
+            source_dir.join("plugin.toml"),
+            "[plugin]\nid = \"hello_plugin\"\n",
+        )
+        .expect("write manifest");
+        std::fs::write(source_dir.join("run.sh"), "echo hello").expect("write script");
+
+        let err = registry
+            .install_plugin_from_path(
+                &source_dir,
+                &InstallOptions {
+                    expected_sha256: Some("0".repeat(64)),
+                    ..InstallOptions::default()
+                },
+            )
+            .expect_err("wrong checksum should fail");
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert!(!state_root.join("plugins").join("hello_plugin").exists());
+
+        let digest = directory_sha256(&source_dir).expect("digest");
+        let installed = registry
+            .install_plugin_from_path(
+                &source_dir,
+                &InstallOptions {
+                    expected_sha256: Some(digest.to_ascii_uppercase()),
+                    ..InstallOptions::default()
+                },
+            )
+            .expect("install plugin");
+        assert_eq!(installed.sha256.as_deref(), Some(digest.as_str()));
+        assert!(PathBuf::from(installed.installed_path).exists());
+    }
+
+    #[test]
+    fn plugin_install_enforces_manifest_integrity() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let source_dir = temp.path().join("plugin-source");
+        std::fs::create_dir_all(&source_dir).expect("create source");
+        std::fs::write(source_dir.join("run.sh"), "echo hello").expect("write script");
+        let script_hash = file_sha256(&source_dir.join("run.sh")).expect("hash");
+        std::fs::write(
+            source_dir.join("plugin.toml"),
+            format!("[plugin]\nid = \"hello_plugin\"\n\n[integrity.files]\n\"run.sh\" = \"{script_hash}\"\n"),
+        )
+        .expect("write manifest");
+
+        std::fs::write(source_dir.join("run.sh"), "echo tampered").expect("tamper script");
+        let err = registry
+            .install_plugin_from_path(&source_dir, &InstallOptions::default())
+            .expect_err("tampered file should fail");
+        assert!(err.to_string().contains("integrity mismatch for 'run.sh'"));
+        assert!(!state_root.join("plugins").join("hello_plugin").exists());
+
+        std::fs::write(source_dir.join("run.sh"), "echo hello").expect("restore script");
+        registry
+            .install_plugin_from_path(&source_dir, &InstallOptions::default())
+            .expect("install plugin");
+    }
+
+    #[test]
+    fn plugin_checksums_detect_tampered_files() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let source_dir = temp.path().join("plugin-source");
+        std::fs::create_dir_all(source_dir.join("bin")).expect("create source");
+        std::fs::write(
+            source_dir.join("plugin.toml"),
+            "[plugin]\nid = \"hello_plugin\"\n",
+        )
+        .expect("write manifest");
+        std::fs::write(source_dir.join("bin/run.sh"), "echo hello").expect("write script");
+
+        let err = registry
+            .install_plugin_from_path(
+                &source_dir,
+                &InstallOptions {
+                    verify: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect_err("verify requires checksums.txt");
+        assert!(err.to_string().contains("missing checksums.txt"));
+
+        let script_hash = file_sha256(&source_dir.join("bin/run.sh")).expect("hash");
+        std::fs::write(
+            source_dir.join("checksums.txt"),
+            format!("{script_hash}  bin/run.sh\n"),
+        )
+        .expect("write checksums");
+        let err = registry
+            .install_plugin_from_path(
+                &source_dir,
+                &InstallOptions {
+                    verify: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect_err("manifest must be covered");
+        assert!(
+            err.to_string()
+                .contains("plugin file 'plugin.toml' is not listed in checksums.txt")
+        );
+
+        let manifest_hash = file_sha256(&source_dir.join("plugin.toml")).expect("hash");
+        std::fs::write(
+            source_dir.join("checksums.txt"),
+            format!("{script_hash}  bin/run.sh\n{manifest_hash}  plugin.toml\n"),
+        )
+        .expect("write checksums");
+        let installed = registry
+            .install_plugin_from_path(
+                &source_dir,
+                &InstallOptions {
+                    verify: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .expect("verified install");
+        let installed_dir = PathBuf::from(installed.installed_path);
+        let report = registry
+            .check_plugins(Some("hello_plugin"))
+            .expect("check plugins");
+        assert!(report.ok);
+
+        std::fs::write(installed_dir.join("bin/run.sh"), "echo tampered").expect("tamper");
+        let report = registry
+            .check_plugins(Some("hello_plugin"))
+            .expect("check plugins");
+        assert!(!report.ok);
+        let item = report.results[0]
+            .checks
+            .iter()
+            .find(|item| item.name == "plugin_checksums")
+            .expect("checksum check");
+        assert!(!item.ok);
+        assert!(item.detail.contains("checksum mismatch for 'bin/run.sh'"));
+
+        std::fs::write(installed_dir.join("bin/run.sh"), "echo hello").expect("restore");
+        std::fs::write(installed_dir.join("bin/doctor.sh"), "echo extra").expect("add hook");
+        let report = registry
+            .check_plugins(Some("hello_plugin"))
+            .expect("check plugins");
+        let item = report.results[0]
+            .checks
+            .iter()
+            .find(|item| item.name == "plugin_checksums")
+            .expect("checksum check");
+        assert!(!item.ok);
+        assert!(item.detail.contains("'bin/doctor.sh' is not listed"));
+    }
+
+    #[tokio::test]
+    async fn plugin_install_from_file_url_tarball() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let write_archive = |name: &str, files: &[(&str, &str)]| {
+            let archive_path = temp.path().join(name);
+            let encoder = flate2::write::GzEncoder::new(
+                File::create(&archive_path).expect("create archive"),
+                flate2::Compression::default(),
+            );
+            let mut builder = tar::Builder::new(encoder);
+            for (path, content) in files {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder
+                    .append_data(&mut header, path, content.as_bytes())
+                    .expect("append entry");
+            }
+            builder
+                .into_inner()
+                .expect("finish tar")
+                .finish()
+                .expect("finish gzip");
+            format!("file://{}", archive_path.display())
+        };
+
+        let missing = write_archive("missing.tar.gz", &[("README.md", "no manifest")]);
+        let err = registry
+            .install_plugin_from_url(&missing, &InstallOptions::default())
+            .await
+            .expect_err("archive without manifest should fail");
+        assert!(err.to_string().contains("does not contain a plugin.toml"));
+
+        let url = write_archive(
+            "hello-0.1.0.tgz",
+            &[
+                (
+                    "hello-0.1.0/plugin.toml",
+                    "[plugin]\nid = \"hello_plugin\"\nversion = \"0.1.0\"\n",
+                ),
+                ("hello-0.1.0/run.sh", "echo hello"),
+            ],
+        );
+        let installed = registry
+            .install_plugin_from_url(&url, &InstallOptions::default())
+            .await
+            .expect("install from url");
+        assert_eq!(installed.id, "hello_plugin");
+        assert_eq!(installed.source_path, url);
+        let installed_dir = state_root.join("plugins").join("hello_plugin");
+        assert_eq!(
+            std::fs::read_to_string(installed_dir.join("run.sh")).expect("read script"),
+            "echo hello"
+        );
+        registry
+            .install_plugin_from_url(&url, &InstallOptions::default())
+            .await
+            .expect_err("existing plugin needs force");
+        let replaced = registry
+            .install_plugin_from_url(
+                &url,
+                &InstallOptions {
+                    force: true,
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .expect("force reinstall");
+        assert!(replaced.replaced);
+    }
+
+    #[tokio::test]
+    async fn plugin_install_from_url_checks_the_archive_sha256() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let archive_path = temp.path().join("hello.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&archive_path).expect("create archive"),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let manifest = "[plugin]\nid = \"hello_plugin\"\nversion = \"0.1.0\"\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "plugin.toml", manifest.as_bytes())
+            .expect("append manifest");
+        builder
+            .into_inner()
+            .expect("finish tar")
+            .finish()
+            .expect("finish gzip");
+        let url = format!("file://{}", archive_path.display());
+        let archive_digest = file_sha256(&archive_path).expect("archive digest");
+
+        let err = registry
+            .install_plugin_from_url(
+                &url,
+                &InstallOptions {
+                    expected_sha256: Some("0".repeat(64)),
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .expect_err("wrong archive digest should fail");
+        assert!(err.to_string().contains("plugin archive checksum mismatch"));
+        assert!(!state_root.join("plugins").exists());
+
+        let installed = registry
+            .install_plugin_from_url(
+                &url,
+                &InstallOptions {
+                    expected_sha256: Some(format!("sha256:{archive_digest}")),
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .expect("archive digest matches");
+        assert_eq!(installed.sha256.as_deref(), Some(archive_digest.as_str()));
+        assert!(
+            state_root
+                .join("plugins/hello_plugin/plugin.toml")
+                .is_file()
+        );
+    }
+
+    #[tokio::test]
+    async fn plugin_install_from_git_url_uses_a_shallow_clone() {
+        let temp = tempdir().expect("tempdir");
+        let state_root = temp.path().join(".mosaic");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: state_root.clone(),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let repo = temp.path().join("hello-plugin");
+        std::fs::create_dir_all(&repo).expect("create repo");
+        std::fs::write(
+            repo.join("plugin.toml"),
+            "[plugin]\nid = \"hello_plugin\"\nversion = \"0.1.0\"\n",
+        )
+        .expect("write manifest");
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .expect("run git");
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        let missing = format!("git+file://{}", temp.path().join("missing").display());
+        let err = registry
+            .install_plugin_from_url(&missing, &InstallOptions::default())
+            .await
+            .expect_err("missing repository should fail");
+        assert!(
+            err.to_string()
+                .contains("failed to clone plugin repository")
+        );
+
+        let url = format!("git+file://{}", repo.display());
+        let installed = registry
+            .install_plugin_from_url(&url, &InstallOptions::default())
+            .await
+            .expect("install from git url");
+        assert_eq!(installed.id, "hello_plugin");
+        assert_eq!(installed.source_path, url);
+        let installed_dir = state_root.join("plugins").join("hello_plugin");
+        assert!(installed_dir.join("plugin.toml").is_file());
+        assert!(!installed_dir.join(".git").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_install_rejects_symlinks() {
+        let temp = tempdir().expect("tempdir");
+        let registry = ExtensionRegistry::new(RegistryRoots {
+            state_root: temp.path().join(".mosaic"),
+            codex_home: None,
+            user_home: None,
+            state_file: None,
+        });
+        let source_dir = temp.path().join("plugin-source");
+        std::fs::create_dir_all(&source_dir).expect("create source");
+        std::fs::write(
+            source_dir.join("plugin.toml"),
+            "[plugin]\nid = \"hello_plugin\"\n",
+        )
+        .expect("write manifest");
+        std::os::unix::fs::symlink("/etc/passwd", source_dir.join("run.sh")).expect("symlink");
+
+        let err = registry
+            .install_plugin_from_path(&source_dir, &InstallOptions::default())
+            .expect_err("symlink should be rejected");
+        assert!(err.to_string().contains("'run.sh' is a symlink"));
+        assert!(!temp.path().join(".mosaic/plugins/hello_plugin").exists());
+
+        let copy_err = copy_dir_recursive(&source_dir, &temp.path().join("copy"))
+            .expect_err("copy should refuse symlinks");
+        assert!(copy_err.to_string().contains("is a symlink"));
+    }
+
     #[test]
     fn skill_install_and_remove_flow() {
         let temp = tempdir().expect("tempdir");
@@ -1178,6 +3003,7 @@ mod tests {
             state_root,
             codex_home: None,
             user_home: None,
+            state_file: None,
         });
 
         let source_dir = temp.path().join("writer");
@@ -1190,7 +3016,7 @@ mod tests {
         std::fs::write(source_dir.join("template.md"), "template").expect("write asset");
 
         let installed = registry
-            .install_skill_from_path(&source_dir, false)
+            .install_skill_from_path(&source_dir, false, false)
             .expect("install skill");
         assert_eq!(installed.kind, "skill");
         assert_eq!(installed.id, "writer");
@@ -1200,7 +3026,7 @@ mod tests {
             .remove_project_skill("writer")
             .expect("remove skill");
         assert!(removed);
-        let after = registry.list_skills().expect("list skills");
+        let after = registry.list_skills(None).expect("list skills");
         assert!(after.is_empty());
     }
 }
@@ -0,0 +1,445 @@
+use std::time::Instant;
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use mosaic_core::config::ProfileConfig;
+use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::http::with_env_proxy;
+use mosaic_core::provider::{
+    ChatMessage, ChatRequest, ChatResponse, ChatRole, ModelInfo, NativeToolCall, Provider,
+    ProviderHealth, TokenUsage, ToolSpec,
+};
+
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// The Messages API requires `max_tokens`; used when the profile leaves it unset.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+#[derive(Debug, Clone)]
+pub struct AnthropicProvider {
+    client: Option<Client>,
+    base_url: String,
+    api_key: String,
+    api_key_env: String,
+    mock_mode: bool,
+}
+
+impl AnthropicProvider {
+    /// Creates a provider from a profile, deferring auth errors to request time.
+    pub fn from_profile(profile: &ProfileConfig) -> Result<Self> {
+        let base_url = profile.provider.base_url.clone();
+        let api_key_env = profile.provider.api_key_env.clone();
+        let api_key = if base_url.starts_with("mock://") {
+            "mock-key".to_string()
+        } else {
+            std::env::var(&api_key_env).unwrap_or_default()
+        };
+        Self::new_with_env(base_url, api_key, api_key_env)
+    }
+
+    pub fn new(base_url: String, api_key: String) -> Result<Self> {
+        Self::new_with_env(base_url, api_key, String::new())
+    }
+
+    fn new_with_env(base_url: String, api_key: String, api_key_env: String) -> Result<Self> {
+        let normalized = base_url.trim_end_matches('/').to_string();
+        let mock_mode = normalized.starts_with("mock://");
+        let client = if mock_mode {
+            None
+        } else {
+            Some(
+                with_env_proxy(Client::builder().timeout(std::time::Duration::from_secs(120)))?
+                    .build()
+                    .map_err(|err| {
+                        MosaicError::Network(format!("failed to initialize HTTP client: {err}"))
+                    })?,
+            )
+        };
+        Ok(Self {
+            client,
+            base_url: normalized,
+            api_key,
+            api_key_env,
+            mock_mode,
+        })
+    }
+
+    fn check_auth(&self) -> Result<()> {
+        if !self.mock_mode && self.api_key.trim().is_empty() {
+            let env = if self.api_key_env.is_empty() {
+                "an API key environment variable".to_string()
+            } else {
+                format!("environment variable {}", self.api_key_env)
+            };
+            return Err(MosaicError::Auth(format!("{env} is required")));
+        }
+        Ok(())
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        let normalized_path = path.trim_start_matches('/');
+        if self.base_url.ends_with("/v1") && normalized_path.starts_with("v1/") {
+            let without_v1 = normalized_path.trim_start_matches("v1/");
+            return format!("{}/{}", self.base_url, without_v1);
+        }
+        format!("{}/{}", self.base_url, normalized_path)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| MosaicError::Network("HTTP client is not initialized".to_string()))?;
+        Ok(client
+            .request(method, self.endpoint(path))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION))
+    }
+
+    async fn request_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        let response = request.send().await.map_err(|err| {
+            if err.is_timeout() {
+                MosaicError::Network("request timed out".to_string())
+            } else {
+                MosaicError::Network(err.to_string())
+            }
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<failed to read error body>".to_string());
+            return Err(match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    MosaicError::Auth(format!("provider rejected API key: {text}"))
+                }
+                _ => MosaicError::Network(format!("provider request failed ({status}): {text}")),
+            });
+        }
+        response.json::<T>().await.map_err(|err| {
+            MosaicError::Network(format!("failed to parse provider response: {err}"))
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for AnthropicProvider {
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        if self.mock_mode {
+            let model = self.base_url.trim_start_matches("mock://").trim();
+            let model = if model.is_empty() {
+                "mock-model"
+            } else {
+                model
+            };
+            return Ok(vec![ModelInfo {
+                id: model.to_string(),
+                owned_by: Some("anthropic".to_string()),
+                context_window: None,
+                capabilities: Vec::new(),
+                warnings: Vec::new(),
+            }]);
+        }
+        self.check_auth()?;
+        let req = self.request(reqwest::Method::GET, "/v1/models")?;
+        let payload: ModelsResponse = self.request_json(req).await?;
+        Ok(payload
+            .data
+            .into_iter()
+            .map(|model| ModelInfo {
+                id: model.id,
+                owned_by: Some("anthropic".to_string()),
+                context_window: None,
+                capabilities: Vec::new(),
+                warnings: Vec::new(),
+            })
+            .collect())
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        if self.mock_mode {
+            let content = std::env::var("MOSAIC_MOCK_CHAT_RESPONSE")
+                .unwrap_or_else(|_| "mock-answer".to_string());
+            return Ok(ChatResponse {
+                content,
+                usage: None,
+                tool_calls: Vec::new(),
+                model: None,
+            });
+        }
+        self.check_auth()?;
+        let req = self
+            .request(reqwest::Method::POST, "/v1/messages")?
+            .json(&MessagesRequest::from_chat(request));
+        let payload: MessagesResponse = self.request_json(req).await?;
+        Ok(payload.into_chat_response())
+    }
+
+    async fn health(&self) -> Result<ProviderHealth> {
+        let started = Instant::now();
+        let ok = self.list_models().await.is_ok();
+        let latency_ms = Some(started.elapsed().as_millis());
+        let detail = if ok {
+            "provider reachable".to_string()
+        } else {
+            "provider request failed".to_string()
+        };
+        Ok(ProviderHealth {
+            ok,
+            latency_ms,
+            detail,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelItem {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MessagesRequest {
+    model: String,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<AnthropicMessage>,
+    temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<AnthropicTool>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+}
+
+impl AnthropicTool {
+    fn from_spec(spec: ToolSpec) -> Self {
+        Self {
+            name: spec.name,
+            description: spec.description,
+            input_schema: spec.parameters,
+        }
+    }
+}
+
+impl MessagesRequest {
+    /// System messages are hoisted into the top-level `system` field, which is
+    /// the only place the Messages API accepts them.
+    fn from_chat(request: ChatRequest) -> Self {
+        let mut system = Vec::new();
+        let mut messages = Vec::new();
+        for message in request.messages {
+            match message.role {
+                ChatRole::System => system.push(message.content),
+                ChatRole::User | ChatRole::Assistant => {
+                    messages.push(AnthropicMessage::from_chat_message(message));
+                }
+            }
+        }
+        Self {
+            model: request.model,
+            max_tokens: request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages,
+            // Anthropic accepts [0.0, 1.0]; profiles allow up to 2.0.
+            temperature: request.temperature.clamp(0.0, 1.0),
+            top_p: request.top_p,
+            stop_sequences: request.stop,
+            tools: request
+                .tools
+                .into_iter()
+                .map(AnthropicTool::from_spec)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicMessage {
+    role: &'static str,
+    content: String,
+}
+
+impl AnthropicMessage {
+    fn from_chat_message(message: ChatMessage) -> Self {
+        let role = match message.role {
+            ChatRole::Assistant => "assistant",
+            ChatRole::User | ChatRole::System => "user",
+        };
+        Self {
+            role,
+            content: message.content,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    #[serde(default)]
+    content: Vec<Value>,
+    #[serde(default)]
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl MessagesResponse {
+    fn into_chat_response(self) -> ChatResponse {
+        ChatResponse {
+            content: content_blocks_to_text(&self.content),
+            usage: self.usage.map(|usage| TokenUsage {
+                prompt_tokens: usage.input_tokens,
+                completion_tokens: usage.output_tokens,
+                total_tokens: usage.input_tokens.saturating_add(usage.output_tokens),
+            }),
+            tool_calls: tool_use_blocks(&self.content),
+            model: None,
+        }
+    }
+}
+
+fn tool_use_blocks(blocks: &[Value]) -> Vec<NativeToolCall> {
+    blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("tool_use"))
+        .filter_map(|block| {
+            Some(NativeToolCall {
+                id: block.get("id").and_then(Value::as_str).map(str::to_string),
+                name: block.get("name").and_then(Value::as_str)?.to_string(),
+                arguments: block
+                    .get("input")
+                    .cloned()
+                    .unwrap_or_else(|| Value::Object(Default::default())),
+            })
+        })
+        .collect()
+}
+
+/// Joins `text` blocks; `tool_use` blocks become tool calls and other block
+/// types (e.g. `thinking`) are skipped.
+fn content_blocks_to_text(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+        .filter_map(|block| block.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_response_parses_text_blocks_and_usage() {
+        let payload: MessagesResponse = serde_json::from_value(serde_json::json!({
+            "id": "msg_01",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                { "type": "thinking", "thinking": "hidden" },
+                { "type": "text", "text": "first" },
+                { "type": "tool_use", "id": "toolu_1", "name": "read_file", "input": {} },
+                { "type": "text", "text": "second" }
+            ],
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 20, "output_tokens": 7 }
+        }))
+        .expect("messages payload");
+        let response = payload.into_chat_response();
+        assert_eq!(response.content, "first\nsecond");
+        assert_eq!(
+            response.tool_calls,
+            vec![NativeToolCall {
+                id: Some("toolu_1".to_string()),
+                name: "read_file".to_string(),
+                arguments: serde_json::json!({}),
+            }]
+        );
+        assert_eq!(
+            response.usage,
+            Some(TokenUsage {
+                prompt_tokens: 20,
+                completion_tokens: 7,
+                total_tokens: 27,
+            })
+        );
+    }
+
+    #[test]
+    fn messages_request_hoists_system_prompt() {
+        let request = MessagesRequest::from_chat(ChatRequest {
+            model: "claude-test".to_string(),
+            temperature: 1.5,
+            messages: vec![
+                ChatMessage {
+                    role: ChatRole::System,
+                    content: "be brief".to_string(),
+                },
+                ChatMessage {
+                    role: ChatRole::User,
+                    content: "hello".to_string(),
+                },
+            ],
+            max_tokens: None,
+            top_p: None,
+            stop: vec!["END".to_string()],
+            tools: Vec::new(),
+        });
+        let value = serde_json::to_value(request).expect("serialize");
+        assert_eq!(value["system"], "be brief");
+        assert_eq!(value["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert_eq!(value["temperature"], 1.0);
+        assert_eq!(value["stop_sequences"], serde_json::json!(["END"]));
+        assert_eq!(
+            value["messages"],
+            serde_json::json!([{ "role": "user", "content": "hello" }])
+        );
+        assert!(value.get("top_p").is_none());
+    }
+
+    #[tokio::test]
+    async fn mock_mode_answers_without_network() {
+        let provider = AnthropicProvider::new("mock://claude-test".to_string(), String::new())
+            .expect("mock provider");
+        let models = provider.list_models().await.expect("models");
+        assert_eq!(models[0].id, "claude-test");
+        let response = provider
+            .chat(ChatRequest {
+                model: "claude-test".to_string(),
+                temperature: 0.2,
+                messages: Vec::new(),
+                max_tokens: None,
+                top_p: None,
+                stop: Vec::new(),
+                tools: Vec::new(),
+            })
+            .await
+            .expect("chat");
+        assert!(!response.content.is_empty());
+    }
+}
@@ -1,13 +1,16 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use reqwest::{Client, StatusCode};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::{Client, StatusCode, header::RETRY_AFTER};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
-use mosaic_core::config::ProfileConfig;
+use mosaic_core::config::{ProfileConfig, ProviderKind};
 use mosaic_core::error::{MosaicError, Result};
+use mosaic_core::http::with_env_proxy;
 use mosaic_core::provider::{
-    ChatMessage, ChatRequest, ChatResponse, ChatRole, ModelInfo, Provider, ProviderHealth,
+    ChatChunkSink, ChatMessage, ChatRequest, ChatResponse, ChatRole, EmbeddingRequest, ModelInfo,
+    NativeToolCall, Provider, ProviderHealth, TokenUsage, ToolSpec,
 };
 
 #[derive(Debug, Clone)]
@@ -17,8 +20,46 @@ pub struct OpenAiCompatibleProvider {
     api_key: String,
     api_key_env: String,
     mock_mode: bool,
+    retry: RetryPolicy,
+    /// `api-version` for Azure OpenAI; `Some` switches to deployment URLs and
+    /// `api-key` auth.
+    azure_api_version: Option<String>,
 }
 
+/// Bounded retry for transient provider failures (429 and 5xx).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (0-based): `Retry-After` when the
+    /// server sent one, exponential backoff otherwise, capped at `max_delay`.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let delay = retry_after.unwrap_or_else(|| {
+            self.base_delay
+                .saturating_mul(2u32.saturating_pow(attempt.min(16)))
+        });
+        delay.min(self.max_delay)
+    }
+}
+
+const DEFAULT_MAX_RETRIES: u32 = 2;
+/// Azure OpenAI `api-version` used when the profile leaves it unset.
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
+
 impl OpenAiCompatibleProvider {
     /// Creates a provider from a profile, deferring auth errors to request time.
     /// This allows the TUI to launch even before an API key is configured.
@@ -30,7 +71,33 @@ impl OpenAiCompatibleProvider {
         } else {
             std::env::var(&api_key_env).unwrap_or_default()
         };
-        Self::new_with_env(base_url, api_key, api_key_env)
+        let mut provider = Self::new_with_env(base_url, api_key, api_key_env)?;
+        if let Some(max_retries) = profile.provider.max_retries {
+            provider.retry.max_retries = max_retries;
+        }
+        if matches!(profile.provider.kind, ProviderKind::AzureOpenAi) {
+            provider = provider.with_azure_api_version(
+                profile
+                    .provider
+                    .api_version
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string()),
+            );
+        }
+        Ok(provider)
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Targets Azure OpenAI: requests go to
+    /// `/openai/deployments/<model>/...?api-version=<version>` and authenticate
+    /// with an `api-key` header instead of a bearer token.
+    pub fn with_azure_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.azure_api_version = Some(api_version.into());
+        self
     }
 
     pub fn new(base_url: String, api_key: String) -> Result<Self> {
@@ -44,8 +111,7 @@ impl OpenAiCompatibleProvider {
             None
         } else {
             Some(
-                Client::builder()
-                    .timeout(std::time::Duration::from_secs(60))
+                with_env_proxy(Client::builder().timeout(std::time::Duration::from_secs(60)))?
                     .build()
                     .map_err(|err| {
                         MosaicError::Network(format!("failed to initialize HTTP client: {err}"))
@@ -58,6 +124,8 @@ impl OpenAiCompatibleProvider {
             api_key,
             api_key_env,
             mock_mode,
+            retry: RetryPolicy::default(),
+            azure_api_version: None,
         })
     }
 
@@ -75,6 +143,14 @@ impl OpenAiCompatibleProvider {
 
     fn endpoint(&self, path: &str) -> String {
         let normalized_path = path.trim_start_matches('/');
+        if let Some(api_version) = &self.azure_api_version {
+            let without_v1 = normalized_path.trim_start_matches("v1/");
+            return format!(
+                "{}/openai/{without_v1}?api-version={}",
+                self.azure_root(),
+                encode_url_component(api_version)
+            );
+        }
         if self.base_url.ends_with("/v1") && normalized_path.starts_with("v1/") {
             let without_v1 = normalized_path.trim_start_matches("v1/");
             return format!("{}/{}", self.base_url, without_v1);
@@ -82,17 +158,77 @@ impl OpenAiCompatibleProvider {
         format!("{}/{}", self.base_url, normalized_path)
     }
 
+    /// Endpoint for a model-scoped call; Azure addresses the model as a
+    /// deployment in the path, everything else uses [`Self::endpoint`].
+    fn model_endpoint(&self, path: &str, model: &str) -> String {
+        let Some(api_version) = &self.azure_api_version else {
+            return self.endpoint(path);
+        };
+        let without_v1 = path.trim_start_matches('/').trim_start_matches("v1/");
+        format!(
+            "{}/openai/deployments/{}/{without_v1}?api-version={}",
+            self.azure_root(),
+            encode_url_component(model),
+            encode_url_component(api_version)
+        )
+    }
+
+    /// Azure resource URL with any `/openai` or `/openai/v1` suffix removed.
+    fn azure_root(&self) -> &str {
+        let base = self.base_url.trim_end_matches("/v1");
+        base.strip_suffix("/openai").unwrap_or(base)
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> Result<reqwest::RequestBuilder> {
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| MosaicError::Network("HTTP client is not initialized".to_string()))?;
+        let request = client.request(method, url);
+        Ok(if self.azure_api_version.is_some() {
+            request.header("api-key", &self.api_key)
+        } else {
+            request.bearer_auth(&self.api_key)
+        })
+    }
+
     async fn request_json<T: for<'de> Deserialize<'de>>(
         &self,
         request: reqwest::RequestBuilder,
     ) -> Result<T> {
-        let response = request.send().await.map_err(|err| {
-            if err.is_timeout() {
-                MosaicError::Network("request timed out".to_string())
+        let response = self.send_checked(request).await?;
+        response.json::<T>().await.map_err(|err| {
+            MosaicError::Network(format!("failed to parse provider response: {err}"))
+        })
+    }
+
+    async fn send_checked(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let mut current = request;
+        let mut attempt = 0;
+        let response = loop {
+            // Bodies built with `.json` clone cheaply; anything else is sent once.
+            let retry_copy = if attempt < self.retry.max_retries {
+                current.try_clone()
             } else {
-                MosaicError::Network(err.to_string())
+                None
+            };
+            let response = current.send().await.map_err(|err| {
+                if err.is_timeout() {
+                    MosaicError::Network("request timed out".to_string())
+                } else {
+                    MosaicError::Network(err.to_string())
+                }
+            })?;
+            match retry_copy {
+                Some(next) if is_retryable_status(response.status()) => {
+                    let retry_after = parse_retry_after(response.headers());
+                    tokio::time::sleep(self.retry.delay(attempt, retry_after)).await;
+                    current = next;
+                    attempt += 1;
+                }
+                _ => break response,
             }
-        })?;
+        };
         let status = response.status();
         if !status.is_success() {
             let text = response
@@ -106,9 +242,7 @@ impl OpenAiCompatibleProvider {
                 _ => MosaicError::Network(format!("provider request failed ({status}): {text}")),
             });
         }
-        response.json::<T>().await.map_err(|err| {
-            MosaicError::Network(format!("failed to parse provider response: {err}"))
-        })
+        Ok(response)
     }
 }
 
@@ -129,60 +263,117 @@ impl Provider for OpenAiCompatibleProvider {
             return Ok(vec![ModelInfo {
                 id: model,
                 owned_by: Some("mock".to_string()),
+                context_window: None,
+                capabilities: Vec::new(),
+                warnings: Vec::new(),
             }]);
         }
         self.check_auth()?;
-        let endpoint = self.endpoint("/v1/models");
-        let req = self
-            .client
-            .as_ref()
-            .ok_or_else(|| MosaicError::Network("HTTP client is not initialized".to_string()))?
-            .get(endpoint)
-            .bearer_auth(&self.api_key);
+        let req = self.request(reqwest::Method::GET, self.endpoint("/v1/models"))?;
         let payload: ModelsResponse = self.request_json(req).await?;
-        let models = payload
+        Ok(payload
             .data
             .into_iter()
-            .map(|item| ModelInfo {
-                id: item.id,
-                owned_by: item.owned_by,
-            })
-            .collect();
-        Ok(models)
+            .filter_map(model_info_from_value)
+            .collect())
     }
 
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         if self.mock_mode {
             maybe_capture_mock_chat_request(&request);
-            let content = std::env::var("MOSAIC_MOCK_CHAT_RESPONSE")
-                .unwrap_or_else(|_| "mock-answer".to_string());
-            return Ok(ChatResponse { content });
+            let content = mock_chat_response();
+            let usage = mock_usage(&request, &content);
+            return Ok(ChatResponse {
+                content,
+                usage: Some(usage),
+                tool_calls: Vec::new(),
+                model: None,
+            });
         }
         self.check_auth()?;
-        let endpoint = self.endpoint("/v1/chat/completions");
-        let messages = request
-            .messages
-            .into_iter()
-            .map(OpenAiMessage::from_chat_message)
-            .collect::<Vec<_>>();
+        let endpoint = self.model_endpoint("/v1/chat/completions", &request.model);
         let req = self
-            .client
-            .as_ref()
-            .ok_or_else(|| MosaicError::Network("HTTP client is not initialized".to_string()))?
-            .post(endpoint)
-            .bearer_auth(&self.api_key)
-            .json(&ChatCompletionRequest {
-                model: request.model,
-                temperature: request.temperature,
-                messages,
-            });
+            .request(reqwest::Method::POST, endpoint)?
+            .json(&ChatCompletionRequest::from_chat(request, false));
         let payload: ChatCompletionResponse = self.request_json(req).await?;
         let choice =
             payload.choices.into_iter().next().ok_or_else(|| {
                 MosaicError::Network("provider returned no chat choices".to_string())
             })?;
         Ok(ChatResponse {
+            tool_calls: choice.message.native_tool_calls(),
             content: content_to_text(choice.message.content),
+            usage: payload.usage,
+            model: None,
+        })
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+        on_chunk: &mut ChatChunkSink<'_>,
+    ) -> Result<ChatResponse> {
+        if self.mock_mode {
+            maybe_capture_mock_chat_request(&request);
+            let content = mock_chat_response();
+            let usage = mock_usage(&request, &content);
+            let mut split = content.len() / 2;
+            while !content.is_char_boundary(split) {
+                split += 1;
+            }
+            let (head, tail) = content.split_at(split);
+            for chunk in [head, tail] {
+                if !chunk.is_empty() {
+                    on_chunk(chunk);
+                }
+            }
+            return Ok(ChatResponse {
+                content,
+                usage: Some(usage),
+                tool_calls: Vec::new(),
+                model: None,
+            });
+        }
+        self.check_auth()?;
+        let endpoint = self.model_endpoint("/v1/chat/completions", &request.model);
+        let req = self
+            .request(reqwest::Method::POST, endpoint)?
+            .json(&ChatCompletionRequest::from_chat(request, true));
+        let mut response = self.send_checked(req).await?;
+
+        let mut parser = SseParser::default();
+        let mut content = String::new();
+        let mut usage = None;
+        loop {
+            let chunk = response.chunk().await.map_err(|err| {
+                MosaicError::Network(format!("failed to read provider stream: {err}"))
+            })?;
+            let Some(bytes) = chunk else {
+                break;
+            };
+            for event in parser.push(&bytes)? {
+                match event {
+                    SseEvent::Delta(delta) => {
+                        on_chunk(&delta);
+                        content.push_str(&delta);
+                    }
+                    SseEvent::Usage(reported) => usage = Some(reported),
+                    SseEvent::Done => {
+                        return Ok(ChatResponse {
+                            content,
+                            usage,
+                            tool_calls: Vec::new(),
+                            model: None,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(ChatResponse {
+            content,
+            usage,
+            tool_calls: Vec::new(),
+            model: None,
         })
     }
 
@@ -201,6 +392,92 @@ impl Provider for OpenAiCompatibleProvider {
             detail,
         })
     }
+
+    async fn embed(&self, request: EmbeddingRequest) -> Result<Vec<Vec<f32>>> {
+        if self.mock_mode {
+            return Ok(request
+                .inputs
+                .iter()
+                .map(|input| mock_embedding(input))
+                .collect());
+        }
+        self.check_auth()?;
+        let endpoint = self.model_endpoint("/v1/embeddings", &request.model);
+        let input_count = request.inputs.len();
+        let req = self.request(reqwest::Method::POST, endpoint)?.json(&json!({
+            "model": request.model,
+            "input": request.inputs,
+        }));
+        let mut payload: EmbeddingsResponse = self.request_json(req).await?;
+        if payload.data.len() != input_count {
+            return Err(MosaicError::Network(format!(
+                "provider returned {} embeddings for {input_count} inputs",
+                payload.data.len()
+            )));
+        }
+        payload.data.sort_by_key(|item| item.index);
+        Ok(payload
+            .data
+            .into_iter()
+            .map(|item| item.embedding)
+            .collect())
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Reads `Retry-After` in its delay-seconds form; HTTP dates fall back to backoff.
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+// Mock embeddings hash each lowercase word into a fixed number of buckets, so
+// texts sharing words get similar vectors without a model.
+const MOCK_EMBEDDING_DIMENSIONS: usize = 64;
+
+fn mock_embedding(input: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; MOCK_EMBEDDING_DIMENSIONS];
+    for word in input
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let bucket = word.to_lowercase().bytes().fold(0usize, |hash, byte| {
+            hash.wrapping_mul(31).wrapping_add(byte as usize)
+        }) % MOCK_EMBEDDING_DIMENSIONS;
+        vector[bucket] += 1.0;
+    }
+    vector
+}
+
+fn mock_chat_response() -> String {
+    std::env::var("MOSAIC_MOCK_CHAT_RESPONSE").unwrap_or_else(|_| "mock-answer".to_string())
+}
+
+/// Mock mode has no tokenizer, so usage is approximated as four characters per token.
+fn mock_usage(request: &ChatRequest, content: &str) -> TokenUsage {
+    let estimate = |chars: usize| chars.div_ceil(4) as u64;
+    let prompt_tokens = estimate(
+        request
+            .messages
+            .iter()
+            .map(|message| message.content.chars().count())
+            .sum(),
+    );
+    let completion_tokens = estimate(content.chars().count());
+    TokenUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    }
 }
 
 fn maybe_capture_mock_chat_request(request: &ChatRequest) {
@@ -221,11 +498,20 @@ fn maybe_capture_mock_chat_request(request: &ChatRequest) {
             })
         })
         .collect::<Vec<_>>();
-    let payload = json!({
+    let mut payload = json!({
         "model": request.model,
         "temperature": request.temperature,
         "messages": messages,
     });
+    if let Some(max_tokens) = request.max_tokens {
+        payload["max_tokens"] = json!(max_tokens);
+    }
+    if let Some(top_p) = request.top_p {
+        payload["top_p"] = json!(top_p);
+    }
+    if !request.stop.is_empty() {
+        payload["stop"] = json!(request.stop);
+    }
     if let Ok(raw) = serde_json::to_vec_pretty(&payload) {
         let _ = std::fs::write(path, raw);
     }
@@ -233,13 +519,112 @@ fn maybe_capture_mock_chat_request(request: &ChatRequest) {
 
 #[derive(Debug, Deserialize)]
 struct ModelsResponse {
-    data: Vec<ModelItem>,
+    data: Vec<Value>,
 }
 
 #[derive(Debug, Deserialize)]
-struct ModelItem {
-    id: String,
-    owned_by: Option<String>,
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingItem {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+// OpenAI itself omits these; compatible servers name them differently.
+const CONTEXT_WINDOW_KEYS: [&str; 3] = ["context_window", "context_length", "max_model_len"];
+
+/// Parses one `/v1/models` entry without letting it fail the whole listing.
+/// Optional fields that do not parse are dropped and named in `warnings`;
+/// entries without a string id cannot be selected and are skipped.
+fn model_info_from_value(value: Value) -> Option<ModelInfo> {
+    let Value::Object(mut item) = value else {
+        return None;
+    };
+    let id = match item.remove("id") {
+        Some(Value::String(id)) if !id.trim().is_empty() => id,
+        _ => return None,
+    };
+    let mut warnings = Vec::new();
+    let owned_by = match item.remove("owned_by") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(owner)) => Some(owner),
+        Some(other) => {
+            warnings.push(format!("ignored owned_by: expected a string, got {other}"));
+            None
+        }
+    };
+    let mut context_window = None;
+    for key in CONTEXT_WINDOW_KEYS {
+        let Some(raw) = item.remove(key).filter(|raw| !raw.is_null()) else {
+            continue;
+        };
+        match context_window_from_value(&raw) {
+            Some(parsed) if context_window.is_none() => context_window = Some(parsed),
+            Some(_) => {}
+            None => warnings.push(format!(
+                "ignored {key}: expected a token count up to {}, got {raw}",
+                u32::MAX
+            )),
+        }
+    }
+    let capabilities = match item.remove("capabilities") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(value @ (Value::Array(_) | Value::Object(_))) => capabilities_from_value(value),
+        Some(other) => {
+            warnings.push(format!(
+                "ignored capabilities: expected a list or an object of flags, got {other}"
+            ));
+            Vec::new()
+        }
+    };
+    Some(ModelInfo {
+        id,
+        owned_by,
+        context_window,
+        capabilities,
+        warnings,
+    })
+}
+
+/// Accepts integers, whole floats (`131072.0`) and numeric strings.
+fn context_window_from_value(value: &Value) -> Option<u32> {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .or_else(|| {
+                number
+                    .as_f64()
+                    .filter(|value| value.fract() == 0.0 && *value >= 0.0)
+                    .map(|value| value as u64)
+            })
+            .and_then(|value| u32::try_from(value).ok()),
+        Value::String(text) => text.trim().parse::<u32>().ok(),
+        _ => None,
+    }
+}
+
+/// Accepts either a list of names or an object of `name: bool` flags.
+fn capabilities_from_value(value: Value) -> Vec<String> {
+    let mut capabilities = match value {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| item.as_str().map(|text| text.trim().to_string()))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>(),
+        Value::Object(map) => map
+            .into_iter()
+            .filter(|(_, enabled)| enabled.as_bool() == Some(true))
+            .map(|(name, _)| name)
+            .collect(),
+        _ => Vec::new(),
+    };
+    capabilities.sort();
+    capabilities.dedup();
+    capabilities
 }
 
 #[derive(Debug, Serialize)]
@@ -247,12 +632,141 @@ struct ChatCompletionRequest {
     model: String,
     temperature: f32,
     messages: Vec<OpenAiMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OpenAiTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+}
+
+/// Asks for a final chunk carrying token usage, which streams otherwise omit.
+#[derive(Debug, Serialize)]
+struct StreamOptions {
+    include_usage: bool,
+}
+
+impl ChatCompletionRequest {
+    fn from_chat(request: ChatRequest, stream: bool) -> Self {
+        Self {
+            model: request.model,
+            temperature: request.temperature,
+            messages: request
+                .messages
+                .into_iter()
+                .map(OpenAiMessage::from_chat_message)
+                .collect(),
+            max_tokens: request.max_tokens,
+            top_p: request.top_p,
+            stop: request.stop,
+            // Streamed tool call deltas are not assembled, so streaming
+            // requests rely on the text tool protocol instead.
+            tools: if stream {
+                Vec::new()
+            } else {
+                request
+                    .tools
+                    .into_iter()
+                    .map(OpenAiTool::from_spec)
+                    .collect()
+            },
+            stream: stream.then_some(true),
+            stream_options: stream.then_some(StreamOptions {
+                include_usage: true,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SseEvent {
+    Delta(String),
+    Usage(TokenUsage),
+    Done,
+}
+
+/// Incremental parser for `text/event-stream` chat completion bodies. Bytes are
+/// buffered until a full line arrives, so chunk boundaries may fall anywhere.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<SseEvent>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = self.buffer.drain(..=newline).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim();
+            if data == "[DONE]" {
+                events.push(SseEvent::Done);
+                continue;
+            }
+            let chunk: StreamChunk = serde_json::from_str(data).map_err(|err| {
+                MosaicError::Network(format!("failed to parse provider stream event: {err}"))
+            })?;
+            if let Some(error) = chunk.error {
+                return Err(MosaicError::Network(format!(
+                    "provider stream failed: {error}"
+                )));
+            }
+            // With `include_usage`, the last chunk has no choices and reports usage.
+            if let Some(usage) = chunk.usage {
+                events.push(SseEvent::Usage(usage));
+            }
+            let delta = chunk
+                .choices
+                .into_iter()
+                .filter_map(|choice| choice.delta.content)
+                .collect::<String>();
+            if !delta.is_empty() {
+                events.push(SseEvent::Delta(delta));
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    #[serde(default)]
+    error: Option<Value>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StreamDelta {
+    #[serde(default)]
+    content: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct OpenAiMessage {
     role: String,
+    #[serde(default)]
     content: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<OpenAiToolCall>,
 }
 
 impl OpenAiMessage {
@@ -266,6 +780,62 @@ impl OpenAiMessage {
         Self {
             role,
             content: Value::String(message.content),
+            tool_calls: Vec::new(),
+        }
+    }
+
+    /// Function arguments arrive as a JSON-encoded string; anything that fails
+    /// to parse is passed through verbatim so the tool reports the error.
+    fn native_tool_calls(&self) -> Vec<NativeToolCall> {
+        self.tool_calls
+            .iter()
+            .map(|call| NativeToolCall {
+                id: call.id.clone(),
+                name: call.function.name.clone(),
+                arguments: serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| Value::String(call.function.arguments.clone())),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenAiToolCall {
+    #[serde(default)]
+    id: Option<String>,
+    function: OpenAiFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenAiFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiTool {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: OpenAiFunction,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiFunction {
+    name: String,
+    description: String,
+    parameters: Value,
+}
+
+impl OpenAiTool {
+    fn from_spec(spec: ToolSpec) -> Self {
+        Self {
+            kind: "function",
+            function: OpenAiFunction {
+                name: spec.name,
+                description: spec.description,
+                parameters: spec.parameters,
+            },
         }
     }
 }
@@ -273,6 +843,8 @@ impl OpenAiMessage {
 #[derive(Debug, Deserialize)]
 struct ChatCompletionResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -280,9 +852,24 @@ struct Choice {
     message: OpenAiMessage,
 }
 
+/// Characters left as-is in a URL path segment or query value: RFC 3986
+/// unreserved characters.
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Percent-encodes a user-supplied deployment name or `api-version` so it
+/// stays one path segment or query value.
+fn encode_url_component(value: &str) -> String {
+    utf8_percent_encode(value, URL_COMPONENT).to_string()
+}
+
 fn content_to_text(content: Value) -> String {
     match content {
         Value::String(text) => text,
+        Value::Null => String::new(),
         Value::Array(parts) => parts
             .into_iter()
             .filter_map(|part| {
@@ -309,6 +896,381 @@ mod tests {
         assert_eq!(content_to_text(value), "a\nb");
     }
 
+    #[test]
+    fn chat_completion_response_parses_optional_usage() {
+        let with_usage: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": "hi" } }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15 }
+        }))
+        .expect("response with usage");
+        assert_eq!(
+            with_usage.usage,
+            Some(TokenUsage {
+                prompt_tokens: 12,
+                completion_tokens: 3,
+                total_tokens: 15,
+            })
+        );
+
+        let without_usage: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": "hi" } }]
+        }))
+        .expect("response without usage");
+        assert_eq!(without_usage.usage, None);
+    }
+
+    #[test]
+    fn chat_completion_response_parses_native_tool_calls() {
+        let payload: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        {
+                            "id": "call_1",
+                            "type": "function",
+                            "function": { "name": "read_file", "arguments": "{\"path\":\"README.md\"}" }
+                        },
+                        {
+                            "id": "call_2",
+                            "type": "function",
+                            "function": { "name": "run_cmd", "arguments": "not json" }
+                        }
+                    ]
+                }
+            }]
+        }))
+        .expect("tool call payload");
+        let message = &payload.choices[0].message;
+        assert_eq!(
+            message.native_tool_calls(),
+            vec![
+                NativeToolCall {
+                    id: Some("call_1".to_string()),
+                    name: "read_file".to_string(),
+                    arguments: serde_json::json!({ "path": "README.md" }),
+                },
+                NativeToolCall {
+                    id: Some("call_2".to_string()),
+                    name: "run_cmd".to_string(),
+                    arguments: Value::String("not json".to_string()),
+                },
+            ]
+        );
+        assert_eq!(content_to_text(message.content.clone()), "");
+    }
+
+    #[test]
+    fn chat_completion_request_offers_tools_only_when_not_streaming() {
+        let request = ChatRequest {
+            model: "gpt-test".to_string(),
+            temperature: 0.2,
+            messages: Vec::new(),
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: vec![ToolSpec {
+                name: "read_file".to_string(),
+                description: "Read a file.".to_string(),
+                parameters: serde_json::json!({ "type": "object" }),
+            }],
+        };
+        let value = serde_json::to_value(ChatCompletionRequest::from_chat(request.clone(), false))
+            .expect("serialize");
+        assert_eq!(
+            value["tools"],
+            serde_json::json!([{
+                "type": "function",
+                "function": {
+                    "name": "read_file",
+                    "description": "Read a file.",
+                    "parameters": { "type": "object" }
+                }
+            }])
+        );
+        let streamed = serde_json::to_value(ChatCompletionRequest::from_chat(request, true))
+            .expect("serialize");
+        assert!(streamed.get("tools").is_none());
+    }
+
+    #[test]
+    fn models_response_captures_optional_context_and_capabilities() {
+        let payload: ModelsResponse = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [
+                { "id": "gpt-4o-mini", "owned_by": "openai" },
+                {
+                    "id": "local-large",
+                    "owned_by": "vllm",
+                    "context_window": 131072,
+                    "capabilities": ["vision", "chat", "chat"]
+                },
+                {
+                    "id": "router-model",
+                    "context_length": 32768,
+                    "capabilities": { "tools": true, "vision": false, "chat": true }
+                }
+            ]
+        }))
+        .expect("models payload");
+        let models = payload
+            .data
+            .into_iter()
+            .filter_map(model_info_from_value)
+            .collect::<Vec<_>>();
+
+        assert_eq!(models[0].context_window, None);
+        assert!(models[0].capabilities.is_empty());
+        assert_eq!(models[1].context_window, Some(131072));
+        assert_eq!(models[1].capabilities, vec!["chat", "vision"]);
+        assert_eq!(models[2].owned_by, None);
+        assert_eq!(models[2].context_window, Some(32768));
+        assert_eq!(models[2].capabilities, vec!["chat", "tools"]);
+        assert!(models.iter().all(|model| model.warnings.is_empty()));
+    }
+
+    #[test]
+    fn models_response_keeps_entries_with_malformed_optional_fields() {
+        let payload: ModelsResponse = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [
+                { "id": "huge", "context_window": 10_000_000_000u64 },
+                { "id": "both-keys", "context_window": 8192, "context_length": 8192 },
+                { "id": "float", "max_model_len": 4096.0, "owned_by": 7 },
+                { "id": "text", "context_length": "32768", "capabilities": "chat" },
+                { "owned_by": "nobody" },
+                "not-an-object"
+            ]
+        }))
+        .expect("models payload");
+        let models = payload
+            .data
+            .into_iter()
+            .filter_map(model_info_from_value)
+            .collect::<Vec<_>>();
+
+        let ids = models
+            .iter()
+            .map(|model| model.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["huge", "both-keys", "float", "text"]);
+        assert_eq!(models[0].context_window, None);
+        assert!(models[0].warnings[0].starts_with("ignored context_window"));
+        assert_eq!(models[1].context_window, Some(8192));
+        assert!(models[1].warnings.is_empty());
+        assert_eq!(models[2].context_window, Some(4096));
+        assert_eq!(models[2].owned_by, None);
+        assert!(models[2].warnings[0].starts_with("ignored owned_by"));
+        assert_eq!(models[3].context_window, Some(32768));
+        assert!(models[3].capabilities.is_empty());
+        assert!(models[3].warnings[0].starts_with("ignored capabilities"));
+    }
+
+    #[tokio::test]
+    async fn mock_chat_stream_chunks_concatenate_to_full_answer() {
+        let provider =
+            OpenAiCompatibleProvider::new("mock://mock-model".to_string(), String::new())
+                .expect("mock provider");
+        let request = ChatRequest {
+            model: "mock-model".to_string(),
+            temperature: 0.2,
+            messages: vec![ChatMessage {
+                role: ChatRole::User,
+                content: "hello".to_string(),
+            }],
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: Vec::new(),
+        };
+        let mut chunks = Vec::new();
+        let response = provider
+            .chat_stream(request.clone(), &mut |chunk| chunks.push(chunk.to_string()))
+            .await
+            .expect("stream");
+        let full = provider.chat(request).await.expect("chat");
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), full.content);
+        assert_eq!(response.content, full.content);
+    }
+
+    #[test]
+    fn chat_completion_request_serializes_optional_sampling_fields_only_when_set() {
+        let request = ChatRequest {
+            model: "gpt-4o-mini".to_string(),
+            temperature: 0.5,
+            messages: Vec::new(),
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: Vec::new(),
+        };
+        let plain = serde_json::to_value(ChatCompletionRequest::from_chat(request.clone(), false))
+            .expect("serialize");
+        let keys = plain
+            .as_object()
+            .expect("object")
+            .keys()
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["messages", "model", "temperature"]);
+
+        let tuned = ChatRequest {
+            max_tokens: Some(256),
+            top_p: Some(0.5),
+            stop: vec!["END".to_string()],
+            tools: Vec::new(),
+            ..request
+        };
+        let tuned =
+            serde_json::to_value(ChatCompletionRequest::from_chat(tuned, true)).expect("serialize");
+        assert_eq!(tuned["max_tokens"], 256);
+        assert_eq!(tuned["top_p"], 0.5);
+        assert_eq!(tuned["stop"], serde_json::json!(["END"]));
+        assert_eq!(tuned["stream"], true);
+        assert_eq!(tuned["stream_options"]["include_usage"], true);
+    }
+
+    #[test]
+    fn sse_parser_handles_split_lines_and_done_marker() {
+        let mut parser = SseParser::default();
+        let mut events = parser
+            .push(b"data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\ndata: {\"choi")
+            .expect("first push");
+        assert!(events.is_empty());
+        events.extend(
+            parser
+                .push(b"ces\":[{\"delta\":{\"content\":\"Hel\"}}]}\r\n\n: keep-alive\n")
+                .expect("second push"),
+        );
+        events.extend(
+            parser
+                .push(b"data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n")
+                .expect("third push"),
+        );
+        events.extend(
+            parser
+                .push(b"data: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":2,\"total_tokens\":7}}\n\ndata: [DONE]\n\n")
+                .expect("usage push"),
+        );
+        assert_eq!(
+            events,
+            vec![
+                SseEvent::Delta("Hel".to_string()),
+                SseEvent::Delta("lo".to_string()),
+                SseEvent::Usage(TokenUsage {
+                    prompt_tokens: 5,
+                    completion_tokens: 2,
+                    total_tokens: 7,
+                }),
+                SseEvent::Done,
+            ]
+        );
+
+        let err = SseParser::default()
+            .push(b"data: {\"error\":{\"message\":\"overloaded\"}}\n")
+            .expect_err("stream error");
+        assert!(err.to_string().contains("overloaded"));
+    }
+
+    /// Serves canned HTTP responses in order, one per connection, and counts requests.
+    fn serve_responses(
+        responses: Vec<&'static str>,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{Read, Write};
+        use std::sync::atomic::Ordering;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base_url = format!("http://{}", listener.local_addr().expect("addr"));
+        let hits = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = hits.clone();
+        std::thread::spawn(move || {
+            for body in responses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                let mut buffer = [0u8; 8192];
+                let _ = stream.read(&mut buffer);
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = stream.write_all(body.as_bytes());
+            }
+        });
+        (base_url, hits)
+    }
+
+    #[tokio::test]
+    async fn chat_retries_service_unavailable_then_succeeds() {
+        let unavailable = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 4\r\nConnection: close\r\n\r\nbusy";
+        let ok = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 63\r\nConnection: close\r\n\r\n{\"choices\":[{\"message\":{\"role\":\"assistant\",\"content\":\"pong\"}}]}";
+        let (base_url, hits) = serve_responses(vec![unavailable, unavailable, ok]);
+        let provider = OpenAiCompatibleProvider::new(base_url, "test-key".to_string())
+            .expect("provider")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                base_delay: Duration::from_millis(5),
+                max_delay: Duration::from_millis(50),
+            });
+
+        let response = provider
+            .chat(ChatRequest {
+                model: "gpt-4o-mini".to_string(),
+                temperature: 0.0,
+                messages: Vec::new(),
+                max_tokens: None,
+                top_p: None,
+                stop: Vec::new(),
+                tools: Vec::new(),
+            })
+            .await
+            .expect("chat succeeds after retries");
+        assert_eq!(response.content, "pong");
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn chat_does_not_retry_auth_errors_and_surfaces_last_failure() {
+        let unauthorized =
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 3\r\nConnection: close\r\n\r\nbad";
+        let (base_url, hits) = serve_responses(vec![unauthorized]);
+        let provider =
+            OpenAiCompatibleProvider::new(base_url, "test-key".to_string()).expect("provider");
+        let err = provider.list_models().await.expect_err("auth error");
+        assert!(matches!(err, MosaicError::Auth(_)));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let unavailable = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 4\r\nConnection: close\r\n\r\nbusy";
+        let (base_url, hits) = serve_responses(vec![unavailable, unavailable]);
+        let provider = OpenAiCompatibleProvider::new(base_url, "test-key".to_string())
+            .expect("provider")
+            .with_retry_policy(RetryPolicy {
+                max_retries: 1,
+                base_delay: Duration::from_millis(5),
+                max_delay: Duration::from_millis(50),
+            });
+        let err = provider.list_models().await.expect_err("still unavailable");
+        assert!(err.to_string().contains("503"));
+        assert!(err.to_string().contains("busy"));
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn retry_delay_prefers_retry_after_and_caps_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        assert_eq!(policy.delay(0, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(400));
+        assert_eq!(policy.delay(5, None), Duration::from_secs(1));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(0))),
+            Duration::from_secs(0)
+        );
+    }
+
     #[test]
     fn endpoint_avoids_duplicate_v1_when_base_url_already_has_v1() {
         let provider = OpenAiCompatibleProvider {
@@ -317,6 +1279,8 @@ mod tests {
             api_key: "mock".to_string(),
             api_key_env: String::new(),
             mock_mode: false,
+            retry: RetryPolicy::default(),
+            azure_api_version: None,
         };
         assert_eq!(
             provider.endpoint("/v1/models"),
@@ -336,10 +1300,50 @@ mod tests {
             api_key: "mock".to_string(),
             api_key_env: String::new(),
             mock_mode: false,
+            retry: RetryPolicy::default(),
+            azure_api_version: None,
         };
         assert_eq!(
             provider.endpoint("/v1/models"),
             "https://api.openai.com/v1/models"
         );
     }
+
+    #[test]
+    fn azure_flavor_builds_deployment_endpoints_and_api_key_header() {
+        let provider = OpenAiCompatibleProvider {
+            client: Some(Client::new()),
+            base_url: "https://example.openai.azure.com/openai/v1".to_string(),
+            api_key: "azure-secret".to_string(),
+            api_key_env: String::new(),
+            mock_mode: false,
+            retry: RetryPolicy::default(),
+            azure_api_version: None,
+        }
+        .with_azure_api_version("2024-10-21");
+        assert_eq!(
+            provider.model_endpoint("/v1/chat/completions", "gpt-4o"),
+            "https://example.openai.azure.com/openai/deployments/gpt-4o/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(
+            provider.endpoint("/v1/models"),
+            "https://example.openai.azure.com/openai/models?api-version=2024-10-21"
+        );
+        let odd = provider.clone().with_azure_api_version("2024-10-21&x=1");
+        assert_eq!(
+            odd.model_endpoint("/v1/chat/completions", "team/gpt 4o?"),
+            "https://example.openai.azure.com/openai/deployments/team%2Fgpt%204o%3F/chat/completions?api-version=2024-10-21%26x%3D1"
+        );
+
+        let request = provider
+            .request(
+                reqwest::Method::POST,
+                provider.model_endpoint("/v1/chat/completions", "gpt-4o"),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["api-key"], "azure-secret");
+        assert!(request.headers().get("authorization").is_none());
+    }
 }
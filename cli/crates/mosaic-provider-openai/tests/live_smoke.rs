@@ -30,6 +30,10 @@ async fn live_models_and_chat_smoke() {
                 role: ChatRole::User,
                 content: "Reply with exactly: pong".to_string(),
             }],
+            max_tokens: None,
+            top_p: None,
+            stop: Vec::new(),
+            tools: Vec::new(),
         })
         .await
         .expect("chat");
@@ -1,7 +1,11 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
 
 use chrono::{DateTime, Utc};
+use rayon::prelude::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
@@ -15,7 +19,14 @@ use mosaic_core::privacy::{
 
 const DEFAULT_MAX_FILES: usize = 800;
 const DEFAULT_MAX_FILE_SIZE: usize = 256 * 1024;
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+pub const DEFAULT_CONTEXT_LINES: usize = 2;
+const REDACTED: &str = "[REDACTED]";
 const CURRENT_BASELINE_VERSION: u32 = 1;
+const CURRENT_SCAN_CACHE_VERSION: u32 = 1;
+pub const SECURITY_REPORT_SCHEMA_VERSION: u32 = 1;
+/// Bump whenever scan rules change so cached findings are not reused across rule sets.
+const RULES_VERSION: u32 = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -35,6 +46,13 @@ pub struct SecurityFinding {
     pub detail: String,
     pub path: String,
     pub line: Option<usize>,
+    /// The matched line with secret values redacted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_before: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_after: Vec<String>,
     pub suggestion: Option<String>,
 }
 
@@ -48,9 +66,15 @@ pub struct SecurityAuditSummary {
     pub ignored: usize,
     pub scanned_files: usize,
     pub skipped_files: usize,
+    #[serde(default)]
+    pub binary_files: usize,
+    #[serde(default)]
+    pub cached_files: usize,
     pub generated_at: DateTime<Utc>,
     pub root: String,
     pub baseline_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -96,6 +120,11 @@ pub struct SecurityAuditOptions {
     pub deep: bool,
     pub max_files: usize,
     pub max_file_size: usize,
+    pub cache_path: Option<PathBuf>,
+    /// Restricts the scan to files changed versus this git ref (plus untracked files).
+    pub changed_since: Option<String>,
+    /// Lines of surrounding context captured before and after each finding.
+    pub context_lines: usize,
 }
 
 impl Default for SecurityAuditOptions {
@@ -105,10 +134,90 @@ impl Default for SecurityAuditOptions {
             deep: false,
             max_files: DEFAULT_MAX_FILES,
             max_file_size: DEFAULT_MAX_FILE_SIZE,
+            cache_path: None,
+            changed_since: None,
+            context_lines: DEFAULT_CONTEXT_LINES,
         }
     }
 }
 
+/// Per-file scan results keyed by path relative to the audit root, reused while
+/// a file's mtime and size are unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecurityScanCache {
+    version: u32,
+    rules_version: String,
+    entries: BTreeMap<String, SecurityScanCacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecurityScanCacheEntry {
+    stamp: FileStamp,
+    binary: bool,
+    findings: Vec<SecurityFinding>,
+    #[serde(default)]
+    suppressed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    fn from_metadata(metadata: &std::fs::Metadata) -> Option<Self> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            mtime_secs: modified.as_secs(),
+            mtime_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+}
+
+impl SecurityScanCache {
+    fn new(rules_version: String) -> Self {
+        Self {
+            version: CURRENT_SCAN_CACHE_VERSION,
+            rules_version,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the cache, discarding it when missing, unreadable, or built by other rules.
+    fn load(path: &Path, rules_version: &str) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Self>(&raw).ok())
+            .filter(|cache| {
+                cache.version == CURRENT_SCAN_CACHE_VERSION && cache.rules_version == rules_version
+            })
+            .unwrap_or_else(|| Self::new(rules_version.to_string()))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| {
+                MosaicError::Io(format!(
+                    "failed to create security cache dir {}: {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+        let encoded = serde_json::to_vec(self).map_err(|err| {
+            MosaicError::Validation(format!("failed to encode security cache: {err}"))
+        })?;
+        std::fs::write(path, encoded).map_err(|err| {
+            MosaicError::Io(format!(
+                "failed to write security cache {}: {err}",
+                path.display()
+            ))
+        })
+    }
+}
+
 impl Default for SecurityBaselineConfig {
     fn default() -> Self {
         Self {
@@ -141,6 +250,17 @@ impl SecurityBaselineConfig {
         write_pretty_state_toml_file(path, &normalized, "security baseline state")
     }
 
+    /// Unions the ignore lists of `other` into this baseline.
+    pub fn merge(&mut self, other: &SecurityBaselineConfig) {
+        self.ignored_fingerprints
+            .extend(other.ignored_fingerprints.iter().cloned());
+        self.ignored_paths
+            .extend(other.ignored_paths.iter().cloned());
+        self.ignored_categories
+            .extend(other.ignored_categories.iter().cloned());
+        self.normalize();
+    }
+
     pub fn add_findings(&mut self, findings: &[SecurityFinding]) -> usize {
         let mut added = 0usize;
         let mut seen = self
@@ -214,6 +334,19 @@ pub fn apply_baseline(
     SecurityBaselineApplyResult { report, ignored }
 }
 
+/// Compact machine-readable report with a stable top-level shape:
+/// `schema`, `summary`, `risk`, and deterministically sorted `findings`.
+pub fn report_to_json(report: &SecurityAuditReport) -> Value {
+    let mut findings = report.findings.clone();
+    sort_findings(&mut findings);
+    json!({
+        "schema": SECURITY_REPORT_SCHEMA_VERSION,
+        "summary": report.summary,
+        "risk": report.risk,
+        "findings": findings,
+    })
+}
+
 pub fn report_to_sarif(report: &SecurityAuditReport) -> Value {
     let results = report
         .findings
@@ -228,6 +361,21 @@ pub fn report_to_sarif(report: &SecurityAuditReport) -> Value {
             });
             if let Some(line) = finding.line {
                 location["physicalLocation"]["region"] = json!({ "startLine": line });
+                if let Some(snippet) = &finding.snippet {
+                    let text = finding
+                        .context_before
+                        .iter()
+                        .chain(std::iter::once(snippet))
+                        .chain(&finding.context_after)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    location["physicalLocation"]["contextRegion"] = json!({
+                        "startLine": line - finding.context_before.len(),
+                        "endLine": line + finding.context_after.len(),
+                        "snippet": { "text": text },
+                    });
+                }
             }
 
             json!({
@@ -404,48 +552,158 @@ impl SecurityAuditor {
         let mut finding_keys = HashSet::new();
         let mut scanned_files = 0usize;
         let mut skipped_files = 0usize;
+        let mut binary_files = 0usize;
+        let mut cached_files = 0usize;
+        let mut suppressed_findings = 0usize;
+        let mut warnings = Vec::new();
+
+        let changed_files = match options.changed_since.as_deref() {
+            Some(git_ref) => {
+                let git_dir = if root.is_file() {
+                    root.parent().unwrap_or(&root)
+                } else {
+                    &root
+                };
+                match git_changed_files(git_dir, git_ref) {
+                    Ok(files) => Some(files),
+                    Err(err) => {
+                        warnings.push(format!(
+                            "changed-since '{git_ref}' unavailable ({err}); ran a full scan"
+                        ));
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
 
-        for entry in WalkDir::new(&root).into_iter().flatten() {
-            if scanned_files >= options.max_files {
+        let mut candidates = Vec::new();
+        let mut stamps = Vec::new();
+        for entry in WalkDir::new(&root)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+        {
+            if candidates.len() >= options.max_files {
                 break;
             }
             let path = entry.path();
             if path.is_dir() {
                 continue;
             }
+            if let Some(changed) = &changed_files
+                && !changed.contains(path)
+            {
+                continue;
+            }
             if should_skip(path) {
                 skipped_files += 1;
                 continue;
             }
-
-            let metadata = match std::fs::metadata(path) {
-                Ok(metadata) => metadata,
-                Err(_) => {
-                    skipped_files += 1;
-                    continue;
+            match std::fs::metadata(path) {
+                Ok(metadata) if metadata.len() as usize <= options.max_file_size => {
+                    candidates.push(path.to_path_buf());
+                    stamps.push(FileStamp::from_metadata(&metadata));
                 }
+                _ => skipped_files += 1,
+            }
+        }
+
+        let rules_version = format!(
+            "{RULES_VERSION}+ctx{}{}",
+            options.context_lines,
+            if options.deep { "+deep" } else { "" }
+        );
+        let previous_cache = options
+            .cache_path
+            .as_deref()
+            .map(|path| SecurityScanCache::load(path, &rules_version));
+        let mut next_cache = SecurityScanCache::new(rules_version);
+        // A partial walk (changed-since or the file cap) leaves the entries of
+        // files it did not visit valid, so carry them over instead of dropping them.
+        let partial_walk = changed_files.is_some() || candidates.len() >= options.max_files;
+        if partial_walk && let Some(previous) = &previous_cache {
+            next_cache.entries.extend(
+                previous
+                    .entries
+                    .iter()
+                    .filter(|(relative, _)| root.join(relative).is_file())
+                    .map(|(relative, entry)| (relative.clone(), entry.clone())),
+            );
+        }
+
+        let mut scans = Vec::with_capacity(candidates.len());
+        let mut pending = Vec::new();
+        for (path, stamp) in candidates.iter().zip(&stamps) {
+            let relative = relative_path(&root, path);
+            let cached = match (&previous_cache, stamp) {
+                (Some(cache), Some(stamp)) => cache
+                    .entries
+                    .get(&relative)
+                    .filter(|entry| entry.stamp == *stamp)
+                    .cloned(),
+                _ => None,
             };
-            if metadata.len() as usize > options.max_file_size {
-                skipped_files += 1;
-                continue;
+            if cached.is_some() {
+                cached_files += 1;
+            } else {
+                pending.push(path.clone());
             }
+            scans.push((relative, *stamp, cached));
+        }
 
-            let content = match std::fs::read_to_string(path) {
-                Ok(value) => value,
-                Err(_) => {
+        let mut fresh =
+            scan_files(&rules, &root, &pending, options.context_lines, true).into_iter();
+        for (relative, stamp, cached) in scans {
+            let scan = match cached {
+                Some(entry) if entry.binary => FileScan::Binary,
+                Some(entry) => FileScan::Scanned {
+                    findings: entry.findings,
+                    suppressed: entry.suppressed,
+                },
+                None => fresh.next().unwrap_or(FileScan::Unreadable),
+            };
+            if let Some(stamp) = stamp {
+                let entry = match &scan {
+                    FileScan::Scanned {
+                        findings,
+                        suppressed,
+                    } => Some((false, findings.clone(), *suppressed)),
+                    FileScan::Binary => Some((true, Vec::new(), 0)),
+                    FileScan::Unreadable => None,
+                };
+                if let Some((binary, findings, suppressed)) = entry {
+                    next_cache.entries.insert(
+                        relative,
+                        SecurityScanCacheEntry {
+                            stamp,
+                            binary,
+                            findings,
+                            suppressed,
+                        },
+                    );
+                }
+            }
+            match scan {
+                FileScan::Scanned {
+                    findings: file_findings,
+                    suppressed,
+                } => {
+                    scanned_files += 1;
+                    suppressed_findings += suppressed;
+                    for finding in file_findings {
+                        push_finding(&mut findings, &mut finding_keys, finding);
+                    }
+                }
+                FileScan::Binary => {
                     skipped_files += 1;
-                    continue;
+                    binary_files += 1;
                 }
-            };
-            scanned_files += 1;
-            let relative = relative_path(&root, path);
-            scan_content(
-                &rules,
-                &relative,
-                &content,
-                &mut findings,
-                &mut finding_keys,
-            );
+                FileScan::Unreadable => skipped_files += 1,
+            }
+        }
+        if let Some(cache_path) = options.cache_path.as_deref() {
+            next_cache.save(cache_path)?;
         }
 
         scanned_files = scanned_files.saturating_add(scan_state_persistence_files(
@@ -453,6 +711,10 @@ impl SecurityAuditor {
             &mut findings,
             &mut finding_keys,
         ));
+        if let Some(changed) = &changed_files {
+            findings.retain(|finding| changed.contains(&root.join(&finding.path)));
+        }
+        sort_findings(&mut findings);
 
         let high = findings
             .iter()
@@ -473,12 +735,15 @@ impl SecurityAuditor {
             high,
             medium,
             low,
-            ignored: 0,
+            ignored: suppressed_findings,
             scanned_files,
             skipped_files,
+            binary_files,
+            cached_files,
             generated_at: Utc::now(),
             root: root.display().to_string(),
             baseline_path: None,
+            warnings,
         };
         let risk = build_risk_profile(&findings);
 
@@ -490,17 +755,165 @@ impl SecurityAuditor {
     }
 }
 
+enum FileScan {
+    Scanned {
+        findings: Vec<SecurityFinding>,
+        suppressed: usize,
+    },
+    Binary,
+    Unreadable,
+}
+
+/// Scans candidate files independently; results keep the order of `candidates`.
+fn scan_files(
+    rules: &Rules,
+    root: &Path,
+    candidates: &[PathBuf],
+    context_lines: usize,
+    parallel: bool,
+) -> Vec<FileScan> {
+    let scan_one = |path: &PathBuf| match read_text_file(path) {
+        Ok(Some(content)) => {
+            let mut file_findings = Vec::new();
+            let mut keys = HashSet::new();
+            let suppressed = scan_content(
+                rules,
+                &relative_path(root, path),
+                &content,
+                context_lines,
+                &mut file_findings,
+                &mut keys,
+            );
+            FileScan::Scanned {
+                findings: file_findings,
+                suppressed,
+            }
+        }
+        Ok(None) => FileScan::Binary,
+        Err(_) => FileScan::Unreadable,
+    };
+    if parallel {
+        candidates.par_iter().map(scan_one).collect()
+    } else {
+        candidates.iter().map(scan_one).collect()
+    }
+}
+
+/// Lists files changed versus `git_ref` plus untracked files, as paths under `dir`.
+fn git_changed_files(dir: &Path, git_ref: &str) -> std::result::Result<HashSet<PathBuf>, String> {
+    let git_ref = git_ref.trim();
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err(format!("invalid git ref '{git_ref}'"));
+    }
+    let run = |args: &[&str]| -> std::result::Result<String, String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map_err(|err| format!("failed to run git: {err}"))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let changed = run(&["diff", "--name-only", "--relative", git_ref, "--"])?;
+    let untracked = run(&["ls-files", "--others", "--exclude-standard"])?;
+    Ok(changed
+        .lines()
+        .chain(untracked.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| dir.join(line))
+        .collect())
+}
+
+fn sort_findings(findings: &mut [SecurityFinding]) {
+    findings.sort_by(|lhs, rhs| {
+        (&lhs.path, lhs.line, &lhs.category, &lhs.title).cmp(&(
+            &rhs.path,
+            rhs.line,
+            &rhs.category,
+            &rhs.title,
+        ))
+    });
+}
+
 #[derive(Debug)]
 struct Rules {
     hardcoded_secret: Regex,
     default_secret_literal: Regex,
     aws_access_key: Regex,
+    /// The trailing quoted value inside a `hardcoded_secret` match.
+    quoted_literal: Regex,
+    provider_secrets: Vec<ProviderSecretRule>,
     insecure_http: Regex,
     insecure_tls_disable: Regex,
     curl_pipe_shell: Regex,
     wildcard_cors: Regex,
     weak_hash_usage: Regex,
     javascript_eval: Regex,
+    inline_ignore: Regex,
+}
+
+/// High-confidence credential formats issued by well-known providers.
+#[derive(Debug)]
+struct ProviderSecretRule {
+    pattern: Regex,
+    title: &'static str,
+    detail: &'static str,
+    suggestion: &'static str,
+}
+
+impl ProviderSecretRule {
+    fn new(
+        name: &str,
+        pattern: &str,
+        title: &'static str,
+        detail: &'static str,
+        suggestion: &'static str,
+    ) -> Result<Self> {
+        Ok(Self {
+            pattern: Regex::new(pattern)
+                .map_err(|err| MosaicError::Validation(format!("invalid regex {name}: {err}")))?,
+            title,
+            detail,
+            suggestion,
+        })
+    }
+}
+
+fn provider_secret_rules() -> Result<Vec<ProviderSecretRule>> {
+    Ok(vec![
+        ProviderSecretRule::new(
+            "github_token",
+            r"\b(gh[pousr]_[A-Za-z0-9]{36}|github_pat_[A-Za-z0-9_]{22,})\b",
+            "GitHub token detected",
+            "Line matches a GitHub personal access or app token format.",
+            "Revoke the token in GitHub settings and load a fine-grained token from the environment.",
+        )?,
+        ProviderSecretRule::new(
+            "stripe_live_key",
+            r"\b[sr]k_live_[A-Za-z0-9]{16,}\b",
+            "Stripe live secret key detected",
+            "Line matches a Stripe live-mode secret or restricted key.",
+            "Roll the key in the Stripe dashboard and keep live keys in a secret manager.",
+        )?,
+        ProviderSecretRule::new(
+            "google_api_key",
+            r"\bAIza[0-9A-Za-z_\-]{35}",
+            "Google API key detected",
+            "Line matches the AIza-prefixed Google API key format.",
+            "Regenerate the key in Google Cloud console and restrict it by API and referrer.",
+        )?,
+        ProviderSecretRule::new(
+            "jwt",
+            r"\beyJ[A-Za-z0-9_-]{5,}\.eyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]*",
+            "JSON Web Token detected",
+            "Line contains a JWT (three base64url segments with a JSON header).",
+            "Invalidate the token and avoid committing bearer tokens; mint them at runtime.",
+        )?,
+    ])
 }
 
 impl Rules {
@@ -516,6 +929,9 @@ impl Rules {
             .map_err(|err| MosaicError::Validation(format!("invalid regex default_secret_literal: {err}")))?,
             aws_access_key: Regex::new(r"\bAKIA[0-9A-Z]{16}\b")
                 .map_err(|err| MosaicError::Validation(format!("invalid regex aws_access_key: {err}")))?,
+            quoted_literal: Regex::new(r#"["'][^"'\s]{12,}["']$"#)
+                .map_err(|err| MosaicError::Validation(format!("invalid regex quoted_literal: {err}")))?,
+            provider_secrets: provider_secret_rules()?,
             insecure_http: Regex::new(r#"http://[^\s"'<>]+"#)
                 .map_err(|err| MosaicError::Validation(format!("invalid regex insecure_http: {err}")))?,
             insecure_tls_disable: Regex::new(
@@ -530,17 +946,23 @@ impl Rules {
                 .map_err(|err| MosaicError::Validation(format!("invalid regex weak_hash_usage: {err}")))?,
             javascript_eval: Regex::new(r"\beval\s*\(")
                 .map_err(|err| MosaicError::Validation(format!("invalid regex javascript_eval: {err}")))?,
+            inline_ignore: Regex::new(r"(?:#|//)\s*mosaic:ignore(?:\[([^\]]*)\])?")
+                .map_err(|err| MosaicError::Validation(format!("invalid regex inline_ignore: {err}")))?,
         })
     }
 }
 
+/// Scans one file's content and returns how many findings were dropped by
+/// inline `mosaic:ignore` comments.
 fn scan_content(
     rules: &Rules,
     path: &str,
     content: &str,
+    context_lines: usize,
     findings: &mut Vec<SecurityFinding>,
     keys: &mut HashSet<String>,
-) {
+) -> usize {
+    let start = findings.len();
     if content.contains("BEGIN PRIVATE KEY") || content.contains("BEGIN RSA PRIVATE KEY") {
         push_finding(
             findings,
@@ -554,6 +976,9 @@ fn scan_content(
                 detail: "File contains PEM private key markers.".to_string(),
                 path: path.to_string(),
                 line: line_of(content, "BEGIN").or(Some(1)),
+                snippet: None,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
                 suggestion: Some(
                     "Move private keys to a secure secret store and rotate immediately."
                         .to_string(),
@@ -577,6 +1002,9 @@ fn scan_content(
                         .to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Replace literals with environment variables or secret manager references."
                             .to_string(),
@@ -598,6 +1026,9 @@ fn scan_content(
                     detail: "Line matches AKIA-style access key format.".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Revoke this key and replace with scoped runtime credentials.".to_string(),
                     ),
@@ -605,6 +1036,29 @@ fn scan_content(
             );
         }
 
+        for rule in &rules.provider_secrets {
+            if rule.pattern.is_match(line) {
+                push_finding(
+                    findings,
+                    keys,
+                    SecurityFinding {
+                        id: format!("sec_{}", uuid::Uuid::new_v4()),
+                        fingerprint: String::new(),
+                        severity: SecuritySeverity::High,
+                        category: "credential_exposure".to_string(),
+                        title: rule.title.to_string(),
+                        detail: rule.detail.to_string(),
+                        path: path.to_string(),
+                        line: Some(line_number + 1),
+                        snippet: None,
+                        context_before: Vec::new(),
+                        context_after: Vec::new(),
+                        suggestion: Some(rule.suggestion.to_string()),
+                    },
+                );
+            }
+        }
+
         if rules.default_secret_literal.is_match(line) {
             push_finding(
                 findings,
@@ -619,6 +1073,9 @@ fn scan_content(
                         .to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Replace defaults with rotated secrets provided by runtime secret storage."
                             .to_string(),
@@ -640,6 +1097,9 @@ fn scan_content(
                     detail: "Direct execution of remote script was detected.".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Download script, pin checksum/signature, and review before execution."
                             .to_string(),
@@ -661,6 +1121,9 @@ fn scan_content(
                     detail: "Plain HTTP URL found; consider TLS-protected HTTPS.".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some("Migrate to HTTPS where possible.".to_string()),
                 },
             );
@@ -679,6 +1142,9 @@ fn scan_content(
                     detail: "Code disables TLS certificate verification.".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Enable certificate verification and trust only approved roots."
                             .to_string(),
@@ -700,6 +1166,9 @@ fn scan_content(
                     detail: "Access-Control-Allow-Origin is configured as '*'".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Use explicit trusted origins instead of wildcard.".to_string(),
                     ),
@@ -722,6 +1191,9 @@ fn scan_content(
                             .to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Prefer modern hashes like SHA-256 (or password KDFs such as Argon2)."
                             .to_string(),
@@ -743,6 +1215,9 @@ fn scan_content(
                     detail: "Dynamic code execution increases injection risk.".to_string(),
                     path: path.to_string(),
                     line: Some(line_number + 1),
+                    snippet: None,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
                     suggestion: Some(
                         "Replace eval() with safer parsing/execution strategy.".to_string(),
                     ),
@@ -750,6 +1225,101 @@ fn scan_content(
             );
         }
     }
+
+    let lines = content.lines().collect::<Vec<_>>();
+    let mut redacted = None;
+    let mut suppressed = 0usize;
+    for mut finding in findings.split_off(start) {
+        if inline_ignored(rules, &lines, &finding) {
+            keys.remove(&finding.fingerprint);
+            suppressed += 1;
+            continue;
+        }
+        if let Some(line) = finding
+            .line
+            .filter(|line| *line > 0 && *line <= lines.len())
+        {
+            let redacted = redacted.get_or_insert_with(|| redact_lines(rules, &lines));
+            let index = line - 1;
+            finding.snippet = Some(redacted[index].clone());
+            finding.context_before = redacted[index.saturating_sub(context_lines)..index].to_vec();
+            finding.context_after =
+                redacted[line..(line + context_lines).min(redacted.len())].to_vec();
+        }
+        findings.push(finding);
+    }
+    suppressed
+}
+
+/// Masks secret values so finding context can be shown without leaking them.
+/// Lines inside PEM private key blocks are masked entirely.
+fn redact_lines(rules: &Rules, lines: &[&str]) -> Vec<String> {
+    let mut in_private_key = false;
+    lines
+        .iter()
+        .map(|line| {
+            if line.contains("-----BEGIN") && line.contains("PRIVATE KEY") {
+                in_private_key = true;
+                return line.to_string();
+            }
+            if in_private_key {
+                if line.contains("-----END") {
+                    in_private_key = false;
+                    return line.to_string();
+                }
+                return REDACTED.to_string();
+            }
+            redact_line(rules, line)
+        })
+        .collect()
+}
+
+fn redact_line(rules: &Rules, line: &str) -> String {
+    let line = rules
+        .hardcoded_secret
+        .replace_all(line, |captures: &regex::Captures<'_>| {
+            rules
+                .quoted_literal
+                .replace(&captures[0], |_: &regex::Captures<'_>| {
+                    format!("\"{REDACTED}\"")
+                })
+                .into_owned()
+        });
+    let mut line = rules
+        .aws_access_key
+        .replace_all(&line, REDACTED)
+        .into_owned();
+    for rule in &rules.provider_secrets {
+        line = rule.pattern.replace_all(&line, REDACTED).into_owned();
+    }
+    line
+}
+
+/// A finding is ignored when its line carries a trailing `# mosaic:ignore` /
+/// `// mosaic:ignore[category,...]` comment, or the line above is such a comment.
+fn inline_ignored(rules: &Rules, lines: &[&str], finding: &SecurityFinding) -> bool {
+    let Some(line) = finding.line.filter(|line| *line > 0) else {
+        return false;
+    };
+    let previous = line
+        .checked_sub(2)
+        .and_then(|index| lines.get(index))
+        .filter(|text| {
+            let text = text.trim_start();
+            text.starts_with('#') || text.starts_with("//")
+        });
+    [previous, lines.get(line - 1)]
+        .into_iter()
+        .flatten()
+        .filter_map(|text| rules.inline_ignore.captures(text))
+        .any(|captures| match captures.get(1) {
+            None => true,
+            Some(scope) => scope
+                .as_str()
+                .split(',')
+                .map(str::trim)
+                .any(|category| category.eq_ignore_ascii_case(&finding.category)),
+        })
 }
 
 fn scan_state_persistence_files(
@@ -917,6 +1487,9 @@ fn state_persistence_finding(
         detail,
         path: path.to_string(),
         line: None,
+        snippet: None,
+        context_before: Vec::new(),
+        context_after: Vec::new(),
         suggestion: Some(
             "Move the credential out of persisted state/config and reference it via environment variables or a secret manager."
                 .to_string(),
@@ -941,6 +1514,26 @@ fn push_finding(
     }
 }
 
+/// Returns `None` for binary files, detected by a NUL byte in the leading bytes.
+/// Only those leading bytes are read before deciding, so binaries are never
+/// loaded whole. Text files that are not valid UTF-8 are decoded lossily so
+/// they are still scanned.
+fn read_text_file(path: &Path) -> std::io::Result<Option<String>> {
+    let mut file = std::fs::File::open(path)?;
+    let mut bytes = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    (&mut file)
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut bytes)?;
+    if bytes.contains(&0) {
+        return Ok(None);
+    }
+    file.read_to_end(&mut bytes)?;
+    Ok(Some(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+    }))
+}
+
 fn line_of(content: &str, needle: &str) -> Option<usize> {
     content
         .lines()
@@ -1104,6 +1697,57 @@ mod tests {
         assert!(report.risk.score >= 35);
     }
 
+    #[test]
+    fn audit_detects_known_provider_secrets() {
+        let temp = tempdir().expect("tempdir");
+        let github = format!("ghp_{}", "a1B2".repeat(9));
+        let github_pat = format!("github_pat_{}", "11ABCDEFG0_abcdefghij".repeat(2));
+        let stripe = format!("sk_live_{}", "4eC39HqLyjWDarjtT1zdp7dc");
+        let google = format!("AIza{}", "SyA-1234567890abcdefghijklmnopqrstu");
+        let jwt = format!(
+            "{}.{}.",
+            "eyJhbGciOiJub25lIn0", "eyJzdWIiOiIxMjM0NTY3ODkwIn0"
+        );
+        std::fs::write(
+            temp.path().join("fixtures.txt"),
+            format!(
+                "github {github} {github}\nfine-grained {github_pat}\nstripe {stripe}\ngoogle {google}\njwt {jwt}\n"
+            ),
+        )
+        .expect("write fixtures");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+
+        let expected = [
+            (1, "GitHub token detected"),
+            (2, "GitHub token detected"),
+            (3, "Stripe live secret key detected"),
+            (4, "Google API key detected"),
+            (5, "JSON Web Token detected"),
+        ];
+        for (line, title) in expected {
+            let matches = report
+                .findings
+                .iter()
+                .filter(|finding| finding.line == Some(line) && finding.title == title)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                matches.len(),
+                1,
+                "expected one '{title}' finding on line {line}"
+            );
+            assert_eq!(matches[0].severity, SecuritySeverity::High);
+            assert_eq!(matches[0].category, "credential_exposure");
+            assert!(matches[0].suggestion.is_some());
+        }
+        assert_eq!(report.summary.findings, expected.len());
+    }
+
     #[test]
     fn audit_detects_curl_pipe_and_http() {
         let temp = tempdir().expect("tempdir");
@@ -1275,6 +1919,378 @@ api_key = "sk-live-secret-12345678901234567890"
         }));
     }
 
+    #[test]
+    fn read_text_file_sniffs_the_head_and_reads_the_rest() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let text_path = temp.path().join("long.txt");
+        let mut text = "a".repeat(BINARY_SNIFF_BYTES - 1);
+        text.push('é');
+        text.push_str("tail\n");
+        std::fs::write(&text_path, &text).expect("write text");
+        assert_eq!(
+            read_text_file(&text_path).expect("read text").as_deref(),
+            Some(text.as_str())
+        );
+
+        let late_nul = temp.path().join("late-nul.txt");
+        let mut bytes = vec![b'a'; BINARY_SNIFF_BYTES];
+        bytes.push(0);
+        std::fs::write(&late_nul, &bytes).expect("write late nul");
+        assert!(read_text_file(&late_nul).expect("read late nul").is_some());
+
+        let binary_path = temp.path().join("blob.bin");
+        let mut binary = b"\x7fELF\x00".to_vec();
+        binary.extend(vec![b'x'; BINARY_SNIFF_BYTES * 4]);
+        std::fs::write(&binary_path, binary).expect("write binary");
+        assert!(read_text_file(&binary_path).expect("read binary").is_none());
+    }
+
+    #[test]
+    fn audit_counts_binary_files_separately() {
+        let temp = tempdir().expect("tempdir");
+        let mut binary = b"\x7fELF\x00\x00".to_vec();
+        binary.extend_from_slice(b"api_key = \"sk-test-secret-value-123456\"\n");
+        std::fs::write(temp.path().join("blob.bin"), binary).expect("write binary");
+        let mut latin1 = b"caf\xe9 ".to_vec();
+        latin1.extend_from_slice(b"api_key = \"sk-test-secret-value-123456\"\n");
+        std::fs::write(temp.path().join("notes.txt"), latin1).expect("write latin1");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+
+        assert_eq!(report.summary.binary_files, 1);
+        assert_eq!(report.summary.skipped_files, 1);
+        assert_eq!(report.summary.scanned_files, 1);
+        assert!(
+            report
+                .findings
+                .iter()
+                .any(|finding| finding.path == "notes.txt")
+        );
+        assert!(
+            !report
+                .findings
+                .iter()
+                .any(|finding| finding.path == "blob.bin")
+        );
+    }
+
+    #[test]
+    fn parallel_scan_matches_sequential_scan() {
+        let temp = tempdir().expect("tempdir");
+        for index in 0..200 {
+            let content = match index % 4 {
+                0 => format!("api_key = \"sk-test-secret-value-{index:06}\"\n"),
+                1 => format!("BASE_URL=http://example.com/{index}\nconst h = md5(x);\n"),
+                2 => "curl https://example.com/install.sh | sh\n".to_string(),
+                _ => format!("fn main() {{ println!(\"{index}\"); }}\n"),
+            };
+            std::fs::write(temp.path().join(format!("file_{index:03}.txt")), content)
+                .expect("write fixture");
+        }
+        let root = canonicalize_root(temp.path()).expect("root");
+        let rules = Rules::new(false).expect("rules");
+        let candidates = WalkDir::new(&root)
+            .sort_by_file_name()
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.path().to_path_buf())
+            .collect::<Vec<_>>();
+        assert_eq!(candidates.len(), 200);
+
+        let flatten = |scans: Vec<FileScan>| {
+            scans
+                .into_iter()
+                .flat_map(|scan| match scan {
+                    FileScan::Scanned { findings, .. } => findings,
+                    FileScan::Binary | FileScan::Unreadable => Vec::new(),
+                })
+                .map(|finding| finding.fingerprint)
+                .collect::<Vec<_>>()
+        };
+        let sequential = flatten(scan_files(
+            &rules,
+            &root,
+            &candidates,
+            DEFAULT_CONTEXT_LINES,
+            false,
+        ));
+        let parallel = flatten(scan_files(
+            &rules,
+            &root,
+            &candidates,
+            DEFAULT_CONTEXT_LINES,
+            true,
+        ));
+        assert!(!sequential.is_empty());
+        assert_eq!(sequential, parallel);
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        let repeat = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        let fingerprints = |report: &SecurityAuditReport| {
+            report
+                .findings
+                .iter()
+                .map(|finding| finding.fingerprint.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(report.summary.scanned_files, 200);
+        assert_eq!(fingerprints(&report), fingerprints(&repeat));
+        let mut sorted = sequential.clone();
+        sorted.sort();
+        let mut reported = fingerprints(&report);
+        reported.sort();
+        assert_eq!(sorted, reported);
+    }
+
+    #[test]
+    fn inline_ignore_suppresses_matching_line() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("config.py"),
+            "api_key = \"sk-test-secret-value-123456\"  # mosaic:ignore\n\
+             # mosaic:ignore\n\
+             token = \"sk-test-secret-value-654321\"\n\
+             password = \"sk-test-secret-value-000000\"\n",
+        )
+        .expect("write config");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+
+        assert_eq!(report.summary.ignored, 2);
+        assert_eq!(report.summary.findings, 1);
+        assert_eq!(report.findings[0].line, Some(4));
+    }
+
+    #[test]
+    fn inline_ignore_category_scope_only_suppresses_that_category() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("client.ts"),
+            "const apiKey = \"sk-test-secret-value-123456\"; // mosaic:ignore[credential_exposure]\n\
+             const token = \"sk-test-secret-value-654321\"; // mosaic:ignore[transport_security]\n",
+        )
+        .expect("write client");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+
+        assert_eq!(report.summary.ignored, 1);
+        assert_eq!(report.summary.findings, 1);
+        assert_eq!(report.findings[0].category, "credential_exposure");
+        assert_eq!(report.findings[0].line, Some(2));
+    }
+
+    #[test]
+    fn changed_since_limits_scan_to_git_changes() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .expect("run git");
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        std::fs::write(
+            root.join("old.env"),
+            "API_KEY = \"sk-live-secret-value-111111\"\n",
+        )
+        .expect("write old");
+        std::fs::write(root.join("edited.sh"), "echo ok\n").expect("write edited");
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        std::fs::write(
+            root.join("edited.sh"),
+            "curl https://example.com/install.sh | sh\n",
+        )
+        .expect("edit tracked file");
+        std::fs::write(
+            root.join("new.env"),
+            "TOKEN = \"sk-live-secret-value-222222\"\n",
+        )
+        .expect("write untracked");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: root.to_path_buf(),
+                changed_since: Some("HEAD".to_string()),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        let mut paths = report
+            .findings
+            .iter()
+            .map(|finding| finding.path.as_str())
+            .collect::<Vec<_>>();
+        paths.dedup();
+        assert_eq!(paths, vec!["edited.sh", "new.env"]);
+        assert!(report.summary.warnings.is_empty());
+
+        let full = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: root.to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("full audit report");
+        assert!(
+            full.findings
+                .iter()
+                .any(|finding| finding.path == "old.env")
+        );
+    }
+
+    #[test]
+    fn changed_since_scan_keeps_cache_entries_of_unchanged_files() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().join("repo");
+        std::fs::create_dir_all(&root).expect("create root");
+        let cache_path = temp.path().join("security-scan.json");
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .expect("run git");
+            assert!(output.status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        std::fs::write(root.join("a.txt"), "const h = md5(x);\n").expect("write a");
+        std::fs::write(root.join("b.txt"), "fn main() {}\n").expect("write b");
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        let audit = |changed_since: Option<&str>| {
+            SecurityAuditor::new()
+                .audit(SecurityAuditOptions {
+                    root: root.clone(),
+                    cache_path: Some(cache_path.clone()),
+                    changed_since: changed_since.map(str::to_string),
+                    ..SecurityAuditOptions::default()
+                })
+                .expect("audit report")
+        };
+
+        audit(None);
+        std::fs::write(root.join("b.txt"), "fn main() { run(); }\n").expect("edit b");
+        let incremental = audit(Some("HEAD"));
+        assert_eq!(incremental.summary.cached_files, 0);
+
+        let cache: SecurityScanCache =
+            serde_json::from_str(&std::fs::read_to_string(&cache_path).expect("read cache"))
+                .expect("parse cache");
+        assert!(cache.entries.contains_key("a.txt"));
+        assert!(cache.entries.contains_key("b.txt"));
+        let full = audit(None);
+        assert!(full.summary.cached_files >= 2);
+        assert!(full.findings.iter().any(|finding| finding.path == "a.txt"));
+    }
+
+    #[test]
+    fn changed_since_outside_git_falls_back_to_full_scan() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("secrets.env"),
+            "API_KEY = \"sk-live-secret-value-123456\"\n",
+        )
+        .expect("write secrets");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                changed_since: Some("HEAD".to_string()),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        assert_eq!(report.summary.findings, 1);
+        assert_eq!(report.summary.warnings.len(), 1);
+        assert!(report.summary.warnings[0].contains("ran a full scan"));
+    }
+
+    #[test]
+    fn cache_rescans_only_modified_files() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path().join("repo");
+        std::fs::create_dir_all(&root).expect("create root");
+        let cache_path = temp.path().join("cache").join("security-scan.json");
+        std::fs::write(root.join("a.txt"), "BASE_URL=http://example.com\n").expect("write a");
+        std::fs::write(root.join("b.txt"), "const h = md5(x);\n").expect("write b");
+        std::fs::write(root.join("c.txt"), "fn main() {}\n").expect("write c");
+        let audit = || {
+            SecurityAuditor::new()
+                .audit(SecurityAuditOptions {
+                    root: root.clone(),
+                    cache_path: Some(cache_path.clone()),
+                    ..SecurityAuditOptions::default()
+                })
+                .expect("audit report")
+        };
+
+        let first = audit();
+        assert_eq!(first.summary.cached_files, 0);
+        assert_eq!(first.summary.findings, 2);
+        assert!(cache_path.exists());
+
+        let second = audit();
+        assert_eq!(second.summary.cached_files, 3);
+        assert_eq!(second.summary.scanned_files, 3);
+        assert_eq!(second.summary.findings, 2);
+
+        std::fs::write(
+            root.join("c.txt"),
+            "curl https://example.com/install.sh | sh\n",
+        )
+        .expect("modify c");
+        let third = audit();
+        assert_eq!(third.summary.cached_files, 2);
+        assert_eq!(third.summary.scanned_files, 3);
+        assert_eq!(third.summary.findings, 3);
+        assert!(third.findings.iter().any(|finding| finding.path == "c.txt"));
+
+        let mut cache: SecurityScanCache =
+            serde_json::from_str(&std::fs::read_to_string(&cache_path).expect("read cache"))
+                .expect("parse cache");
+        cache.rules_version = "0".to_string();
+        std::fs::write(
+            &cache_path,
+            serde_json::to_vec(&cache).expect("encode cache"),
+        )
+        .expect("write cache");
+        let invalidated = audit();
+        assert_eq!(invalidated.summary.cached_files, 0);
+        assert_eq!(invalidated.summary.findings, 3);
+    }
+
     #[test]
     fn baseline_filters_known_fingerprints() {
         let temp = tempdir().expect("tempdir");
@@ -1304,14 +2320,74 @@ api_key = "sk-live-secret-12345678901234567890"
         assert_eq!(filtered.report.risk.score, 0);
     }
 
+    #[test]
+    fn merged_baselines_suppress_union_of_findings() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("secrets.env"),
+            "API_KEY = \"sk-live-secret-value-123456\"\n",
+        )
+        .expect("write secrets");
+        std::fs::write(
+            temp.path().join("client.py"),
+            "BASE_URL = 'http://example.com'\n",
+        )
+        .expect("write client");
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        assert_eq!(report.summary.findings, 2);
+
+        let repo = SecurityBaselineConfig {
+            ignored_categories: vec!["credential_exposure".to_string()],
+            ..SecurityBaselineConfig::default()
+        };
+        let team = SecurityBaselineConfig {
+            ignored_paths: vec!["client.py".to_string()],
+            ..SecurityBaselineConfig::default()
+        };
+        assert_eq!(
+            apply_baseline(report.clone(), &repo)
+                .report
+                .summary
+                .findings,
+            1
+        );
+        assert_eq!(
+            apply_baseline(report.clone(), &team)
+                .report
+                .summary
+                .findings,
+            1
+        );
+
+        let mut merged = repo.clone();
+        merged.merge(&team);
+        merged.merge(&repo);
+        assert_eq!(
+            merged.ignored_categories,
+            vec!["credential_exposure".to_string()]
+        );
+        assert_eq!(merged.ignored_paths, vec!["client.py".to_string()]);
+        let applied = apply_baseline(report, &merged);
+        assert_eq!(applied.ignored, 2);
+        assert_eq!(applied.report.summary.findings, 0);
+        assert!(applied.report.summary.ok);
+    }
+
     #[test]
     fn baseline_load_and_save_roundtrip() {
         let temp = tempdir().expect("tempdir");
         let baseline_path = temp.path().join("baseline.toml");
-        let mut baseline = SecurityBaselineConfig::default();
-        baseline.ignored_fingerprints = vec!["a:b:c:d".to_string()];
-        baseline.ignored_paths = vec!["src/".to_string()];
-        baseline.ignored_categories = vec!["transport_security".to_string()];
+        let baseline = SecurityBaselineConfig {
+            ignored_fingerprints: vec!["a:b:c:d".to_string()],
+            ignored_paths: vec!["src/".to_string()],
+            ignored_categories: vec!["transport_security".to_string()],
+            ..SecurityBaselineConfig::default()
+        };
 
         baseline
             .save_to_path(&baseline_path)
@@ -1342,6 +2418,33 @@ api_key = "sk-live-secret-12345678901234567890"
         assert!(err.to_string().contains("blocked security baseline state"));
     }
 
+    #[test]
+    fn json_report_round_trips_with_schema_version() {
+        let temp = tempdir().expect("tempdir");
+        std::fs::write(
+            temp.path().join("secrets.env"),
+            "API_KEY = \"sk-live-secret-value-123456\"\nBASE_URL=http://example.com\n",
+        )
+        .expect("write secrets");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        let encoded = serde_json::to_string(&report_to_json(&report)).expect("encode json report");
+        let decoded: Value = serde_json::from_str(&encoded).expect("decode json report");
+        assert_eq!(decoded["schema"], SECURITY_REPORT_SCHEMA_VERSION);
+        assert_eq!(decoded["summary"]["findings"], report.summary.findings);
+
+        let findings: Vec<SecurityFinding> =
+            serde_json::from_value(decoded["findings"].clone()).expect("decode findings");
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings.len(), report.findings.len());
+        assert!(findings.windows(2).all(|pair| pair[0].line <= pair[1].line));
+    }
+
     #[test]
     fn sarif_conversion_contains_results() {
         let temp = tempdir().expect("tempdir");
@@ -1362,11 +2465,75 @@ api_key = "sk-live-secret-12345678901234567890"
         assert_eq!(sarif["version"], "2.1.0");
         assert!(sarif["runs"][0]["results"].is_array());
         assert!(
-            sarif["runs"][0]["results"]
+            !sarif["runs"][0]["results"]
                 .as_array()
                 .expect("results")
-                .len()
-                >= 1
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn findings_capture_redacted_context_lines() {
+        let temp = tempdir().expect("tempdir");
+        let secret = format!("sk-{}", "q7".repeat(10));
+        let aws_key = format!("AKIA{}", "Z".repeat(16));
+        std::fs::write(
+            temp.path().join("settings.py"),
+            format!(
+                "# settings\nname = 'demo'\nAPI_KEY = \"{secret}\"\nregion = 'us-east-1'\nbackup = '{aws_key}'\n"
+            ),
+        )
+        .expect("write settings");
+
+        let report = SecurityAuditor::new()
+            .audit(SecurityAuditOptions {
+                root: temp.path().to_path_buf(),
+                ..SecurityAuditOptions::default()
+            })
+            .expect("audit report");
+        let finding = report
+            .findings
+            .iter()
+            .find(|finding| finding.title == "Potential hardcoded secret")
+            .expect("hardcoded secret finding");
+        assert_eq!(finding.line, Some(3));
+        assert_eq!(
+            finding.context_before,
+            vec!["# settings".to_string(), "name = 'demo'".to_string()]
+        );
+        assert_eq!(finding.snippet.as_deref(), Some("API_KEY = \"[REDACTED]\""));
+        assert_eq!(
+            finding.context_after,
+            vec![
+                "region = 'us-east-1'".to_string(),
+                "backup = '[REDACTED]'".to_string()
+            ]
+        );
+
+        let aws = report
+            .findings
+            .iter()
+            .find(|finding| finding.title == "AWS access key pattern detected")
+            .expect("aws finding");
+        assert_eq!(aws.context_before.len(), 2);
+        assert!(aws.context_after.is_empty());
+
+        let sarif = report_to_sarif(&report);
+        let serialized = sarif.to_string();
+        assert!(!serialized.contains(&secret));
+        assert!(!serialized.contains(&aws_key));
+        let result = sarif["runs"][0]["results"]
+            .as_array()
+            .expect("results")
+            .iter()
+            .find(|result| result["locations"][0]["physicalLocation"]["region"]["startLine"] == 3)
+            .expect("line 3 result");
+        let context = &result["locations"][0]["physicalLocation"]["contextRegion"];
+        assert_eq!(context["startLine"], 1);
+        assert_eq!(context["endLine"], 5);
+        assert_eq!(
+            context["snippet"]["text"],
+            "# settings\nname = 'demo'\nAPI_KEY = \"[REDACTED]\"\nregion = 'us-east-1'\nbackup = '[REDACTED]'"
         );
     }
 }
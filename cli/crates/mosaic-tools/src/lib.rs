@@ -1,24 +1,40 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, Write};
-use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Instant;
-
-use regex::Regex;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use walkdir::WalkDir;
 
 use mosaic_core::config::RunGuardMode;
 use mosaic_core::error::{MosaicError, Result};
-use mosaic_ops::{ApprovalDecision, RuntimePolicy, evaluate_approval, evaluate_sandbox};
+use mosaic_core::provider::ToolSpec;
+use mosaic_ops::{
+    ApprovalAudit, ApprovalAuditDecision, ApprovalDecision, ApprovalStore, RuntimePolicy,
+    evaluate_approval, evaluate_sandbox_policy_in,
+};
+
+mod patch;
 
 const MAX_DEFAULT_SEARCH_RESULTS: usize = 50;
+const MAX_DEFAULT_LIST_ENTRIES: usize = 200;
+const MAX_LIST_ENTRIES: usize = 2_000;
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 1_000_000;
+const TIMED_OUT_EXIT_CODE: i32 = -2;
+const TRUNCATED_MARKER: &str = "[truncated]";
+const MAX_SYMLINK_HOPS: usize = 40;
 
 #[derive(Debug, Clone)]
 pub struct ToolExecutor {
     guard_mode: RunGuardMode,
     runtime_policy: Option<RuntimePolicy>,
+    approval_audit: Option<ApprovalStore>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +53,11 @@ pub struct RunCommandOutput {
     pub stderr: String,
     pub exit_code: i32,
     pub duration_ms: u128,
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Names of environment overrides applied to the command; values are never echoed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_keys: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +87,8 @@ struct SensitivePathMatch {
 #[derive(Debug, Deserialize)]
 struct ReadFileArgs {
     path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -74,16 +97,206 @@ struct WriteFileArgs {
     content: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ApplyPatchArgs {
+    patch: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct SearchTextArgs {
     query: String,
     path: Option<String>,
     max_results: Option<usize>,
+    #[serde(default)]
+    ignore_case: bool,
+    #[serde(default)]
+    literal: bool,
+}
+
+/// How `search_text` compares a query against each line.
+enum TextMatcher {
+    Regex(Regex),
+    Literal { needle: String, ignore_case: bool },
+}
+
+impl TextMatcher {
+    fn new(query: &str, literal: bool, ignore_case: bool) -> Result<Self> {
+        if literal {
+            let needle = if ignore_case {
+                query.to_lowercase()
+            } else {
+                query.to_string()
+            };
+            return Ok(Self::Literal {
+                needle,
+                ignore_case,
+            });
+        }
+        let regex = RegexBuilder::new(query)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| {
+                MosaicError::Tool(format!(
+                    "invalid search regex '{query}': {err}. pass literal: true for plain text"
+                ))
+            })?;
+        Ok(Self::Regex(regex))
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::Regex(regex) => regex.is_match(line),
+            Self::Literal {
+                needle,
+                ignore_case: true,
+            } => line.to_lowercase().contains(needle.as_str()),
+            Self::Literal { needle, .. } => line.contains(needle.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteFileArgs {
+    path: String,
+    #[serde(default)]
+    recursive: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListFilesArgs {
+    path: Option<String>,
+    recursive: Option<bool>,
+    max_entries: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlobArgs {
+    pattern: String,
+    max_results: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
 struct RunCommandArgs {
     command: String,
+    timeout_ms: Option<u64>,
+    max_output_bytes: Option<usize>,
+    env: BTreeMap<String, String>,
+}
+
+/// JSON schemas for every tool `ToolExecutor::execute` accepts, for providers
+/// that support native function calling.
+pub fn builtin_tool_specs() -> Vec<ToolSpec> {
+    let spec = |name: &str, description: &str, parameters: Value| ToolSpec {
+        name: name.to_string(),
+        description: description.to_string(),
+        parameters,
+    };
+    let path = json!({ "type": "string", "description": "path relative to the workspace" });
+    vec![
+        spec(
+            "read_file",
+            "Read a text file, optionally limited to a 1-based inclusive line range.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": path,
+                    "start_line": { "type": "integer", "minimum": 1 },
+                    "end_line": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["path"]
+            }),
+        ),
+        spec(
+            "write_file",
+            "Write the full contents of a file, creating parent directories.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": path,
+                    "content": { "type": "string" }
+                },
+                "required": ["path", "content"]
+            }),
+        ),
+        spec(
+            "search_text",
+            "Search file contents by regex or literal text.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "path": path,
+                    "max_results": { "type": "integer", "minimum": 1 },
+                    "literal": { "type": "boolean" },
+                    "ignore_case": { "type": "boolean" }
+                },
+                "required": ["query"]
+            }),
+        ),
+        spec(
+            "apply_patch",
+            "Apply a unified diff to files in the workspace.",
+            json!({
+                "type": "object",
+                "properties": { "patch": { "type": "string" } },
+                "required": ["patch"]
+            }),
+        ),
+        spec(
+            "glob",
+            "Find files matching a glob pattern such as src/**/*.rs.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string" },
+                    "max_results": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["pattern"]
+            }),
+        ),
+        spec(
+            "list_files",
+            "List directory entries, optionally recursively.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": path,
+                    "recursive": { "type": "boolean" },
+                    "max_entries": { "type": "integer", "minimum": 1 }
+                }
+            }),
+        ),
+        spec(
+            "delete_file",
+            "Delete a file, or a directory when recursive is true.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": path,
+                    "recursive": { "type": "boolean" }
+                },
+                "required": ["path"]
+            }),
+        ),
+        spec(
+            "run_cmd",
+            "Run a shell command in the workspace.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string" },
+                    "timeout_ms": { "type": "integer", "minimum": 1 },
+                    "max_output_bytes": { "type": "integer", "minimum": 1 },
+                    "env": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "extra variables; only logging, color, locale and build-mode keys such as RUST_LOG, NODE_ENV, CI or LC_* are accepted"
+                    }
+                },
+                "required": ["command"]
+            }),
+        ),
+    ]
 }
 
 impl ToolExecutor {
@@ -91,14 +304,25 @@ impl ToolExecutor {
         Self {
             guard_mode,
             runtime_policy,
+            approval_audit: None,
         }
     }
 
+    /// Records every `run_cmd` approval outcome through `store`.
+    pub fn with_approval_audit(mut self, store: ApprovalStore) -> Self {
+        self.approval_audit = Some(store);
+        self
+    }
+
     pub fn execute(&self, name: &str, args: Value, context: &ToolContext) -> Result<Value> {
         match name {
             "read_file" => self.read_file(args, context),
             "write_file" => self.write_file(args, context),
+            "apply_patch" => self.apply_patch(args, context),
             "search_text" => self.search_text(args, context),
+            "list_files" => self.list_files(args, context),
+            "glob" => self.glob(args, context),
+            "delete_file" => self.delete_file(args, context),
             "run_cmd" => self.run_cmd(args, context),
             _ => Err(MosaicError::Tool(format!("unknown tool '{name}'"))),
         }
@@ -111,10 +335,40 @@ impl ToolExecutor {
         let content = fs::read_to_string(&path).map_err(|err| {
             MosaicError::Tool(format!("failed to read {}: {err}", path.display()))
         })?;
-        let content = redact_sensitive_text(&content);
+        let total_lines = content.lines().count();
+        if parsed.start_line.is_none() && parsed.end_line.is_none() {
+            return Ok(json!({
+                "path": path.display().to_string(),
+                "content": redact_sensitive_text(&content),
+                "total_lines": total_lines,
+            }));
+        }
+
+        let start_line = parsed.start_line.unwrap_or(1);
+        let end_line = parsed.end_line.unwrap_or(usize::MAX);
+        if start_line == 0 || end_line == 0 {
+            return Err(MosaicError::Validation(
+                "read_file line numbers are 1-based".to_string(),
+            ));
+        }
+        if start_line > end_line {
+            return Err(MosaicError::Validation(format!(
+                "read_file start_line {start_line} is greater than end_line {end_line}"
+            )));
+        }
+        // Ranges past the end of the file are clamped rather than rejected.
+        let end_line = end_line.min(total_lines);
+        let slice = content
+            .split_inclusive('\n')
+            .skip(start_line - 1)
+            .take(end_line.saturating_sub(start_line - 1))
+            .collect::<String>();
         Ok(json!({
             "path": path.display().to_string(),
-            "content": content,
+            "content": redact_sensitive_text(&slice),
+            "start_line": start_line.min(total_lines + 1),
+            "end_line": end_line,
+            "total_lines": total_lines,
         }))
     }
 
@@ -134,6 +388,100 @@ impl ToolExecutor {
         }))
     }
 
+    fn apply_patch(&self, args: Value, context: &ToolContext) -> Result<Value> {
+        let parsed: ApplyPatchArgs = serde_json::from_value(args)?;
+        let file_patches = patch::parse_unified_diff(&parsed.patch)?;
+
+        // Resolve and apply every hunk in memory first so a mismatch in one file
+        // leaves the whole workspace untouched. Later sections for a path build
+        // on the content planned by earlier ones.
+        let mut plan = PatchPlan::default();
+        for file_patch in &file_patches {
+            let target = file_patch.target_path().unwrap_or_default();
+            let path = self.resolve_in_cwd(&context.cwd, target)?;
+            enforce_sensitive_path_policy(&path, context, "apply_patch")?;
+            let source = match file_patch.rename_source() {
+                Some(old) => {
+                    let source = self.resolve_in_cwd(&context.cwd, old)?;
+                    enforce_sensitive_path_policy(&source, context, "apply_patch")?;
+                    if plan.exists(&path) {
+                        return Err(MosaicError::Tool(format!(
+                            "patch renames {} to {} but the destination already exists",
+                            source.display(),
+                            path.display()
+                        )));
+                    }
+                    Some(source)
+                }
+                None => None,
+            };
+            let read_path = source.as_deref().unwrap_or(&path);
+            let original = if file_patch.is_creation() {
+                if plan.exists(&path) {
+                    return Err(MosaicError::Tool(format!(
+                        "patch creates {} but it already exists",
+                        path.display()
+                    )));
+                }
+                String::new()
+            } else {
+                plan.content(read_path)?
+            };
+            let patched = patch::apply_hunks(target, &original, &file_patch.hunks)?;
+            let hunks = file_patch.hunks.len();
+            if file_patch.is_deletion() {
+                if !patched.is_empty() {
+                    return Err(MosaicError::Tool(format!(
+                        "patch deletes {} but does not remove all of its content",
+                        path.display()
+                    )));
+                }
+                plan.set(path, None, "deleted", hunks, None);
+            } else if let Some(source) = source {
+                plan.set(source.clone(), None, "deleted", 0, None);
+                plan.set(path, Some(patched), "renamed", hunks, Some(source));
+            } else if file_patch.is_creation() {
+                plan.set(path, Some(patched), "created", hunks, None);
+            } else {
+                plan.set(path, Some(patched), "modified", hunks, None);
+            }
+        }
+
+        let mut changed = Vec::with_capacity(plan.changes.len());
+        for change in plan.changes {
+            match &change.content {
+                Some(content) => {
+                    if let Some(parent) = change.path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&change.path, content.as_bytes()).map_err(|err| {
+                        MosaicError::Tool(format!(
+                            "failed to write {}: {err}",
+                            change.path.display()
+                        ))
+                    })?;
+                }
+                None => fs::remove_file(&change.path).map_err(|err| {
+                    MosaicError::Tool(format!("failed to delete {}: {err}", change.path.display()))
+                })?,
+            }
+            let mut entry = json!({
+                "path": change.path.display().to_string(),
+                "status": change.status,
+                "hunks": change.hunks,
+            });
+            if let Some(from) = change.renamed_from {
+                entry["from"] = json!(from.display().to_string());
+            }
+            changed.push(entry);
+        }
+
+        Ok(json!({
+            "changed": changed,
+            "applied": true,
+        }))
+    }
+
     fn search_text(&self, args: Value, context: &ToolContext) -> Result<Value> {
         let parsed: SearchTextArgs = serde_json::from_value(args)?;
         if parsed.query.trim().is_empty() {
@@ -149,7 +497,7 @@ impl ToolExecutor {
             enforce_sensitive_path_policy(&root, context, "search_text")?;
         }
         let max_results = parsed.max_results.unwrap_or(MAX_DEFAULT_SEARCH_RESULTS);
-        let regex = Regex::new(&parsed.query).ok();
+        let matcher = TextMatcher::new(&parsed.query, parsed.literal, parsed.ignore_case)?;
         let skip_sensitive = !allow_sensitive_file_access_override();
         let mut matches = Vec::new();
         for entry in WalkDir::new(&root).into_iter().flatten() {
@@ -157,13 +505,11 @@ impl ToolExecutor {
             if !path.is_file() || should_skip(path) {
                 continue;
             }
-            if skip_sensitive {
-                if let Some(sensitive) = classify_sensitive_path(path) {
-                    match sensitive.level {
-                        SensitivePathLevel::Critical => continue,
-                        SensitivePathLevel::Confidential if !context.yes => continue,
-                        SensitivePathLevel::Confidential => {}
-                    }
+            if skip_sensitive && let Some(sensitive) = classify_sensitive_path(path) {
+                match sensitive.level {
+                    SensitivePathLevel::Critical => continue,
+                    SensitivePathLevel::Confidential if !context.yes => continue,
+                    SensitivePathLevel::Confidential => {}
                 }
             }
             let content = match fs::read_to_string(path) {
@@ -171,12 +517,7 @@ impl ToolExecutor {
                 Err(_) => continue,
             };
             for (idx, line) in content.lines().enumerate() {
-                let matched = if let Some(re) = &regex {
-                    re.is_match(line)
-                } else {
-                    line.contains(&parsed.query)
-                };
-                if matched {
+                if matcher.is_match(line) {
                     matches.push(json!({
                         "path": path.display().to_string(),
                         "line_number": idx + 1,
@@ -191,101 +532,315 @@ impl ToolExecutor {
         Ok(json!({ "matches": matches, "truncated": false }))
     }
 
-    fn run_cmd(&self, args: Value, context: &ToolContext) -> Result<Value> {
-        let parsed = parse_run_command_args(args)?;
-        let decision = self.classify_command(&parsed.command);
+    fn delete_file(&self, args: Value, context: &ToolContext) -> Result<Value> {
+        let parsed: DeleteFileArgs = serde_json::from_value(args)?;
+        let path = self.resolve_in_cwd(&context.cwd, &parsed.path)?;
+        enforce_sensitive_path_policy(&path, context, "delete_file")?;
+        let metadata = fs::symlink_metadata(&path).map_err(|err| {
+            MosaicError::Tool(format!("failed to inspect {}: {err}", path.display()))
+        })?;
+        let is_dir = metadata.is_dir();
+        if is_dir && !parsed.recursive {
+            return Err(MosaicError::Tool(format!(
+                "{} is a directory; pass recursive: true to delete it",
+                path.display()
+            )));
+        }
+        if is_dir && canonicalize_virtual(&path)? == canonicalize_virtual(&context.cwd)? {
+            return Err(MosaicError::Tool(
+                "refusing to delete the workspace root".to_string(),
+            ));
+        }
+
+        // Checked as the equivalent `rm` so sandbox writable_paths, the approval
+        // policy and the audit log treat it like the same deletion via run_cmd.
+        let command = format!(
+            "rm {}-- {}",
+            if is_dir { "-r " } else { "" },
+            shell_quote(&path.display().to_string())
+        );
+        let decision = match self.guard_mode {
+            RunGuardMode::Unrestricted => GuardDecision::AllowAuto,
+            RunGuardMode::ConfirmDangerous => GuardDecision::NeedsConfirmation {
+                reason: "file deletion is a mutating operation".to_string(),
+            },
+            RunGuardMode::AllConfirm => GuardDecision::NeedsConfirmation {
+                reason: "all mutating tools require confirmation in this profile".to_string(),
+            },
+        };
+        let approved_by =
+            self.authorize_command(&command, decision, "auto_unrestricted", context)?;
+
+        let removed = if is_dir {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        removed.map_err(|err| {
+            MosaicError::Tool(format!("failed to delete {}: {err}", path.display()))
+        })?;
+        Ok(json!({
+            "path": path.display().to_string(),
+            "deleted": true,
+            "is_dir": is_dir,
+            "approved_by": approved_by,
+        }))
+    }
+
+    fn list_files(&self, args: Value, context: &ToolContext) -> Result<Value> {
+        let parsed: ListFilesArgs = serde_json::from_value(args)?;
+        let root = match parsed.path {
+            Some(path) => self.resolve_in_cwd(&context.cwd, &path)?,
+            None => context.cwd.clone(),
+        };
+        if !root.is_dir() {
+            return Err(MosaicError::Tool(format!(
+                "{} is not a directory",
+                root.display()
+            )));
+        }
+        let max_entries = parsed
+            .max_entries
+            .unwrap_or(MAX_DEFAULT_LIST_ENTRIES)
+            .clamp(1, MAX_LIST_ENTRIES);
+        let max_depth = if parsed.recursive.unwrap_or(false) {
+            usize::MAX
+        } else {
+            1
+        };
+        let walker = WalkDir::new(&root)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| !should_skip_entry(entry.path(), entry.file_type().is_dir()));
+        let mut entries = Vec::new();
+        for entry in walker.flatten() {
+            if entries.len() >= max_entries {
+                return Ok(json!({ "entries": entries, "truncated": true }));
+            }
+            let path = entry.path();
+            ensure_within(&context.cwd, path)?;
+            let is_dir = entry.file_type().is_dir();
+            let size_bytes = if is_dir {
+                None
+            } else {
+                entry.metadata().ok().map(|metadata| metadata.len())
+            };
+            entries.push(json!({
+                "path": path.display().to_string(),
+                "is_dir": is_dir,
+                "size_bytes": size_bytes,
+            }));
+        }
+        Ok(json!({ "entries": entries, "truncated": false }))
+    }
+
+    fn glob(&self, args: Value, context: &ToolContext) -> Result<Value> {
+        let parsed: GlobArgs = serde_json::from_value(args)?;
+        let pattern = parsed.pattern.trim();
+        if pattern.is_empty() {
+            return Err(MosaicError::Tool(
+                "glob pattern cannot be empty".to_string(),
+            ));
+        }
+        if Path::new(pattern).is_absolute() {
+            return Err(MosaicError::Tool(
+                "glob pattern must be relative to the workspace".to_string(),
+            ));
+        }
+        let max_results = parsed
+            .max_results
+            .unwrap_or(MAX_DEFAULT_LIST_ENTRIES)
+            .clamp(1, MAX_LIST_ENTRIES);
+        let root = glob::Pattern::escape(&context.cwd.display().to_string());
+        let full_pattern = format!("{root}/{pattern}");
+        let paths = glob::glob(&full_pattern)
+            .map_err(|err| MosaicError::Tool(format!("invalid glob pattern '{pattern}': {err}")))?;
+
+        let mut matches = Vec::new();
+        for path in paths.flatten() {
+            if should_skip_entry(&path, path.is_dir()) {
+                continue;
+            }
+            ensure_within(&context.cwd, &path)?;
+            if matches.len() >= max_results {
+                return Ok(json!({ "matches": matches, "truncated": true }));
+            }
+            let relative = path.strip_prefix(&context.cwd).unwrap_or(&path);
+            matches.push(relative.display().to_string());
+        }
+        Ok(json!({ "matches": matches, "truncated": false }))
+    }
+
+    fn record_approval(&self, audit: &ApprovalAudit) -> Result<()> {
+        match &self.approval_audit {
+            Some(store) => store.append_audit(audit),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`obtain_approval`], but also records the confirmation or refusal.
+    fn confirm_command_approval(
+        &self,
+        command: &str,
+        reason: &str,
+        context: &ToolContext,
+    ) -> Result<String> {
+        match obtain_approval(command, reason, context) {
+            Ok(approved_by) => {
+                self.record_approval(&ApprovalAudit::approved(
+                    command,
+                    ApprovalAuditDecision::Confirm,
+                    &approved_by,
+                ))?;
+                Ok(approved_by)
+            }
+            Err(err) => {
+                self.record_approval(&ApprovalAudit::denied(command, &err.to_string()))?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs `command` through the runtime sandbox and approval policies, then
+    /// the guard `decision`, recording the outcome in the approval audit log.
+    /// `auto_approved_by` labels an automatic approval the policy did not name.
+    fn authorize_command(
+        &self,
+        command: &str,
+        decision: GuardDecision,
+        auto_approved_by: &str,
+        context: &ToolContext,
+    ) -> Result<String> {
         let mut confirmation_reasons = Vec::new();
-        let mut auto_approved_by: Option<String> = None;
+        let mut policy_approved_by: Option<String> = None;
 
         if let Some(runtime_policy) = &self.runtime_policy {
-            if let Some(reason) = evaluate_sandbox(&parsed.command, runtime_policy.sandbox.profile)
+            if let Some(reason) =
+                evaluate_sandbox_policy_in(command, &runtime_policy.sandbox, &context.cwd)
             {
+                self.record_approval(&ApprovalAudit::denied(command, &reason))?;
                 return Err(MosaicError::SandboxDenied(reason));
             }
 
-            match evaluate_approval(&parsed.command, &runtime_policy.approval) {
+            match evaluate_approval(command, &runtime_policy.approval) {
                 ApprovalDecision::Auto { approved_by } => {
-                    auto_approved_by = Some(approved_by);
+                    policy_approved_by = Some(approved_by);
                 }
                 ApprovalDecision::NeedsConfirmation { reason } => {
                     confirmation_reasons.push(reason);
                 }
                 ApprovalDecision::Deny { reason } => {
+                    self.record_approval(&ApprovalAudit::denied(command, &reason))?;
                     return Err(MosaicError::ApprovalRequired(reason));
                 }
             }
         }
 
-        let approved_by = match decision {
+        match decision {
             GuardDecision::AllowAuto => {
                 if confirmation_reasons.is_empty() {
-                    auto_approved_by.unwrap_or_else(|| "auto_safe".to_string())
-                } else if context.yes {
-                    "flag_yes".to_string()
-                } else if context.interactive {
-                    let reason = confirmation_reasons.join("; ");
-                    if confirm_command(&parsed.command, &reason)? {
-                        "user_prompt".to_string()
-                    } else {
-                        return Err(MosaicError::ApprovalRequired(
-                            "command execution cancelled by user".to_string(),
-                        ));
-                    }
+                    let approved_by =
+                        policy_approved_by.unwrap_or_else(|| auto_approved_by.to_string());
+                    self.record_approval(&ApprovalAudit::approved(
+                        command,
+                        ApprovalAuditDecision::Auto,
+                        &approved_by,
+                    ))?;
+                    Ok(approved_by)
                 } else {
                     let reason = confirmation_reasons.join("; ");
-                    return Err(MosaicError::ApprovalRequired(format!(
-                        "command requires approval: {reason}. rerun with --yes"
-                    )));
+                    self.confirm_command_approval(command, &reason, context)
                 }
             }
             GuardDecision::NeedsConfirmation { reason } => {
                 confirmation_reasons.push(reason);
                 let reason = confirmation_reasons.join("; ");
-                if context.yes {
-                    "flag_yes".to_string()
-                } else if context.interactive {
-                    if confirm_command(&parsed.command, &reason)? {
-                        "user_prompt".to_string()
-                    } else {
-                        return Err(MosaicError::ApprovalRequired(
-                            "command execution cancelled by user".to_string(),
-                        ));
-                    }
-                } else {
-                    return Err(MosaicError::ApprovalRequired(format!(
-                        "command requires approval: {reason}. rerun with --yes"
-                    )));
-                }
+                self.confirm_command_approval(command, &reason, context)
             }
             GuardDecision::Blocked { reason, suggestion } => {
                 let suffix = suggestion
                     .map(|value| format!(" suggestion: {value}"))
                     .unwrap_or_default();
-                return Err(MosaicError::Tool(format!(
-                    "blocked command '{}': {reason}.{suffix}",
-                    parsed.command
-                )));
+                self.record_approval(&ApprovalAudit::denied(command, &reason))?;
+                Err(MosaicError::Tool(format!(
+                    "blocked command '{command}': {reason}.{suffix}"
+                )))
             }
-        };
+        }
+    }
+
+    fn run_cmd(&self, args: Value, context: &ToolContext) -> Result<Value> {
+        let parsed = parse_run_command_args(args)?;
+        let decision = self.classify_command(&parsed.command);
+        let approved_by =
+            self.authorize_command(&parsed.command, decision, "auto_safe", context)?;
 
         let shell = std::env::var("SHELL").unwrap_or_else(|_| "zsh".to_string());
+        let max_output_bytes = parsed.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES);
         let started = Instant::now();
-        let output = Command::new(shell)
+        let mut command = Command::new(shell);
+        command
             .arg("-lc")
             .arg(&parsed.command)
             .current_dir(&context.cwd)
-            .output()
+            .envs(&parsed.env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Its own process group lets a timeout kill everything the shell started.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .spawn()
             .map_err(|err| MosaicError::Tool(format!("failed to execute command: {err}")))?;
+        let stdout = CapturedStream::spawn(child.stdout.take(), max_output_bytes);
+        let stderr = CapturedStream::spawn(child.stderr.take(), max_output_bytes);
+
+        let deadline = parsed
+            .timeout_ms
+            .map(|timeout_ms| started + Duration::from_millis(timeout_ms));
+        let mut timed_out = false;
+        let status = loop {
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|err| MosaicError::Tool(format!("failed to wait for command: {err}")))?
+            {
+                break Some(status);
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                timed_out = true;
+                kill_command(&mut child);
+                let _ = child.wait();
+                break None;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        // Processes that left the group may keep the pipes open after a kill,
+        // so only wait briefly for the readers instead of until EOF.
+        let reader_grace = if timed_out {
+            Some(Duration::from_millis(100))
+        } else {
+            None
+        };
+        let stdout = stdout.finish(reader_grace);
+        let stderr = stderr.finish(reader_grace);
         let elapsed = started.elapsed().as_millis();
-        let exit_code = output.status.code().unwrap_or(-1);
+        let exit_code = match status {
+            Some(status) => status.code().unwrap_or(-1),
+            None => TIMED_OUT_EXIT_CODE,
+        };
         let result = RunCommandOutput {
             command: parsed.command,
             cwd: context.cwd.display().to_string(),
             approved_by,
-            stdout: redact_sensitive_text(&String::from_utf8_lossy(&output.stdout)),
-            stderr: redact_sensitive_text(&String::from_utf8_lossy(&output.stderr)),
+            stdout: redact_sensitive_text(&stdout),
+            stderr: redact_sensitive_text(&stderr),
             exit_code,
             duration_ms: elapsed,
+            timed_out,
+            env_keys: parsed.env.into_keys().collect(),
         };
         Ok(serde_json::to_value(result)?)
     }
@@ -351,15 +906,24 @@ fn parse_run_command_args(args: Value) -> Result<RunCommandArgs> {
     match args {
         Value::String(command) => normalize_run_command_arg("command", &command),
         Value::Object(map) => {
+            let mut parsed = None;
             for field_name in ["command", "cmd", "shell_command"] {
                 if let Some(value) = map.get(field_name).and_then(Value::as_str) {
-                    return normalize_run_command_arg(field_name, value);
+                    parsed = Some(normalize_run_command_arg(field_name, value)?);
+                    break;
                 }
             }
-            Err(MosaicError::Validation(
-                "run_cmd args must include `command` as a string (accepted aliases: `cmd`, `shell_command`)"
-                    .to_string(),
-            ))
+            let mut parsed = parsed.ok_or_else(|| {
+                MosaicError::Validation(
+                    "run_cmd args must include `command` as a string (accepted aliases: `cmd`, `shell_command`)"
+                        .to_string(),
+                )
+            })?;
+            parsed.timeout_ms = parse_optional_positive(&map, "timeout_ms")?;
+            parsed.max_output_bytes =
+                parse_optional_positive(&map, "max_output_bytes")?.map(|value| value as usize);
+            parsed.env = parse_run_command_env(&map)?;
+            Ok(parsed)
         }
         _ => Err(MosaicError::Validation(
             "run_cmd args must be a JSON object or string".to_string(),
@@ -376,9 +940,241 @@ fn normalize_run_command_arg(field_name: &str, value: &str) -> Result<RunCommand
     }
     Ok(RunCommandArgs {
         command: command.to_string(),
+        timeout_ms: None,
+        max_output_bytes: None,
+        env: BTreeMap::new(),
     })
 }
 
+fn parse_run_command_env(map: &serde_json::Map<String, Value>) -> Result<BTreeMap<String, String>> {
+    let entries = match map.get("env") {
+        None | Some(Value::Null) => return Ok(BTreeMap::new()),
+        Some(Value::Object(entries)) => entries,
+        Some(_) => {
+            return Err(MosaicError::Validation(
+                "run_cmd field `env` must be an object of string values".to_string(),
+            ));
+        }
+    };
+    let mut env = BTreeMap::new();
+    for (key, value) in entries {
+        let key = key.trim();
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            return Err(MosaicError::Validation(format!(
+                "run_cmd env key '{key}' must be non-empty and cannot contain '=' or NUL"
+            )));
+        }
+        if !is_allowed_env_key(key) {
+            return Err(MosaicError::Validation(format!(
+                "run_cmd env key '{key}' is not allowed; allowed keys: {}, LC_*",
+                ALLOWED_ENV_KEYS.join(", ")
+            )));
+        }
+        let Some(value) = value.as_str() else {
+            return Err(MosaicError::Validation(format!(
+                "run_cmd env value for '{key}' must be a string"
+            )));
+        };
+        env.insert(key.to_string(), value.to_string());
+    }
+    Ok(env)
+}
+
+/// Variables `run_cmd` accepts in `env`. Guard and approval checks only see
+/// the command string, and many variables change which code a command runs
+/// (loaders, shell startup files, pagers, editors, git and tool configs), so
+/// only keys that tune logging, colors, locale or build modes are allowed.
+const ALLOWED_ENV_KEYS: &[&str] = &[
+    "CI",
+    "NO_COLOR",
+    "FORCE_COLOR",
+    "CLICOLOR",
+    "CLICOLOR_FORCE",
+    "TERM",
+    "TZ",
+    "LANG",
+    "LANGUAGE",
+    "RUST_LOG",
+    "RUST_BACKTRACE",
+    "RUST_TEST_THREADS",
+    "CARGO_TERM_COLOR",
+    "CARGO_INCREMENTAL",
+    "NODE_ENV",
+    "PYTHONUNBUFFERED",
+    "PYTHONDONTWRITEBYTECODE",
+];
+
+fn is_allowed_env_key(key: &str) -> bool {
+    ALLOWED_ENV_KEYS.contains(&key) || key.starts_with("LC_")
+}
+
+fn parse_optional_positive(
+    map: &serde_json::Map<String, Value>,
+    field: &str,
+) -> Result<Option<u64>> {
+    match map.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => match value.as_u64() {
+            Some(value) if value > 0 => Ok(Some(value)),
+            _ => Err(MosaicError::Validation(format!(
+                "run_cmd field `{field}` must be a positive integer"
+            ))),
+        },
+    }
+}
+
+/// Final state of every file touched by one `apply_patch` call, in the order
+/// the patch first touched them. `content: None` means the file is removed.
+#[derive(Default)]
+struct PatchPlan {
+    changes: Vec<PlannedChange>,
+}
+
+struct PlannedChange {
+    path: PathBuf,
+    content: Option<String>,
+    status: &'static str,
+    hunks: usize,
+    renamed_from: Option<PathBuf>,
+}
+
+impl PatchPlan {
+    fn planned(&self, path: &Path) -> Option<&PlannedChange> {
+        self.changes.iter().find(|change| change.path == path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.planned(path) {
+            Some(change) => change.content.is_some(),
+            None => path.exists(),
+        }
+    }
+
+    /// Content as left by earlier sections of the patch, or as on disk.
+    fn content(&self, path: &Path) -> Result<String> {
+        match self.planned(path) {
+            Some(PlannedChange {
+                content: Some(content),
+                ..
+            }) => Ok(content.clone()),
+            Some(_) => Err(MosaicError::Tool(format!(
+                "patch changes {} after removing it",
+                path.display()
+            ))),
+            None => fs::read_to_string(path).map_err(|err| {
+                MosaicError::Tool(format!("failed to read {}: {err}", path.display()))
+            }),
+        }
+    }
+
+    /// Records the new state of `path`. A file created or renamed earlier in
+    /// the patch keeps that status when later sections modify it.
+    fn set(
+        &mut self,
+        path: PathBuf,
+        content: Option<String>,
+        status: &'static str,
+        hunks: usize,
+        renamed_from: Option<PathBuf>,
+    ) {
+        let Some(change) = self.changes.iter_mut().find(|change| change.path == path) else {
+            self.changes.push(PlannedChange {
+                path,
+                content,
+                status,
+                hunks,
+                renamed_from,
+            });
+            return;
+        };
+        let keeps_status = status == "modified" && matches!(change.status, "created" | "renamed");
+        if !keeps_status {
+            change.status = status;
+            change.renamed_from = renamed_from;
+        }
+        change.content = content;
+        change.hunks += hunks;
+    }
+}
+
+/// Kills a timed-out command. On unix the whole process group goes, so
+/// background jobs and pipeline stages die with the shell.
+fn kill_command(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: killpg only sends a signal; the group was created at spawn
+        // with the child as its leader, so it cannot name unrelated processes.
+        unsafe { libc::killpg(pgid, libc::SIGKILL) };
+    }
+    let _ = child.kill();
+}
+
+/// Drains a child pipe on a background thread, keeping at most `cap` bytes
+/// so a chatty command cannot exhaust memory.
+struct CapturedStream {
+    buffer: Arc<Mutex<CapturedBytes>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct CapturedBytes {
+    data: Vec<u8>,
+    truncated: bool,
+}
+
+impl CapturedStream {
+    fn spawn<R: Read + Send + 'static>(pipe: Option<R>, cap: usize) -> Self {
+        let buffer = Arc::new(Mutex::new(CapturedBytes::default()));
+        let handle = pipe.map(|mut pipe| {
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || {
+                let mut chunk = [0u8; 8192];
+                while let Ok(read) = pipe.read(&mut chunk) {
+                    if read == 0 {
+                        break;
+                    }
+                    let mut captured = buffer.lock().unwrap_or_else(|err| err.into_inner());
+                    let room = cap.saturating_sub(captured.data.len());
+                    if read > room {
+                        captured.truncated = true;
+                    }
+                    let keep = read.min(room);
+                    captured.data.extend_from_slice(&chunk[..keep]);
+                }
+            })
+        });
+        Self { buffer, handle }
+    }
+
+    fn finish(mut self, grace: Option<Duration>) -> String {
+        if let Some(handle) = self.handle.take() {
+            match grace {
+                None => {
+                    let _ = handle.join();
+                }
+                Some(grace) => {
+                    let deadline = Instant::now() + grace;
+                    while !handle.is_finished() && Instant::now() < deadline {
+                        thread::sleep(Duration::from_millis(5));
+                    }
+                    if handle.is_finished() {
+                        let _ = handle.join();
+                    }
+                }
+            }
+        }
+        let captured = self.buffer.lock().unwrap_or_else(|err| err.into_inner());
+        let mut text = String::from_utf8_lossy(&captured.data).into_owned();
+        if captured.truncated {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(TRUNCATED_MARKER);
+        }
+        text
+    }
+}
+
 fn should_skip(path: &Path) -> bool {
     let text = path.display().to_string();
     text.contains("/.git/")
@@ -387,6 +1183,16 @@ fn should_skip(path: &Path) -> bool {
         || text.contains("/.pnpm-store/")
 }
 
+/// Directories are checked with a trailing separator so `repo/.git` itself is
+/// pruned, not just the files below it.
+fn should_skip_entry(path: &Path, is_dir: bool) -> bool {
+    if is_dir {
+        should_skip(&path.join(""))
+    } else {
+        should_skip(path)
+    }
+}
+
 fn allow_sensitive_file_access_override() -> bool {
     env_truthy("MOSAIC_ALLOW_SENSITIVE_FILES")
 }
@@ -621,14 +1427,29 @@ fn ensure_within(cwd: &Path, path: &Path) -> Result<()> {
 }
 
 fn canonicalize_virtual(path: &Path) -> Result<PathBuf> {
+    canonicalize_virtual_inner(path, 0)
+}
+
+/// Resolves `path` to its real location even when its tail does not exist yet.
+/// The nearest existing ancestor is canonicalized (following symlinks, including
+/// dangling ones), and the missing components are applied lexically so neither
+/// a symlink nor a `..` in the tail can step outside the resolved anchor.
+fn canonicalize_virtual_inner(path: &Path, hops: usize) -> Result<PathBuf> {
     if path.exists() {
         return path.canonicalize().map_err(|err| {
             MosaicError::Tool(format!("failed to resolve {}: {err}", path.display()))
         });
     }
+    if hops > MAX_SYMLINK_HOPS {
+        return Err(MosaicError::Tool(format!(
+            "failed to resolve {}: too many levels of symbolic links",
+            path.display()
+        )));
+    }
 
+    // `symlink_metadata` also sees dangling links, which `exists()` reports as missing.
     let mut anchor = path;
-    while !anchor.exists() {
+    while fs::symlink_metadata(anchor).is_err() {
         anchor = anchor.parent().ok_or_else(|| {
             MosaicError::Tool(format!(
                 "failed to resolve {}: no existing parent directory",
@@ -636,16 +1457,63 @@ fn canonicalize_virtual(path: &Path) -> Result<PathBuf> {
             ))
         })?;
     }
-    let anchored = anchor.canonicalize().map_err(|err| {
-        MosaicError::Tool(format!("failed to resolve {}: {err}", anchor.display()))
-    })?;
+    let anchored = if anchor.exists() {
+        anchor.canonicalize().map_err(|err| {
+            MosaicError::Tool(format!("failed to resolve {}: {err}", anchor.display()))
+        })?
+    } else {
+        let target = fs::read_link(anchor).map_err(|err| {
+            MosaicError::Tool(format!("failed to resolve {}: {err}", anchor.display()))
+        })?;
+        let target = match anchor.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+        canonicalize_virtual_inner(&target, hops + 1)?
+    };
     let suffix = path.strip_prefix(anchor).map_err(|err| {
         MosaicError::Tool(format!(
             "failed to resolve relative path from {}: {err}",
             path.display()
         ))
     })?;
-    Ok(anchored.join(suffix))
+
+    let mut resolved = anchored;
+    for component in suffix.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            _ => {}
+        }
+    }
+    Ok(resolved)
+}
+
+/// Returns who approved `command`: `--yes`, an interactive prompt, or an error
+/// when neither is available.
+/// Single-quotes `value` for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn obtain_approval(command: &str, reason: &str, context: &ToolContext) -> Result<String> {
+    if context.yes {
+        return Ok("flag_yes".to_string());
+    }
+    if !context.interactive {
+        return Err(MosaicError::ApprovalRequired(format!(
+            "command requires approval: {reason}. rerun with --yes"
+        )));
+    }
+    if confirm_command(command, reason)? {
+        Ok("user_prompt".to_string())
+    } else {
+        Err(MosaicError::ApprovalRequired(
+            "command execution cancelled by user".to_string(),
+        ))
+    }
 }
 
 fn confirm_command(command: &str, reason: &str) -> Result<bool> {
@@ -697,29 +1565,444 @@ fn is_dangerous_or_mutating(command: &str) -> bool {
 mod tests {
     use std::fs;
 
-    use mosaic_ops::{ApprovalMode, ApprovalPolicy, RuntimePolicy, SandboxPolicy, SandboxProfile};
+    use mosaic_ops::{
+        ApprovalAudit, ApprovalAuditDecision, ApprovalMode, ApprovalPolicy, ApprovalStore,
+        RuntimePolicy, SandboxPolicy, SandboxProfile,
+    };
 
     use super::*;
     use tempfile::tempdir;
 
     #[test]
-    fn read_write_search_tool_flow() {
+    fn apply_patch_applies_two_hunks_to_one_file() {
         let temp = tempdir().unwrap();
+        let original = (1..=12)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+        fs::write(temp.path().join("notes.txt"), original).unwrap();
         let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
         let ctx = ToolContext {
             cwd: temp.path().to_path_buf(),
-            yes: true,
+            yes: false,
             interactive: false,
         };
 
-        executor
-            .execute(
-                "write_file",
-                json!({"path":"notes/hello.txt","content":"rust cli rocks"}),
-                &ctx,
-            )
+        let diff = "\
+--- a/notes.txt
++++ b/notes.txt
+@@ -1,3 +1,3 @@
+ line 1
+-line 2
++line two
+ line 3
+@@ -9,4 +9,5 @@
+ line 9
+ line 10
++line 10.5
+ line 11
+ line 12
+";
+        let result = executor
+            .execute("apply_patch", json!({ "patch": diff }), &ctx)
             .unwrap();
-        let read = executor
+        assert_eq!(result["changed"].as_array().unwrap().len(), 1);
+        assert_eq!(result["changed"][0]["status"], "modified");
+        assert_eq!(result["changed"][0]["hunks"], 2);
+
+        let patched = fs::read_to_string(temp.path().join("notes.txt")).unwrap();
+        assert!(patched.contains("line 1\nline two\nline 3\n"));
+        assert!(patched.contains("line 10\nline 10.5\nline 11\n"));
+        assert!(patched.ends_with("line 12\n"));
+    }
+
+    #[test]
+    fn apply_patch_rejects_mismatched_context_and_outside_paths() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("a.txt"), "alpha\nbeta\n").unwrap();
+        fs::write(temp.path().join("b.txt"), "one\ntwo\n").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let diff = "\
+--- a/a.txt
++++ b/a.txt
+@@ -1,2 +1,2 @@
+-alpha
++ALPHA
+ beta
+--- a/b.txt
++++ b/b.txt
+@@ -1,2 +1,2 @@
+ uno
+-two
++dos
+";
+        let err = executor
+            .execute("apply_patch", json!({ "patch": diff }), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Tool(_)));
+        assert!(err.to_string().contains("does not match b.txt"));
+        // Nothing is written when any file fails to apply.
+        assert_eq!(
+            fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            "alpha\nbeta\n"
+        );
+
+        let outside = "\
+--- a/../escape.txt
++++ b/../escape.txt
+@@ -0,0 +1 @@
++nope
+";
+        let err = executor
+            .execute("apply_patch", json!({ "patch": outside }), &ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("outside workspace"));
+    }
+
+    #[test]
+    fn apply_patch_keeps_crlf_and_stacks_sections_for_one_file() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("win.txt"), "one\r\ntwo\r\nthree\r\n").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let diff = "\
+--- a/win.txt
++++ b/win.txt
+@@ -1,2 +1,2 @@
+-one
++ONE
+ two
+--- a/win.txt
++++ b/win.txt
+@@ -2,2 +2,2 @@
+ two
+-three
++THREE
+";
+        let result = executor
+            .execute("apply_patch", json!({ "patch": diff }), &ctx)
+            .unwrap();
+        assert_eq!(result["changed"].as_array().unwrap().len(), 1);
+        assert_eq!(result["changed"][0]["status"], "modified");
+        assert_eq!(result["changed"][0]["hunks"], 2);
+        assert_eq!(
+            fs::read_to_string(temp.path().join("win.txt")).unwrap(),
+            "ONE\r\ntwo\r\nTHREE\r\n"
+        );
+    }
+
+    #[test]
+    fn apply_patch_renames_remove_the_source() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("old.txt"), "alpha\nbeta\n").unwrap();
+        fs::write(temp.path().join("taken.txt"), "keep\n").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let diff = "\
+--- a/old.txt
++++ b/docs/new.txt
+@@ -1,2 +1,2 @@
+ alpha
+-beta
++gamma
+";
+        let result = executor
+            .execute("apply_patch", json!({ "patch": diff }), &ctx)
+            .unwrap();
+        let changed = result["changed"].as_array().unwrap();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[0]["status"], "deleted");
+        assert_eq!(changed[1]["status"], "renamed");
+        assert!(changed[1]["from"].as_str().unwrap().ends_with("old.txt"));
+        assert!(!temp.path().join("old.txt").exists());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("docs/new.txt")).unwrap(),
+            "alpha\ngamma\n"
+        );
+
+        let onto_existing = "\
+--- a/docs/new.txt
++++ b/taken.txt
+@@ -1,2 +1,2 @@
+ alpha
+-gamma
++delta
+";
+        let err = executor
+            .execute("apply_patch", json!({ "patch": onto_existing }), &ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("destination already exists"));
+        assert!(temp.path().join("docs/new.txt").exists());
+    }
+
+    #[test]
+    fn delete_file_removes_file_with_yes_and_requires_approval_otherwise() {
+        let temp = tempdir().unwrap();
+        fs::write(temp.path().join("scratch.txt"), "tmp").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let mut ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let err = executor
+            .execute("delete_file", json!({"path":"scratch.txt"}), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::ApprovalRequired(_)));
+        assert!(temp.path().join("scratch.txt").exists());
+
+        ctx.yes = true;
+        let deleted = executor
+            .execute("delete_file", json!({"path":"scratch.txt"}), &ctx)
+            .unwrap();
+        assert_eq!(deleted["deleted"], true);
+        assert_eq!(deleted["approved_by"], "flag_yes");
+        assert!(!temp.path().join("scratch.txt").exists());
+
+        let unrestricted = ToolExecutor::new(RunGuardMode::Unrestricted, None);
+        fs::write(temp.path().join("again.txt"), "tmp").unwrap();
+        ctx.yes = false;
+        let deleted = unrestricted
+            .execute("delete_file", json!({"path":"again.txt"}), &ctx)
+            .unwrap();
+        assert_eq!(deleted["approved_by"], "auto_unrestricted");
+    }
+
+    #[test]
+    fn delete_file_blocks_directories_without_recursive_flag() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("build/cache")).unwrap();
+        fs::write(temp.path().join("build/cache/blob"), "data").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let err = executor
+            .execute("delete_file", json!({"path":"build"}), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Tool(_)));
+        assert!(err.to_string().contains("pass recursive: true"));
+        assert!(temp.path().join("build/cache/blob").exists());
+
+        let err = executor
+            .execute("delete_file", json!({"path":".","recursive":true}), &ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("workspace root"));
+
+        let deleted = executor
+            .execute(
+                "delete_file",
+                json!({"path":"build","recursive":true}),
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(deleted["is_dir"], true);
+        assert!(!temp.path().join("build").exists());
+    }
+
+    #[test]
+    fn delete_file_goes_through_sandbox_approval_policy_and_audit() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/scratch.txt"), "tmp").unwrap();
+        fs::write(temp.path().join("keep.txt"), "keep").unwrap();
+        let audit_path = temp.path().join("approvals.jsonl");
+        let store = ApprovalStore::new(temp.path().join("approvals.toml"))
+            .with_audit_path(audit_path.clone());
+        let policy = RuntimePolicy {
+            approval: ApprovalPolicy::default(),
+            sandbox: SandboxPolicy {
+                writable_paths: vec!["src".to_string()],
+                ..SandboxPolicy::default()
+            },
+        };
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(policy))
+            .with_approval_audit(store.clone());
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let err = executor
+            .execute("delete_file", json!({"path":"keep.txt"}), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::SandboxDenied(_)));
+        assert!(temp.path().join("keep.txt").exists());
+
+        executor
+            .execute("delete_file", json!({"path":"src/scratch.txt"}), &ctx)
+            .unwrap();
+        assert!(!temp.path().join("src/scratch.txt").exists());
+
+        let deny = RuntimePolicy {
+            approval: ApprovalPolicy::new(ApprovalMode::Deny, Vec::new()).unwrap(),
+            sandbox: SandboxPolicy::default(),
+        };
+        let err = ToolExecutor::new(RunGuardMode::Unrestricted, Some(deny))
+            .with_approval_audit(store)
+            .execute("delete_file", json!({"path":"keep.txt"}), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::ApprovalRequired(_)));
+        assert!(temp.path().join("keep.txt").exists());
+
+        let records = fs::read_to_string(&audit_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<ApprovalAudit>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        assert!(records[0].command.starts_with("rm -- '"));
+        assert!(records[0].command.ends_with("/keep.txt'"));
+        assert_eq!(records[0].decision, ApprovalAuditDecision::Deny);
+        assert_eq!(records[1].decision, ApprovalAuditDecision::Confirm);
+        assert_eq!(records[1].approved_by.as_deref(), Some("flag_yes"));
+        assert_eq!(records[2].decision, ApprovalAuditDecision::Deny);
+    }
+
+    #[test]
+    fn list_files_lists_small_tree_and_skips_vendor_dirs() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("src/nested")).unwrap();
+        fs::create_dir_all(temp.path().join("target/debug")).unwrap();
+        fs::write(temp.path().join("README.md"), "hello").unwrap();
+        fs::write(temp.path().join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(temp.path().join("src/nested/mod.rs"), "").unwrap();
+        fs::write(temp.path().join("target/debug/out"), "bin").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+        let relative = |value: &Value| {
+            value["entries"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| {
+                    Path::new(entry["path"].as_str().unwrap())
+                        .strip_prefix(temp.path())
+                        .unwrap()
+                        .display()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let top = executor.execute("list_files", json!({}), &ctx).unwrap();
+        assert_eq!(relative(&top), vec!["README.md", "src"]);
+        assert_eq!(top["entries"][0]["size_bytes"], 5);
+        assert_eq!(top["entries"][1]["is_dir"], true);
+        assert!(top["entries"][1]["size_bytes"].is_null());
+        assert_eq!(top["truncated"], false);
+
+        let recursive = executor
+            .execute("list_files", json!({"path":"src","recursive":true}), &ctx)
+            .unwrap();
+        assert_eq!(
+            relative(&recursive),
+            vec!["src/lib.rs", "src/nested", "src/nested/mod.rs"]
+        );
+
+        let capped = executor
+            .execute(
+                "list_files",
+                json!({"recursive":true,"max_entries":2}),
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(capped["entries"].as_array().unwrap().len(), 2);
+        assert_eq!(capped["truncated"], true);
+
+        let err = executor
+            .execute("list_files", json!({"path":"../"}), &ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("outside workspace"));
+        let err = executor
+            .execute("list_files", json!({"path":"README.md"}), &ctx)
+            .unwrap_err();
+        assert!(err.to_string().contains("is not a directory"));
+    }
+
+    #[test]
+    fn glob_matches_nested_files_and_skips_vendor_dirs() {
+        let temp = tempdir().unwrap();
+        fs::create_dir_all(temp.path().join("docs/guides")).unwrap();
+        fs::create_dir_all(temp.path().join("node_modules/pkg")).unwrap();
+        fs::write(temp.path().join("readme.txt"), "top").unwrap();
+        fs::write(temp.path().join("docs/intro.txt"), "intro").unwrap();
+        fs::write(temp.path().join("docs/guides/setup.txt"), "setup").unwrap();
+        fs::write(temp.path().join("docs/guides/setup.md"), "setup").unwrap();
+        fs::write(temp.path().join("node_modules/pkg/notes.txt"), "vendored").unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let result = executor
+            .execute("glob", json!({"pattern":"**/*.txt"}), &ctx)
+            .unwrap();
+        let matches = result["matches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value.as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            vec!["docs/guides/setup.txt", "docs/intro.txt", "readme.txt"]
+        );
+        assert_eq!(result["truncated"], false);
+
+        let capped = executor
+            .execute("glob", json!({"pattern":"**/*.txt","max_results":1}), &ctx)
+            .unwrap();
+        assert_eq!(capped["matches"].as_array().unwrap().len(), 1);
+        assert_eq!(capped["truncated"], true);
+    }
+
+    #[test]
+    fn read_write_search_tool_flow() {
+        let temp = tempdir().unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        executor
+            .execute(
+                "write_file",
+                json!({"path":"notes/hello.txt","content":"rust cli rocks"}),
+                &ctx,
+            )
+            .unwrap();
+        let read = executor
             .execute("read_file", json!({"path":"notes/hello.txt"}), &ctx)
             .unwrap();
         assert_eq!(read["content"], "rust cli rocks");
@@ -734,6 +2017,52 @@ mod tests {
         assert_eq!(found["matches"].as_array().unwrap().len(), 1);
     }
 
+    #[test]
+    fn read_file_returns_requested_line_range() {
+        let temp = tempdir().unwrap();
+        fs::write(
+            temp.path().join("five.txt"),
+            "one\ntwo\nthree\nfour\nfive\n",
+        )
+        .unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+
+        let read = executor
+            .execute(
+                "read_file",
+                json!({"path":"five.txt","start_line":2,"end_line":3}),
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(read["content"], "two\nthree\n");
+        assert_eq!(read["total_lines"], 5);
+        assert_eq!(read["end_line"], 3);
+
+        let clamped = executor
+            .execute(
+                "read_file",
+                json!({"path":"five.txt","start_line":4,"end_line":99}),
+                &ctx,
+            )
+            .unwrap();
+        assert_eq!(clamped["content"], "four\nfive\n");
+        assert_eq!(clamped["end_line"], 5);
+
+        let err = executor
+            .execute(
+                "read_file",
+                json!({"path":"five.txt","start_line":3,"end_line":2}),
+                &ctx,
+            )
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Validation(_)));
+    }
+
     #[test]
     fn blocks_high_risk_command() {
         let temp = tempdir().unwrap();
@@ -772,6 +2101,161 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_command_times_out_and_caps_output() {
+        let temp = tempdir().unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let started = Instant::now();
+        let value = executor
+            .execute(
+                "run_cmd",
+                json!({"command": "sleep 5", "timeout_ms": 200}),
+                &ctx,
+            )
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(3));
+        let output: RunCommandOutput = serde_json::from_value(value).unwrap();
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, -2);
+
+        let value = executor
+            .execute(
+                "run_cmd",
+                json!({"command": "printf 'abcdefghijklmnop'", "max_output_bytes": 4}),
+                &ctx,
+            )
+            .unwrap();
+        let output: RunCommandOutput = serde_json::from_value(value).unwrap();
+        assert!(!output.timed_out);
+        assert_eq!(output.stdout, "abcd\n[truncated]");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_command_timeout_kills_background_jobs() {
+        let temp = tempdir().unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let value = executor
+            .execute(
+                "run_cmd",
+                json!({
+                    "command": "sh -c 'echo $$ > job.pid; exec sleep 30' & wait",
+                    "timeout_ms": 5000,
+                }),
+                &ctx,
+            )
+            .unwrap();
+        let output: RunCommandOutput = serde_json::from_value(value).unwrap();
+        assert!(output.timed_out);
+
+        let pid = std::fs::read_to_string(temp.path().join("job.pid")).unwrap();
+        let stat_path = format!("/proc/{}/stat", pid.trim());
+        // The orphaned job is reaped asynchronously; a zombie counts as dead.
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let alive = loop {
+            let alive = std::fs::read_to_string(&stat_path).is_ok_and(|stat| {
+                !stat
+                    .rsplit(')')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .starts_with('Z')
+            });
+            if !alive || Instant::now() >= deadline {
+                break alive;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+        assert!(!alive, "background job survived the timeout");
+    }
+
+    #[test]
+    fn run_command_applies_env_overrides() {
+        let temp = tempdir().unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let value = executor
+            .execute(
+                "run_cmd",
+                json!({"command": "echo $NODE_ENV", "env": {"NODE_ENV": "mosaic-test"}}),
+                &ctx,
+            )
+            .unwrap();
+        let output: RunCommandOutput = serde_json::from_value(value).unwrap();
+        assert_eq!(output.stdout.trim(), "mosaic-test");
+        assert_eq!(output.env_keys, vec!["NODE_ENV".to_string()]);
+        assert_ne!(
+            std::env::var("NODE_ENV").ok().as_deref(),
+            Some("mosaic-test")
+        );
+
+        let err = executor
+            .execute(
+                "run_cmd",
+                json!({"command": "echo hi", "env": {" ": "x"}}),
+                &ctx,
+            )
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Validation(_)));
+    }
+
+    #[test]
+    fn run_command_rejects_env_keys_outside_the_allowlist() {
+        let temp = tempdir().unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::Unrestricted, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+        for key in [
+            "LD_PRELOAD",
+            "DYLD_INSERT_LIBRARIES",
+            "BASH_ENV",
+            "ZDOTDIR",
+            "PATH",
+            "path",
+            "GIT_EXTERNAL_DIFF",
+            "GIT_CONFIG_COUNT",
+            "GIT_CONFIG_KEY_0",
+            "GIT_PAGER",
+            "PAGER",
+            "EDITOR",
+            "RIPGREP_CONFIG_PATH",
+            "node_env",
+            "MOSAIC_TEST_NODE_ENV",
+        ] {
+            let err = executor
+                .execute(
+                    "run_cmd",
+                    json!({"command": "echo hi", "env": {key: "/tmp/x"}}),
+                    &ctx,
+                )
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("is not allowed"),
+                "{key} should be rejected: {err}"
+            );
+        }
+    }
+
     #[test]
     fn run_command_accepts_cmd_alias() {
         let temp = tempdir().unwrap();
@@ -817,8 +2301,7 @@ mod tests {
     #[test]
     fn approval_policy_deny_blocks_run_command() {
         let temp = tempdir().unwrap();
-        let mut approval = ApprovalPolicy::default();
-        approval.mode = ApprovalMode::Deny;
+        let approval = ApprovalPolicy::new(ApprovalMode::Deny, Vec::new()).unwrap();
         let policy = RuntimePolicy {
             approval,
             sandbox: SandboxPolicy::default(),
@@ -836,16 +2319,68 @@ mod tests {
         assert!(matches!(err, MosaicError::ApprovalRequired(_)));
     }
 
+    #[test]
+    fn approval_outcomes_are_written_to_audit_log() {
+        let temp = tempdir().unwrap();
+        let audit_path = temp.path().join("audit").join("approvals.jsonl");
+        let store = ApprovalStore::new(temp.path().join("approvals.toml"))
+            .with_audit_path(audit_path.clone());
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        let deny = RuntimePolicy {
+            approval: ApprovalPolicy::new(ApprovalMode::Deny, Vec::new()).unwrap(),
+            sandbox: SandboxPolicy::default(),
+        };
+        ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(deny))
+            .with_approval_audit(store.clone())
+            .execute("run_cmd", json!({"command":"echo blocked"}), &ctx)
+            .unwrap_err();
+
+        let confirm = RuntimePolicy {
+            approval: ApprovalPolicy::default(),
+            sandbox: SandboxPolicy::default(),
+        };
+        ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(confirm))
+            .with_approval_audit(store)
+            .execute("run_cmd", json!({"command":"echo ok"}), &ctx)
+            .unwrap();
+
+        let records = fs::read_to_string(&audit_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<ApprovalAudit>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].command, "echo blocked");
+        assert_eq!(records[0].decision, ApprovalAuditDecision::Deny);
+        assert_eq!(
+            records[0].reason.as_deref(),
+            Some("approval mode is set to deny")
+        );
+        assert_eq!(records[1].decision, ApprovalAuditDecision::Confirm);
+        assert_eq!(records[1].approved_by.as_deref(), Some("flag_yes"));
+    }
+
     #[test]
     fn sandbox_restricted_blocks_network_command() {
         let temp = tempdir().unwrap();
-        let mut sandbox = SandboxPolicy::default();
-        sandbox.profile = SandboxProfile::Restricted;
+        let sandbox = SandboxPolicy {
+            profile: SandboxProfile::Restricted,
+            ..SandboxPolicy::default()
+        };
         let policy = RuntimePolicy {
             approval: ApprovalPolicy::default(),
             sandbox,
         };
-        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(policy));
+        let audit_path = temp.path().join("approvals.jsonl");
+        let store = ApprovalStore::new(temp.path().join("approvals.toml"))
+            .with_audit_path(audit_path.clone());
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, Some(policy))
+            .with_approval_audit(store);
         let ctx = ToolContext {
             cwd: temp.path().to_path_buf(),
             yes: true,
@@ -860,6 +2395,11 @@ mod tests {
             )
             .unwrap_err();
         assert!(matches!(err, MosaicError::SandboxDenied(_)));
+        let record =
+            serde_json::from_str::<ApprovalAudit>(fs::read_to_string(&audit_path).unwrap().trim())
+                .unwrap();
+        assert_eq!(record.command, "curl https://example.com");
+        assert_eq!(record.decision, ApprovalAuditDecision::Deny);
     }
 
     #[test]
@@ -969,4 +2509,80 @@ mod tests {
         let path = matches[0]["path"].as_str().unwrap_or_default();
         assert!(path.ends_with("/notes.txt"));
     }
+
+    #[test]
+    fn search_text_honors_literal_and_ignore_case_flags() {
+        let temp = tempdir().unwrap();
+        fs::write(
+            temp.path().join("notes.txt"),
+            "Value = fn(a)\nvalue = fn(b)\nother line\n",
+        )
+        .unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: false,
+            interactive: false,
+        };
+        let count = |args: Value| {
+            executor.execute("search_text", args, &ctx).unwrap()["matches"]
+                .as_array()
+                .unwrap()
+                .len()
+        };
+
+        // Regex, case-sensitive and case-insensitive.
+        assert_eq!(count(json!({"query":"^value"})), 1);
+        assert_eq!(count(json!({"query":"^value","ignore_case":true})), 2);
+        // Literal, case-sensitive and case-insensitive.
+        assert_eq!(count(json!({"query":"fn(a)","literal":true})), 1);
+        assert_eq!(
+            count(json!({"query":"VALUE = FN(","literal":true,"ignore_case":true})),
+            2
+        );
+        assert_eq!(count(json!({"query":"VALUE = FN(","literal":true})), 0);
+
+        let err = executor
+            .execute("search_text", json!({"query":"fn("}), &ctx)
+            .unwrap_err();
+        assert!(matches!(err, MosaicError::Tool(_)));
+        assert!(err.to_string().contains("invalid search regex"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_symlinks_escaping_workspace_are_rejected() {
+        let temp = tempdir().unwrap();
+        let outside = tempdir().unwrap();
+        std::os::unix::fs::symlink("/tmp", temp.path().join("tmp-link")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("missing.txt"),
+            temp.path().join("dangling"),
+        )
+        .unwrap();
+        let executor = ToolExecutor::new(RunGuardMode::ConfirmDangerous, None);
+        let ctx = ToolContext {
+            cwd: temp.path().to_path_buf(),
+            yes: true,
+            interactive: false,
+        };
+
+        for target in [
+            "tmp-link/mosaic-escape.txt",
+            "dangling",
+            "missing/../../escape.txt",
+        ] {
+            let err = executor
+                .execute(
+                    "write_file",
+                    json!({"path": target, "content": "nope"}),
+                    &ctx,
+                )
+                .unwrap_err();
+            assert!(matches!(err, MosaicError::Tool(_)), "{target}: {err}");
+            assert!(err.to_string().contains("outside workspace"), "{target}");
+        }
+        assert!(!outside.path().join("missing.txt").exists());
+        assert!(!Path::new("/tmp/mosaic-escape.txt").exists());
+    }
 }
@@ -0,0 +1,251 @@
+use mosaic_core::error::{MosaicError, Result};
+
+#[derive(Debug, Clone)]
+pub(crate) struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Hunk {
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+impl FilePatch {
+    /// Path the patch applies to: the new path unless the file is being deleted.
+    pub fn target_path(&self) -> Option<&str> {
+        self.new_path.as_deref().or(self.old_path.as_deref())
+    }
+
+    pub fn is_creation(&self) -> bool {
+        self.old_path.is_none()
+    }
+
+    pub fn is_deletion(&self) -> bool {
+        self.new_path.is_none()
+    }
+
+    /// Source path when the patch moves a file (`--- a/old` / `+++ b/new`).
+    pub fn rename_source(&self) -> Option<&str> {
+        match (self.old_path.as_deref(), self.new_path.as_deref()) {
+            (Some(old), Some(new)) if old != new => Some(old),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse_unified_diff(patch: &str) -> Result<Vec<FilePatch>> {
+    let lines = patch.lines().collect::<Vec<_>>();
+    let mut files = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        if !line.starts_with("--- ") {
+            index += 1;
+            continue;
+        }
+        let old_path = parse_header_path(&line[4..]);
+        let Some(next) = lines.get(index + 1).filter(|next| next.starts_with("+++ ")) else {
+            return Err(MosaicError::Tool(format!(
+                "invalid patch: expected '+++' header after line {}",
+                index + 1
+            )));
+        };
+        let new_path = parse_header_path(&next[4..]);
+        if old_path.is_none() && new_path.is_none() {
+            return Err(MosaicError::Tool(
+                "invalid patch: both file headers are /dev/null".to_string(),
+            ));
+        }
+        index += 2;
+
+        let mut hunks = Vec::new();
+        while index < lines.len() && lines[index].starts_with("@@") {
+            let (old_start, old_count, new_count) = parse_hunk_header(lines[index])?;
+            index += 1;
+            let mut hunk_lines = Vec::new();
+            let (mut old_seen, mut new_seen) = (0, 0);
+            while (old_seen < old_count || new_seen < new_count) && index < lines.len() {
+                let line = lines[index];
+                let parsed = match line.chars().next() {
+                    Some(' ') => HunkLine::Context(line[1..].to_string()),
+                    Some('-') => HunkLine::Remove(line[1..].to_string()),
+                    Some('+') => HunkLine::Add(line[1..].to_string()),
+                    Some('\\') => {
+                        index += 1;
+                        continue;
+                    }
+                    // Some generators drop the leading space on blank context lines.
+                    None => HunkLine::Context(String::new()),
+                    Some(_) => {
+                        return Err(MosaicError::Tool(format!(
+                            "invalid patch: unexpected line in hunk: {line}"
+                        )));
+                    }
+                };
+                match parsed {
+                    HunkLine::Context(_) => {
+                        old_seen += 1;
+                        new_seen += 1;
+                    }
+                    HunkLine::Remove(_) => old_seen += 1,
+                    HunkLine::Add(_) => new_seen += 1,
+                }
+                hunk_lines.push(parsed);
+                index += 1;
+            }
+            if old_seen != old_count || new_seen != new_count {
+                return Err(MosaicError::Tool(format!(
+                    "invalid patch: hunk at -{old_start} is truncated"
+                )));
+            }
+            while lines.get(index).is_some_and(|line| line.starts_with('\\')) {
+                index += 1;
+            }
+            hunks.push(Hunk {
+                old_start,
+                lines: hunk_lines,
+            });
+        }
+
+        if hunks.is_empty() {
+            return Err(MosaicError::Tool(format!(
+                "invalid patch: no hunks for {}",
+                new_path.as_deref().or(old_path.as_deref()).unwrap_or("?")
+            )));
+        }
+        files.push(FilePatch {
+            old_path,
+            new_path,
+            hunks,
+        });
+    }
+
+    if files.is_empty() {
+        return Err(MosaicError::Tool(
+            "invalid patch: no file headers found".to_string(),
+        ));
+    }
+    Ok(files)
+}
+
+/// Applies `hunks` to `original`, returning the patched text. Each hunk must
+/// match its context exactly; it is looked up at the position from its header
+/// first, then anywhere after the previous hunk. CRLF files keep their line
+/// endings whichever endings the patch itself uses.
+pub(crate) fn apply_hunks(path: &str, original: &str, hunks: &[Hunk]) -> Result<String> {
+    let trailing_newline = original.is_empty() || original.ends_with('\n');
+    let line_ending = match original.find('\n') {
+        Some(end) if original[..end].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+    let mut lines = original.lines().map(str::to_string).collect::<Vec<_>>();
+    let mut cursor = 0usize;
+    let mut offset = 0isize;
+
+    for hunk in hunks {
+        let expected = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Remove(text) => Some(text.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let replacement = hunk
+            .lines
+            .iter()
+            .filter_map(|line| match line {
+                HunkLine::Context(text) | HunkLine::Add(text) => Some(text.clone()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let hinted = (hunk.old_start.saturating_sub(1) as isize + offset).max(0) as usize;
+        // A pure insertion at the top of a file uses old_start 0.
+        let hinted = if expected.is_empty() && hunk.old_start == 0 {
+            0
+        } else {
+            hinted
+        };
+        let matches_at = |start: usize| {
+            start >= cursor
+                && start + expected.len() <= lines.len()
+                && lines[start..start + expected.len()]
+                    .iter()
+                    .zip(&expected)
+                    .all(|(actual, expected)| actual == expected)
+        };
+        let start = if matches_at(hinted) {
+            hinted
+        } else {
+            (cursor..=lines.len().saturating_sub(expected.len()))
+                .find(|start| matches_at(*start))
+                .ok_or_else(|| {
+                    MosaicError::Tool(format!(
+                        "patch context does not match {path} near line {}",
+                        hunk.old_start
+                    ))
+                })?
+        };
+
+        let inserted = replacement.len();
+        lines.splice(start..start + expected.len(), replacement);
+        offset += inserted as isize - expected.len() as isize;
+        cursor = start + inserted;
+    }
+
+    let mut patched = lines.join(line_ending);
+    if trailing_newline && !lines.is_empty() {
+        patched.push_str(line_ending);
+    }
+    Ok(patched)
+}
+
+fn parse_header_path(raw: &str) -> Option<String> {
+    let raw = raw.split('\t').next().unwrap_or(raw).trim();
+    if raw == "/dev/null" {
+        return None;
+    }
+    let stripped = raw
+        .strip_prefix("a/")
+        .or_else(|| raw.strip_prefix("b/"))
+        .unwrap_or(raw);
+    Some(stripped.to_string())
+}
+
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize)> {
+    let invalid = || MosaicError::Tool(format!("invalid patch: malformed hunk header: {line}"));
+    let body = line
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(invalid)?;
+    let mut parts = body.split_whitespace();
+    let old = parts
+        .next()
+        .and_then(|part| part.strip_prefix('-'))
+        .ok_or_else(invalid)?;
+    let new = parts
+        .next()
+        .and_then(|part| part.strip_prefix('+'))
+        .ok_or_else(invalid)?;
+    let parse_range = |range: &str| -> Result<(usize, usize)> {
+        let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+        let start = start.parse::<usize>().map_err(|_| invalid())?;
+        let count = count.parse::<usize>().map_err(|_| invalid())?;
+        Ok((start, count))
+    };
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Ok((old_start, old_count, new_count))
+}
@@ -258,24 +258,24 @@ pub(crate) fn command_suggestions(
         return Vec::new();
     }
 
-    if let Some(query) = raw.strip_prefix("/agent").map(str::trim) {
-        if raw == "/agent" || raw.starts_with("/agent ") {
-            let suggestions = agent_suggestions(query, agents);
-            if !suggestions.is_empty() {
-                return suggestions;
-            }
-            return agent_fallback_suggestions();
+    if let Some(query) = raw.strip_prefix("/agent").map(str::trim)
+        && (raw == "/agent" || raw.starts_with("/agent "))
+    {
+        let suggestions = agent_suggestions(query, agents);
+        if !suggestions.is_empty() {
+            return suggestions;
         }
+        return agent_fallback_suggestions();
     }
 
-    if let Some(query) = raw.strip_prefix("/session").map(str::trim) {
-        if raw == "/session" || raw.starts_with("/session ") {
-            let suggestions = session_suggestions(query, sessions, active_session_id);
-            if !suggestions.is_empty() {
-                return suggestions;
-            }
-            return session_fallback_suggestions();
+    if let Some(query) = raw.strip_prefix("/session").map(str::trim)
+        && (raw == "/session" || raw.starts_with("/session "))
+    {
+        let suggestions = session_suggestions(query, sessions, active_session_id);
+        if !suggestions.is_empty() {
+            return suggestions;
         }
+        return session_fallback_suggestions();
     }
 
     command_palette_items(input)
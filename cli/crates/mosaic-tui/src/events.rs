@@ -4,5 +4,7 @@ use mosaic_core::error::Result;
 #[derive(Debug)]
 pub(crate) enum AppEvent {
     Agent(AgentEvent),
+    /// Reply text streamed before the turn's `Assistant` event arrives.
+    AssistantDelta(String),
     AskDone(Result<AgentRunResult>),
 }
@@ -12,6 +12,9 @@ use crate::pickers::{
 use crate::state::{TuiAction, TuiState};
 use crate::{TuiFocus, TuiOptions, TuiRuntime};
 
+// The nested `if`s keep a key that fails its condition from falling through to a
+// later arm, so they are kept instead of being turned into match guards.
+#[allow(clippy::collapsible_match, clippy::collapsible_if)]
 pub(crate) async fn handle_key(
     state: &mut TuiState,
     session_store: &SessionStore,
@@ -60,6 +60,9 @@ impl TuiFocus {
     }
 }
 
+type SwitchAgentFn = dyn Fn(&str) -> Result<SwitchedTuiRuntime> + Send + Sync;
+type LoadSessionRuntimeFn = dyn Fn(Option<&str>) -> Result<SwitchedTuiRuntime> + Send + Sync;
+
 #[derive(Clone)]
 pub struct TuiRuntime {
     pub agent: AgentRunner,
@@ -68,8 +71,8 @@ pub struct TuiRuntime {
     pub model_name: String,
     pub session_metadata: SessionRuntimeMetadata,
     pub policy_summary: String,
-    pub switch_agent: Arc<dyn Fn(&str) -> Result<SwitchedTuiRuntime> + Send + Sync>,
-    pub load_session_runtime: Arc<dyn Fn(Option<&str>) -> Result<SwitchedTuiRuntime> + Send + Sync>,
+    pub switch_agent: Arc<SwitchAgentFn>,
+    pub load_session_runtime: Arc<LoadSessionRuntimeFn>,
     pub list_agents: Arc<dyn Fn() -> Result<Vec<TuiAgentOption>> + Send + Sync>,
     pub run_local_command: Arc<dyn Fn(TuiLocalCommand) -> TuiLocalCommandFuture + Send + Sync>,
 }
@@ -210,8 +213,12 @@ async fn run_app(
                 AppEvent::Agent(agent_event) => {
                     state.apply_agent_event(agent_event);
                 }
+                AppEvent::AssistantDelta(delta) => {
+                    state.apply_assistant_delta(&delta);
+                }
                 AppEvent::AskDone(result) => {
                     state.running = false;
+                    state.streaming_reply = false;
                     match result {
                         Ok(run) => {
                             state.active_session_id = Some(run.session_id);
@@ -276,6 +283,7 @@ fn spawn_agent_task(
 ) {
     let tx_done = app_tx.clone();
     let tx_events = app_tx.clone();
+    let tx_deltas = app_tx.clone();
     let runner = runtime.agent.clone();
     let cwd = options.cwd.clone();
     let yes = options.yes;
@@ -286,7 +294,7 @@ fn spawn_agent_task(
             let _ = tx_events.send(AppEvent::Agent(event));
         });
         let result = runner
-            .ask(
+            .ask_streaming(
                 &prompt,
                 AgentRunOptions {
                     session_id,
@@ -295,6 +303,10 @@ fn spawn_agent_task(
                     yes,
                     interactive: true,
                     event_callback: Some(callback),
+                    label: None,
+                },
+                &mut |chunk| {
+                    let _ = tx_deltas.send(AppEvent::AssistantDelta(chunk.to_string()));
                 },
             )
             .await;
@@ -548,6 +560,33 @@ mod tests {
         assert_eq!(state.inspector[1].kind, "tool_result");
     }
 
+    #[test]
+    fn streamed_reply_grows_one_message_that_the_final_event_replaces() {
+        let mut state = state_with_sessions(true);
+        state.apply_agent_event(AgentEvent::User {
+            session_id: "session-a".to_string(),
+            text: "hi".to_string(),
+        });
+        state.apply_assistant_delta("Hel");
+        state.apply_assistant_delta("lo");
+        assert_eq!(state.messages.len(), 2);
+        assert_eq!(state.messages[1].text, "Hello");
+
+        state.apply_agent_event(AgentEvent::Assistant {
+            session_id: "session-a".to_string(),
+            text: "Hello!".to_string(),
+        });
+        assert_eq!(state.messages.len(), 2);
+        assert_eq!(state.messages[1].text, "Hello!");
+        assert!(!state.streaming_reply);
+
+        state.apply_agent_event(AgentEvent::Assistant {
+            session_id: "session-a".to_string(),
+            text: "again".to_string(),
+        });
+        assert_eq!(state.messages.len(), 3);
+    }
+
     #[test]
     fn local_command_output_populates_transcript_and_inspector() {
         let mut state = state_with_sessions(true);
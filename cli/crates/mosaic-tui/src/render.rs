@@ -14,6 +14,7 @@ const MOSAIC_TUI_TITLE: &str = concat!("Mosaic CLI v", env!("CARGO_PKG_VERSION")
 const STARTUP_PLACEHOLDER: &str =
     "Type @ to mention files, # for issues/PRs, / for commands, or ? for shortcuts";
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn render(
     frame: &mut ratatui::Frame,
     state: &TuiState,
@@ -392,9 +393,7 @@ fn render_session_group(
         Paragraph::new(Line::styled(
             {
                 let prefix = format!("── {} ", title);
-                let dashes = area
-                    .width
-                    .saturating_sub(prefix.chars().count() as u16) as usize;
+                let dashes = area.width.saturating_sub(prefix.chars().count() as u16) as usize;
                 format!("{prefix}{}", "─".repeat(dashes))
             },
             Style::default()
@@ -676,6 +675,7 @@ fn render_startup_environment(
     frame.render_widget(Paragraph::new(line), area);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_startup_location(
     frame: &mut ratatui::Frame,
     area: Rect,
@@ -695,6 +695,7 @@ fn render_startup_location(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_context_strip(
     frame: &mut ratatui::Frame,
     state: &TuiState,
@@ -930,13 +931,11 @@ fn render_command_palette(
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .split(inner);
-    let list = List::new(rows)
-        .highlight_symbol("› ")
-        .highlight_style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+    let list = List::new(rows).highlight_symbol("› ").highlight_style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
     frame.render_stateful_widget(list, sections[0], &mut list_state);
     frame.render_widget(
         Paragraph::new(command_assistant_footer(
@@ -1153,10 +1152,10 @@ pub(crate) fn compose_input_placeholder(startup_surface: bool, running: bool) ->
 }
 
 pub(crate) fn display_cwd(cwd: &str) -> String {
-    if let Ok(home) = std::env::var("HOME") {
-        if cwd.starts_with(&home) {
-            return cwd.replacen(&home, "~", 1);
-        }
+    if let Ok(home) = std::env::var("HOME")
+        && cwd.starts_with(&home)
+    {
+        return cwd.replacen(&home, "~", 1);
     }
     cwd.to_string()
 }
@@ -1216,11 +1215,7 @@ fn command_palette_item_lines(width: u16, item: &CommandSuggestion) -> Vec<Line<
     let desc_width = available.saturating_sub(label_width + 2);
 
     let label_cell = truncate_text(&item.label, label_width);
-    let label_padded = format!(
-        "{:<width$}",
-        label_cell,
-        width = label_width
-    );
+    let label_padded = format!("{:<width$}", label_cell, width = label_width);
     let desc_cell = truncate_text(&item.description, desc_width);
 
     let line_text = format!("{label_padded}  {desc_cell}");
@@ -1251,15 +1246,6 @@ fn command_assistant_footer(width: u16, selected: Option<&CommandSuggestion>) ->
     format!("↑↓ navigate • Tab complete • {helper}")
 }
 
-fn command_suggestion_badge(source: &CommandSuggestionSource) -> &'static str {
-    match source {
-        CommandSuggestionSource::Local => "local",
-        CommandSuggestionSource::Shell => "shell",
-        CommandSuggestionSource::Agent => "agent",
-        CommandSuggestionSource::Session => "session",
-    }
-}
-
 fn command_suggestion_color(source: &CommandSuggestionSource) -> Color {
     match source {
         CommandSuggestionSource::Local => Color::Cyan,
@@ -47,6 +47,8 @@ pub struct TuiState {
     pub(crate) active_session_id: Option<String>,
     pub(crate) messages: Vec<ChatLine>,
     pub(crate) inspector: Vec<InspectorLine>,
+    /// Whether the last assistant message is still being streamed.
+    pub(crate) streaming_reply: bool,
 }
 
 impl TuiState {
@@ -85,6 +87,7 @@ impl TuiState {
             active_session_id,
             messages: Vec::new(),
             inspector: Vec::new(),
+            streaming_reply: false,
         }
     }
 
@@ -306,12 +309,31 @@ impl TuiState {
         }
     }
 
+    /// Appends streamed reply text, starting a new assistant message for the
+    /// first chunk of a reply.
+    pub(crate) fn apply_assistant_delta(&mut self, delta: &str) {
+        self.startup_visible = false;
+        match self.messages.last_mut() {
+            Some(line) if self.streaming_reply && line.role == "assistant" => {
+                line.text.push_str(delta);
+            }
+            _ => {
+                self.messages.push(ChatLine {
+                    role: "assistant".to_string(),
+                    text: delta.to_string(),
+                });
+                self.streaming_reply = true;
+            }
+        }
+    }
+
     pub(crate) fn apply_agent_event(&mut self, event: AgentEvent) {
         self.startup_visible = false;
         self.command_palette_index = 0;
         match event {
             AgentEvent::User { session_id, text } => {
                 self.active_session_id = Some(session_id.clone());
+                self.streaming_reply = false;
                 self.messages.push(ChatLine {
                     role: "user".to_string(),
                     text,
@@ -320,10 +342,17 @@ impl TuiState {
             }
             AgentEvent::Assistant { session_id, text } => {
                 self.active_session_id = Some(session_id.clone());
-                self.messages.push(ChatLine {
-                    role: "assistant".to_string(),
-                    text,
-                });
+                // The final text replaces what was streamed for this reply.
+                match self.messages.last_mut() {
+                    Some(line) if self.streaming_reply && line.role == "assistant" => {
+                        line.text = text;
+                    }
+                    _ => self.messages.push(ChatLine {
+                        role: "assistant".to_string(),
+                        text,
+                    }),
+                }
+                self.streaming_reply = false;
                 self.status = format!("assistant replied session={session_id}");
             }
             AgentEvent::ToolCall {